@@ -1,5 +1,5 @@
 use pixelart::{
-    animation::{simple::create_simple_animation, Repeat},
+    animation::{simple::create_simple_animation, FrameCount, LoopBehavior},
     pixels::canvas::{
         templates::{alien_monster::AlienMonster, heart::Heart, Template},
         SharedMutPixelCanvasExt, SharedPixelCanvasExt,
@@ -28,27 +28,33 @@ fn default() {
     canvas.draw_exact_abs(AlienMonster);
 
     view([
-        create_simple_animation::<5, 5, 1, 1>(
-            TOP_LEFT,
-            5,
-            Repeat::Infinite,
-            Repeat::Infinite,
-            |ctx| {
-                ctx.update_body_color(YELLOW);
-                ctx.update_part_color(BLUE);
-            },
-            |i, ctx| {
-                if let Some(next) = ctx.part().position().next() {
-                    ctx.part_mut().crop_to(next);
-                    ctx.update_part_color(PixelColor::from_blue(255 - (i as u8 * 10) % 250));
-                    true
-                } else {
-                    false
-                }
-            },
-        )
-        .take_images(),
-        vec![canvas.default_image_builder().with_scale(2).get_image()],
+        (
+            "Moving part",
+            create_simple_animation::<5, 5, 1, 1>(
+                TOP_LEFT,
+                5,
+                LoopBehavior::default(),
+                FrameCount::UntilStopped,
+                |ctx| {
+                    ctx.update_body_color(YELLOW);
+                    ctx.update_part_color(BLUE);
+                },
+                |i, ctx| {
+                    if let Some(next) = ctx.part().position().next() {
+                        ctx.part_mut().crop_to(next);
+                        ctx.update_part_color(PixelColor::from_blue(255 - (i as u8 * 10) % 250));
+                        true
+                    } else {
+                        false
+                    }
+                },
+            )
+            .take_images(),
+        ),
+        (
+            "Alien monster",
+            vec![canvas.default_image_builder().with_scale(2).get_image()],
+        ),
     ])
     .unwrap()
 }
@@ -1,5 +1,5 @@
 use pixelart::{
-    animation::{simple::create_simple_animation, Repeat},
+    animation::{simple::create_simple_animation, FrameCount, LoopBehavior},
     prelude::*,
 };
 
@@ -7,8 +7,8 @@ pub fn moving_plus() {
     create_simple_animation::<10, 10, 3, 3>(
         TOP_LEFT,
         5,
-        Repeat::Infinite,
-        Repeat::Infinite,
+        LoopBehavior::default(),
+        FrameCount::UntilStopped,
         |ctx| {
             let canvas = ctx.body_mut();
 
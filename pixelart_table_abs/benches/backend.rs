@@ -0,0 +1,51 @@
+//! Compares the backends on a small table which is mostly filled, where the dense one is
+//! expected to win.
+//!
+//! Run with `cargo bench -p pixelart_table_abs`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use pixelart_table_abs::{table::IllusionTable, IllusionBackend};
+
+const ROUNDS: usize = 200;
+
+/// Fills every item of a 32x32 table one by one, then reads them all back.
+fn fill_and_read(backend: IllusionBackend) -> Duration {
+    let start = Instant::now();
+    for round in 0..ROUNDS {
+        let mut table = IllusionTable::<32, 32, u32>::with_backend(0, backend);
+        for row in 0..32 {
+            for column in 0..32 {
+                table.try_modify((row, column), |item| {
+                    *item.get_mut() = (round + row * 32 + column) as u32 + 1
+                });
+            }
+        }
+
+        let mut sum = 0;
+        for row in 0..32 {
+            for column in 0..32 {
+                sum += *table.get((row, column)).unwrap();
+            }
+        }
+        black_box(sum);
+    }
+    start.elapsed()
+}
+
+fn main() {
+    // Warm up, so the first backend isn't paying for it.
+    fill_and_read(IllusionBackend::Sparse);
+
+    let sparse = fill_and_read(IllusionBackend::Sparse);
+    let dense = fill_and_read(IllusionBackend::Dense);
+    println!("sparse: {sparse:?}");
+    println!("dense:  {dense:?}");
+    println!(
+        "dense is {:.1}x as fast",
+        sparse.as_secs_f64() / dense.as_secs_f64()
+    );
+}
@@ -1,10 +1,45 @@
 use std::{
-    collections::HashMap,
+    cell::RefCell,
     fmt::Debug,
     ops::{Deref, DerefMut},
+    rc::Rc,
 };
 
+use storage::IllusionStorage;
+
+mod storage;
 pub mod table;
+pub mod tags;
+
+/// Map of real items of the sparse backend, keyed by their index.
+///
+/// With the `ordered` feature it's a `BTreeMap`, so real items are always iterated in order
+/// (row-major for tables), otherwise a faster `HashMap` with an arbitrary order.
+#[cfg(feature = "ordered")]
+type IllusionMap<K, V> = std::collections::BTreeMap<K, V>;
+#[cfg(not(feature = "ordered"))]
+type IllusionMap<K, V> = std::collections::HashMap<K, V>;
+
+/// How real items of an illusion container are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub enum IllusionBackend {
+    /// Only real items are stored in a map, so empty items take no space.
+    /// Best for big containers which are mostly empty.
+    #[default]
+    Sparse,
+    /// There's a slot for every item, so items are found without hashing.
+    /// Best for small containers which are mostly filled.
+    Dense,
+}
+
+/// How a mut handle reaches its container.
+pub(crate) enum HandleInner<'a, T> {
+    /// The handle borrows the container alone, so items are read and written in place.
+    Unique(&'a mut T),
+    /// Handles of a mut iterator share the container, each one works on its own copy of the
+    /// item and writes it back when dropped. So no two handles alias, even if collected.
+    Shared(Rc<RefCell<&'a mut T>>),
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
 pub struct IllusionItem<T> {
@@ -16,7 +51,7 @@ pub struct InnerIllusionArray<const W: usize, P>
 where
     P: Default,
 {
-    inner: HashMap<usize, IllusionItem<P>>,
+    inner: IllusionStorage<IllusionItem<P>>,
     default: P,
 }
 
@@ -28,6 +63,10 @@ where
         self.inner.len()
     }
 
+    fn real_items(&self) -> impl Iterator<Item = (usize, &P)> {
+        self.inner.iter().map(|(index, item)| (index, &item.value))
+    }
+
     /// Returns actual element if exists, or the default value otherwise. And none if the index is out of bounds.
     fn get(&self, index: usize) -> Option<&P> {
         // Return none if the index is out of bounds
@@ -41,6 +80,39 @@ where
             .unwrap_or(&self.default)
             .into()
     }
+
+    /// Stores the value at given index, or removes the stored one if it's the default value.
+    fn set(&mut self, index: usize, value: P)
+    where
+        P: PartialEq,
+    {
+        if value == self.default {
+            self.inner.remove(&index);
+        } else if let Some(item) = self.inner.get_mut(&index) {
+            item.value = value;
+        } else {
+            self.inner.insert(index, IllusionItem { value });
+        }
+    }
+
+    /// Calls `f` on the item at `index` (or a copy of the default value, if it's not stored),
+    /// only a value other than the default value is stored afterwards.
+    #[cfg(feature = "rayon")]
+    fn modify(&mut self, index: usize, f: impl FnOnce(&mut P))
+    where
+        P: Clone + PartialEq,
+    {
+        if let Some(item) = self.inner.get_mut(&index) {
+            f(&mut item.value);
+            if item.value == self.default {
+                self.inner.remove(&index);
+            }
+        } else {
+            let mut value = self.default.clone();
+            f(&mut value);
+            self.set(index, value);
+        }
+    }
 }
 
 /// This kind of array assumes (illusional) that is filled with `W` elements.
@@ -59,7 +131,7 @@ impl<const W: usize, P: Default> Default for IllusionArray<W, P> {
     fn default() -> Self {
         Self {
             inner: InnerIllusionArray {
-                inner: HashMap::new(),
+                inner: IllusionStorage::default(),
                 default: Default::default(),
             },
         }
@@ -70,20 +142,64 @@ impl<const W: usize, P> IllusionArray<W, P>
 where
     P: Default,
 {
-    pub fn iter(&self) -> IllusionArrayIter<W, P> {
+    /// Creates an empty array where every item is `default` instead of `P::default()`.
+    ///
+    /// Items equal to `default` are not stored, so e.g. a black background costs nothing.
+    pub fn with_default(default: P) -> Self {
+        Self::with_backend(default, IllusionBackend::Sparse)
+    }
+
+    /// Creates an empty array where every item is `default`, using the given backend.
+    pub fn with_backend(default: P, backend: IllusionBackend) -> Self {
+        Self {
+            inner: InnerIllusionArray {
+                inner: IllusionStorage::new(backend, W),
+                default,
+            },
+        }
+    }
+
+    pub fn backend(&self) -> IllusionBackend {
+        self.inner.inner.backend()
+    }
+
+    /// The value of items which are not stored.
+    pub fn default_value(&self) -> &P {
+        &self.inner.default
+    }
+
+    pub fn iter(&self) -> IllusionArrayIter<'_, W, P> {
         IllusionArrayIter {
             inner: &self.inner,
             curr_index: 0,
         }
     }
 
-    pub fn iter_mut(&mut self) -> IllusionArrayIterMut<W, P> {
+    /// Handles of all items which can be collected and used at the same time.
+    ///
+    /// Each handle works on its own copy of the item and writes it back when dropped, prefer
+    /// [`IllusionArray::for_each_mut`] if the handles are only used one by one.
+    pub fn iter_mut(&mut self) -> IllusionArrayIterMut<'_, W, P> {
         IllusionArrayIterMut {
-            inner: &mut self.inner,
+            inner: Rc::new(RefCell::new(&mut self.inner)),
             curr_index: 0,
         }
     }
 
+    /// Calls `f` on handle of every item, one by one.
+    ///
+    /// Unlike [`IllusionArray::iter_mut`], stored items are modified in place.
+    pub fn for_each_mut(&mut self, mut f: impl FnMut(IllusionArrayHandleMut<'_, W, P>))
+    where
+        P: Clone + PartialEq,
+    {
+        for index in 0..W {
+            f(self
+                .get_mut(index)
+                .expect("Index of IllusionArray::for_each_mut should be in bounds."));
+        }
+    }
+
     pub fn filled_len(&self) -> usize {
         self.inner.filled_len()
     }
@@ -110,14 +226,37 @@ where
             return None;
         }
 
-        let current = P::default().into();
+        let current = self.inner.default.clone().into();
         Some(IllusionArrayHandleMut {
-            inner: &mut self.inner,
+            inner: HandleInner::Unique(&mut self.inner),
             index,
             current,
         })
     }
 
+    /// Gets the entry of an item, to read or modify it in place. None if the index is out of bounds.
+    ///
+    /// Unlike [`IllusionArray::get_mut`], nothing is cloned or compared unless the item is changed,
+    /// and default items are never stored.
+    pub fn entry(&mut self, index: usize) -> Option<IllusionEntry<'_, W, P>> {
+        if index >= W {
+            return None;
+        }
+
+        let entry = if self.inner.inner.contains_key(&index) {
+            IllusionEntry::Occupied(OccupiedIllusionEntry {
+                inner: &mut self.inner,
+                index,
+            })
+        } else {
+            IllusionEntry::Vacant(VacantIllusionEntry {
+                inner: &mut self.inner,
+                index,
+            })
+        };
+        Some(entry)
+    }
+
     pub fn try_modify(
         &mut self,
         index: usize,
@@ -134,14 +273,11 @@ where
         }
     }
 
-    pub fn real_items(&self) -> impl Iterator<Item = (&usize, &P)> {
-        self.inner
-            .inner
-            .iter()
-            .map(|(index, item)| (index, &item.value))
+    pub fn real_items(&self) -> impl Iterator<Item = (usize, &P)> {
+        self.inner.real_items()
     }
 
-    pub fn real_items_mut(&mut self) -> impl Iterator<Item = (&usize, &mut P)> {
+    pub fn real_items_mut(&mut self) -> impl Iterator<Item = (usize, &mut P)> {
         self.inner
             .inner
             .iter_mut()
@@ -149,6 +285,151 @@ where
     }
 }
 
+/// Entry of a single item of an [`IllusionArray`], see [`IllusionArray::entry`].
+pub enum IllusionEntry<'a, const W: usize, P>
+where
+    P: Default,
+{
+    /// The item is actually stored.
+    Occupied(OccupiedIllusionEntry<'a, W, P>),
+    /// The item is not stored, so it has the default value.
+    Vacant(VacantIllusionEntry<'a, W, P>),
+}
+
+impl<'a, const W: usize, P> IllusionEntry<'a, W, P>
+where
+    P: Default,
+{
+    pub fn index(&self) -> usize {
+        match self {
+            IllusionEntry::Occupied(entry) => entry.index(),
+            IllusionEntry::Vacant(entry) => entry.index(),
+        }
+    }
+
+    /// The actual item, or the default value if it's vacant.
+    pub fn get(&self) -> &P {
+        match self {
+            IllusionEntry::Occupied(entry) => entry.get(),
+            IllusionEntry::Vacant(entry) => entry.get(),
+        }
+    }
+
+    /// Modifies the item in place, a vacant item starts from a clone of the default value.
+    ///
+    /// The item is stored only if it's not equal to the default value afterwards.
+    pub fn modify(self, f: impl FnOnce(&mut P))
+    where
+        P: Clone + PartialEq,
+    {
+        match self {
+            IllusionEntry::Occupied(entry) => entry.modify(f),
+            IllusionEntry::Vacant(entry) => {
+                let mut value = entry.get().clone();
+                f(&mut value);
+                entry.insert(value);
+            }
+        }
+    }
+}
+
+/// An item of an [`IllusionArray`] which is actually stored.
+pub struct OccupiedIllusionEntry<'a, const W: usize, P>
+where
+    P: Default,
+{
+    inner: &'a mut InnerIllusionArray<W, P>,
+    index: usize,
+}
+
+impl<'a, const W: usize, P> OccupiedIllusionEntry<'a, W, P>
+where
+    P: Default,
+{
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn get(&self) -> &P {
+        &self.inner.inner[&self.index].value
+    }
+
+    /// Modifies the item in place, it's removed if it's equal to the default value afterwards.
+    pub fn modify(self, f: impl FnOnce(&mut P))
+    where
+        P: PartialEq,
+    {
+        let item = self
+            .inner
+            .inner
+            .get_mut(&self.index)
+            .expect("OccupiedIllusionEntry should be stored.");
+        f(&mut item.value);
+        if item.value == self.inner.default {
+            self.inner.inner.remove(&self.index);
+        }
+    }
+
+    /// Replaces the item and returns the old one, it's removed if the new one is the default value.
+    pub fn insert(self, value: P) -> P
+    where
+        P: PartialEq,
+    {
+        if value == self.inner.default {
+            return self.remove();
+        }
+
+        let item = self
+            .inner
+            .inner
+            .get_mut(&self.index)
+            .expect("OccupiedIllusionEntry should be stored.");
+        std::mem::replace(&mut item.value, value)
+    }
+
+    /// Removes the item (so it's the default value now) and returns it.
+    pub fn remove(self) -> P {
+        self.inner
+            .inner
+            .remove(&self.index)
+            .expect("OccupiedIllusionEntry should be stored.")
+            .value
+    }
+}
+
+/// An item of an [`IllusionArray`] which is not stored, so it has the default value.
+pub struct VacantIllusionEntry<'a, const W: usize, P>
+where
+    P: Default,
+{
+    inner: &'a mut InnerIllusionArray<W, P>,
+    index: usize,
+}
+
+impl<'a, const W: usize, P> VacantIllusionEntry<'a, W, P>
+where
+    P: Default,
+{
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The default value.
+    pub fn get(&self) -> &P {
+        &self.inner.default
+    }
+
+    /// Stores the item, unless it's equal to the default value.
+    pub fn insert(self, value: P)
+    where
+        P: PartialEq,
+    {
+        if value != self.inner.default {
+            self.inner.inner.insert(self.index, IllusionItem { value });
+        }
+    }
+}
+
 pub struct IllusionArrayIter<'a, const W: usize, P>
 where
     P: Default,
@@ -235,7 +516,7 @@ pub struct IllusionArrayIterMut<'a, const W: usize, P>
 where
     P: Default,
 {
-    inner: &'a mut InnerIllusionArray<W, P>,
+    inner: Rc<RefCell<&'a mut InnerIllusionArray<W, P>>>,
     curr_index: usize,
 }
 
@@ -260,10 +541,11 @@ where
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.curr_index < W {
+            let current = self.inner.borrow().get(self.curr_index).cloned();
             let handle = IllusionArrayHandleMut {
-                inner: unsafe { &mut *(self.inner as *mut _) },
+                inner: HandleInner::Shared(self.inner.clone()),
                 index: self.curr_index,
-                current: P::default().into(),
+                current,
             };
             self.curr_index += 1;
             Some(handle)
@@ -277,7 +559,7 @@ pub struct IllusionArrayHandleMut<'a, const W: usize, P>
 where
     P: std::cmp::PartialEq + std::clone::Clone + Default,
 {
-    inner: &'a mut InnerIllusionArray<W, P>,
+    inner: HandleInner<'a, InnerIllusionArray<W, P>>,
     index: usize,
     current: Option<P>,
 }
@@ -287,21 +569,31 @@ where
     P: std::cmp::PartialEq + std::clone::Clone + std::default::Default + Default,
 {
     fn drop(&mut self) {
+        let inner = match &mut self.inner {
+            HandleInner::Unique(inner) => inner,
+            HandleInner::Shared(inner) => {
+                if let Some(current) = self.current.take() {
+                    inner.borrow_mut().set(self.index, current);
+                }
+                return;
+            }
+        };
+
         if let Some(current) = self.current.take() {
-            if &current != &self.inner.default {
-                if let Some(item) = self.inner.inner.get_mut(&self.index) {
+            if current != inner.default {
+                if let Some(item) = inner.inner.get_mut(&self.index) {
                     item.value = current;
                 } else {
-                    self.inner
+                    inner
                         .inner
                         .insert(self.index, IllusionItem { value: current });
                 }
             }
         }
 
-        if let Some(item) = self.inner.inner.get(&self.index) {
-            if &item.value == &self.inner.default {
-                self.inner.inner.remove(&self.index);
+        if let Some(item) = inner.inner.get(&self.index) {
+            if item.value == inner.default {
+                inner.inner.remove(&self.index);
             }
         }
     }
@@ -332,9 +624,15 @@ where
     P: std::cmp::PartialEq + std::clone::Clone + Default,
 {
     pub fn get(&self) -> &P {
-        self.inner
-            .get(self.index)
-            .expect("IllusionArrayHandleMut should be in bounds.")
+        match &self.inner {
+            HandleInner::Unique(inner) => inner
+                .get(self.index)
+                .expect("IllusionArrayHandleMut should be in bounds."),
+            HandleInner::Shared(_) => self
+                .current
+                .as_ref()
+                .expect("IllusionArrayHandleMut should have its item."),
+        }
     }
 
     pub fn mapped<F, T>(&self, f: F) -> T
@@ -348,11 +646,14 @@ where
     where
         P: Clone,
     {
-        self.inner
-            .inner
-            .get_mut(&self.index)
-            .map(|item| &mut item.value)
-            .unwrap_or(self.current.as_mut().unwrap())
+        match &mut self.inner {
+            HandleInner::Unique(inner) => inner
+                .inner
+                .get_mut(&self.index)
+                .map(|item| &mut item.value)
+                .unwrap_or(self.current.as_mut().unwrap()),
+            HandleInner::Shared(_) => self.current.as_mut().unwrap(),
+        }
     }
 
     pub fn modify(&mut self, f: impl FnOnce(&mut Self)) {
@@ -388,4 +689,59 @@ mod tests {
         println!("Filled len: {:?}", arr.filled_len());
         println!("---");
     }
+
+    #[test]
+    fn test_entry() {
+        let mut arr = IllusionArray::<3, i32>::default();
+        assert!(arr.entry(3).is_none());
+
+        let entry = arr.entry(1).unwrap();
+        assert!(matches!(entry, IllusionEntry::Vacant(_)));
+        assert_eq!(*entry.get(), 0);
+        entry.modify(|value| *value += 5);
+        assert_eq!(arr.filled_len(), 1);
+
+        arr.entry(2).unwrap().modify(|value| *value *= 2);
+        assert_eq!(arr.filled_len(), 1);
+
+        match arr.entry(1).unwrap() {
+            IllusionEntry::Occupied(entry) => assert_eq!(entry.insert(7), 5),
+            IllusionEntry::Vacant(_) => panic!("Item 1 should be stored."),
+        }
+        assert_eq!(*arr.get(1).unwrap(), 7);
+
+        arr.entry(1).unwrap().modify(|value| *value = 0);
+        assert_eq!(arr.filled_len(), 0);
+    }
+
+    #[test]
+    fn test_with_default() {
+        let mut arr = IllusionArray::<3, i32>::with_default(9);
+        assert_eq!(arr.iter().map(|item| *item).collect::<Vec<_>>(), [9, 9, 9]);
+
+        for mut item in arr.iter_mut() {
+            if item.index() == 1 {
+                *item = 0;
+            }
+        }
+        arr.entry(2).unwrap().modify(|value| *value = 9);
+        assert_eq!(arr.filled_len(), 1);
+        assert_eq!(*arr.get(1).unwrap(), 0);
+        assert_eq!(*arr.default_value(), 9);
+    }
+
+    #[test]
+    fn test_mut_handles() {
+        let mut arr = IllusionArray::<3, i32>::default();
+
+        let mut handles: Vec<_> = arr.iter_mut().collect();
+        *handles[2] = 2;
+        *handles[0] = *handles[2] + 1;
+        drop(handles);
+        assert_eq!(arr.iter().map(|item| *item).collect::<Vec<_>>(), [3, 0, 2]);
+
+        arr.for_each_mut(|mut item| *item = item.index() as i32 % 2);
+        assert_eq!(arr.filled_len(), 1);
+        assert_eq!(*arr.get(1).unwrap(), 1);
+    }
 }
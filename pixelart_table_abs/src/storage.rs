@@ -0,0 +1,209 @@
+use std::ops::Index;
+
+use crate::{IllusionBackend, IllusionMap};
+
+/// Storage of real items of an illusion container, keyed by their index.
+///
+/// It's a map with the [`IllusionBackend::Sparse`] backend, or a slot per index with
+/// [`IllusionBackend::Dense`], where items are found without hashing.
+///
+/// Every method matches the backend once and then does a single lookup, so the dispatch
+/// costs a (well predicted) branch per access.
+#[derive(Debug, Clone)]
+pub(crate) enum IllusionStorage<V> {
+    Sparse(IllusionMap<usize, V>),
+    /// The index of an item is its slot, so it's not stored along.
+    Dense {
+        slots: Vec<Option<V>>,
+        len: usize,
+    },
+}
+
+impl<V> Default for IllusionStorage<V> {
+    fn default() -> Self {
+        Self::new(IllusionBackend::Sparse, 0)
+    }
+}
+
+impl<V> IllusionStorage<V> {
+    /// Creates an empty storage, `capacity` is the count of slots of a dense storage.
+    pub(crate) fn new(backend: IllusionBackend, capacity: usize) -> Self {
+        match backend {
+            IllusionBackend::Sparse => Self::Sparse(IllusionMap::new()),
+            IllusionBackend::Dense => Self::Dense {
+                slots: std::iter::repeat_with(|| None).take(capacity).collect(),
+                len: 0,
+            },
+        }
+    }
+
+    pub(crate) fn backend(&self) -> IllusionBackend {
+        match self {
+            Self::Sparse(_) => IllusionBackend::Sparse,
+            Self::Dense { .. } => IllusionBackend::Dense,
+        }
+    }
+
+    /// Approximate count of bytes taken by `stored` items, dense storages take space for their
+    /// empty slots too.
+    pub(crate) fn approx_bytes(&self, stored: usize) -> usize {
+        match self {
+            Self::Sparse(_) => stored * std::mem::size_of::<(usize, V)>(),
+            Self::Dense { slots, .. } => slots.len() * std::mem::size_of::<Option<V>>(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::Sparse(map) => map.len(),
+            Self::Dense { len, .. } => *len,
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn get(&self, key: &usize) -> Option<&V> {
+        match self {
+            Self::Sparse(map) => map.get(key),
+            Self::Dense { slots, .. } => slots.get(*key)?.as_ref(),
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, key: &usize) -> Option<&mut V> {
+        match self {
+            Self::Sparse(map) => map.get_mut(key),
+            Self::Dense { slots, .. } => slots.get_mut(*key)?.as_mut(),
+        }
+    }
+
+    pub(crate) fn contains_key(&self, key: &usize) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub(crate) fn insert(&mut self, key: usize, value: V) -> Option<V> {
+        match self {
+            Self::Sparse(map) => map.insert(key, value),
+            Self::Dense { slots, len } => {
+                if key >= slots.len() {
+                    slots.resize_with(key + 1, || None);
+                }
+
+                let old = slots[key].replace(value);
+                if old.is_none() {
+                    *len += 1;
+                }
+                old
+            }
+        }
+    }
+
+    pub(crate) fn remove(&mut self, key: &usize) -> Option<V> {
+        match self {
+            Self::Sparse(map) => map.remove(key),
+            Self::Dense { slots, len } => {
+                let value = slots.get_mut(*key)?.take()?;
+                *len -= 1;
+                Some(value)
+            }
+        }
+    }
+
+    pub(crate) fn get_or_insert_with(&mut self, key: usize, f: impl FnOnce() -> V) -> &mut V {
+        match self {
+            Self::Sparse(map) => map.entry(key).or_insert_with(f),
+            Self::Dense { slots, len } => {
+                if key >= slots.len() {
+                    slots.resize_with(key + 1, || None);
+                }
+
+                let slot = &mut slots[key];
+                if slot.is_none() {
+                    *len += 1;
+                }
+                slot.get_or_insert_with(f)
+            }
+        }
+    }
+
+    pub(crate) fn retain(&mut self, mut f: impl FnMut(&usize, &mut V) -> bool) {
+        match self {
+            Self::Sparse(map) => map.retain(f),
+            Self::Dense { slots, len } => {
+                for (key, slot) in slots.iter_mut().enumerate() {
+                    if let Some(value) = slot {
+                        if !f(&key, value) {
+                            *slot = None;
+                            *len -= 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Stored items, dense storages are always visited in order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (usize, &V)> {
+        let (sparse, dense) = match self {
+            Self::Sparse(map) => (Some(map.iter()), None),
+            Self::Dense { slots, .. } => (None, Some(slots.iter().enumerate())),
+        };
+        sparse
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| (*key, value))
+            .chain(
+                dense
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|(key, slot)| Some((key, slot.as_ref()?))),
+            )
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = (usize, &mut V)> {
+        let (sparse, dense) = match self {
+            Self::Sparse(map) => (Some(map.iter_mut()), None),
+            Self::Dense { slots, .. } => (None, Some(slots.iter_mut().enumerate())),
+        };
+        sparse
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| (*key, value))
+            .chain(
+                dense
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|(key, slot)| Some((key, slot.as_mut()?))),
+            )
+    }
+}
+
+impl<V> Extend<(usize, V)> for IllusionStorage<V> {
+    fn extend<T: IntoIterator<Item = (usize, V)>>(&mut self, iter: T) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<V> Index<&usize> for IllusionStorage<V> {
+    type Output = V;
+
+    fn index(&self, key: &usize) -> &Self::Output {
+        self.get(key)
+            .expect("Item of IllusionStorage should exist.")
+    }
+}
+
+/// Storages are equal if they have the same items, no matter what their backends are.
+impl<V: PartialEq> PartialEq for IllusionStorage<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get(&key) == Some(value))
+    }
+}
+
+impl<V: Eq> Eq for IllusionStorage<V> {}
@@ -1,16 +1,208 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    cell::RefCell,
+    ops::{Deref, DerefMut, RangeInclusive},
+    rc::Rc,
+};
+
+use crate::{HandleInner, IllusionArray, IllusionBackend, IllusionItem, InnerIllusionArray};
+
+/// An empty stored row, having the same default value as its table.
+fn empty_row<const W: usize, P>(
+    default: &P,
+    backend: IllusionBackend,
+) -> IllusionItem<IllusionArray<W, P>>
+where
+    P: Default + Clone,
+{
+    IllusionItem {
+        value: IllusionArray::with_backend(default.clone(), backend),
+    }
+}
 
-use crate::{IllusionArray, IllusionItem};
+/// As same as [`empty_row`], for tables whose default value is `P::default()`, so nothing is
+/// cloned.
+fn default_row<const W: usize, P>(
+    _default: &P,
+    backend: IllusionBackend,
+) -> IllusionItem<IllusionArray<W, P>>
+where
+    P: Default,
+{
+    IllusionItem {
+        value: IllusionArray::with_backend(P::default(), backend),
+    }
+}
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// Count of changed cells remembered for each row, before older ones are merged into the
+/// whole row.
+const TRACKED_CELLS: usize = 4;
+
+/// Changes of a row since it was first tracked, see [`IllusionTable::track_changes`].
+#[derive(Debug, Clone, Copy, Default)]
+struct RowChange {
+    /// Version of the last change which may have touched every cell of the row, zero if
+    /// there's none.
+    all: u64,
+    /// Last changed cells as `(column, version)`, a slot of version zero is free.
+    ///
+    /// Only changes newer than `all` are kept.
+    cells: [(usize, u64); TRACKED_CELLS],
+}
+
+impl RowChange {
+    /// Marks the whole row as changed under the `version`.
+    fn mark_all(&mut self, version: u64) {
+        *self = Self {
+            all: version,
+            ..Default::default()
+        };
+    }
+
+    /// Marks a single cell as changed under the `version`.
+    ///
+    /// If there's no free slot, the oldest cell is merged into the whole row.
+    fn mark(&mut self, column: usize, version: u64) {
+        let slot = match self
+            .cells
+            .iter()
+            .position(|(changed, version)| *version != 0 && *changed == column)
+        {
+            Some(slot) => slot,
+            None => {
+                let oldest = (0..TRACKED_CELLS)
+                    .min_by_key(|slot| self.cells[*slot].1)
+                    .unwrap_or_default();
+                let all = self.all.max(self.cells[oldest].1);
+                if all > self.all {
+                    self.all = all;
+                    // Changes as old as the merged one are covered by the whole row now.
+                    for cell in &mut self.cells {
+                        if cell.1 <= all {
+                            *cell = Default::default();
+                        }
+                    }
+                }
+                oldest
+            }
+        };
+        self.cells[slot] = (column, version);
+    }
+
+    /// Columns changed after the `version`, in order.
+    fn columns_since(&self, version: u64, width: usize) -> Vec<usize> {
+        if self.all > version {
+            return (0..width).collect();
+        }
+
+        let mut columns: Vec<_> = self
+            .cells
+            .iter()
+            .filter(|(_, changed)| *changed > version)
+            .map(|(column, _)| *column)
+            .collect();
+        columns.sort_unstable();
+        columns
+    }
+}
+
+/// Change history of a table, see [`IllusionTable::version`].
+///
+/// Only a few changed cells are kept for each row (see [`RowChange`]), so it never takes more
+/// than a few words per row, no matter how many items are changed.
+#[derive(Debug, Clone, Default)]
+struct IllusionChanges {
+    version: u64,
+    /// Version of the last change which may have touched every cell.
+    all: u64,
+    /// Changes of each row, only if changes are tracked.
+    rows: Option<Vec<RowChange>>,
+}
+
+impl IllusionChanges {
+    /// Marks `columns` of each row as changed under a new version, nothing happens if there're
+    /// no spans.
+    ///
+    /// Spans wider than [`TRACKED_CELLS`] mark the whole row.
+    fn mark_spans(&mut self, spans: impl IntoIterator<Item = (usize, RangeInclusive<usize>)>) {
+        let mut spans = spans.into_iter().peekable();
+        if spans.peek().is_none() {
+            return;
+        }
+        self.version += 1;
+
+        let Some(rows) = &mut self.rows else {
+            return;
+        };
+        for (row, columns) in spans {
+            if let Some(change) = rows.get_mut(row) {
+                if columns.clone().count() > TRACKED_CELLS {
+                    change.mark_all(self.version);
+                } else {
+                    for column in columns {
+                        change.mark(column, self.version);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Marks single `cells` as changed under a new version.
+    fn mark(&mut self, cells: impl IntoIterator<Item = (usize, usize)>) {
+        self.mark_spans(
+            cells
+                .into_iter()
+                .map(|(row, column)| (row, column..=column)),
+        );
+    }
+
+    /// Marks every cell as changed, for accesses which can't be tracked per cell.
+    fn mark_all(&mut self) {
+        self.version += 1;
+        self.all = self.version;
+        if let Some(rows) = &mut self.rows {
+            // Older changes are all covered now.
+            rows.fill(RowChange::default());
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct InnerIllusionTable<const H: usize, const W: usize, P>
 where
     P: Default,
 {
     inner: IllusionArray<H, IllusionArray<W, P>>,
     default: P,
+    changes: IllusionChanges,
+    /// Makes an empty row having the default value of the table.
+    ///
+    /// It's picked by the constructor, so adding rows doesn't need `P: Clone` unless the
+    /// default value is a custom one.
+    new_row: fn(&P, IllusionBackend) -> IllusionItem<IllusionArray<W, P>>,
 }
 
+impl<const H: usize, const W: usize, P: Default> Default for InnerIllusionTable<H, W, P> {
+    fn default() -> Self {
+        Self {
+            inner: Default::default(),
+            default: Default::default(),
+            changes: Default::default(),
+            new_row: default_row,
+        }
+    }
+}
+
+/// Tables are equal if they have the same items, no matter what their change history is.
+impl<const H: usize, const W: usize, P: Default + PartialEq> PartialEq
+    for InnerIllusionTable<H, W, P>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner && self.default == other.default
+    }
+}
+
+impl<const H: usize, const W: usize, P: Default + Eq> Eq for InnerIllusionTable<H, W, P> {}
+
 impl<const H: usize, const W: usize, P> InnerIllusionTable<H, W, P>
 where
     P: Default,
@@ -25,26 +217,134 @@ where
             return None;
         }
 
-        if let Some(row) = self.inner.inner.inner.get(&row) {
-            if let Some(item) = row.value.inner.get(column) {
-                return Some(item);
-            }
-        }
+        self.find_raw((row, column)).or(Some(&self.default))
+    }
 
-        return Some(&self.default);
+    fn find_raw(&self, (row, column): (usize, usize)) -> Option<&P> {
+        let row = self.inner.inner.inner.get(&row)?;
+        row.value.inner.inner.get(&column).map(|item| &item.value)
     }
 
     fn find_raw_mut(&mut self, (row, column): (usize, usize)) -> Option<&mut P> {
-        if let Some(row) = self.inner.inner.inner.get_mut(&row) {
-            if let Some(item) = row.value.inner.inner.get_mut(&column) {
-                return Some(&mut item.value);
-            }
+        let row = self.inner.inner.inner.get_mut(&row)?;
+        row.value
+            .inner
+            .inner
+            .get_mut(&column)
+            .map(|item| &mut item.value)
+    }
+
+    /// Items of the stored row, an empty row is stored first if there's none.
+    fn row_mut_or_insert(&mut self, row: usize) -> &mut InnerIllusionArray<W, P> {
+        let backend = self.inner.backend();
+        let (rows, default, new_row) = (&mut self.inner.inner.inner, &self.default, self.new_row);
+        &mut rows
+            .get_or_insert_with(row, || new_row(default, backend))
+            .value
+            .inner
+    }
+
+    /// Takes out the stored items of the row, leaving the row empty.
+    #[cfg(feature = "rayon")]
+    fn take_row(&mut self, row: usize) -> Option<InnerIllusionArray<W, P>> {
+        self.inner
+            .inner
+            .inner
+            .remove(&row)
+            .map(|items| items.value.inner)
+    }
+
+    /// Stores the items as the row, the row is dropped if there's no item.
+    #[cfg(feature = "rayon")]
+    fn put_row(&mut self, row: usize, items: InnerIllusionArray<W, P>) {
+        if items.inner.is_empty() {
+            self.inner.inner.inner.remove(&row);
+        } else {
+            self.inner.inner.inner.insert(
+                row,
+                IllusionItem {
+                    value: IllusionArray { inner: items },
+                },
+            );
+        }
+    }
+
+    /// Stores the value at given index as is, even if it's the default value.
+    fn insert_raw(&mut self, (row, column): (usize, usize), value: P) {
+        self.row_mut_or_insert(row)
+            .inner
+            .insert(column, IllusionItem { value });
+    }
+
+    /// Takes out the stored value at given index, the row is dropped if it's left empty.
+    fn remove_raw(&mut self, (row, column): (usize, usize)) -> Option<P> {
+        let rows = &mut self.inner.inner.inner;
+        let items = &mut rows.get_mut(&row)?.value.inner.inner;
+        let item = items.remove(&column)?;
+        if items.is_empty() {
+            rows.remove(&row);
         }
+        Some(item.value)
+    }
 
-        return None;
+    /// Stores the value at given index, or removes the stored one if it's the default value.
+    fn set(&mut self, (row, column): (usize, usize), value: P)
+    where
+        P: PartialEq + Clone,
+    {
+        if self.get((row, column)) == Some(&value) {
+            return;
+        }
+        self.changes.mark([(row, column)]);
+
+        if value == self.default {
+            self.remove_raw((row, column));
+        } else {
+            self.row_mut_or_insert(row).set(column, value);
+        }
+    }
+}
+
+/// Occupancy and storage cost of an [`IllusionTable`], see [`IllusionTable::stats`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IllusionTableStats {
+    /// Count of real items in each row, from top to bottom.
+    pub filled_per_row: Vec<usize>,
+    /// Count of all real items.
+    pub filled_len: usize,
+    /// Count of all cells, real or not (`H` * `W`).
+    pub cells: usize,
+    /// Count of rows having any real item (only these rows are stored).
+    pub stored_rows: usize,
+    /// Approximate count of bytes used by the table, including stored rows and items
+    /// (and empty slots of the dense backend, and tracked changes).
+    ///
+    /// It ignores the overhead of the underlying maps, so the real cost is a bit higher.
+    pub approx_bytes: usize,
+}
+
+impl IllusionTableStats {
+    /// Ratio of real items to all cells of the table, between `0.0` and `1.0`.
+    pub fn occupancy(&self) -> f64 {
+        if self.cells == 0 {
+            0.
+        } else {
+            self.filled_len as f64 / self.cells as f64
+        }
     }
 }
 
+/// A horizontal span of `length` real items with the same value, starting at `(row, column)`.
+///
+/// See [`IllusionTable::to_rle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IllusionRun<P> {
+    pub row: usize,
+    pub column: usize,
+    pub length: usize,
+    pub value: P,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct IllusionTable<const H: usize, const W: usize, P>
 where
@@ -57,24 +357,136 @@ impl<const H: usize, const W: usize, P> IllusionTable<H, W, P>
 where
     P: Default,
 {
+    /// Creates an empty table where every item is `default` instead of `P::default()`.
+    ///
+    /// Items equal to `default` are not stored, so e.g. a black background costs nothing.
+    pub fn with_default(default: P) -> Self
+    where
+        P: Clone,
+    {
+        Self::with_backend(default, IllusionBackend::Sparse)
+    }
+
+    /// Creates an empty table where every item is `default`, using the given backend for
+    /// all of its rows.
+    pub fn with_backend(default: P, backend: IllusionBackend) -> Self
+    where
+        P: Clone,
+    {
+        Self {
+            inner: InnerIllusionTable {
+                inner: IllusionArray::with_backend(
+                    IllusionArray::with_backend(default.clone(), backend),
+                    backend,
+                ),
+                default,
+                changes: Default::default(),
+                new_row: empty_row,
+            },
+        }
+    }
+
+    pub fn backend(&self) -> IllusionBackend {
+        self.inner.inner.backend()
+    }
+
+    /// Count of changes made to this table so far, it only grows.
+    ///
+    /// Keep it and pass it to [`IllusionTable::dirty_since`] later, to find out what changed.
+    pub fn version(&self) -> u64 {
+        self.inner.changes.version
+    }
+
+    /// Starts tracking which items are changed, see [`IllusionTable::dirty_since`].
+    ///
+    /// It takes a few words per row (even for a sparse table), so it's off by default.
+    /// Changes made before tracking started count as changes of every item.
+    pub fn track_changes(&mut self) {
+        let changes = &mut self.inner.changes;
+        if changes.rows.is_none() {
+            changes.rows = Some(vec![RowChange::default(); H]);
+            changes.all = changes.version;
+        }
+    }
+
+    /// Indexes of items changed after the `version`, in row-major order.
+    ///
+    /// Only a few changed items are kept for each row, past that (or after a change of a wide
+    /// span) the whole row counts as changed since the version of the oldest merged change.
+    /// Items borrowed mutably count as changed, even if they're left the same.
+    ///
+    /// Every item counts as changed if changes are not tracked (see
+    /// [`IllusionTable::track_changes`]), or after accesses which can't be tracked per item
+    /// (like [`IllusionTable::inner_mut`]).
+    pub fn dirty_since(&self, version: u64) -> Vec<(usize, usize)> {
+        let changes = &self.inner.changes;
+        let all_changed = match &changes.rows {
+            Some(_) => changes.all > version,
+            None => changes.version > version,
+        };
+        if all_changed {
+            return (0..H)
+                .flat_map(|row| (0..W).map(move |column| (row, column)))
+                .collect();
+        }
+
+        changes
+            .rows
+            .iter()
+            .flatten()
+            .enumerate()
+            .flat_map(|(row, change)| {
+                change
+                    .columns_since(version, W)
+                    .into_iter()
+                    .map(move |column| (row, column))
+            })
+            .collect()
+    }
+
+    /// The value of items which are not stored.
+    pub fn default_value(&self) -> &P {
+        &self.inner.default
+    }
+
     pub fn filled_len(&self) -> usize {
         self.inner.filled_len()
     }
 
-    pub fn iter(&self) -> IllusionTableIter<H, W, P> {
+    pub fn iter(&self) -> IllusionTableIter<'_, H, W, P> {
         IllusionTableIter {
             inner: &self.inner,
             curr_index: (0, 0),
         }
     }
 
-    pub fn iter_mut(&mut self) -> IllusionTableIterMut<H, W, P> {
+    /// Handles of all items, row by row, which can be collected and used at the same time.
+    ///
+    /// Each handle works on its own copy of the item and writes it back when dropped, prefer
+    /// [`IllusionTable::for_each_mut`] if the handles are only used one by one.
+    pub fn iter_mut(&mut self) -> IllusionTableIterMut<'_, H, W, P> {
         IllusionTableIterMut {
-            inner: &mut self.inner,
+            inner: Rc::new(RefCell::new(&mut self.inner)),
             curr_index: (0, 0),
         }
     }
 
+    /// Calls `f` on handle of every item, row by row.
+    ///
+    /// Unlike [`IllusionTable::iter_mut`], stored items are modified in place.
+    pub fn for_each_mut(&mut self, mut f: impl FnMut(IllusionArray2DHandleMut<'_, H, W, P>))
+    where
+        P: Clone + PartialEq,
+    {
+        for row in 0..H {
+            for column in 0..W {
+                f(self
+                    .get_mut((row, column))
+                    .expect("Index of IllusionTable::for_each_mut should be in bounds."));
+            }
+        }
+    }
+
     pub fn get(&self, (row, column): (usize, usize)) -> Option<IllusionArray2DHandle<'_, H, W, P>> {
         // Return none if the index is out of bounds
         if column >= W || row >= H {
@@ -100,14 +512,19 @@ where
             return None;
         }
 
-        let current = Some(Default::default());
+        let current = Some(self.inner.default.clone());
         Some(IllusionArray2DHandleMut {
-            inner: &mut self.inner,
+            inner: HandleInner::Unique(&mut self.inner),
             index: (row, column),
             current,
         })
     }
 
+    /// A plain reference to the item, which outlives handles of [`IllusionTable::get`].
+    pub fn item(&self, index: (usize, usize)) -> Option<&P> {
+        self.inner.get(index)
+    }
+
     pub fn try_modify(
         &mut self,
         (row, column): (usize, usize),
@@ -138,39 +555,470 @@ where
     where
         P: std::cmp::PartialEq + std::clone::Clone,
     {
+        if row < H && W > 0 {
+            self.inner.changes.mark_spans([(row, 0..=W - 1)]);
+        }
         self.inner.inner.get_mut(row)
     }
 
+    pub fn get_column(&self, column: usize) -> Option<IllusionColumnHandle<'_, H, W, P>> {
+        // Return none if the index is out of bounds
+        if column >= W {
+            return None;
+        }
+
+        Some(IllusionColumnHandle {
+            inner: &self.inner,
+            column,
+        })
+    }
+
+    /// Handles of a column from top to bottom, empty if the column is out of bounds.
+    pub fn iter_column(
+        &self,
+        column: usize,
+    ) -> impl Iterator<Item = IllusionArray2DHandle<'_, H, W, P>> {
+        self.get_column(column)
+            .into_iter()
+            .flat_map(|column| column.iter())
+    }
+
+    /// Handles of all columns, from left to right.
+    pub fn iter_columns(&self) -> impl Iterator<Item = IllusionColumnHandle<'_, H, W, P>> {
+        (0..W).map(|column| IllusionColumnHandle {
+            inner: &self.inner,
+            column,
+        })
+    }
+
     pub fn inner(&self) -> &IllusionArray<H, IllusionArray<W, P>> {
         &self.inner.inner
     }
 
+    /// Every item counts as changed afterwards, see [`IllusionTable::dirty_since`].
     pub fn inner_mut(&mut self) -> &mut IllusionArray<H, IllusionArray<W, P>> {
+        self.inner.changes.mark_all();
         &mut self.inner.inner
     }
 
-    pub fn real_items(&self) -> impl Iterator<Item = ((&usize, &usize), &P)> {
-        self.inner
-            .inner
+    /// Items which are actually stored (not the default value).
+    ///
+    /// They're in row-major order with the `ordered` feature, otherwise in an arbitrary order.
+    pub fn real_items(&self) -> impl Iterator<Item = ((usize, usize), &P)> {
+        self.inner.inner.real_items().flat_map(|(row, items)| {
+            items
+                .real_items()
+                .map(move |(column, item)| ((row, column), item))
+        })
+    }
+
+    pub fn real_items_mut(&mut self) -> impl Iterator<Item = ((usize, usize), &mut P)> {
+        let real: Vec<_> = self
             .real_items()
-            .map(|(row, items)| {
+            .map(|((row, column), _)| (row, column))
+            .collect();
+        self.inner.changes.mark(real);
+
+        self.inner.inner.real_items_mut().flat_map(|(row, items)| {
+            items
+                .real_items_mut()
+                .map(move |(column, item)| ((row, column), item))
+        })
+    }
+
+    /// Like [`IllusionTable::real_items`], but only items from `top_left` to `bottom_right`
+    /// (both included).
+    ///
+    /// Only rows of the region are looked up, other rows are not visited at all.
+    pub fn real_items_in(
+        &self,
+        (top, left): (usize, usize),
+        (bottom, right): (usize, usize),
+    ) -> impl Iterator<Item = ((usize, usize), &P)> {
+        let rows = &self.inner.inner.inner.inner;
+        (top..=bottom.min(H.saturating_sub(1)))
+            .filter_map(|row| rows.get(&row).map(|items| (row, items)))
+            .flat_map(move |(row, items)| {
                 items
+                    .value
                     .real_items()
+                    .filter(move |(column, _)| (left..=right).contains(column))
                     .map(move |(column, item)| ((row, column), item))
             })
-            .flatten()
     }
 
-    pub fn real_items_mut(&mut self) -> impl Iterator<Item = ((&usize, &usize), &mut P)> {
-        self.inner
+    /// Counts real items of each row and estimates the memory they take.
+    pub fn stats(&self) -> IllusionTableStats {
+        let rows = &self.inner.inner.inner.inner;
+        let filled_per_row: Vec<_> = (0..H)
+            .map(|row| rows.get(&row).map_or(0, |row| row.value.filled_len()))
+            .collect();
+        let filled_len = filled_per_row.iter().sum();
+
+        let stored_rows = rows.len();
+        let items_bytes: usize = rows
+            .iter()
+            .map(|(_, row)| row.value.inner.inner.approx_bytes(row.value.filled_len()))
+            .sum();
+        let changes_size = self
             .inner
-            .real_items_mut()
+            .changes
+            .rows
+            .as_ref()
+            .map_or(0, |rows| rows.len() * std::mem::size_of::<RowChange>());
+        IllusionTableStats {
+            filled_len,
+            cells: H * W,
+            stored_rows,
+            approx_bytes: std::mem::size_of::<Self>()
+                + rows.approx_bytes(stored_rows)
+                + items_bytes
+                + changes_size,
+            filled_per_row,
+        }
+    }
+
+    /// Parallel version of [`IllusionTable::iter`], row by row (`rayon` feature).
+    #[cfg(feature = "rayon")]
+    pub fn par_iter(
+        &self,
+    ) -> impl rayon::iter::IndexedParallelIterator<Item = IllusionArray2DHandle<'_, H, W, P>>
+    where
+        P: Sync,
+    {
+        use rayon::prelude::*;
+
+        let inner = &self.inner;
+        (0..H * W)
+            .into_par_iter()
+            .map(move |index| IllusionArray2DHandle {
+                inner,
+                index: (index / W, index % W),
+            })
+    }
+
+    /// Calls `f` on every item (and its `(row, column)`), rows are processed in parallel
+    /// (`rayon` feature).
+    ///
+    /// Mut handles borrow the whole table, so a closure is used instead of a parallel iterator.
+    /// Items equal to the default value are not kept afterwards.
+    #[cfg(feature = "rayon")]
+    pub fn par_for_each_mut<F>(&mut self, f: F)
+    where
+        P: Clone + PartialEq + Send + Sync,
+        F: Fn((usize, usize), &mut P) + Sync,
+    {
+        use rayon::prelude::*;
+
+        self.inner.changes.mark_all();
+        let taken: Vec<_> = (0..H).map(|row| self.inner.take_row(row)).collect();
+
+        let backend = self.backend();
+        let (default, new_row) = (&self.inner.default, self.inner.new_row);
+        let updated: Vec<_> = taken
+            .into_par_iter()
+            .enumerate()
             .map(|(row, items)| {
+                let mut items = items.unwrap_or_else(|| new_row(default, backend).value.inner);
+                for column in 0..W {
+                    items.modify(column, |value| f((row, column), value));
+                }
                 items
-                    .real_items_mut()
-                    .map(move |(column, item)| ((row, column), item))
             })
-            .flatten()
+            .collect();
+
+        for (row, items) in updated.into_iter().enumerate() {
+            self.inner.put_row(row, items);
+        }
+    }
+
+    /// Swaps rows and columns, only real items are moved to their new keys.
+    pub fn transposed(&self) -> IllusionTable<W, H, P>
+    where
+        P: Clone,
+    {
+        let backend = self.backend();
+        let mut result =
+            IllusionTable::<W, H, P>::with_backend(self.inner.default.clone(), backend);
+        for ((row, column), item) in self.real_items() {
+            result.inner.insert_raw((column, row), item.clone());
+        }
+        result
+    }
+
+    /// Run-length encodes real items of each row, row by row and from left to right.
+    ///
+    /// Default items aren't included, so the result is as sparse as the table itself.
+    /// Use [`IllusionTable::from_rle`] to get the table back.
+    pub fn to_rle(&self) -> Vec<IllusionRun<P>>
+    where
+        P: Clone + PartialEq,
+    {
+        let mut rows: Vec<_> = self.inner.inner.real_items().collect();
+        rows.sort_unstable_by_key(|(row, _)| *row);
+
+        let mut runs = Vec::<IllusionRun<P>>::new();
+        for (row, items) in rows {
+            let mut items: Vec<_> = items.real_items().collect();
+            items.sort_unstable_by_key(|(column, _)| *column);
+
+            for (column, value) in items {
+                match runs.last_mut() {
+                    Some(run)
+                        if run.row == row
+                            && run.column + run.length == column
+                            && run.value == *value =>
+                    {
+                        run.length += 1
+                    }
+                    _ => runs.push(IllusionRun {
+                        row,
+                        column,
+                        length: 1,
+                        value: value.clone(),
+                    }),
+                }
+            }
+        }
+        runs
+    }
+
+    /// Builds a table from runs made by [`IllusionTable::to_rle`], where every other item is
+    /// `default`.
+    ///
+    /// Runs leave out items having the default value, so pass the
+    /// [`default_value`](IllusionTable::default_value) of the encoded table.
+    /// Later runs overwrite earlier ones, parts of the runs out of bounds are ignored.
+    pub fn from_rle(default: P, runs: impl IntoIterator<Item = IllusionRun<P>>) -> Self
+    where
+        P: Clone + PartialEq,
+    {
+        Self::from_rle_with_backend(default, runs, IllusionBackend::Sparse)
+    }
+
+    /// As same as [`IllusionTable::from_rle`], using the given backend.
+    pub fn from_rle_with_backend(
+        default: P,
+        runs: impl IntoIterator<Item = IllusionRun<P>>,
+        backend: IllusionBackend,
+    ) -> Self
+    where
+        P: Clone + PartialEq,
+    {
+        let mut table = Self::with_backend(default, backend);
+        for run in runs.into_iter().filter(|run| run.length > 0) {
+            table.set_region(
+                (run.row, run.column),
+                (run.row, run.column + run.length - 1),
+                run.value,
+            );
+        }
+        table
+    }
+
+    /// Sets every item from `top_left` to `bottom_right` (both included) to the `value`.
+    ///
+    /// Parts of the region out of bounds are ignored. Rows are written at once, instead of
+    /// creating a mut handle per item.
+    pub fn set_region(
+        &mut self,
+        (top, left): (usize, usize),
+        (bottom, right): (usize, usize),
+        value: P,
+    ) where
+        P: Clone + PartialEq,
+    {
+        let (bottom, right) = (bottom.min(H - 1), right.min(W - 1));
+        if top > bottom || left > right {
+            return;
+        }
+
+        if value == self.inner.default {
+            // Default items are not stored, just drop whatever is there.
+            self.clear_region((top, left), (bottom, right));
+            return;
+        }
+
+        self.inner
+            .changes
+            .mark_spans((top..=bottom).map(|row| (row, left..=right)));
+        for row in top..=bottom {
+            self.inner
+                .row_mut_or_insert(row)
+                .inner
+                .extend((left..=right).map(|column| {
+                    (
+                        column,
+                        IllusionItem {
+                            value: value.clone(),
+                        },
+                    )
+                }));
+        }
+    }
+
+    /// Resets every item from `top_left` to `bottom_right` (both included) to the default value,
+    /// by dropping them. Rows left empty are dropped too.
+    pub fn clear_region(&mut self, (top, left): (usize, usize), (bottom, right): (usize, usize)) {
+        let mut removed = Vec::new();
+        let rows = &mut self.inner.inner.inner.inner;
+        for row in top..=bottom.min(H.saturating_sub(1)) {
+            if let Some(items) = rows.get_mut(&row) {
+                let cells = &mut items.value.inner.inner;
+                cells.retain(|column, _| {
+                    let keep = !(left..=right).contains(column);
+                    if !keep {
+                        removed.push((row, *column));
+                    }
+                    keep
+                });
+                if cells.is_empty() {
+                    rows.remove(&row);
+                }
+            }
+        }
+        self.inner.changes.mark(removed);
+    }
+
+    /// Keeps only real items for which `f` returns `true`, the others are reset to the
+    /// default value. Rows left empty are dropped too.
+    pub fn retain(&mut self, mut f: impl FnMut((usize, usize), &P) -> bool) {
+        let mut removed = Vec::new();
+        self.inner.inner.inner.inner.retain(|row, items| {
+            let cells = &mut items.value.inner.inner;
+            cells.retain(|column, item| {
+                let keep = f((*row, *column), &item.value);
+                if !keep {
+                    removed.push((*row, *column));
+                }
+                keep
+            });
+            !cells.is_empty()
+        });
+        self.inner.changes.mark(removed);
+    }
+
+    /// Copies real items of `other` from `top_left` to `bottom_right` (both included) into
+    /// this table, so that `top_left` lands on `destination`.
+    ///
+    /// Tables may have any size, items falling out of bounds of either table are ignored.
+    /// Items which are not stored in `other` leave this table untouched.
+    pub fn copy_from<const OH: usize, const OW: usize>(
+        &mut self,
+        other: &IllusionTable<OH, OW, P>,
+        top_left: (usize, usize),
+        bottom_right: (usize, usize),
+        destination: (usize, usize),
+    ) where
+        P: Clone + PartialEq,
+    {
+        self.copy_from_with(other, top_left, bottom_right, |index, value| {
+            Self::moved_index(index, top_left, destination).map(|index| (index, value.clone()))
+        });
+    }
+
+    /// The general form of [`IllusionTable::copy_from`]: `place` gets every real item of
+    /// `other` from `top_left` to `bottom_right` (both included) with its index, and returns
+    /// where to put it in this table and as what, or none to skip it.
+    ///
+    /// Places out of bounds are ignored.
+    pub fn copy_from_with<const OH: usize, const OW: usize, Q>(
+        &mut self,
+        other: &IllusionTable<OH, OW, Q>,
+        top_left: (usize, usize),
+        bottom_right: (usize, usize),
+        mut place: impl FnMut((usize, usize), &Q) -> Option<((usize, usize), P)>,
+    ) where
+        P: Clone + PartialEq,
+        Q: Default,
+    {
+        for ((row, column), value) in other.real_items_in(top_left, bottom_right) {
+            match place((row, column), value) {
+                Some(((row, column), value)) if row < H && column < W => {
+                    self.inner.set((row, column), value)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Like [`IllusionTable::copy_from`], but copied items are also reset to the default value
+    /// in `other`.
+    pub fn move_from<const OH: usize, const OW: usize>(
+        &mut self,
+        other: &mut IllusionTable<OH, OW, P>,
+        top_left: (usize, usize),
+        bottom_right: (usize, usize),
+        destination: (usize, usize),
+    ) where
+        P: Clone + PartialEq,
+    {
+        self.copy_from(other, top_left, bottom_right, destination);
+
+        let ((top, left), (bottom, right)) = (top_left, bottom_right);
+        other.retain(|(row, column), _| {
+            !((top..=bottom).contains(&row)
+                && (left..=right).contains(&column)
+                && Self::moved_index((row, column), top_left, destination).is_some())
+        });
+    }
+
+    /// Where an item at `index` of a region starting at `top_left` lands, if it's in bounds.
+    fn moved_index(
+        (row, column): (usize, usize),
+        (top, left): (usize, usize),
+        (dst_row, dst_column): (usize, usize),
+    ) -> Option<(usize, usize)> {
+        let (row, column) = (row - top + dst_row, column - left + dst_column);
+        (row < H && column < W).then_some((row, column))
+    }
+
+    /// Swaps two whole rows, by moving their stored rows instead of items one by one.
+    ///
+    /// Rows out of bounds are ignored.
+    pub fn swap_rows(&mut self, a: usize, b: usize) {
+        if a == b || a >= H || b >= H {
+            return;
+        }
+
+        let rows = &mut self.inner.inner.inner.inner;
+        let (row_a, row_b) = (rows.remove(&a), rows.remove(&b));
+        if row_a.is_some() || row_b.is_some() {
+            self.inner
+                .changes
+                .mark_spans([(a, 0..=W - 1), (b, 0..=W - 1)]);
+        }
+        if let Some(row) = row_a {
+            rows.insert(b, row);
+        }
+        if let Some(row) = row_b {
+            rows.insert(a, row);
+        }
+    }
+
+    /// Swaps two whole columns, only stored rows are visited.
+    ///
+    /// Columns out of bounds are ignored.
+    pub fn swap_columns(&mut self, a: usize, b: usize) {
+        if a == b || a >= W || b >= W {
+            return;
+        }
+
+        let mut changed = Vec::new();
+        for (index, row) in self.inner.inner.inner.inner.iter_mut() {
+            let cells = &mut row.value.inner.inner;
+            let (item_a, item_b) = (cells.remove(&a), cells.remove(&b));
+            if item_a.is_some() || item_b.is_some() {
+                changed.extend([(index, a), (index, b)]);
+            }
+            if let Some(item) = item_a {
+                cells.insert(b, item);
+            }
+            if let Some(item) = item_b {
+                cells.insert(a, item);
+            }
+        }
+        self.inner.changes.mark(changed);
     }
 
     pub fn swap(&mut self, a: (usize, usize), b: (usize, usize)) {
@@ -180,16 +1028,13 @@ where
 
         let a_exists = self.inner.find_raw_mut(a).is_some();
         let b_exists = self.inner.find_raw_mut(b).is_some();
+        if a_exists || b_exists {
+            self.inner.changes.mark([a, b]);
+        }
 
         let mut replace = |a: (usize, usize), b: (usize, usize)| {
-            let row = self.inner.inner.inner.inner.get_mut(&a.0).unwrap();
-            let item = row.value.inner.inner.remove(&a.1).unwrap();
-            if row.value.inner.inner.is_empty() {
-                self.inner.inner.inner.inner.remove(&a.0);
-            }
-
-            let entry = self.inner.inner.inner.inner.entry(b.0);
-            entry.or_default().value.inner.inner.insert(b.1, item);
+            let item = self.inner.remove_raw(a).unwrap();
+            self.inner.insert_raw(b, item);
         };
 
         if a_exists && b_exists {
@@ -256,6 +1101,77 @@ where
     }
 }
 
+/// A column of an [`IllusionTable`], the vertical counterpart of a row handle.
+pub struct IllusionColumnHandle<'a, const H: usize, const W: usize, P>
+where
+    P: Default,
+{
+    inner: &'a InnerIllusionTable<H, W, P>,
+    column: usize,
+}
+
+impl<'a, const H: usize, const W: usize, P> Clone for IllusionColumnHandle<'a, H, W, P>
+where
+    P: Default,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, const H: usize, const W: usize, P> Copy for IllusionColumnHandle<'a, H, W, P> where
+    P: Default
+{
+}
+
+impl<'a, const H: usize, const W: usize, P> IllusionColumnHandle<'a, H, W, P>
+where
+    P: Default,
+{
+    pub fn index(&self) -> usize {
+        self.column
+    }
+
+    /// Returns the handle at given row, none if it's out of bounds.
+    pub fn get(&self, row: usize) -> Option<IllusionArray2DHandle<'a, H, W, P>> {
+        (row < H).then_some(IllusionArray2DHandle {
+            inner: self.inner,
+            index: (row, self.column),
+        })
+    }
+
+    /// Handles of this column, from top to bottom.
+    pub fn iter(&self) -> impl Iterator<Item = IllusionArray2DHandle<'a, H, W, P>> {
+        let (inner, column) = (self.inner, self.column);
+        (0..H).map(move |row| IllusionArray2DHandle {
+            inner,
+            index: (row, column),
+        })
+    }
+
+    /// Actual existing items of this column with their rows, in no particular order.
+    ///
+    /// Only rows having any real item are visited.
+    pub fn real_items(&self) -> impl Iterator<Item = (usize, &'a P)> {
+        let column = self.column;
+        self.inner
+            .inner
+            .real_items()
+            .filter_map(move |(row, items)| {
+                items
+                    .inner
+                    .inner
+                    .get(&column)
+                    .map(|item| (row, &item.value))
+            })
+    }
+
+    /// Count of real items in this column.
+    pub fn filled_len(&self) -> usize {
+        self.real_items().count()
+    }
+}
+
 pub struct IllusionArray2DHandle<'a, const H: usize, const W: usize, P>
 where
     P: Default,
@@ -314,7 +1230,7 @@ pub struct IllusionTableIterMut<'a, const H: usize, const W: usize, P>
 where
     P: Default,
 {
-    inner: &'a mut InnerIllusionTable<H, W, P>,
+    inner: Rc<RefCell<&'a mut InnerIllusionTable<H, W, P>>>,
     curr_index: (usize, usize), // Represents the current index of the 2d array (row, col)
 }
 
@@ -328,10 +1244,11 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         // Start from first row and first column (0, 0) to last row and last column (H, W)
         if self.curr_index.0 < H {
+            let current = self.inner.borrow().get(self.curr_index).cloned();
             let handle = IllusionArray2DHandleMut {
-                inner: unsafe { &mut *(self.inner as *mut _) },
+                inner: HandleInner::Shared(self.inner.clone()),
                 index: self.curr_index,
-                current: Some(P::default()),
+                current,
             };
 
             // Increment the column index
@@ -354,7 +1271,7 @@ pub struct IllusionArray2DHandleMut<'a, const H: usize, const W: usize, P>
 where
     P: std::cmp::PartialEq + std::clone::Clone + Default,
 {
-    inner: &'a mut InnerIllusionTable<H, W, P>,
+    inner: HandleInner<'a, InnerIllusionTable<H, W, P>>,
     index: (usize, usize),
     current: Option<P>,
 }
@@ -398,46 +1315,25 @@ where
     P: std::cmp::PartialEq + std::clone::Clone + Default,
 {
     fn drop(&mut self) {
-        if let Some(current) = self.current.take() {
-            if &current != &self.inner.default {
-                if let Some(row) = self.inner.inner.inner.inner.get_mut(&self.index.0) {
-                    if let Some(item) = row.value.inner.inner.get_mut(&self.index.1) {
-                        item.value = current;
-                    } else {
-                        // Row exists but item doesn't
-                        // Add a new item
-                        row.value
-                            .inner
-                            .inner
-                            .insert(self.index.1, IllusionItem { value: current });
-                    }
-                } else {
-                    // Row doesn't exist
-                    // Add a new row and item
-                    let mut row = IllusionArray::<W, P>::default();
-                    row.inner
-                        .inner
-                        .insert(self.index.1, IllusionItem { value: current });
-                    self.inner
-                        .inner
-                        .inner
-                        .inner
-                        .insert(self.index.0, IllusionItem { value: row });
+        let inner = match &mut self.inner {
+            HandleInner::Unique(inner) => inner,
+            HandleInner::Shared(inner) => {
+                if let Some(current) = self.current.take() {
+                    inner.borrow_mut().set(self.index, current);
                 }
+                return;
+            }
+        };
+
+        if let Some(current) = self.current.take() {
+            if current != inner.default {
+                inner.insert_raw(self.index, current);
             }
         }
 
         // remove old since it's now default.
-        if let Some(row) = self.inner.inner.inner.inner.get_mut(&self.index.0) {
-            if let Some(item) = row.value.inner.inner.get(&self.index.1) {
-                if item.value == self.inner.default {
-                    row.value.inner.inner.remove(&self.index.1);
-                    if row.value.inner.inner.is_empty() {
-                        // If an entity is remove and there're no others, clean up.
-                        self.inner.inner.inner.inner.remove(&self.index.0);
-                    }
-                }
-            }
+        if inner.find_raw(self.index) == Some(&inner.default) {
+            inner.remove_raw(self.index);
         }
     }
 }
@@ -447,9 +1343,15 @@ where
     P: std::cmp::PartialEq + std::clone::Clone + Default,
 {
     pub fn get(&self) -> &P {
-        self.inner
-            .get(self.index)
-            .expect("IllusionArray2DHandle's Row index should be in bounds.")
+        match &self.inner {
+            HandleInner::Unique(inner) => inner
+                .get(self.index)
+                .expect("IllusionArray2DHandle's Row index should be in bounds."),
+            HandleInner::Shared(_) => self
+                .current
+                .as_ref()
+                .expect("IllusionArray2DHandleMut should have its item."),
+        }
     }
 
     pub fn mapped<F, T>(&self, f: F) -> T
@@ -463,18 +1365,16 @@ where
     where
         P: PartialEq + Clone,
     {
-        // What are these inners anyway?
-        if let Some(row) = self.inner.inner.inner.inner.get_mut(&self.index.0) {
-            let item = row
-                .value
-                .inner
-                .inner
-                .get_mut(&self.index.1)
-                .map(|item| &mut item.value)
-                .unwrap_or(self.current.as_mut().unwrap());
-            item
-        } else {
-            self.current.as_mut().unwrap()
+        let inner = match &mut self.inner {
+            HandleInner::Unique(inner) => inner,
+            HandleInner::Shared(_) => return self.current.as_mut().unwrap(),
+        };
+        // The item is modified in place, so it's marked as soon as it's borrowed.
+        inner.changes.mark([self.index]);
+
+        match inner.find_raw_mut(self.index) {
+            Some(item) => item,
+            None => self.current.as_mut().unwrap(),
         }
     }
 
@@ -518,4 +1418,424 @@ mod tests {
         println!("Filled len: {:?}", table.filled_len());
         println!("---");
     }
+
+    #[test]
+    fn test_transposed() {
+        let mut table = IllusionTable::<2, 3, i32>::default();
+        table.try_modify((0, 2), |v| *v.get_mut() = 7);
+        table.try_modify((1, 0), |v| *v.get_mut() = 3);
+
+        let transposed = table.transposed();
+        assert_eq!(transposed.filled_len(), 2);
+        assert_eq!(*transposed.get((2, 0)).unwrap(), 7);
+        assert_eq!(*transposed.get((0, 1)).unwrap(), 3);
+        assert_eq!(*transposed.get((1, 1)).unwrap(), 0);
+        assert_eq!(transposed.transposed(), table);
+    }
+
+    #[test]
+    fn test_columns() {
+        let mut table = IllusionTable::<3, 2, i32>::default();
+        table.try_modify((0, 1), |v| *v.get_mut() = 4);
+        table.try_modify((2, 1), |v| *v.get_mut() = 6);
+        table.try_modify((1, 0), |v| *v.get_mut() = 1);
+
+        let values: Vec<i32> = table.iter_column(1).map(|item| *item).collect();
+        assert_eq!(values, vec![4, 0, 6]);
+        assert_eq!(table.iter_column(2).count(), 0);
+
+        let column = table.get_column(1).unwrap();
+        assert_eq!(column.filled_len(), 2);
+        assert_eq!(column.get(2).map(|item| *item), Some(6));
+        assert!(column.get(3).is_none());
+        assert_eq!(
+            table
+                .iter_columns()
+                .map(|column| column.filled_len())
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut table = IllusionTable::<3, 4, i32>::default();
+        table.try_modify((0, 1), |v| *v.get_mut() = 1);
+        table.try_modify((0, 3), |v| *v.get_mut() = 2);
+        table.try_modify((2, 0), |v| *v.get_mut() = 3);
+
+        let stats = table.stats();
+        assert_eq!(stats.filled_per_row, vec![2, 0, 1]);
+        assert_eq!(stats.filled_len, 3);
+        assert_eq!(stats.stored_rows, 2);
+        assert_eq!(stats.occupancy(), 0.25);
+
+        let empty = IllusionTable::<3, 4, i32>::default().stats();
+        assert_eq!(empty.filled_len, 0);
+        assert!(empty.approx_bytes < stats.approx_bytes);
+    }
+
+    #[test]
+    fn test_set_region() {
+        let mut table = IllusionTable::<4, 5, i32>::default();
+        table.set_region((1, 1), (2, 10), 7);
+        assert_eq!(table.filled_len(), 8);
+        assert_eq!(*table.get((2, 4)).unwrap(), 7);
+        assert_eq!(*table.get((0, 1)).unwrap(), 0);
+
+        table.set_region((0, 0), (1, 2), 0);
+        assert_eq!(table.filled_len(), 6);
+        assert_eq!(*table.get((1, 3)).unwrap(), 7);
+
+        table.set_region((2, 0), (3, 4), 0);
+        assert_eq!(table.filled_len(), 2);
+        assert_eq!(table.stats().stored_rows, 1);
+
+        table.set_region((3, 3), (1, 1), 9);
+        assert_eq!(table.filled_len(), 2);
+    }
+
+    #[test]
+    fn test_rle() {
+        let mut table = IllusionTable::<3, 6, i32>::default();
+        table.set_region((0, 1), (0, 3), 5);
+        table.try_modify((0, 4), |v| *v.get_mut() = 6);
+        table.set_region((2, 0), (2, 5), 5);
+
+        let runs = table.to_rle();
+        assert_eq!(
+            runs,
+            vec![
+                IllusionRun {
+                    row: 0,
+                    column: 1,
+                    length: 3,
+                    value: 5
+                },
+                IllusionRun {
+                    row: 0,
+                    column: 4,
+                    length: 1,
+                    value: 6
+                },
+                IllusionRun {
+                    row: 2,
+                    column: 0,
+                    length: 6,
+                    value: 5
+                },
+            ]
+        );
+        assert_eq!(IllusionTable::from_rle(0, runs), table);
+        assert!(IllusionTable::<3, 6, i32>::default().to_rle().is_empty());
+    }
+
+    #[test]
+    fn test_real_items_in() {
+        let mut table = IllusionTable::<4, 4, i32>::default();
+        table.set_region((0, 0), (3, 3), 1);
+        table.try_modify((2, 2), |v| *v.get_mut() = 0);
+
+        let mut indexes: Vec<_> = table
+            .real_items_in((1, 1), (2, 8))
+            .map(|((row, column), _)| (row, column))
+            .collect();
+        indexes.sort();
+        assert_eq!(indexes, vec![(1, 1), (1, 2), (1, 3), (2, 1), (2, 3)]);
+        assert_eq!(table.real_items_in((3, 3), (2, 2)).count(), 0);
+    }
+
+    #[test]
+    fn test_swap_rows_and_columns() {
+        let mut table = IllusionTable::<3, 3, i32>::default();
+        table.try_modify((0, 0), |v| *v.get_mut() = 1);
+        table.try_modify((0, 2), |v| *v.get_mut() = 2);
+        table.try_modify((2, 1), |v| *v.get_mut() = 3);
+
+        table.swap_rows(0, 1);
+        assert_eq!(*table.get((1, 0)).unwrap(), 1);
+        assert_eq!(*table.get((1, 2)).unwrap(), 2);
+        assert_eq!(*table.get((0, 0)).unwrap(), 0);
+
+        table.swap_columns(1, 2);
+        assert_eq!(*table.get((1, 1)).unwrap(), 2);
+        assert_eq!(*table.get((2, 2)).unwrap(), 3);
+        assert_eq!(*table.get((2, 1)).unwrap(), 0);
+
+        let before = table.clone();
+        table.swap_rows(0, 3);
+        table.swap_columns(3, 0);
+        assert_eq!(table, before);
+        assert_eq!(table.filled_len(), 3);
+    }
+
+    #[test]
+    fn test_clear_region_and_retain() {
+        let mut table = IllusionTable::<4, 4, i32>::default();
+        table.set_region((0, 0), (3, 3), 1);
+        table.set_region((0, 0), (0, 3), 2);
+
+        table.clear_region((1, 0), (2, 9));
+        assert_eq!(table.filled_len(), 8);
+        assert_eq!(table.stats().stored_rows, 2);
+
+        table.retain(|(_, column), value| *value == 2 && column < 2);
+        assert_eq!(table.filled_len(), 2);
+        assert_eq!(*table.get((0, 1)).unwrap(), 2);
+        assert_eq!(table.stats().stored_rows, 1);
+
+        table.retain(|_, _| false);
+        assert_eq!(table, IllusionTable::default());
+    }
+
+    #[test]
+    fn test_copy_from() {
+        let mut source = IllusionTable::<3, 3, i32>::default();
+        source.set_region((0, 0), (2, 2), 1);
+        source.try_modify((1, 1), |v| *v.get_mut() = 2);
+
+        let mut table = IllusionTable::<4, 5, i32>::with_default(1);
+        table.copy_from(&source, (1, 0), (9, 9), (2, 3));
+        // Only 4 of 6 copied items are in bounds, and 3 of them are the default.
+        assert_eq!(table.filled_len(), 1);
+        assert_eq!(*table.get((2, 4)).unwrap(), 2);
+
+        let mut table = IllusionTable::<4, 5, i32>::default();
+        table.move_from(&mut source, (0, 0), (1, 2), (3, 3));
+        assert_eq!(table.real_items().count(), 2);
+        assert_eq!(*table.get((3, 4)).unwrap(), 1);
+        assert_eq!(source.filled_len(), 7);
+        assert_eq!(*source.get((1, 1)).unwrap(), 2);
+
+        // Mirror the items and keep only the odd ones, as strings.
+        let mut table = IllusionTable::<3, 3, String>::default();
+        table.copy_from_with(&source, (0, 0), (2, 2), |(row, column), value| {
+            (value % 2 == 1).then(|| ((row, 2 - column), value.to_string()))
+        });
+        assert_eq!(table.filled_len(), 6);
+        assert_eq!(*table.get((0, 0)).unwrap(), "1");
+        assert_eq!(*table.get((1, 1)).unwrap(), "");
+    }
+
+    #[test]
+    fn test_version() {
+        let mut table = IllusionTable::<3, 4, i32>::default();
+        table.track_changes();
+        assert_eq!(table.version(), 0);
+
+        table.set_region((0, 0), (0, 1), 1);
+        let version = table.version();
+        assert_eq!(version, 1);
+        assert_eq!(table.dirty_since(0), vec![(0, 0), (0, 1)]);
+        assert!(table.dirty_since(version).is_empty());
+
+        // Handles which don't change their item aren't counted.
+        for mut item in table.iter_mut() {
+            if item.index() == (2, 3) {
+                *item = 7;
+            }
+        }
+        table.swap((0, 1), (1, 1));
+        table.clear_region((2, 0), (2, 2));
+        assert_eq!(table.dirty_since(version), vec![(0, 1), (1, 1), (2, 3)]);
+        assert_eq!(table.version(), version + 2);
+
+        let version = table.version();
+        table.inner_mut();
+        assert!(table.version() > version);
+        assert_eq!(table.dirty_since(version).len(), 12);
+        assert_eq!(table, table.clone().transposed().transposed());
+    }
+
+    #[test]
+    fn test_older_changes_are_not_reported() {
+        let mut table = IllusionTable::<2, 10, i32>::default();
+        table.track_changes();
+        table.set_region((0, 0), (0, 9), 1);
+        for column in 0..TRACKED_CELLS + 2 {
+            table.try_modify((1, column), |v| *v.get_mut() = 2);
+        }
+
+        let version = table.version();
+        table.try_modify((0, 5), |v| *v.get_mut() = 3);
+        table.try_modify((1, 8), |v| *v.get_mut() = 3);
+        assert_eq!(table.dirty_since(version), vec![(0, 5), (1, 8)]);
+        assert_eq!(table.dirty_since(version + 1), vec![(1, 8)]);
+
+        assert_eq!(table.dirty_since(version - 1), vec![(0, 5), (1, 5), (1, 8)]);
+
+        // Past the limit, older changes of the row are merged into the whole row.
+        assert_eq!(table.dirty_since(1).len(), 11);
+        assert_eq!(table.dirty_since(0).len(), 20);
+    }
+
+    #[test]
+    fn test_untracked_changes() {
+        let mut table = IllusionTable::<3, 4, i32>::default();
+        table.set_region((0, 0), (0, 1), 1);
+        assert_eq!(table.dirty_since(0).len(), 12);
+        assert!(table.dirty_since(table.version()).is_empty());
+
+        let version = table.version();
+        table.track_changes();
+        table.try_modify((2, 2), |v| *v.get_mut() = 3);
+        assert_eq!(table.dirty_since(version), vec![(2, 2)]);
+        assert_eq!(table.dirty_since(0).len(), 12);
+    }
+
+    #[test]
+    fn test_tracked_changes_stay_bounded() {
+        let mut table = IllusionTable::<100, 200, i32>::default();
+        table.track_changes();
+        let empty = table.stats().approx_bytes;
+
+        table.set_region((0, 0), (99, 199), 1);
+        table.clear_region((0, 0), (99, 199));
+        assert_eq!(table.filled_len(), 0);
+        assert_eq!(table.stats().approx_bytes, empty);
+        // Only a few changed cells are kept per row.
+        assert!(
+            empty
+                < IllusionTable::<100, 200, i32>::default()
+                    .stats()
+                    .approx_bytes
+                    + 100 * 96
+        );
+        assert_eq!(table.dirty_since(0).len(), 100 * 200);
+    }
+
+    #[test]
+    fn test_with_default() {
+        let mut table = IllusionTable::<2, 3, i32>::with_default(-1);
+        assert!(table.iter().all(|item| *item == -1));
+
+        for mut item in table.iter_mut() {
+            if item.index() == (1, 1) {
+                *item = 4;
+            }
+        }
+        table.try_modify((0, 0), |v| *v.get_mut() = -1);
+        assert_eq!(table.filled_len(), 1);
+        assert_eq!(*table.get_row(1).unwrap().get().get(2).unwrap(), -1);
+
+        table.swap((1, 1), (0, 2));
+        table.set_region((1, 0), (1, 0), 0);
+        let transposed = table.transposed();
+        assert_eq!(*transposed.get((2, 0)).unwrap(), 4);
+        assert_eq!(*transposed.get((1, 1)).unwrap(), -1);
+        assert_eq!(*transposed.get_row(0).unwrap().get().get(0).unwrap(), -1);
+        assert_eq!(transposed.filled_len(), 2);
+
+        let decoded = IllusionTable::from_rle(-1, table.to_rle());
+        assert_eq!(decoded, table);
+        assert_eq!(*decoded.get((0, 0)).unwrap(), -1);
+    }
+
+    #[test]
+    fn test_mut_handles() {
+        let mut table = IllusionTable::<2, 2, i32>::default();
+        table.try_modify((1, 1), |v| *v.get_mut() = 3);
+
+        // Handles may live at the same time.
+        let mut handles: Vec<_> = table.iter_mut().collect();
+        *handles[0] += 1;
+        *handles[3] += 1;
+        *handles[1] = *handles[0] + *handles[3];
+        assert_eq!(*handles[1], 5);
+        drop(handles);
+
+        assert_eq!(table.filled_len(), 3);
+        assert_eq!(*table.get((0, 1)).unwrap(), 5);
+        assert_eq!(*table.get((1, 1)).unwrap(), 4);
+
+        table.for_each_mut(|mut item| {
+            let (row, column) = item.index();
+            *item = if row == column { 0 } else { *item * 2 };
+        });
+        assert_eq!(table.filled_len(), 1);
+        assert_eq!(*table.get((0, 1)).unwrap(), 10);
+    }
+
+    #[test]
+    fn test_dense() {
+        let mut dense = IllusionTable::<3, 4, i32>::with_backend(0, IllusionBackend::Dense);
+        let mut sparse = IllusionTable::<3, 4, i32>::default();
+        for table in [&mut dense, &mut sparse] {
+            table.set_region((0, 0), (1, 3), 2);
+            table.try_modify((2, 3), |v| *v.get_mut() = 5);
+            table.clear_region((0, 1), (0, 1));
+            table.swap((2, 3), (2, 0));
+            table.swap_rows(0, 2);
+            for mut item in table.iter_mut() {
+                if item.index().1 == 2 {
+                    *item += 1;
+                }
+            }
+        }
+
+        assert_eq!(dense.backend(), IllusionBackend::Dense);
+        assert_eq!(dense, sparse);
+        assert_eq!(dense.filled_len(), 9);
+        assert_eq!(dense.to_rle(), sparse.to_rle());
+        assert_eq!(dense.transposed().backend(), IllusionBackend::Dense);
+        let decoded =
+            IllusionTable::from_rle_with_backend(0, dense.to_rle(), IllusionBackend::Dense);
+        assert_eq!(decoded.backend(), IllusionBackend::Dense);
+        assert_eq!(decoded, dense);
+        // Dense rows are always visited in order.
+        let indexes: Vec<_> = dense
+            .real_items()
+            .map(|((row, column), _)| (row, column))
+            .collect();
+        assert!(indexes.windows(2).all(|pair| pair[0] < pair[1]));
+
+        dense.clear_region((0, 0), (2, 3));
+        assert_eq!(dense.filled_len(), 0);
+        assert!(
+            dense.stats().approx_bytes > IllusionTable::<3, 4, i32>::default().stats().approx_bytes
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par() {
+        use rayon::prelude::*;
+
+        let mut table = IllusionTable::<3, 4, i32>::default();
+        table.try_modify((0, 0), |v| *v.get_mut() = 5);
+        table.par_for_each_mut(|(row, column), item| {
+            if column == 1 {
+                *item = row as i32 + 1;
+            } else if row == 0 {
+                *item = 0;
+            }
+        });
+
+        assert_eq!(table.filled_len(), 3);
+        assert_eq!(*table.get((0, 0)).unwrap(), 0);
+        assert_eq!(*table.get((2, 1)).unwrap(), 3);
+        assert_eq!(table.par_iter().map(|item| *item).sum::<i32>(), 6);
+        assert_eq!(
+            table
+                .par_iter()
+                .map(|item| item.index())
+                .collect::<Vec<_>>(),
+            table.iter().map(|item| item.index()).collect::<Vec<_>>()
+        );
+    }
+
+    #[cfg(feature = "ordered")]
+    #[test]
+    fn test_ordered() {
+        let mut table = IllusionTable::<3, 3, i32>::default();
+        for (index, value) in [((2, 0), 1), ((0, 2), 2), ((1, 1), 3), ((0, 0), 4)] {
+            table.try_modify(index, |v| *v.get_mut() = value);
+        }
+
+        let indexes: Vec<_> = table
+            .real_items()
+            .map(|((row, column), _)| (row, column))
+            .collect();
+        assert_eq!(indexes, vec![(0, 0), (0, 2), (1, 1), (2, 0)]);
+    }
 }
@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+/// Sparse `u16` tags for cells of a `H` * `W` table, untagged cells take no space.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TagLayer<const H: usize, const W: usize> {
+    inner: BTreeMap<(usize, usize), u16>,
+}
+
+impl<const H: usize, const W: usize> TagLayer<H, W> {
+    /// Number of tagged cells.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns the tag of a cell, none if it's not tagged or the index is out of bounds.
+    pub fn get(&self, index: (usize, usize)) -> Option<u16> {
+        self.inner.get(&index).copied()
+    }
+
+    /// Tags a cell (or removes its tag using `None`) and returns the previous tag.
+    ///
+    /// Indexes out of bounds are ignored.
+    pub fn set(&mut self, (row, column): (usize, usize), tag: Option<u16>) -> Option<u16> {
+        if row >= H || column >= W {
+            return None;
+        }
+
+        match tag {
+            Some(tag) => self.inner.insert((row, column), tag),
+            None => self.inner.remove(&(row, column)),
+        }
+    }
+
+    /// Tagged cells and their tags, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), u16)> + '_ {
+        self.inner.iter().map(|(index, tag)| (*index, *tag))
+    }
+
+    /// Cells having the given tag, row by row.
+    pub fn iter_by_tag(&self, tag: u16) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.iter()
+            .filter(move |(_, other)| *other == tag)
+            .map(|(index, _)| index)
+    }
+
+    pub fn swap(&mut self, a: (usize, usize), b: (usize, usize)) {
+        let tag_a = self.inner.remove(&a);
+        let tag_b = self.inner.remove(&b);
+        self.set(a, tag_b);
+        self.set(b, tag_a);
+    }
+
+    /// Swaps tags of two whole rows, rows out of bounds are ignored.
+    pub fn swap_rows(&mut self, a: usize, b: usize) {
+        if a >= H || b >= H {
+            return;
+        }
+
+        self.remap(|(row, column)| {
+            (row == a || row == b).then_some((if row == a { b } else { a }, column))
+        });
+    }
+
+    /// Swaps tags of two whole columns, columns out of bounds are ignored.
+    pub fn swap_columns(&mut self, a: usize, b: usize) {
+        if a >= W || b >= W {
+            return;
+        }
+
+        self.remap(|(row, column)| {
+            (column == a || column == b).then_some((row, if column == a { b } else { a }))
+        });
+    }
+
+    /// Moves tags of cells to the index `f` returns for them, if any.
+    fn remap(&mut self, f: impl Fn((usize, usize)) -> Option<(usize, usize)>) {
+        let moved: Vec<_> = self
+            .iter()
+            .filter_map(|(index, tag)| f(index).map(|target| (index, target, tag)))
+            .collect();
+        for (index, _, _) in &moved {
+            self.inner.remove(index);
+        }
+        self.inner
+            .extend(moved.into_iter().map(|(_, target, tag)| (target, tag)));
+    }
+
+    /// Swaps rows and columns of tagged cells.
+    pub fn transposed(&self) -> TagLayer<W, H> {
+        TagLayer {
+            inner: self
+                .iter()
+                .map(|((row, column), tag)| ((column, row), tag))
+                .collect(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tags() {
+        let mut tags = TagLayer::<2, 3>::default();
+        assert_eq!(tags.set((0, 1), Some(7)), None);
+        assert_eq!(tags.set((1, 2), Some(7)), None);
+        assert_eq!(tags.set((5, 5), Some(7)), None);
+        assert_eq!(tags.len(), 2);
+
+        tags.swap((0, 1), (1, 0));
+        assert_eq!(tags.get((0, 1)), None);
+        assert_eq!(
+            tags.iter_by_tag(7).collect::<Vec<_>>(),
+            vec![(1, 0), (1, 2)]
+        );
+
+        assert_eq!(tags.set((1, 0), None), Some(7));
+        assert_eq!(tags.len(), 1);
+
+        tags.swap_rows(1, 0);
+        assert_eq!(tags.get((0, 2)), Some(7));
+        tags.swap_columns(2, 0);
+        assert_eq!(tags.iter().collect::<Vec<_>>(), vec![((0, 0), 7)]);
+    }
+}
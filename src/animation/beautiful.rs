@@ -1,14 +1,18 @@
 use std::marker::PhantomData;
 
-use image::codecs::gif::Repeat;
-
 use crate::pixels::{color::RgbaInterface, Pixel, PixelInitializer, PixelInterface};
 
 use super::{
     Animated, AnimationContext, AnimationFrameFinisher, AnimationFrameFinisherEmpty,
-    AnimationFrameFinisherHolder,
+    AnimationFrameFinisherHolder, FrameCount,
 };
 
+type BeautifulUpdater<Extras, const H: usize, const W: usize, P> =
+    Box<dyn Fn(&mut AnimationContext<H, W, P>, u16, &mut Extras) -> bool>;
+
+type BeautifulSetup<Extras, const H: usize, const W: usize, P> =
+    Box<dyn Fn(&mut AnimationContext<H, W, P>, &mut Extras)>;
+
 pub struct BeautifulAnimation<
     Extras,
     const H: usize,
@@ -17,10 +21,10 @@ pub struct BeautifulAnimation<
 > where
     <P as PixelInterface>::ColorType: std::fmt::Debug,
 {
-    updater: Box<dyn Fn(&mut AnimationContext<H, W, P>, u16, &mut Extras) -> bool>,
-    setup: Box<dyn Fn(&mut AnimationContext<H, W, P>, &mut Extras)>,
+    updater: BeautifulUpdater<Extras, H, W, P>,
+    setup: BeautifulSetup<Extras, H, W, P>,
     finisher: Box<dyn AnimationFrameFinisher<AnimationContext<H, W, P>>>,
-    frame_count: Repeat,
+    frame_count: FrameCount,
     extras_holder: Option<Extras>,
     _phantom: PhantomData<(P, Extras)>,
 }
@@ -31,7 +35,7 @@ where
     <P as PixelInterface>::ColorType: std::fmt::Debug,
 {
     pub fn new_with_finisher(
-        frame_count: Repeat,
+        frame_count: FrameCount,
         extras: Extras,
         setup: impl Fn(&mut AnimationContext<H, W, P>, &mut Extras) + 'static,
         updater: impl Fn(&mut AnimationContext<H, W, P>, u16, &mut Extras) -> bool + 'static,
@@ -54,7 +58,7 @@ where
     <P as PixelInterface>::ColorType: std::fmt::Debug,
 {
     pub fn new(
-        frame_count: Repeat,
+        frame_count: FrameCount,
         extras: Extras,
         setup: impl Fn(&mut AnimationContext<H, W, P>, &mut Extras) + 'static,
         updater: impl Fn(&mut AnimationContext<H, W, P>, u16, &mut Extras) -> bool + 'static,
@@ -114,7 +118,7 @@ mod tests {
     #[test]
     fn test_name() {
         let _animation = BeautifulAnimation::<_, 5>::new(
-            Repeat::Finite(10),
+            FrameCount::Finite(10),
             (10u32, "Hello World".to_string()),
             |_ctx, (_number, _string)| {},
             |_ctx, _i, (_number, _string)| true,
@@ -0,0 +1,148 @@
+//! Easing curves, to make motion and color transitions non-linear, see [`Easing`].
+//!
+
+use std::f32::consts::PI;
+
+/// Maps linear progress (`0.0` to `1.0`) to eased progress.
+///
+/// ## Example
+/// ```rust
+/// # use pixelart::{animation::easing::Easing, prelude::*};
+/// // Halfway through a 9 frames animation.
+/// let t = Easing::EaseInQuad.at_frame(4, 9);
+/// assert_eq!(t, 0.25);
+/// assert_eq!(BLACK.lerp(WHITE, t), PixelColor::new(64, 64, 64));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+
+    /// Starts slow.
+    EaseInQuad,
+    /// Ends slow.
+    EaseOutQuad,
+    /// Starts and ends slow.
+    EaseInOutQuad,
+
+    EaseInCubic,
+    EaseOutCubic,
+    EaseInOutCubic,
+
+    /// Bounces a few times before settling at the end.
+    Bounce,
+
+    /// Overshoots the end and springs back, like a rubber band.
+    Elastic,
+}
+
+impl Easing {
+    /// Eased progress of `t`, which is clamped to `0.0..=1.0`.
+    ///
+    /// The result is `0.0` at the start and `1.0` at the end, [`Easing::Elastic`] goes
+    /// outside of this range in between.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0., 1.);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInQuad => t * t,
+            Easing::EaseOutQuad => 1. - (1. - t) * (1. - t),
+            Easing::EaseInOutQuad => {
+                if t < 0.5 {
+                    2. * t * t
+                } else {
+                    1. - (-2. * t + 2.).powi(2) / 2.
+                }
+            }
+            Easing::EaseInCubic => t.powi(3),
+            Easing::EaseOutCubic => 1. - (1. - t).powi(3),
+            Easing::EaseInOutCubic => {
+                if t < 0.5 {
+                    4. * t.powi(3)
+                } else {
+                    1. - (-2. * t + 2.).powi(3) / 2.
+                }
+            }
+            Easing::Bounce => bounce_out(t),
+            Easing::Elastic => {
+                if t == 0. || t == 1. {
+                    t
+                } else {
+                    2f32.powf(-10. * t) * ((t * 10. - 0.75) * (2. * PI / 3.)).sin() + 1.
+                }
+            }
+        }
+    }
+
+    /// Eased progress of a frame, see [`progress`].
+    pub fn at_frame(&self, frame: u16, frame_count: u16) -> f32 {
+        self.apply(progress(frame, frame_count))
+    }
+
+    /// A value between `from` and `to` at eased progress of `t`.
+    pub fn interpolate(&self, from: f32, to: f32, t: f32) -> f32 {
+        from + (to - from) * self.apply(t)
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+
+    if t < 1. / D {
+        N * t * t
+    } else if t < 2. / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+/// Linear progress of the `frame`th frame of `frame_count` frames,
+/// `0.0` at the first frame and `1.0` at the last one.
+pub fn progress(frame: u16, frame_count: u16) -> f32 {
+    if frame_count <= 1 {
+        return 1.;
+    }
+    (frame as f32 / (frame_count - 1) as f32).min(1.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_easing() {
+        let all = [
+            Easing::Linear,
+            Easing::EaseInQuad,
+            Easing::EaseOutQuad,
+            Easing::EaseInOutQuad,
+            Easing::EaseInCubic,
+            Easing::EaseOutCubic,
+            Easing::EaseInOutCubic,
+            Easing::Bounce,
+            Easing::Elastic,
+        ];
+        for easing in all {
+            assert_eq!(easing.apply(0.), 0., "{easing:?}");
+            assert!((easing.apply(1.) - 1.).abs() < 1e-6, "{easing:?}");
+        }
+
+        assert_eq!(Easing::EaseInOutQuad.apply(0.5), 0.5);
+        assert_eq!(Easing::EaseOutCubic.apply(0.5), 0.875);
+        assert!(Easing::Elastic.apply(0.2) > 1.);
+        assert_eq!(Easing::Linear.apply(2.), 1.);
+
+        assert_eq!(progress(0, 5), 0.);
+        assert_eq!(progress(2, 5), 0.5);
+        assert_eq!(progress(9, 5), 1.);
+        assert_eq!(progress(0, 1), 1.);
+        assert_eq!(Easing::EaseInQuad.interpolate(10., 20., 0.5), 12.5);
+    }
+}
@@ -1,7 +1,5 @@
 use std::path::Path;
 
-use image::codecs::gif::Repeat;
-
 use crate::{
     pixels::{
         canvas::{layered::LayeredCanvas, SharedPixelCanvasExt},
@@ -10,13 +8,13 @@ use crate::{
     prelude::PixelColor,
 };
 
-use super::{AnimatedContext, PixelAnimationBuilder};
+use super::{AnimatedContext, FrameCount, PixelAnimationBuilder};
 
 #[cfg(feature = "viewer")]
 use crate::viewer::ViewResult;
 
 pub struct LayeredAnimationContext<const H: usize, const W: usize, P: PixelInterface + Default> {
-    pub(crate) frame_count: Repeat,
+    pub(crate) frame_count: FrameCount,
     pub(crate) layered_canvas: LayeredCanvas<H, W, P>,
     pub(crate) builder: PixelAnimationBuilder,
 }
@@ -25,7 +23,7 @@ impl<const H: usize, const W: usize, P: PixelInterface + Default> LayeredAnimati
     pub fn new(
         layered_canvas: LayeredCanvas<H, W, P>,
         builder: PixelAnimationBuilder,
-        frame_count: Repeat,
+        frame_count: FrameCount,
     ) -> Self {
         Self {
             layered_canvas,
@@ -46,6 +44,21 @@ impl<const H: usize, const W: usize, P: PixelInterface + Default> LayeredAnimati
         self.builder.save(path)
     }
 
+    /// See [`PixelAnimationBuilder::save_sprite_sheet`].
+    pub fn save_sprite_sheet<PA: AsRef<Path>>(&self, path: PA) -> Result<(), image::ImageError> {
+        self.builder.save_sprite_sheet(path)
+    }
+
+    /// See [`PixelAnimationBuilder::save_frames`].
+    pub fn save_frames<PA: AsRef<Path>>(&self, dir: PA) -> Result<(), image::ImageError> {
+        self.builder.save_frames(dir)
+    }
+
+    /// See [`PixelAnimationBuilder::save_apng`].
+    pub fn save_apng<PA: AsRef<Path>>(&self, path: PA) -> Result<(), image::ImageError> {
+        self.builder.save_apng(path)
+    }
+
     #[cfg(feature = "viewer")]
     pub fn view(self) -> ViewResult {
         self.builder.view()
@@ -74,7 +87,7 @@ where
         self.layered_canvas.base_layer_mut()
     }
 
-    fn frame_count(&self) -> &Repeat {
+    fn frame_count(&self) -> &FrameCount {
         &self.frame_count
     }
 
@@ -92,7 +105,7 @@ where
 
 pub mod examples {
     use crate::{
-        animation::Animated,
+        animation::{Animated, LoopBehavior},
         pixels::{
             canvas::{
                 layered::LayerData,
@@ -114,8 +127,8 @@ pub mod examples {
         fn create_context(&mut self) -> Self::ContextType {
             LayeredAnimationContext::new(
                 LayeredCanvas::default(),
-                PixelAnimationBuilder::new_empty(Repeat::Infinite, 2),
-                Repeat::Finite(30),
+                PixelAnimationBuilder::new_empty(LoopBehavior::default(), 2),
+                FrameCount::Finite(30),
             )
         }
 
@@ -0,0 +1,267 @@
+//! Macros that remove the boilerplate of implementing [`Animated`](super::Animated).
+//!
+
+/// Implements [`Animated`](crate::animation::Animated) for a struct, so only the `#[setup]`
+/// and `#[update]` (and optionally `#[finisher]`) methods need to be written.
+///
+/// The context is an [`AnimationContext`](crate::animation::AnimationContext), or a
+/// [`LayeredAnimationContext`](crate::animation::layered::LayeredAnimationContext) with
+/// `context = LayeredAnimationContext;`.
+///
+/// The pixel type defaults to [`Pixel`](crate::pixels::Pixel), `scale` and `loop_behavior` are optional.
+///
+/// ```
+/// use pixelart::{
+///     animated,
+///     animation::{Animated, AnimatedContext, FrameCount},
+///     pixels::canvas::layered::LayerData,
+///     prelude::*,
+/// };
+///
+/// animated! {
+///     /// Blinks between red and blue.
+///     pub struct Blink {
+///         on: bool,
+///     }
+///
+///     impl Animated<5, 5> for Blink {
+///         frames = FrameCount::Finite(6);
+///         scale = 2;
+///
+///         #[setup]
+///         fn setup(&mut self, ctx) {
+///             ctx.canvas.fill(BLUE);
+///         }
+///
+///         #[update]
+///         fn update(&mut self, ctx, _i) -> bool {
+///             self.on = !self.on;
+///             ctx.canvas.fill(if self.on { RED } else { BLUE });
+///             true
+///         }
+///     }
+/// }
+///
+/// let ctx = Blink { on: false }.create();
+/// assert_eq!(ctx.builder.frames().len(), 6);
+///
+/// animated! {
+///     /// Moves a red layer over a blue base.
+///     pub struct Slide;
+///
+///     impl Animated<5, 5> for Slide {
+///         context = LayeredAnimationContext;
+///         frames = FrameCount::Finite(5);
+///
+///         #[setup]
+///         fn setup(&mut self, ctx) {
+///             let layered = ctx.layered_canvas_mut();
+///             layered.base_layer_mut().fill(BLUE);
+///             layered
+///                 .new_layer(LayerData::build_new("red".to_string(), |c| c.fill(RED)))
+///                 .unwrap();
+///         }
+///
+///         #[update]
+///         fn update(&mut self, ctx, _i) -> bool {
+///             ctx.layered_canvas_mut()
+///                 .top_layer_mut("red")
+///                 .unwrap()
+///                 .update_offset(|offset| offset.translated(0, 1));
+///             true
+///         }
+///     }
+/// }
+///
+/// assert_eq!(Slide.create().builder().frames().len(), 5);
+/// ```
+#[macro_export]
+macro_rules! animated {
+    (@pixel) => { $crate::pixels::Pixel };
+    (@pixel $p:ty) => { $p };
+
+    (@scale) => { 1 };
+    (@scale $scale:expr) => { $scale };
+
+    (@context_type $h:tt, $w:tt, $p:ty $(, AnimationContext)?) => {
+        $crate::animation::AnimationContext<{ $h }, { $w }, $p>
+    };
+    (@context_type $h:tt, $w:tt, $p:ty, LayeredAnimationContext) => {
+        $crate::animation::layered::LayeredAnimationContext<{ $h }, { $w }, $p>
+    };
+
+    (
+        @create_context $h:tt, $w:tt, $p:ty $(, AnimationContext)?;
+        $frames:expr; $($scale:expr)?; $($loop_behavior:expr)?
+    ) => {
+        $crate::animation::AnimationContext::<{ $h }, { $w }, $p>::new($frames)
+            $(.with_scale($scale))?
+            $(.with_loop_behavior($loop_behavior))?
+    };
+    (
+        @create_context $h:tt, $w:tt, $p:ty, LayeredAnimationContext;
+        $frames:expr; $($scale:expr)?; $($loop_behavior:expr)?
+    ) => {
+        $crate::animation::layered::LayeredAnimationContext::<{ $h }, { $w }, $p>::new(
+            ::std::default::Default::default(),
+            $crate::animation::PixelAnimationBuilder::new_empty(
+                ::std::default::Default::default(),
+                $crate::animated!(@scale $($scale)?),
+            )
+            $(.with_loop_behavior($loop_behavior))?,
+            $frames,
+        )
+    };
+
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident $body:tt
+
+        impl Animated<$h:tt, $w:tt $(, $p:ty)?> for $impl_name:ident {
+            $(context = $context:ident;)?
+            frames = $frames:expr;
+            $(scale = $scale:expr;)?
+            $(loop_behavior = $loop_behavior:expr;)?
+
+            #[setup]
+            fn $setup:ident(&mut $setup_self:ident, $setup_ctx:ident) $setup_body:block
+
+            #[update]
+            fn $update:ident(&mut $update_self:ident, $update_ctx:ident, $update_i:ident) -> bool
+                $update_body:block
+
+            $(
+                #[finisher]
+                fn $finisher:ident(&mut $finisher_self:ident, $finisher_ctx:ident, $finisher_i:ident)
+                    $finisher_body:block
+            )?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name $body
+
+        impl $crate::animation::Animated<{ $h }, { $w }, $crate::animated!(@pixel $($p)?)>
+            for $impl_name
+        {
+            type ContextType = $crate::animated!(
+                @context_type $h, $w, $crate::animated!(@pixel $($p)?) $(, $context)?
+            );
+
+            fn create_context(&mut self) -> Self::ContextType {
+                $crate::animated!(
+                    @create_context $h, $w, $crate::animated!(@pixel $($p)?) $(, $context)?;
+                    $frames; $($scale)?; $($loop_behavior)?
+                )
+            }
+
+            fn $setup(&mut $setup_self, $setup_ctx: &mut Self::ContextType) $setup_body
+
+            fn $update(
+                &mut $update_self,
+                $update_ctx: &mut Self::ContextType,
+                $update_i: u16,
+            ) -> bool $update_body
+
+            $(
+                fn $finisher(
+                    &mut $finisher_self,
+                    $finisher_ctx: &mut Self::ContextType,
+                    $finisher_i: u16,
+                ) $finisher_body
+            )?
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        animation::{Animated, AnimatedContext, FrameCount, LoopBehavior},
+        pixels::canvas::layered::{LayerData, LayerOffset},
+        prelude::*,
+    };
+
+    crate::animated! {
+        struct Walker {
+            steps: u16,
+        }
+
+        impl Animated<3, 4, MaybePixel> for Walker {
+            frames = FrameCount::UntilStopped;
+            loop_behavior = LoopBehavior::HoldLastFrame;
+
+            #[setup]
+            fn setup(&mut self, ctx) {
+                ctx.canvas.get_pixel_mut(TOP_LEFT).update_color(RED);
+            }
+
+            #[update]
+            fn update(&mut self, ctx, i) -> bool {
+                ctx.canvas.get_pixel_mut((0, i as usize % 4)).update_color(RED);
+                i < 3
+            }
+
+            #[finisher]
+            fn finisher(&mut self, _ctx, _i) {
+                self.steps += 1;
+            }
+        }
+    }
+
+    crate::animated! {
+        struct Fall;
+
+        impl Animated<4, 4> for Fall {
+            context = LayeredAnimationContext;
+            frames = FrameCount::Finite(3);
+            scale = 3;
+            loop_behavior = LoopBehavior::HoldLastFrame;
+
+            #[setup]
+            fn setup(&mut self, ctx) {
+                ctx.layered_canvas_mut()
+                    .new_layer(LayerData::build_new("drop".to_string(), |canvas| {
+                        canvas.update_color_at(TOP_LEFT, BLUE);
+                    }))
+                    .unwrap();
+            }
+
+            #[update]
+            fn update(&mut self, ctx, _i) -> bool {
+                ctx.layered_canvas_mut()
+                    .top_layer_mut("drop")
+                    .unwrap()
+                    .update_offset(|offset| offset.translated(1, 0));
+                true
+            }
+        }
+    }
+
+    #[test]
+    fn test_animated_macro() {
+        let mut walker = Walker { steps: 0 };
+        let ctx = walker.create();
+
+        assert_eq!(walker.steps, 3);
+        assert_eq!(ctx.builder.frames().len(), 3);
+        assert_eq!(ctx.canvas.filled_len(), 4);
+    }
+
+    #[test]
+    fn test_animated_macro_layered() {
+        let ctx = Fall.create();
+        let frames = ctx.builder().frames();
+
+        assert_eq!(frames.len(), 3);
+        // 15 * 15 of the default style, scaled by 3.
+        assert_eq!(frames[0].dimensions(), (45, 45));
+        assert!(matches!(
+            ctx.builder().loop_behavior(),
+            LoopBehavior::HoldLastFrame
+        ));
+        assert_eq!(
+            ctx.layered_canvas().top_layer("drop").unwrap().offset,
+            LayerOffset::new(3, 0)
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Marquee (chase light) animations, running lit pixels around a path while cycling their colors.
+//!
+
+use crate::{
+    pixels::{
+        canvas::SharedMutPixelCanvasExt,
+        color::RgbaInterface,
+        position::{IntoPixelStrictPosition, PixelStrictPosition},
+        PixelMutInterface,
+    },
+    prelude::{PixelCanvas, PixelColor, RED},
+};
+
+use super::{LoopBehavior, PixelAnimationBuilder};
+
+/// Lights that run along a path of positions, like LEDs around an arcade sign.
+///
+/// On each frame the lights move `speed` positions forward and their colors move one step
+/// forward in the palette.
+#[derive(Debug, Clone)]
+pub struct Marquee<const H: usize, const W: usize = H> {
+    path: Vec<PixelStrictPosition<H, W>>,
+    lit: usize,
+    gap: Option<usize>,
+    speed: usize,
+    palette: Vec<PixelColor>,
+    off_color: Option<PixelColor>,
+}
+
+impl<const H: usize, const W: usize> Marquee<H, W> {
+    /// A marquee with a single lit pixel running along the given path.
+    pub fn new(path: impl IntoIterator<Item = impl IntoPixelStrictPosition<H, W>>) -> Self {
+        Self {
+            path: path
+                .into_iter()
+                .map(|pos| pos.into_pixel_strict_position())
+                .collect(),
+            lit: 1,
+            gap: None,
+            speed: 1,
+            palette: vec![RED],
+            off_color: None,
+        }
+    }
+
+    /// A marquee running clockwise around the border of the canvas, starting at top left.
+    pub fn border() -> Self {
+        Self::new(border_path::<H, W>())
+    }
+
+    /// Number of lit pixels in a row.
+    pub fn with_lit(mut self, lit: usize) -> Self {
+        self.lit = lit.max(1);
+        self
+    }
+
+    /// Repeat the lit pixels all over the path, separated by `gap` unlit pixels.
+    pub fn with_gap(mut self, gap: usize) -> Self {
+        self.gap = Some(gap);
+        self
+    }
+
+    /// Positions to move forward on each frame.
+    pub fn with_speed(mut self, speed: usize) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Colors to cycle through on lit pixels, instead of a single red.
+    pub fn with_palette(
+        mut self,
+        palette: impl IntoIterator<Item = impl Into<PixelColor>>,
+    ) -> Self {
+        let palette: Vec<_> = palette.into_iter().map(Into::into).collect();
+        if !palette.is_empty() {
+            self.palette = palette;
+        }
+        self
+    }
+
+    /// Color of path pixels which are not lit, they're left untouched by default.
+    pub fn with_off_color(mut self, color: impl Into<PixelColor>) -> Self {
+        self.off_color = Some(color.into());
+        self
+    }
+
+    pub fn path(&self) -> &[PixelStrictPosition<H, W>] {
+        &self.path
+    }
+
+    /// Number of frames after which the marquee looks exactly like the first frame.
+    pub fn cycle_len(&self) -> usize {
+        if self.path.is_empty() {
+            return 1;
+        }
+
+        // Repeated lights only look the same sooner if they fit the path evenly.
+        let period = match self.gap {
+            Some(gap) if self.path.len().is_multiple_of(self.lit + gap) => self.lit + gap,
+            _ => self.path.len(),
+        };
+        let movement = period / gcd(period, self.speed % period);
+        lcm(movement, self.palette.len())
+    }
+
+    /// Lit positions and their colors at the given frame.
+    pub fn lit_at(&self, frame: usize) -> Vec<(PixelStrictPosition<H, W>, PixelColor)> {
+        let len = self.path.len();
+        if len == 0 {
+            return Vec::new();
+        }
+
+        let offset = frame * self.speed;
+        let period = self.gap.map(|gap| self.lit + gap);
+
+        (0..len)
+            .filter_map(|index| {
+                // Distance of this position from the head of lights, along the path.
+                let along = (index + len - offset % len) % len;
+                let along = match period {
+                    Some(period) => along % period,
+                    None => along,
+                };
+                (along < self.lit).then(|| {
+                    let color = self.palette[(along + frame) % self.palette.len()];
+                    (self.path[index], color)
+                })
+            })
+            .collect()
+    }
+
+    /// Draws the given frame of marquee on the canvas.
+    pub fn draw_frame<P>(&self, canvas: &mut PixelCanvas<H, W, P>, frame: usize)
+    where
+        P: PixelMutInterface + Default + PartialEq + Clone,
+        P::ColorType: From<PixelColor>,
+    {
+        if let Some(off_color) = self.off_color {
+            for pos in self.path.iter() {
+                canvas.update_color_at(*pos, off_color);
+            }
+        }
+
+        for (pos, color) in self.lit_at(frame) {
+            canvas.update_color_at(pos, color);
+        }
+    }
+
+    /// Creates a full cycle of this marquee on top of the `base` canvas.
+    pub fn create<P>(&self, base: &PixelCanvas<H, W, P>, scale: usize) -> PixelAnimationBuilder
+    where
+        P: PixelMutInterface + Default + PartialEq + Clone,
+        P::ColorType: From<PixelColor> + RgbaInterface + Default + Clone,
+    {
+        let mut builder = PixelAnimationBuilder::new_empty(LoopBehavior::default(), scale);
+        for frame in 0..self.cycle_len() {
+            let mut canvas = base.clone();
+            self.draw_frame(&mut canvas, frame);
+            builder.push_frame_from_canvas(&canvas);
+        }
+        builder
+    }
+}
+
+/// Positions on the border of a `H` * `W` canvas, clockwise from top left.
+pub fn border_path<const H: usize, const W: usize>() -> Vec<PixelStrictPosition<H, W>> {
+    if H == 0 || W == 0 {
+        return Vec::new();
+    }
+
+    let top = (0..W).map(|column| (0, column));
+    let right = (1..H).map(|row| (row, W - 1));
+    let bottom = (0..W - 1)
+        .rev()
+        .map(|column| (H - 1, column))
+        .filter(|_| H > 1);
+    let left = (1..H - 1).rev().map(|row| (row, 0)).filter(|_| W > 1);
+
+    top.chain(right)
+        .chain(bottom)
+        .chain(left)
+        .map(|pos| pos.into_pixel_strict_position())
+        .collect()
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn lcm(a: usize, b: usize) -> usize {
+    a / gcd(a, b) * b
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_marquee() {
+        assert_eq!(border_path::<3, 4>().len(), 10);
+        assert_eq!(border_path::<1, 4>().len(), 4);
+
+        let marquee = Marquee::<3, 4>::border()
+            .with_lit(2)
+            .with_gap(3)
+            .with_palette([RED, BLUE]);
+        assert_eq!(marquee.cycle_len(), 10);
+
+        let lit = marquee.lit_at(0);
+        assert_eq!(lit.len(), 4);
+        assert_eq!(lit[0], (TOP_LEFT.into_pixel_strict_position(), RED));
+        assert_eq!(lit[1], ((0, 1).into_pixel_strict_position(), BLUE));
+
+        let moved = marquee.lit_at(1);
+        assert_eq!(moved[0], ((0, 1).into_pixel_strict_position(), BLUE));
+
+        let builder = marquee
+            .with_off_color(BLACK)
+            .create(&PixelCanvas::<3, 4>::default(), 2);
+        assert_eq!(builder.frames().len(), 10);
+    }
+}
@@ -1,74 +1,497 @@
+use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::marker::PhantomData;
-use std::{fs::File, path::Path};
+use std::time::Duration;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
 
-use image::{codecs::gif::GifEncoder, Frame, ImageResult};
+use image::{
+    codecs::gif::GifEncoder,
+    error::{EncodingError, ImageFormatHint},
+    Delay, Frame, ImageError, ImageFormat, ImageResult,
+};
 
-use crate::image::DefaultImageBuffer;
+use crate::image::{strip::ImageStrip, DefaultImageBuffer};
 use crate::pixels::{canvas::SharedPixelCanvasExt, color::RgbaInterface};
 use crate::pixels::{Pixel, PixelInitializer};
 use crate::{
     pixels::{canvas::PixelCanvasInterface, PixelInterface},
     prelude::PixelCanvas,
 };
+use quantization::{encode_quantized, encoding_error, GifQuantization};
 
 pub use image::codecs::gif::Repeat;
 
 #[cfg(feature = "viewer")]
-use crate::viewer::{view, ViewResult};
+use crate::viewer::{view_with_options, ViewEntry, ViewResult, ViewerOptions};
 
 pub mod beautiful;
+pub mod easing;
 pub mod layered;
+mod macros;
+pub mod marquee;
+pub mod quantization;
 pub mod simple;
+pub mod state_machine;
+pub mod subpixel;
+pub mod tween;
+
+/// How many frames an [`Animated`] creates, independent of how the resulting GIF loops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameCount {
+    /// Create at most this many frames.
+    Finite(u16),
+
+    /// Keep creating frames until the updater returns `false`.
+    UntilStopped,
+}
+
+impl From<u16> for FrameCount {
+    fn from(value: u16) -> Self {
+        Self::Finite(value)
+    }
+}
+
+/// What happens when the animation reaches its last frame, applied at encode time.
+#[derive(Debug, Clone, Copy)]
+pub enum LoopBehavior {
+    /// Start over from the first frame, as many times as [`Repeat`] says.
+    Loop(Repeat),
+
+    /// Play once and stay on the last frame.
+    HoldLastFrame,
+
+    /// Play the frames forward and then backward, looping as [`Repeat`] says.
+    Reverse(Repeat),
+}
+
+impl Default for LoopBehavior {
+    fn default() -> Self {
+        Self::Loop(Repeat::Infinite)
+    }
+}
+
+impl LoopBehavior {
+    /// The GIF repeat value to encode with.
+    pub fn gif_repeat(&self) -> Repeat {
+        match self {
+            LoopBehavior::Loop(repeat) | LoopBehavior::Reverse(repeat) => *repeat,
+            // No loop extension at all, so viewers stop at the last frame.
+            LoopBehavior::HoldLastFrame => Repeat::Finite(0),
+        }
+    }
+
+    /// Applies this behavior to a sequence of frames.
+    pub fn apply<T: Clone>(&self, mut frames: Vec<T>) -> Vec<T> {
+        if let LoopBehavior::Reverse(_) = self {
+            if frames.len() > 2 {
+                let backward: Vec<T> = frames[1..frames.len() - 1].iter().rev().cloned().collect();
+                frames.extend(backward);
+            }
+        }
+        frames
+    }
+}
+
+/// Frames being encoded as soon as they're captured, see [`PixelAnimationBuilder::stream_to`].
+pub(crate) struct GifStream {
+    /// The writer, until the first frame tells the size of the GIF.
+    writer: Option<Box<dyn Write>>,
+    encoder: Option<gif::Encoder<Box<dyn Write>>>,
+    frames: usize,
+    error: Option<ImageError>,
+}
+
+impl Debug for GifStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GifStream")
+            .field("frames", &self.frames)
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GifStream {
+    fn new(writer: impl Write + 'static) -> Self {
+        Self {
+            writer: Some(Box::new(writer)),
+            encoder: None,
+            frames: 0,
+            error: None,
+        }
+    }
+
+    fn encode(&mut self, image: DefaultImageBuffer, delay: Option<Duration>, repeat: Repeat) {
+        if self.error.is_some() {
+            return;
+        }
+
+        match self.try_encode(image, delay, repeat) {
+            Ok(_) => self.frames += 1,
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    fn try_encode(
+        &mut self,
+        image: DefaultImageBuffer,
+        delay: Option<Duration>,
+        repeat: Repeat,
+    ) -> ImageResult<()> {
+        let (Ok(width), Ok(height)) = (u16::try_from(image.width()), u16::try_from(image.height()))
+        else {
+            return Err(gif_error("The frame is too large for a GIF."));
+        };
+
+        let encoder = match &mut self.encoder {
+            Some(encoder) => encoder,
+            None => {
+                // Repeat goes before the first frame, so loop behavior can be changed until then.
+                let writer = self
+                    .writer
+                    .take()
+                    .expect("Writer is kept until the first frame.");
+                let mut encoder =
+                    gif::Encoder::new(writer, width, height, &[]).map_err(encoding_error)?;
+                encoder
+                    .set_repeat(match repeat {
+                        Repeat::Finite(n) => gif::Repeat::Finite(n),
+                        Repeat::Infinite => gif::Repeat::Infinite,
+                    })
+                    .map_err(encoding_error)?;
+                self.encoder.insert(encoder)
+            }
+        };
+
+        let mut frame = gif::Frame::from_rgba_speed(width, height, &mut image.into_raw(), 1);
+        frame.dispose = gif::DisposalMethod::Background;
+        if let Some(delay) = delay {
+            frame.delay = (delay.as_millis() / 10).min(u16::MAX as u128) as u16;
+        }
+        encoder.write_frame(&frame).map_err(encoding_error)
+    }
+
+    /// Writes the end of the GIF and flushes the writer.
+    fn finish(self) -> ImageResult<()> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let Some(encoder) = self.encoder else {
+            return Err(gif_error("At least one frame is required."));
+        };
+        encoder.into_inner()?.flush()?;
+        Ok(())
+    }
+}
+
+pub(crate) fn gif_error(message: &str) -> ImageError {
+    ImageError::Encoding(EncodingError::new(
+        ImageFormatHint::Exact(ImageFormat::Gif),
+        message,
+    ))
+}
 
 #[derive(Debug)]
 pub struct PixelAnimationBuilder {
-    pub(crate) repeat: Repeat,
+    pub(crate) loop_behavior: LoopBehavior,
     pub(crate) scale: usize,
     pub(crate) images: Vec<DefaultImageBuffer>,
+    pub(crate) quantization: Option<GifQuantization>,
+    pub(crate) frame_delay: Option<Duration>,
+    /// Delays of single frames (by index), overriding `frame_delay`.
+    pub(crate) frame_delays: BTreeMap<usize, Duration>,
+    pub(crate) stream: Option<GifStream>,
 }
 
 impl Default for PixelAnimationBuilder {
     fn default() -> Self {
         Self {
-            repeat: Repeat::Infinite,
+            loop_behavior: LoopBehavior::default(),
             scale: 1,
             images: Default::default(),
+            quantization: None,
+            frame_delay: None,
+            frame_delays: BTreeMap::new(),
+            stream: None,
         }
     }
 }
 
 impl PixelAnimationBuilder {
     pub fn new(
-        repeat: Repeat,
+        loop_behavior: LoopBehavior,
         scale: usize,
         images: impl IntoIterator<Item = DefaultImageBuffer>,
     ) -> Self {
         Self {
-            repeat,
+            loop_behavior,
             scale,
             images: images.into_iter().collect(),
+            quantization: None,
+            frame_delay: None,
+            frame_delays: BTreeMap::new(),
+            stream: None,
         }
     }
 
-    pub fn new_empty(repeat: Repeat, scale: usize) -> Self {
-        Self::new(repeat, scale, [])
+    pub fn new_empty(loop_behavior: LoopBehavior, scale: usize) -> Self {
+        Self::new(loop_behavior, scale, [])
     }
 
+    pub fn with_loop_behavior(mut self, loop_behavior: LoopBehavior) -> Self {
+        self.loop_behavior = loop_behavior;
+        self
+    }
+
+    pub fn loop_behavior(&self) -> LoopBehavior {
+        self.loop_behavior
+    }
+
+    /// Frames captured so far, always empty when [streaming](Self::stream_to).
+    pub fn frames(&self) -> &[DefaultImageBuffer] {
+        &self.images
+    }
+
+    /// Number of frames captured so far, including the streamed ones.
+    pub fn frame_len(&self) -> usize {
+        match &self.stream {
+            Some(stream) => stream.frames,
+            None => self.images.len(),
+        }
+    }
+
+    /// Encodes every captured frame right away as GIF into the `writer`, instead of keeping
+    /// all of them in memory. Useful for long or large animations.
+    ///
+    /// Call [`Self::finish`] when done to see if everything was written.
+    ///
+    /// Streamed frames are not kept, so custom [quantization](Self::with_quantization) and
+    /// [`LoopBehavior::Reverse`] are not supported, and [`Self::finish`] fails if either is set.
+    pub fn stream_to(mut self, writer: impl Write + 'static) -> Self {
+        self.stream = Some(GifStream::new(writer));
+        self
+    }
+
+    /// Streams the GIF into a file at `path`, see [`Self::stream_to`].
+    pub fn stream_to_file<P: AsRef<Path>>(self, path: P) -> ImageResult<Self> {
+        Ok(self.stream_to(BufWriter::new(File::create(path)?)))
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    /// Ends the [stream](Self::stream_to) by writing the end of the GIF and flushing the
+    /// writer, returning the first error happened while encoding.
+    ///
+    /// Fails if no frame was streamed, or the builder uses something streaming doesn't support.
+    /// Does nothing if the animation is not streamed.
+    pub fn finish(mut self) -> ImageResult<()> {
+        let unsupported = self.stream_unsupported();
+        match self.stream.take() {
+            Some(stream) => match unsupported {
+                Some(error) => Err(error),
+                None => stream.finish(),
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// The error for settings which can't be applied to streamed frames.
+    fn stream_unsupported(&self) -> Option<ImageError> {
+        if self.quantization.is_some() {
+            Some(gif_error(
+                "Streamed animations don't support custom quantization.",
+            ))
+        } else if let LoopBehavior::Reverse(_) = self.loop_behavior {
+            Some(gif_error(
+                "Streamed animations don't support playing in reverse.",
+            ))
+        } else {
+            None
+        }
+    }
+
+    /// Adds a captured frame, or encodes it if streaming.
+    pub(crate) fn push_image(&mut self, image: DefaultImageBuffer) {
+        let delay = self.frame_delay(self.frame_len());
+        let repeat = self.loop_behavior.gif_repeat();
+        let unsupported = self.stream_unsupported();
+        match &mut self.stream {
+            Some(stream) => match unsupported {
+                // No need to encode frames which are never going to be used.
+                Some(error) => {
+                    stream.error.get_or_insert(error);
+                }
+                None => stream.encode(image, delay, repeat),
+            },
+            None => self.images.push(image),
+        }
+    }
+
+    /// How long every frame is shown, unless it has its own delay.
+    ///
+    /// Without this, the default delay of the encoder is used.
+    pub fn with_frame_delay(mut self, delay: Duration) -> Self {
+        self.frame_delay = Some(delay);
+        self
+    }
+
+    /// How long the frame at `index` is shown, if it's not the encoder default.
+    pub fn frame_delay(&self, index: usize) -> Option<Duration> {
+        self.frame_delays.get(&index).copied().or(self.frame_delay)
+    }
+
+    /// The frames to encode, with [`LoopBehavior`] applied.
+    pub(crate) fn into_encoded_frames(self) -> (Repeat, Vec<DefaultImageBuffer>) {
+        (
+            self.loop_behavior.gif_repeat(),
+            self.loop_behavior.apply(self.images),
+        )
+    }
+
+    /// Delays of the frames to encode, in the same order as [`Self::into_encoded_frames`].
+    pub(crate) fn encoded_delays(&self) -> Vec<Option<Duration>> {
+        self.loop_behavior.apply(
+            (0..self.images.len())
+                .map(|index| self.frame_delay(index))
+                .collect(),
+        )
+    }
+
+    /// Use custom [`GifQuantization`] settings when saving the animation as GIF.
+    ///
+    /// Without this, the default quantization of the `image` crate is used.
+    pub fn with_quantization(mut self, quantization: GifQuantization) -> Self {
+        self.quantization = Some(quantization);
+        self
+    }
+
+    /// Saves the animation as GIF.
+    ///
+    /// Fails if the animation is [streamed](Self::stream_to), use [`Self::finish`] instead.
     pub fn save<P>(self, path: P) -> ImageResult<()>
     where
         P: AsRef<Path>,
     {
+        if self.is_streaming() {
+            return Err(ImageError::Encoding(EncodingError::new(
+                ImageFormatHint::Exact(ImageFormat::Gif),
+                "The animation is already streamed, finish it instead.",
+            )));
+        }
+
+        let quantization = self.quantization;
+        let delays = self.encoded_delays();
+        let (repeat, images) = self.into_encoded_frames();
+
+        if let Some(quantization) = &quantization {
+            return encode_quantized(
+                BufWriter::new(File::create(path)?),
+                repeat,
+                &images,
+                &delays,
+                quantization,
+            );
+        }
+
         let mut encoder = GifEncoder::new(File::create(path).unwrap());
-        encoder.set_repeat(self.repeat)?;
-        let frames = self.images.into_iter().map(Frame::new);
+        encoder.set_repeat(repeat)?;
+        let frames = images
+            .into_iter()
+            .zip(delays)
+            .map(|(image, delay)| match delay {
+                Some(delay) => {
+                    Frame::from_parts(image, 0, 0, Delay::from_saturating_duration(delay))
+                }
+                None => Frame::new(image),
+            });
         encoder.encode_frames(frames)?;
         Ok(())
     }
 
+    /// Saves all frames next to each other as a single image, see [`ImageStrip`].
+    pub fn save_sprite_sheet<P: AsRef<Path>>(&self, path: P) -> ImageResult<()> {
+        ImageStrip::new(self.images.iter().cloned()).save(path)
+    }
+
+    /// Saves every frame as a separate `frame_0000.png`, `frame_0001.png`, ... inside the directory.
+    ///
+    /// The directory is created if it doesn't exist.
+    pub fn save_frames<P: AsRef<Path>>(&self, dir: P) -> ImageResult<()> {
+        std::fs::create_dir_all(&dir)?;
+        for (index, image) in self.images.iter().enumerate() {
+            image.save(dir.as_ref().join(format!("frame_{index:04}.png")))?;
+        }
+        Ok(())
+    }
+
+    /// Saves the animation as an animated PNG, which (unlike GIF) keeps all colors.
+    pub fn save_apng<P: AsRef<Path>>(&self, path: P) -> ImageResult<()> {
+        let png_error = |e| {
+            ImageError::Encoding(EncodingError::new(
+                ImageFormatHint::Exact(ImageFormat::Png),
+                e,
+            ))
+        };
+
+        let images = self.loop_behavior.apply(self.images.clone());
+        let delays = self.encoded_delays();
+        let Some(first) = images.first() else {
+            return Err(ImageError::Encoding(EncodingError::new(
+                ImageFormatHint::Exact(ImageFormat::Png),
+                "At least one frame is required.",
+            )));
+        };
+
+        // GIF repeats count extra plays after the first one, APNG counts all plays.
+        let plays = match self.loop_behavior.gif_repeat() {
+            Repeat::Infinite => 0,
+            Repeat::Finite(repeats) => repeats as u32 + 1,
+        };
+
+        let mut encoder = png::Encoder::new(
+            BufWriter::new(File::create(path)?),
+            first.width(),
+            first.height(),
+        );
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .set_animated(images.len() as u32, plays)
+            .map_err(png_error)?;
+
+        let mut writer = encoder.write_header().map_err(png_error)?;
+        for (image, delay) in images.iter().zip(delays) {
+            // The delay sticks to later frames, so it's set for every one of them.
+            let (numerator, denominator) = match delay {
+                Some(delay) => (delay.as_millis().min(u16::MAX as u128) as u16, 1000),
+                None => (1, 10),
+            };
+            writer
+                .set_frame_delay(numerator, denominator)
+                .map_err(png_error)?;
+            writer.write_image_data(image.as_raw()).map_err(png_error)?;
+        }
+        writer.finish().map_err(png_error)
+    }
+
+    /// Shows the animation in the viewer, using the frame delays of this builder.
     #[cfg(feature = "viewer")]
     pub fn view(self) -> ViewResult {
-        view([self.images])
+        self.view_with_options(ViewerOptions::default())
+    }
+
+    /// As same as [`Self::view`], using the given [`ViewerOptions`].
+    #[cfg(feature = "viewer")]
+    pub fn view_with_options(self, options: ViewerOptions) -> ViewResult {
+        let delays = self.encoded_delays();
+        let (_, frames) = self.into_encoded_frames();
+        view_with_options([ViewEntry::new(frames).with_frame_delays(delays)], options)
     }
 
     pub(crate) fn get_frame_to_push<
@@ -101,12 +524,29 @@ impl PixelAnimationBuilder {
         P::ColorType: RgbaInterface + Default,
     {
         let frame = self.get_frame_to_push(value);
-        self.images.push(frame)
+        self.push_image(frame)
+    }
+
+    /// Like [`Self::push_frame_from_canvas`], but the frame is shown for `delay`.
+    pub fn push_frame_with_delay<
+        const H: usize,
+        const W: usize,
+        P: PixelInterface + Default,
+        I: PixelCanvasInterface<H, W, P>,
+    >(
+        &mut self,
+        value: &I,
+        delay: Duration,
+    ) where
+        P::ColorType: RgbaInterface + Default,
+    {
+        self.frame_delays.insert(self.frame_len(), delay);
+        self.push_frame_from_canvas(value)
     }
 }
 
 pub trait AnimatedContext<const H: usize, const W: usize, P: PixelInterface + Default> {
-    fn frame_count(&self) -> &Repeat;
+    fn frame_count(&self) -> &FrameCount;
 
     fn builder(&self) -> &PixelAnimationBuilder;
     fn builder_mut(&mut self) -> &mut PixelAnimationBuilder;
@@ -127,7 +567,7 @@ pub trait AnimatedContext<const H: usize, const W: usize, P: PixelInterface + De
         P::ColorType: Clone + crate::pixels::color::RgbaInterface + Default,
     {
         let frame = self.get_frame_to_capture();
-        self.builder_mut().images.push(frame);
+        self.builder_mut().push_image(frame);
     }
 }
 
@@ -143,7 +583,7 @@ pub struct AnimationContext<
 > where
     P::ColorType: Debug,
 {
-    frames: Repeat,
+    frames: FrameCount,
     pub builder: PixelAnimationBuilder,
     pub canvas: PixelCanvas<H, W, P>,
     extra: Extra,
@@ -155,27 +595,30 @@ where
     P: PartialEq + Clone,
     <P as PixelInterface>::ColorType: Debug,
 {
-    pub fn new(repeat: Repeat) -> AnimationContext<H, W, P, WithoutExtra>
+    pub fn new(frame_count: FrameCount) -> AnimationContext<H, W, P, WithoutExtra>
     where
         <P as PixelInterface>::ColorType: Clone + std::default::Default,
         P: PixelInitializer,
     {
         AnimationContext::<H, W, P, WithoutExtra> {
-            frames: repeat,
+            frames: frame_count,
             builder: Default::default(),
             canvas: Default::default(),
             extra: WithoutExtra,
         }
     }
 
-    pub fn new_with_extra<E>(repeat: Repeat, extra: E) -> AnimationContext<H, W, P, WithExtra<E>>
+    pub fn new_with_extra<E>(
+        frame_count: FrameCount,
+        extra: E,
+    ) -> AnimationContext<H, W, P, WithExtra<E>>
     where
         <P as PixelInterface>::ColorType: std::default::Default,
         <P as PixelInterface>::ColorType: Clone,
         P: PixelInitializer,
     {
         AnimationContext::<H, W, P, WithExtra<E>> {
-            frames: repeat,
+            frames: frame_count,
             builder: Default::default(),
             canvas: Default::default(),
             extra: WithExtra::<E>(extra),
@@ -188,7 +631,28 @@ where
     }
 
     pub fn with_gif_repeat(mut self, repeat: Repeat) -> Self {
-        self.builder.repeat = repeat;
+        self.builder.loop_behavior = LoopBehavior::Loop(repeat);
+        self
+    }
+
+    pub fn with_loop_behavior(mut self, loop_behavior: LoopBehavior) -> Self {
+        self.builder.loop_behavior = loop_behavior;
+        self
+    }
+
+    pub fn with_gif_quantization(mut self, quantization: GifQuantization) -> Self {
+        self.builder.quantization = Some(quantization);
+        self
+    }
+
+    pub fn with_frame_delay(mut self, delay: Duration) -> Self {
+        self.builder.frame_delay = Some(delay);
+        self
+    }
+
+    /// See [`PixelAnimationBuilder::stream_to`].
+    pub fn with_gif_stream(mut self, writer: impl Write + 'static) -> Self {
+        self.builder = self.builder.stream_to(writer);
         self
     }
 
@@ -220,7 +684,7 @@ impl<const H: usize, const W: usize, P: PixelInterface + Default, E> AnimatedCon
 where
     <P as PixelInterface>::ColorType: Debug,
 {
-    fn frame_count(&self) -> &Repeat {
+    fn frame_count(&self) -> &FrameCount {
         &self.frames
     }
 
@@ -264,7 +728,7 @@ where
         let mut ctx = self.create_context();
         self.setup(&mut ctx);
         match ctx.frame_count() {
-            Repeat::Finite(frame_count) => {
+            FrameCount::Finite(frame_count) => {
                 for i in 0..*frame_count {
                     if self.update(&mut ctx, i) {
                         self.finisher(&mut ctx, i);
@@ -274,7 +738,7 @@ where
                     }
                 }
             }
-            Repeat::Infinite => {
+            FrameCount::UntilStopped => {
                 let mut i = 0;
                 loop {
                     if self.update(&mut ctx, i) {
@@ -458,8 +922,8 @@ mod tests {
         let mut animation = SimpleAnimation::<5, 5, 1, 1, _, _>::new(
             TOP_LEFT,
             5,
-            Repeat::Infinite,
-            Repeat::Infinite,
+            LoopBehavior::default(),
+            FrameCount::UntilStopped,
             |ctx| {
                 ctx.update_body_color(YELLOW);
                 ctx.update_part_color(BLUE);
@@ -481,7 +945,7 @@ mod tests {
     #[test]
     fn feature() {
         Animation::new_with_finisher(
-            || AnimationContext::<5>::new_with_extra(Repeat::Finite(20), BLACK).with_scale(5),
+            || AnimationContext::<5>::new_with_extra(FrameCount::Finite(20), BLACK).with_scale(5),
             |ctx| {
                 let current_color = *ctx.extra();
                 ctx.canvas_mut().fill(current_color);
@@ -505,4 +969,238 @@ mod tests {
         .save("arts/animation_3.gif")
         .unwrap();
     }
+
+    #[test]
+    fn test_gif_quantization() {
+        std::fs::create_dir_all("arts/test").unwrap();
+        let path = "arts/test/animation_quantized.gif";
+        Animation::new(
+            || {
+                AnimationContext::<8>::new(FrameCount::Finite(8)).with_gif_quantization(
+                    GifQuantization::new(quantization::GifPalette::Global, 4, true),
+                )
+            },
+            |_| {},
+            |ctx, i| {
+                ctx.canvas_mut().fill(PixelColor::from_red(i as u8 * 30));
+                true
+            },
+        )
+        .create()
+        .builder
+        .save(path)
+        .unwrap();
+
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = options.read_info(File::open(path).unwrap()).unwrap();
+        assert!(decoder.global_palette().unwrap().len() <= 4 * 3);
+
+        let mut frames = 0;
+        while decoder.read_next_frame().unwrap().is_some() {
+            frames += 1;
+        }
+        assert_eq!(frames, 8);
+    }
+
+    #[test]
+    fn test_gif_quantization_too_large() {
+        // GIF sizes are 16 bits, this one must not be truncated to a single pixel.
+        let images = [DefaultImageBuffer::new(u16::MAX as u32 + 2, 1)];
+        assert!(encode_quantized(
+            std::io::sink(),
+            Repeat::Infinite,
+            &images,
+            &[None],
+            &GifQuantization::default(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_frame_delays() {
+        let mut canvas = PixelCanvas::<2>::default();
+        let mut builder = PixelAnimationBuilder::new_empty(LoopBehavior::default(), 1)
+            .with_frame_delay(Duration::from_millis(100));
+        builder.push_frame_from_canvas(&canvas);
+        canvas.fill(RED);
+        builder.push_frame_with_delay(&canvas, Duration::from_millis(500));
+        assert_eq!(builder.frame_delay(0), Some(Duration::from_millis(100)));
+        assert_eq!(builder.frame_delay(1), Some(Duration::from_millis(500)));
+
+        std::fs::create_dir_all("arts/test").unwrap();
+        let path = "arts/test/animation_delays.gif";
+        builder.save(path).unwrap();
+
+        let mut decoder = gif::DecodeOptions::new()
+            .read_info(File::open(path).unwrap())
+            .unwrap();
+        let mut delays = vec![];
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            delays.push(frame.delay);
+        }
+        // GIF delays are in centiseconds.
+        assert_eq!(delays, vec![10, 50]);
+    }
+
+    #[test]
+    fn test_apng_frame_delays() {
+        let mut canvas = PixelCanvas::<2>::default();
+        let mut builder = PixelAnimationBuilder::new_empty(LoopBehavior::default(), 1);
+        builder.push_frame_with_delay(&canvas, Duration::from_millis(500));
+        canvas.fill(RED);
+        builder.push_frame_from_canvas(&canvas);
+        canvas.fill(BLUE);
+        builder.push_frame_with_delay(&canvas, Duration::from_millis(250));
+
+        std::fs::create_dir_all("arts/test").unwrap();
+        let path = "arts/test/animation_delays.png";
+        builder.save_apng(path).unwrap();
+
+        let mut reader = png::Decoder::new(File::open(path).unwrap())
+            .read_info()
+            .unwrap();
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let mut delays = vec![];
+        for _ in 0..3 {
+            reader.next_frame(&mut buffer).unwrap();
+            let control = reader.info().frame_control().unwrap();
+            delays.push((control.delay_num, control.delay_den));
+        }
+        // The frame without a delay doesn't keep the one of the frame before it.
+        assert_eq!(delays, vec![(500, 1000), (1, 10), (250, 1000)]);
+    }
+
+    #[test]
+    fn test_streaming() {
+        std::fs::create_dir_all("arts/test").unwrap();
+        let path = "arts/test/animation_streamed.gif";
+
+        let context = Animation::new(
+            || {
+                AnimationContext::<4>::new(FrameCount::Finite(6))
+                    .with_gif_stream(BufWriter::new(File::create(path).unwrap()))
+            },
+            |_| {},
+            |ctx, i| {
+                ctx.canvas_mut().fill(PixelColor::from_green(i as u8 * 40));
+                true
+            },
+        )
+        .create();
+        assert!(context.builder.frames().is_empty());
+        assert_eq!(context.builder.frame_len(), 6);
+        context.builder.finish().unwrap();
+
+        let mut decoder = gif::DecodeOptions::new()
+            .read_info(File::open(path).unwrap())
+            .unwrap();
+        let mut frames = 0;
+        while decoder.read_next_frame().unwrap().is_some() {
+            frames += 1;
+        }
+        assert_eq!(frames, 6);
+
+        let streamed = PixelAnimationBuilder::default().stream_to(std::io::sink());
+        assert!(streamed.save("arts/test/never.gif").is_err());
+    }
+
+    #[test]
+    fn test_streaming_errors() {
+        let canvas = PixelCanvas::<2>::default();
+
+        // Nothing to make a valid GIF from.
+        let empty = PixelAnimationBuilder::default().stream_to(std::io::sink());
+        assert!(empty.finish().is_err());
+
+        let mut reversed = PixelAnimationBuilder::default()
+            .with_loop_behavior(LoopBehavior::Reverse(Repeat::Infinite))
+            .stream_to(std::io::sink());
+        reversed.push_frame_from_canvas(&canvas);
+        assert!(reversed.finish().is_err());
+
+        let mut quantized = PixelAnimationBuilder::default().stream_to(std::io::sink());
+        quantized.push_frame_from_canvas(&canvas);
+        let quantized = quantized.with_quantization(GifQuantization::default());
+        assert!(quantized.finish().is_err());
+
+        /// A writer which fails to flush.
+        struct Unflushable;
+
+        impl Write for Unflushable {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Err(std::io::ErrorKind::Other.into())
+            }
+        }
+
+        let mut unflushable = PixelAnimationBuilder::default().stream_to(Unflushable);
+        unflushable.push_frame_from_canvas(&canvas);
+        assert!(unflushable.finish().is_err());
+    }
+
+    #[test]
+    fn test_loop_behavior() {
+        let frames = vec![1, 2, 3, 4];
+
+        assert_eq!(
+            LoopBehavior::Loop(Repeat::Infinite).apply(frames.clone()),
+            frames
+        );
+        assert_eq!(
+            LoopBehavior::Reverse(Repeat::Infinite).apply(frames.clone()),
+            vec![1, 2, 3, 4, 3, 2]
+        );
+        assert_eq!(LoopBehavior::HoldLastFrame.apply(frames.clone()), frames);
+        assert!(matches!(
+            LoopBehavior::HoldLastFrame.gif_repeat(),
+            Repeat::Finite(0)
+        ));
+    }
+
+    #[test]
+    fn test_export_formats() {
+        let context = simple::create_simple_animation::<3, 3, 1, 1>(
+            TOP_LEFT,
+            1,
+            LoopBehavior::Reverse(Repeat::Infinite),
+            FrameCount::Finite(3),
+            |ctx| {
+                ctx.update_part_color(RED);
+            },
+            |_, ctx| {
+                if let Some(next) = ctx.part().position().next() {
+                    ctx.part_mut().crop_to(next);
+                    true
+                } else {
+                    false
+                }
+            },
+        );
+        let frames = context.builder.frames().len();
+        let frame_width = context.builder.frames()[0].width();
+
+        std::fs::create_dir_all("arts/test").unwrap();
+        context.save_apng("arts/test/export.png").unwrap();
+        context
+            .save_sprite_sheet("arts/test/export_sheet.png")
+            .unwrap();
+        context.save_frames("arts/test/export_frames").unwrap();
+
+        let decoder = image::codecs::png::PngDecoder::new(std::io::BufReader::new(
+            File::open("arts/test/export.png").unwrap(),
+        ))
+        .unwrap();
+        assert!(decoder.is_apng().unwrap());
+        let decoded = image::AnimationDecoder::into_frames(decoder.apng().unwrap());
+        // Played forward and back, without repeating the ends.
+        assert_eq!(decoded.count(), frames * 2 - 2);
+
+        let sheet = image::open("arts/test/export_sheet.png").unwrap();
+        assert_eq!(sheet.width(), frame_width * frames as u32);
+        assert!(std::path::Path::new("arts/test/export_frames/frame_0000.png").exists());
+    }
 }
@@ -0,0 +1,248 @@
+//! Color quantization settings used when encoding GIF animations.
+//!
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Write,
+    time::Duration,
+};
+
+use image::{
+    codecs::gif::Repeat,
+    error::{EncodingError, ImageFormatHint},
+    ImageError, ImageFormat, ImageResult,
+};
+
+use crate::{
+    image::DefaultImageBuffer,
+    pixels::color::{
+        quantize::{clamp_rgb, median_cut, nearest_index, ErrorDiffusion},
+        PixelColor,
+    },
+};
+
+use super::gif_error;
+
+/// Which palette(s) are used by GIF frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GifPalette {
+    /// Each frame gets its own palette, best for animations with changing colors.
+    #[default]
+    PerFrame,
+
+    /// A single palette is shared by all frames, which avoids flickering of color ramps.
+    Global,
+}
+
+/// Quantization settings for GIF export, see [`PixelAnimationBuilder::with_quantization`].
+///
+/// [`PixelAnimationBuilder::with_quantization`]: super::PixelAnimationBuilder::with_quantization
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GifQuantization {
+    palette: GifPalette,
+    max_colors: usize,
+    dithering: bool,
+}
+
+impl Default for GifQuantization {
+    fn default() -> Self {
+        Self {
+            palette: GifPalette::PerFrame,
+            max_colors: 256,
+            dithering: false,
+        }
+    }
+}
+
+impl GifQuantization {
+    pub fn new(palette: GifPalette, max_colors: usize, dithering: bool) -> Self {
+        Self::default()
+            .with_palette(palette)
+            .with_max_colors(max_colors)
+            .with_dithering(dithering)
+    }
+
+    pub fn with_palette(mut self, palette: GifPalette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Maximum colors of a palette, between 2 and 256.
+    pub fn with_max_colors(mut self, max_colors: usize) -> Self {
+        self.max_colors = max_colors.clamp(2, 256);
+        self
+    }
+
+    /// Use Floyd–Steinberg dithering when a color is not in the palette.
+    pub fn with_dithering(mut self, dithering: bool) -> Self {
+        self.dithering = dithering;
+        self
+    }
+
+    pub fn palette(&self) -> GifPalette {
+        self.palette
+    }
+
+    pub fn max_colors(&self) -> usize {
+        self.max_colors
+    }
+
+    pub fn dithering(&self) -> bool {
+        self.dithering
+    }
+}
+
+pub(crate) fn encoding_error(e: gif::EncodingError) -> ImageError {
+    ImageError::Encoding(EncodingError::new(
+        ImageFormatHint::Exact(ImageFormat::Gif),
+        e,
+    ))
+}
+
+fn has_transparency<'a>(images: impl IntoIterator<Item = &'a DefaultImageBuffer>) -> bool {
+    images
+        .into_iter()
+        .any(|image| image.pixels().any(|pixel| pixel.0[3] == 0))
+}
+
+fn build_palette<'a>(
+    images: impl IntoIterator<Item = &'a DefaultImageBuffer>,
+    max_colors: usize,
+) -> Vec<[u8; 3]> {
+    let mut colors = HashMap::<[u8; 3], u32>::new();
+    for image in images {
+        for pixel in image.pixels().filter(|pixel| pixel.0[3] != 0) {
+            *colors
+                .entry([pixel.0[0], pixel.0[1], pixel.0[2]])
+                .or_default() += 1;
+        }
+    }
+
+    let mut palette = median_cut(&colors, max_colors);
+    if palette.is_empty() {
+        palette.push([0, 0, 0]);
+    }
+    palette
+}
+
+/// Maps every pixel of the image to an index of the palette.
+fn index_image(
+    image: &DefaultImageBuffer,
+    palette: &[[u8; 3]],
+    transparent: Option<u8>,
+    dithering: bool,
+) -> Vec<u8> {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let colors: Vec<PixelColor> = palette
+        .iter()
+        .map(|[r, g, b]| PixelColor::new(*r, *g, *b))
+        .collect();
+    let mut indices = Vec::with_capacity(width * height);
+    let mut cache = BTreeMap::<PixelColor, u8>::new();
+    let mut diffusion = dithering.then(|| ErrorDiffusion::new(width, height));
+
+    for (i, pixel) in image.pixels().enumerate() {
+        if pixel.0[3] == 0 {
+            indices.push(transparent.unwrap_or_default());
+            continue;
+        }
+
+        let mut wanted = [pixel.0[0] as f32, pixel.0[1] as f32, pixel.0[2] as f32];
+        if let Some(diffusion) = &diffusion {
+            wanted = diffusion.adjust(i, wanted);
+        }
+
+        let index = *cache
+            .entry(clamp_rgb(wanted))
+            .or_insert_with_key(|color| nearest_index(&colors, color).unwrap_or_default() as u8);
+        indices.push(index);
+
+        if let Some(diffusion) = &mut diffusion {
+            diffusion.spread(i, wanted, &colors[index as usize]);
+        }
+    }
+    indices
+}
+
+fn flatten_palette(palette: &[[u8; 3]], transparent: bool) -> Vec<u8> {
+    let mut flat: Vec<u8> = palette.iter().flatten().copied().collect();
+    if transparent {
+        flat.extend([0, 0, 0]);
+    }
+    flat
+}
+
+/// Encodes the images as a GIF using the given [`GifQuantization`].
+pub(crate) fn encode_quantized<W: Write>(
+    writer: W,
+    repeat: Repeat,
+    images: &[DefaultImageBuffer],
+    delays: &[Option<Duration>],
+    quantization: &GifQuantization,
+) -> ImageResult<()> {
+    let Some(first) = images.first() else {
+        return Ok(());
+    };
+    let (Ok(width), Ok(height)) = (u16::try_from(first.width()), u16::try_from(first.height()))
+    else {
+        return Err(gif_error("The frame is too large for a GIF."));
+    };
+
+    let transparent = has_transparency(images);
+    let max_colors = if transparent {
+        quantization.max_colors.min(255)
+    } else {
+        quantization.max_colors
+    };
+
+    let global_palette = match quantization.palette {
+        GifPalette::Global => Some(build_palette(images, max_colors)),
+        GifPalette::PerFrame => None,
+    };
+
+    let mut encoder = gif::Encoder::new(
+        writer,
+        width,
+        height,
+        &global_palette
+            .as_ref()
+            .map(|palette| flatten_palette(palette, transparent))
+            .unwrap_or_default(),
+    )
+    .map_err(encoding_error)?;
+    encoder
+        .set_repeat(match repeat {
+            Repeat::Finite(n) => gif::Repeat::Finite(n),
+            Repeat::Infinite => gif::Repeat::Infinite,
+        })
+        .map_err(encoding_error)?;
+
+    for (index, image) in images.iter().enumerate() {
+        let (palette, own_palette) = match &global_palette {
+            Some(palette) => (palette.clone(), false),
+            None => (build_palette([image], max_colors), true),
+        };
+        let transparent_index = transparent.then_some(palette.len() as u8);
+        let indices = index_image(image, &palette, transparent_index, quantization.dithering);
+
+        let mut frame = if own_palette {
+            gif::Frame::from_palette_pixels(
+                width,
+                height,
+                indices,
+                flatten_palette(&palette, transparent),
+                transparent_index,
+            )
+        } else {
+            gif::Frame::from_indexed_pixels(width, height, indices, transparent_index)
+        };
+        frame.dispose = gif::DisposalMethod::Background;
+        if let Some(delay) = delays.get(index).copied().flatten() {
+            frame.delay = (delay.as_millis() / 10).min(u16::MAX as u128) as u16;
+        }
+
+        encoder.write_frame(&frame).map_err(encoding_error)?;
+    }
+
+    Ok(())
+}
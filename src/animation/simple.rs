@@ -1,7 +1,5 @@
 use std::path::Path;
 
-use image::codecs::gif::Repeat;
-
 use crate::{
     image::DefaultImageBuffer,
     pixels::{
@@ -12,16 +10,20 @@ use crate::{
     prelude::{MaybePixel, PixelCanvas, PixelColor},
 };
 
-use super::{Animated, AnimatedContext, PixelAnimationBuilder};
+use super::{
+    subpixel::Supersample, Animated, AnimatedContext, FrameCount, LoopBehavior,
+    PixelAnimationBuilder,
+};
 
 #[cfg(feature = "viewer")]
 use crate::viewer::ViewResult;
 
 pub struct SimpleAnimationContext<const H: usize, const W: usize, const PH: usize, const PW: usize>
 {
-    pub(crate) frame_count: Repeat,
+    pub(crate) frame_count: FrameCount,
     pub(crate) part: CanvasPartition<PH, PW, H, W, PixelCanvas<H, W, Pixel>, Pixel, MaybePixel>,
     pub(crate) builder: PixelAnimationBuilder,
+    pub(crate) supersample: Supersample,
 }
 
 impl<const H: usize, const W: usize, const PH: usize, const PW: usize> AnimatedContext<H, W, Pixel>
@@ -43,16 +45,23 @@ impl<const H: usize, const W: usize, const PH: usize, const PW: usize> AnimatedC
         self.body_mut()
     }
 
-    fn frame_count(&self) -> &Repeat {
+    fn frame_count(&self) -> &FrameCount {
         &self.frame_count
     }
+
+    /// The body with the part moved by its subpixel offset, see [`CanvasPartition::resolve_subpixel_on`].
+    fn get_frame_to_capture(&self) -> DefaultImageBuffer {
+        let mut canvas = self.body().clone();
+        self.part.resolve_subpixel_on(&mut canvas, self.supersample);
+        self.builder.get_frame_to_push(&canvas)
+    }
 }
 
 impl<const H: usize, const W: usize, const PH: usize, const PW: usize>
     SimpleAnimationContext<H, W, PH, PW>
 {
     pub fn new(
-        frame_count: Repeat,
+        frame_count: FrameCount,
         part: CanvasPartition<PH, PW, H, W, PixelCanvas<H, W, Pixel>, Pixel, MaybePixel>,
         builder: PixelAnimationBuilder,
     ) -> Self {
@@ -60,6 +69,7 @@ impl<const H: usize, const W: usize, const PH: usize, const PW: usize>
             part,
             builder,
             frame_count,
+            supersample: Supersample::default(),
         }
     }
 
@@ -91,9 +101,18 @@ impl<const H: usize, const W: usize, const PH: usize, const PW: usize>
         self
     }
 
+    /// Samples per pixel used when the part has a subpixel offset.
+    pub fn set_supersample(
+        &mut self,
+        supersample: Supersample,
+    ) -> &mut SimpleAnimationContext<H, W, PH, PW> {
+        self.supersample = supersample;
+        self
+    }
+
     pub fn capture(&mut self) -> &mut SimpleAnimationContext<H, W, PH, PW> {
-        self.builder
-            .push_frame_from_canvas(self.part.source_table());
+        let frame = self.get_frame_to_capture();
+        self.builder.push_image(frame);
         self
     }
 
@@ -101,6 +120,21 @@ impl<const H: usize, const W: usize, const PH: usize, const PW: usize>
         self.builder.save(path)
     }
 
+    /// See [`PixelAnimationBuilder::save_sprite_sheet`].
+    pub fn save_sprite_sheet<P: AsRef<Path>>(&self, path: P) -> Result<(), image::ImageError> {
+        self.builder.save_sprite_sheet(path)
+    }
+
+    /// See [`PixelAnimationBuilder::save_frames`].
+    pub fn save_frames<P: AsRef<Path>>(&self, dir: P) -> Result<(), image::ImageError> {
+        self.builder.save_frames(dir)
+    }
+
+    /// See [`PixelAnimationBuilder::save_apng`].
+    pub fn save_apng<P: AsRef<Path>>(&self, path: P) -> Result<(), image::ImageError> {
+        self.builder.save_apng(path)
+    }
+
     #[cfg(feature = "viewer")]
     pub fn view(self) -> ViewResult {
         self.builder.view()
@@ -131,10 +165,10 @@ pub struct SimpleAnimation<
     F1: FnOnce(&mut SimpleAnimationContext<H, W, PH, PW>) + Copy,
     F2: FnOnce(u16, &mut SimpleAnimationContext<H, W, PH, PW>) -> bool + Copy,
 > {
-    frame_count: Repeat,
+    frame_count: FrameCount,
     partition_position: PixelStrictPosition<H, W>,
     scale: usize,
-    gif_repeat: Repeat,
+    loop_behavior: LoopBehavior,
     setups: F1,
     updater: F2,
 }
@@ -151,8 +185,8 @@ impl<
     pub fn new(
         partition_position: impl IntoPixelStrictPosition<H, W>,
         scale: usize,
-        gif_repeat: Repeat,
-        frame_count: Repeat,
+        loop_behavior: LoopBehavior,
+        frame_count: FrameCount,
         setups: F1,
         updater: F2,
     ) -> Self {
@@ -160,13 +194,13 @@ impl<
             partition_position: partition_position.into_pixel_strict_position(),
             frame_count,
             scale,
-            gif_repeat,
+            loop_behavior,
             setups,
             updater,
         }
     }
 
-    pub fn frame_count(&self) -> Repeat {
+    pub fn frame_count(&self) -> FrameCount {
         self.frame_count
     }
 }
@@ -186,7 +220,8 @@ impl<
         Self::ContextType {
             frame_count: self.frame_count,
             part: CanvasPartition::new(self.partition_position, PixelCanvas::default()),
-            builder: PixelAnimationBuilder::new_empty(self.gif_repeat, self.scale),
+            builder: PixelAnimationBuilder::new_empty(self.loop_behavior, self.scale),
+            supersample: Supersample::default(),
         }
     }
 
@@ -202,15 +237,15 @@ impl<
 pub fn create_simple_animation<const H: usize, const W: usize, const PH: usize, const PW: usize>(
     partition_position: impl IntoPixelStrictPosition<H, W>,
     scale: usize,
-    gif_repeat: Repeat,
-    frame_count: Repeat,
+    loop_behavior: LoopBehavior,
+    frame_count: FrameCount,
     setups: impl FnOnce(&mut SimpleAnimationContext<H, W, PH, PW>) + Copy,
     updater: impl FnOnce(u16, &mut SimpleAnimationContext<H, W, PH, PW>) -> bool + Copy,
 ) -> SimpleAnimationContext<H, W, PH, PW> {
     let mut animation = SimpleAnimation::<H, W, PH, PW, _, _>::new(
         partition_position,
         scale,
-        gif_repeat,
+        loop_behavior,
         frame_count,
         setups,
         updater,
@@ -0,0 +1,449 @@
+//! Sprite animations organized as named states (idle, walk, jump, ...), each with its
+//! own frames and rules about which state comes next.
+//!
+
+use std::path::{Component, Path};
+
+use image::{GenericImage, ImageError};
+use thiserror::Error;
+
+use crate::{
+    image::DefaultImageBuffer,
+    pixels::{canvas::PixelCanvasInterface, color::RgbaInterface, PixelInterface},
+};
+
+use super::{LoopBehavior, PixelAnimationBuilder, Repeat};
+
+/// What makes a state move to another one.
+///
+/// It's serialized as the name of the event, or `null` for [`TransitionTrigger::OnEnd`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(from = "Option<String>", into = "Option<String>")
+)]
+pub enum TransitionTrigger {
+    /// Move as soon as all frames of the state are played.
+    OnEnd,
+
+    /// Move when the named event happens.
+    Event(String),
+}
+
+impl From<Option<String>> for TransitionTrigger {
+    fn from(value: Option<String>) -> Self {
+        match value {
+            Some(event) => Self::Event(event),
+            None => Self::OnEnd,
+        }
+    }
+}
+
+impl From<TransitionTrigger> for Option<String> {
+    fn from(value: TransitionTrigger) -> Self {
+        match value {
+            TransitionTrigger::OnEnd => None,
+            TransitionTrigger::Event(event) => Some(event),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transition {
+    #[cfg_attr(feature = "serde", serde(rename = "event"))]
+    pub trigger: TransitionTrigger,
+    pub to: String,
+}
+
+/// A named state with its frames and outgoing transitions.
+#[derive(Debug)]
+pub struct AnimationState {
+    name: String,
+    builder: PixelAnimationBuilder,
+    transitions: Vec<Transition>,
+}
+
+impl AnimationState {
+    pub fn new(name: impl Into<String>, scale: usize) -> Self {
+        Self {
+            name: name.into(),
+            builder: PixelAnimationBuilder::new_empty(LoopBehavior::Loop(Repeat::Infinite), scale),
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Go to state `to` after all frames of this state are played.
+    pub fn on_end(mut self, to: impl Into<String>) -> Self {
+        self.transitions.push(Transition {
+            trigger: TransitionTrigger::OnEnd,
+            to: to.into(),
+        });
+        self
+    }
+
+    /// Go to state `to` when `event` happens.
+    pub fn on_event(mut self, event: impl Into<String>, to: impl Into<String>) -> Self {
+        self.transitions.push(Transition {
+            trigger: TransitionTrigger::Event(event.into()),
+            to: to.into(),
+        });
+        self
+    }
+
+    pub fn with_loop_behavior(mut self, loop_behavior: LoopBehavior) -> Self {
+        self.builder = self.builder.with_loop_behavior(loop_behavior);
+        self
+    }
+
+    /// Adds a frame captured from the canvas.
+    pub fn with_frame<
+        const H: usize,
+        const W: usize,
+        P: PixelInterface + Default,
+        I: PixelCanvasInterface<H, W, P>,
+    >(
+        mut self,
+        canvas: &I,
+    ) -> Self
+    where
+        P::ColorType: RgbaInterface + Default,
+    {
+        self.builder.push_frame_from_canvas(canvas);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn frames(&self) -> &[DefaultImageBuffer] {
+        self.builder.frames()
+    }
+
+    pub fn transitions(&self) -> &[Transition] {
+        &self.transitions
+    }
+
+    /// The state to go next, if `event` (or the end of frames, when `None`) triggers any.
+    pub fn next_state(&self, event: Option<&str>) -> Option<&str> {
+        self.transitions
+            .iter()
+            .find(|transition| match (&transition.trigger, event) {
+                (TransitionTrigger::OnEnd, None) => true,
+                (TransitionTrigger::Event(expected), Some(event)) => expected == event,
+                _ => false,
+            })
+            .map(|transition| transition.to.as_str())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum StateMachineError {
+    #[error("There's no state named {0:?}.")]
+    UnknownState(String),
+    #[error("The state {0:?} has no frames.")]
+    EmptyState(String),
+    #[error("Frames of state {0:?} have a different size than the others.")]
+    FrameSizeMismatch(String),
+    #[error("State {0:?} can't be used as a file name.")]
+    InvalidFileName(String),
+    #[error(transparent)]
+    Image(#[from] ImageError),
+}
+
+/// Where a state is located in a sprite sheet created by [`SpriteStateMachine::sprite_sheet`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StateMetadata {
+    pub name: String,
+    pub row: usize,
+    #[cfg_attr(feature = "serde", serde(rename = "frames"))]
+    pub frame_count: usize,
+    pub transitions: Vec<Transition>,
+}
+
+/// Describes the layout of a sprite sheet, one row per state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpriteSheetMetadata {
+    pub frame_width: u32,
+    pub frame_height: u32,
+    pub initial: String,
+    pub states: Vec<StateMetadata>,
+}
+
+#[cfg(feature = "serde")]
+impl SpriteSheetMetadata {
+    /// Metadata as json, for engines that ingest sprite sheets (`serde` feature).
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Metadata has nothing json can't represent.")
+    }
+}
+
+/// Whether the name is a single normal path component, so it stays inside a directory.
+fn is_file_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    !name.contains(['/', '\\'])
+        && matches!(components.next(), Some(Component::Normal(_)))
+        && components.next().is_none()
+}
+
+/// A set of [`AnimationState`]s, starting at an initial one.
+#[derive(Debug)]
+pub struct SpriteStateMachine {
+    initial: String,
+    states: Vec<AnimationState>,
+}
+
+impl SpriteStateMachine {
+    pub fn new(initial: impl Into<String>) -> Self {
+        Self {
+            initial: initial.into(),
+            states: Vec::new(),
+        }
+    }
+
+    /// Adds a state, replacing any other one with the same name.
+    pub fn with_state(mut self, state: AnimationState) -> Self {
+        self.states.retain(|other| other.name != state.name);
+        self.states.push(state);
+        self
+    }
+
+    pub fn state(&self, name: &str) -> Option<&AnimationState> {
+        self.states.iter().find(|state| state.name == name)
+    }
+
+    pub fn states(&self) -> &[AnimationState] {
+        &self.states
+    }
+
+    fn get_state(&self, name: &str) -> Result<&AnimationState, StateMachineError> {
+        self.state(name)
+            .ok_or_else(|| StateMachineError::UnknownState(name.to_string()))
+    }
+
+    /// Plays the machine from the initial state for the given events and returns the visited states.
+    ///
+    /// Each event is handled after the current state finished its frames; if it doesn't trigger
+    /// any transition, [`TransitionTrigger::OnEnd`] is tried, and the state stays otherwise.
+    pub fn run<'e>(
+        &self,
+        events: impl IntoIterator<Item = &'e str>,
+    ) -> Result<Vec<&str>, StateMachineError> {
+        let mut current = self.get_state(&self.initial)?;
+        let mut visited = vec![current.name()];
+
+        for event in events {
+            if let Some(next) = current
+                .next_state(Some(event))
+                .or_else(|| current.next_state(None))
+            {
+                current = self.get_state(next)?;
+                visited.push(current.name());
+            }
+        }
+
+        Ok(visited)
+    }
+
+    /// Saves each state as a separate GIF named `<state>.gif` inside `dir`.
+    ///
+    /// Nothing is saved if a state name isn't a plain file name, e.g. `a/b` or `..`.
+    pub fn save_gifs(&self, dir: impl AsRef<Path>) -> Result<(), StateMachineError> {
+        if let Some(state) = self.states.iter().find(|state| !is_file_name(&state.name)) {
+            return Err(StateMachineError::InvalidFileName(state.name.clone()));
+        }
+
+        for state in &self.states {
+            let builder = PixelAnimationBuilder::new(
+                state.builder.loop_behavior(),
+                state.builder.scale,
+                state.frames().iter().cloned(),
+            );
+            builder.save(dir.as_ref().join(format!("{}.gif", state.name)))?;
+        }
+        Ok(())
+    }
+
+    /// Saves the frames of the visited states (see [`SpriteStateMachine::run`]) as a single GIF.
+    pub fn save_sequence<'e, P: AsRef<Path>>(
+        &self,
+        events: impl IntoIterator<Item = &'e str>,
+        loop_behavior: LoopBehavior,
+        path: P,
+    ) -> Result<(), StateMachineError> {
+        let mut frames = Vec::new();
+        for name in self.run(events)? {
+            frames.extend(self.get_state(name)?.frames().iter().cloned());
+        }
+
+        PixelAnimationBuilder::new(loop_behavior, 1, frames).save(path)?;
+        Ok(())
+    }
+
+    /// Puts all frames in one image, one row per state, together with the metadata describing it.
+    pub fn sprite_sheet(
+        &self,
+    ) -> Result<(DefaultImageBuffer, SpriteSheetMetadata), StateMachineError> {
+        let first = self
+            .states
+            .iter()
+            .find_map(|state| state.frames().first())
+            .ok_or_else(|| StateMachineError::EmptyState(self.initial.clone()))?;
+        let (frame_width, frame_height) = first.dimensions();
+
+        let columns = self
+            .states
+            .iter()
+            .map(|state| state.frames().len())
+            .max()
+            .unwrap_or_default();
+
+        let mut sheet = DefaultImageBuffer::new(
+            frame_width * columns as u32,
+            frame_height * self.states.len() as u32,
+        );
+        let mut states = Vec::with_capacity(self.states.len());
+
+        for (row, state) in self.states.iter().enumerate() {
+            if state.frames().is_empty() {
+                return Err(StateMachineError::EmptyState(state.name.clone()));
+            }
+
+            for (column, frame) in state.frames().iter().enumerate() {
+                if frame.dimensions() != (frame_width, frame_height) {
+                    return Err(StateMachineError::FrameSizeMismatch(state.name.clone()));
+                }
+                sheet.copy_from(
+                    frame,
+                    frame_width * column as u32,
+                    frame_height * row as u32,
+                )?;
+            }
+
+            states.push(StateMetadata {
+                name: state.name.clone(),
+                row,
+                frame_count: state.frames().len(),
+                transitions: state.transitions.clone(),
+            });
+        }
+
+        Ok((
+            sheet,
+            SpriteSheetMetadata {
+                frame_width,
+                frame_height,
+                initial: self.initial.clone(),
+                states,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::*;
+
+    fn filled(color: PixelColor) -> PixelCanvas<2> {
+        let mut canvas = PixelCanvas::<2>::default();
+        canvas.fill(color);
+        canvas
+    }
+
+    #[test]
+    fn test_state_machine() {
+        let machine = SpriteStateMachine::new("idle")
+            .with_state(
+                AnimationState::new("idle", 1)
+                    .with_frame(&filled(WHITE))
+                    .on_event("jump", "jump")
+                    .on_event("walk", "walk"),
+            )
+            .with_state(
+                AnimationState::new("walk", 1)
+                    .with_frame(&filled(BLUE))
+                    .with_frame(&filled(GREEN))
+                    .on_event("stop", "idle"),
+            )
+            .with_state(
+                AnimationState::new("jump", 1)
+                    .with_frame(&filled(RED))
+                    .with_frame(&filled(YELLOW))
+                    .with_frame(&filled(RED))
+                    .on_end("idle"),
+            );
+
+        assert_eq!(
+            machine
+                .run(["walk", "walk", "stop", "jump", "anything"])
+                .unwrap(),
+            vec!["idle", "walk", "idle", "jump", "idle"]
+        );
+
+        let (sheet, metadata) = machine.sprite_sheet().unwrap();
+        assert_eq!(
+            sheet.dimensions(),
+            (metadata.frame_width * 3, metadata.frame_height * 3)
+        );
+        assert_eq!(metadata.states[2].frame_count, 3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_metadata_json() {
+        let machine = SpriteStateMachine::new("idle")
+            .with_state(
+                AnimationState::new("idle", 1)
+                    .with_frame(&filled(WHITE))
+                    .on_event("say \"hi\"\u{1b}", "jump"),
+            )
+            .with_state(
+                AnimationState::new("jump", 1)
+                    .with_frame(&filled(RED))
+                    .on_end("idle"),
+            );
+
+        let (_, metadata) = machine.sprite_sheet().unwrap();
+        let json = metadata.to_json();
+        assert!(json.contains(
+            r#"{"name":"jump","row":1,"frames":1,"transitions":[{"event":null,"to":"idle"}]}"#
+        ));
+        assert!(json.contains(r#"{"event":"say \"hi\"\u001b","to":"jump"}"#));
+        assert_eq!(
+            serde_json::from_str::<SpriteSheetMetadata>(&json).unwrap(),
+            metadata
+        );
+    }
+
+    #[test]
+    fn test_save_gifs_file_names() {
+        let dir = "arts/test/state_machine";
+        std::fs::create_dir_all(dir).unwrap();
+
+        for name in ["../escaped", "a/b", "a\\b", "..", ".", ""] {
+            let machine = SpriteStateMachine::new("idle")
+                .with_state(AnimationState::new("idle", 1).with_frame(&filled(WHITE)))
+                .with_state(AnimationState::new(name, 1).with_frame(&filled(RED)));
+            assert!(
+                matches!(
+                    machine.save_gifs(dir),
+                    Err(StateMachineError::InvalidFileName(invalid)) if invalid == name
+                ),
+                "{name:?} was accepted"
+            );
+        }
+        assert!(!Path::new("arts/test/escaped.gif").exists());
+
+        SpriteStateMachine::new("idle")
+            .with_state(AnimationState::new("idle", 1).with_frame(&filled(WHITE)))
+            .save_gifs(dir)
+            .unwrap();
+        assert!(Path::new(dir).join("idle.gif").exists());
+    }
+}
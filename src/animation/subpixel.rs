@@ -0,0 +1,282 @@
+//! Fractional (subpixel) positioning, by rendering at a higher resolution internally and
+//! downsampling to the canvas size.
+//!
+//! Layers ([`LayerOffset`](crate::pixels::canvas::layered::LayerOffset)) and partitions
+//! ([`CanvasPartition::set_subpixel_offset`](crate::pixels::canvas::partition::CanvasPartition::set_subpixel_offset))
+//! carry a [`SubpixelOffset`] which is resolved when the canvas is composed or captured.
+//! [`SubpixelCanvas`] places loose sprites the same way.
+//!
+
+use crate::{
+    pixels::{PixelInterface, PixelMutInterface},
+    prelude::{MaybePixelCanvas, PixelCanvas, PixelColor},
+};
+
+/// How many samples per pixel (on each axis) are used while rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Supersample {
+    /// Half a pixel steps.
+    #[default]
+    X2,
+
+    /// Quarter of a pixel steps.
+    X4,
+}
+
+impl Supersample {
+    pub fn factor(&self) -> usize {
+        match self {
+            Supersample::X2 => 2,
+            Supersample::X4 => 4,
+        }
+    }
+}
+
+/// How far something is moved down and right, in [`SubpixelOffset::STEPS`] of a pixel,
+/// on top of its whole pixel position.
+///
+/// [`Supersample::X2`] snaps it to the nearest half of a pixel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SubpixelOffset {
+    row: u8,
+    column: u8,
+}
+
+impl SubpixelOffset {
+    /// Number of steps a pixel is divided into.
+    pub const STEPS: u8 = 4;
+
+    pub const ZERO: Self = Self { row: 0, column: 0 };
+
+    /// Splits a fractional `(row, column)` into whole pixels and the nearest steps.
+    pub fn split((row, column): (f32, f32)) -> ((isize, isize), Self) {
+        let split = |value: f32| {
+            let steps = (value * Self::STEPS as f32).round() as isize;
+            let steps_per_pixel = Self::STEPS as isize;
+            (
+                steps.div_euclid(steps_per_pixel),
+                steps.rem_euclid(steps_per_pixel) as u8,
+            )
+        };
+        let (row, row_steps) = split(row);
+        let (column, column_steps) = split(column);
+        (
+            (row, column),
+            Self {
+                row: row_steps,
+                column: column_steps,
+            },
+        )
+    }
+
+    /// `(row, column)` of the offset as fractions of a pixel, e.g. `(0.5, 0.0)`.
+    pub fn fraction(&self) -> (f32, f32) {
+        (
+            self.row as f32 / Self::STEPS as f32,
+            self.column as f32 / Self::STEPS as f32,
+        )
+    }
+
+    pub fn is_zero(&self) -> bool {
+        *self == Self::ZERO
+    }
+}
+
+/// Opaque colors placed at fractional positions on `factor` times the resolution of a
+/// `(height, width)` canvas.
+pub(crate) struct SampleGrid {
+    factor: usize,
+    height: usize,
+    width: usize,
+    samples: Vec<Option<PixelColor>>,
+}
+
+impl SampleGrid {
+    pub(crate) fn new((height, width): (usize, usize), supersample: Supersample) -> Self {
+        let factor = supersample.factor();
+        Self {
+            factor,
+            height,
+            width,
+            samples: vec![None; height * factor * width * factor],
+        }
+    }
+
+    /// Covers the samples of `pixels` with their `(0, 0)` at a fractional `(row, column)`,
+    /// snapped to the nearest sample. Later pixels are on top.
+    pub(crate) fn place(
+        &mut self,
+        (row, column): (f32, f32),
+        pixels: impl IntoIterator<Item = ((usize, usize), PixelColor)>,
+    ) {
+        let factor = self.factor as isize;
+        let (height, width) = (
+            (self.height * self.factor) as isize,
+            (self.width * self.factor) as isize,
+        );
+        let top = (row * factor as f32).round() as isize;
+        let left = (column * factor as f32).round() as isize;
+
+        for ((row, column), color) in pixels {
+            let sample_row = top + row as isize * factor;
+            let sample_column = left + column as isize * factor;
+
+            for r in sample_row.max(0)..(sample_row + factor).min(height) {
+                for c in sample_column.max(0)..(sample_column + factor).min(width) {
+                    self.samples[(r * width + c) as usize] = Some(color);
+                }
+            }
+        }
+    }
+
+    /// Average color of the covered samples of each pixel which has any, with the share of
+    /// its samples they cover.
+    pub(crate) fn resolve(&self) -> impl Iterator<Item = ((usize, usize), PixelColor, f32)> + '_ {
+        let factor = self.factor;
+        let width = self.width * factor;
+        (0..self.height)
+            .flat_map(move |row| (0..self.width).map(move |column| (row, column)))
+            .filter_map(move |(row, column)| {
+                let mut sum = [0usize; 3];
+                let mut count = 0;
+                for r in row * factor..(row + 1) * factor {
+                    for color in self.samples[r * width + column * factor..]
+                        .iter()
+                        .take(factor)
+                        .flatten()
+                    {
+                        sum[0] += color.r as usize;
+                        sum[1] += color.g as usize;
+                        sum[2] += color.b as usize;
+                        count += 1;
+                    }
+                }
+                if count == 0 {
+                    return None;
+                }
+
+                let average = |sum: usize| ((sum + count / 2) / count) as u8;
+                Some((
+                    (row, column),
+                    PixelColor::new(average(sum[0]), average(sum[1]), average(sum[2])),
+                    count as f32 / (factor * factor) as f32,
+                ))
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SubpixelSprite {
+    position: (f32, f32),
+    pixels: Vec<((usize, usize), PixelColor)>,
+}
+
+/// A canvas which sprites can be placed on at fractional `(row, column)`s.
+///
+/// Use [`SubpixelCanvas::render`] to get a regular canvas where pixels partially covered
+/// by a sprite are blended with what's below, which gives smoother perceived motion than
+/// whole-pixel jumps. Sprite pixels are opaque: a pixel with a color covers its samples,
+/// one without is skipped.
+///
+/// ## Example
+/// ```rust
+/// # use pixelart::{animation::{subpixel::*, AnimationContext, AnimatedContext, FrameCount}, prelude::*};
+/// let mut sprite = MaybePixelCanvas::<1>::default();
+/// sprite.update_color_at(TOP_LEFT, BLACK);
+///
+/// let mut subpixel = SubpixelCanvas::<1, 3>::new(PixelCanvas::default(), Supersample::X2);
+/// let mut ctx = AnimationContext::<1, 3>::new(FrameCount::Finite(4));
+/// for i in 0..4 {
+///     subpixel.clear_sprites();
+///     subpixel.place((0.0, i as f32 * 0.5), &sprite);
+///     ctx.canvas = subpixel.render();
+///     ctx.capture();
+/// }
+/// assert_eq!(ctx.builder.frames().len(), 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SubpixelCanvas<const H: usize, const W: usize = H> {
+    supersample: Supersample,
+    base: PixelCanvas<H, W>,
+    sprites: Vec<SubpixelSprite>,
+}
+
+impl<const H: usize, const W: usize> SubpixelCanvas<H, W> {
+    pub fn new(base: PixelCanvas<H, W>, supersample: Supersample) -> Self {
+        Self {
+            supersample,
+            base,
+            sprites: Vec::new(),
+        }
+    }
+
+    pub fn base(&self) -> &PixelCanvas<H, W> {
+        &self.base
+    }
+
+    pub fn base_mut(&mut self) -> &mut PixelCanvas<H, W> {
+        &mut self.base
+    }
+
+    /// Places a sprite with its top left at a fractional position, later sprites are drawn on top.
+    ///
+    /// The position is snapped to the nearest sample, e.g. to halves of a pixel on [`Supersample::X2`].
+    pub fn place<const HS: usize, const WS: usize>(
+        &mut self,
+        position: (f32, f32),
+        sprite: &MaybePixelCanvas<HS, WS>,
+    ) -> &mut Self {
+        let pixels = sprite
+            .real_items()
+            .filter_map(|(pos, pixel)| pixel.color().map(|color| (pos, color)))
+            .collect();
+        self.sprites.push(SubpixelSprite { position, pixels });
+        self
+    }
+
+    /// Removes all placed sprites, keeping the base.
+    pub fn clear_sprites(&mut self) {
+        self.sprites.clear();
+    }
+
+    /// Renders sprites at `factor` times the resolution, then averages every `factor` * `factor` samples.
+    pub fn render(&self) -> PixelCanvas<H, W> {
+        let mut grid = SampleGrid::new((H, W), self.supersample);
+        for sprite in self.sprites.iter() {
+            grid.place(sprite.position, sprite.pixels.iter().copied());
+        }
+
+        let mut result = self.base.clone();
+        for (position, color, coverage) in grid.resolve() {
+            let blended = result.get_pixel(position).color().lerp(color, coverage);
+            result.get_pixel_mut(position).update_color(blended);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_half_pixel_blends() {
+        let mut sprite = MaybePixelCanvas::<1>::default();
+        sprite.update_color_at(TOP_LEFT, BLACK);
+
+        let mut canvas = SubpixelCanvas::<1, 3>::new(PixelCanvas::default(), Supersample::X2);
+        canvas.place((0.0, 0.5), &sprite);
+
+        let rendered = canvas.render();
+        assert_eq!(*rendered.get_pixel((0, 0)).color(), PixelColor::splat(128));
+        assert_eq!(*rendered.get_pixel((0, 1)).color(), PixelColor::splat(128));
+        assert_eq!(*rendered.get_pixel((0, 2)).color(), WHITE);
+
+        canvas.clear_sprites();
+        canvas.place((0.0, 1.0), &sprite);
+        assert_eq!(*canvas.render().get_pixel((0, 1)).color(), BLACK);
+    }
+}
@@ -0,0 +1,261 @@
+//! Declarative motion: values interpolated between keyframes, see [`Tween`] and [`Timeline`].
+//!
+
+use crate::pixels::{
+    color::PixelColor,
+    position::{PixelStrictPosition, PixelStrictPositionInterface},
+};
+
+use super::easing::Easing;
+
+/// Values which can be interpolated between two keyframes.
+pub trait Tweenable: Clone {
+    /// The value at `t` between this (`0.0`) and `other` (`1.0`).
+    ///
+    /// `t` may go a bit outside of `0.0..=1.0` with overshooting easings.
+    fn tween(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Tweenable for f32 {
+    fn tween(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Tweenable for PixelColor {
+    fn tween(&self, other: &Self, t: f32) -> Self {
+        self.lerp(*other, t)
+    }
+}
+
+impl<const H: usize, const W: usize> Tweenable for PixelStrictPosition<H, W> {
+    /// Rows and columns are rounded to the nearest pixel and kept inside the canvas.
+    fn tween(&self, other: &Self, t: f32) -> Self {
+        let axis = |from: usize, to: usize, max: usize| {
+            (from as f32)
+                .tween(&(to as f32), t)
+                .round()
+                .clamp(0., max as f32 - 1.) as usize
+        };
+        let (row, column) = self.expand();
+        let (other_row, other_column) = other.expand();
+        PixelStrictPosition::new(axis(row, other_row, H), axis(column, other_column, W))
+            .expect("Tweened position is clamped into the canvas.")
+    }
+}
+
+/// A value of a [`Tween`] at a specific frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyframe<T> {
+    pub frame: u16,
+    pub value: T,
+    /// How the value moves from the previous keyframe to this one.
+    pub easing: Easing,
+}
+
+/// A value changing over frames, interpolated between [`Keyframe`]s.
+///
+/// Before the first keyframe the value is the first one, and after the last keyframe
+/// it stays the last one.
+///
+/// ## Example
+/// ```rust
+/// # use pixelart::{animation::{easing::Easing, tween::Tween}, prelude::*};
+/// let color = Tween::new(BLACK)
+///     .with_keyframe(4, WHITE)
+///     .with_eased_keyframe(8, RED, Easing::EaseOutQuad);
+/// assert_eq!(color.value_at(2), Some(PixelColor::new(128, 128, 128)));
+/// assert_eq!(color.value_at(20), Some(RED));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tween<T> {
+    keyframes: Vec<Keyframe<T>>,
+}
+
+impl<T> Default for Tween<T> {
+    fn default() -> Self {
+        Self {
+            keyframes: Vec::new(),
+        }
+    }
+}
+
+impl<T: Tweenable> Tween<T> {
+    /// A tween starting with `value` at frame `0`.
+    pub fn new(value: T) -> Self {
+        Self::default().with_keyframe(0, value)
+    }
+
+    /// Adds a keyframe reached linearly, see [`Self::with_eased_keyframe`].
+    pub fn with_keyframe(self, frame: u16, value: T) -> Self {
+        self.with_eased_keyframe(frame, value, Easing::Linear)
+    }
+
+    /// Adds a keyframe, replacing any other one at the same frame.
+    pub fn with_eased_keyframe(mut self, frame: u16, value: T, easing: Easing) -> Self {
+        self.insert(Keyframe {
+            frame,
+            value,
+            easing,
+        });
+        self
+    }
+
+    /// Adds a keyframe, replacing any other one at the same frame.
+    pub fn insert(&mut self, keyframe: Keyframe<T>) {
+        match self
+            .keyframes
+            .binary_search_by_key(&keyframe.frame, |k| k.frame)
+        {
+            Ok(index) => self.keyframes[index] = keyframe,
+            Err(index) => self.keyframes.insert(index, keyframe),
+        }
+    }
+
+    /// Keyframes sorted by their frame.
+    pub fn keyframes(&self) -> &[Keyframe<T>] {
+        &self.keyframes
+    }
+
+    /// Frame of the last keyframe, after which the value doesn't change.
+    pub fn last_frame(&self) -> Option<u16> {
+        self.keyframes.last().map(|keyframe| keyframe.frame)
+    }
+
+    /// The value at `frame`, `None` if there are no keyframes.
+    pub fn value_at(&self, frame: u16) -> Option<T> {
+        let next = self.keyframes.partition_point(|k| k.frame <= frame);
+        let (Some(before), Some(after)) = (
+            next.checked_sub(1).map(|index| &self.keyframes[index]),
+            self.keyframes.get(next),
+        ) else {
+            // Outside the keyframes, hold the closest one.
+            let index = next.min(self.keyframes.len()).saturating_sub(1);
+            return self.keyframes.get(index).map(|k| k.value.clone());
+        };
+
+        let t = (frame - before.frame) as f32 / (after.frame - before.frame) as f32;
+        Some(before.value.tween(&after.value, after.easing.apply(t)))
+    }
+}
+
+type Track<'a, C> = Box<dyn Fn(&mut C, u16) + 'a>;
+
+/// A set of [`Tween`]s applied together on a context (like an [`AnimationContext`]),
+/// e.g. from [`Animated::update`].
+///
+/// [`AnimationContext`]: super::AnimationContext
+/// [`Animated::update`]: super::Animated::update
+///
+/// ## Example
+/// ```rust
+/// # use pixelart::{animation::tween::{Timeline, Tween}, prelude::*};
+/// let mut timeline = Timeline::<PixelCanvas<5>>::new();
+/// timeline
+///     .track(Tween::new(pos!(0, 0; 5, 5)).with_keyframe(4, pos!(4, 4; 5, 5)), |canvas, pos| {
+///         canvas.update_color_at(pos, RED);
+///     })
+///     .track(Tween::new(BLUE).with_keyframe(4, GREEN), |canvas, color| {
+///         canvas.update_color_at(TOP_RIGHT, color);
+///     });
+///
+/// let mut canvas = PixelCanvas::<5>::default();
+/// timeline.apply(&mut canvas, 2);
+/// assert_eq!(canvas.color_at(CENTER), RED);
+/// assert_eq!(timeline.frame_count(), 5);
+/// ```
+pub struct Timeline<'a, C> {
+    tracks: Vec<Track<'a, C>>,
+    frame_count: u16,
+}
+
+impl<C> Default for Timeline<'_, C> {
+    fn default() -> Self {
+        Self {
+            tracks: Vec::new(),
+            frame_count: 0,
+        }
+    }
+}
+
+impl<'a, C> Timeline<'a, C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a tween, whose value at each frame is given to `apply`.
+    pub fn track<T: Tweenable + 'a>(
+        &mut self,
+        tween: Tween<T>,
+        apply: impl Fn(&mut C, T) + 'a,
+    ) -> &mut Self {
+        if let Some(last_frame) = tween.last_frame() {
+            self.frame_count = self.frame_count.max(last_frame.saturating_add(1));
+        }
+        self.tracks.push(Box::new(move |context, frame| {
+            if let Some(value) = tween.value_at(frame) {
+                apply(context, value)
+            }
+        }));
+        self
+    }
+
+    /// Number of frames until all tracks reach their last keyframe.
+    pub fn frame_count(&self) -> u16 {
+        self.frame_count
+    }
+
+    /// Applies the values of all tracks at `frame`, in the order they were added.
+    pub fn apply(&self, context: &mut C, frame: u16) {
+        for track in self.tracks.iter() {
+            track(context, frame)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_tween() {
+        let empty = Tween::<f32>::default();
+        assert_eq!(empty.value_at(3), None);
+
+        let tween = Tween::new(0.)
+            .with_keyframe(10, 10.)
+            .with_eased_keyframe(20, 0., Easing::EaseInQuad)
+            .with_keyframe(10, 20.);
+        assert_eq!(tween.keyframes().len(), 3);
+        assert_eq!(tween.value_at(5), Some(10.));
+        assert_eq!(tween.value_at(10), Some(20.));
+        assert_eq!(tween.value_at(15), Some(15.));
+        assert_eq!(tween.value_at(25), Some(0.));
+
+        let late = Tween::default().with_keyframe(4, 1.);
+        assert_eq!(late.value_at(0), Some(1.));
+
+        let position = Tween::new(pos!(0, 0; 3, 5)).with_keyframe(4, pos!(2, 4; 3, 5));
+        assert_eq!(position.value_at(2), Some(pos!(1, 2; 3, 5)));
+    }
+
+    #[test]
+    fn test_timeline() {
+        let mut timeline = Timeline::<Vec<PixelColor>>::new();
+        timeline
+            .track(
+                Tween::new(BLACK).with_keyframe(2, WHITE),
+                |colors, color| colors.push(color),
+            )
+            .track(Tween::new(RED).with_keyframe(6, BLUE), |colors, color| {
+                colors.push(color)
+            });
+        assert_eq!(timeline.frame_count(), 7);
+
+        let mut colors = vec![];
+        timeline.apply(&mut colors, 6);
+        assert_eq!(colors, vec![WHITE, BLUE]);
+    }
+}
@@ -3,11 +3,10 @@
 
 use std::any::Any;
 
-use image::codecs::gif::Repeat;
 use rand::{rngs::ThreadRng, Rng};
 
 use crate::{
-    animation::{Animated, Animation, AnimationContext},
+    animation::{Animated, Animation, AnimationContext, FrameCount},
     pixels::canvas::templates::square,
     prelude::*,
 };
@@ -60,7 +59,7 @@ fn random_animation() {
     let rng: ThreadRng = Default::default();
 
     Animation::new(
-        || AnimationContext::<50>::new_with_extra(Repeat::Finite(10), ThreadRng::default()),
+        || AnimationContext::<50>::new_with_extra(FrameCount::Finite(10), ThreadRng::default()),
         |ctx| {
             let mut rng = rng.clone();
             ctx.canvas.iter_pixels_mut().for_each(|mut pixel| {
@@ -102,5 +101,6 @@ fn _1() {
     }
 
     // let px = square::<4>(RED);
+    std::fs::create_dir_all("arts/test").unwrap();
     px.default_image_builder().save("arts/test/1.png").unwrap();
 }
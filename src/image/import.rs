@@ -0,0 +1,196 @@
+//! Imports existing pictures (e.g. photos) into canvases, optionally constrained to a [`Palette`].
+//!
+
+use std::path::Path;
+
+use image::{imageops, DynamicImage, ImageResult};
+
+use crate::{
+    pixels::{
+        color::{
+            palettes::Palette,
+            quantize::{clamp_rgb, ErrorDiffusion},
+        },
+        PixelInitializer, PixelMutInterface,
+    },
+    prelude::{PixelCanvas, PixelColor},
+};
+
+/// Pixels with lower alpha are left untouched (transparent on a [`MaybePixelCanvas`]).
+///
+/// [`MaybePixelCanvas`]: crate::pixels::canvas::MaybePixelCanvas
+const ALPHA_THRESHOLD: u8 = 128;
+
+/// 4x4 Bayer threshold matrix, used by [`Dithering::Ordered`].
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// How colors which are not in the palette are approximated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dithering {
+    /// Just the nearest color of the palette.
+    #[default]
+    None,
+
+    /// Spreads the error of each pixel to its neighbors, smooth but noisy.
+    FloydSteinberg,
+
+    /// Adds a fixed (4x4 Bayer) pattern before picking the nearest color, gives a retro look.
+    Ordered,
+}
+
+/// Options of importing a picture into a canvas, see [`PixelCanvas::from_image`].
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    palette: Option<Palette>,
+    dithering: Dithering,
+}
+
+impl ImportOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only use colors of this palette, otherwise colors are kept as is.
+    pub fn with_palette(mut self, palette: impl Into<Palette>) -> Self {
+        self.palette = Some(palette.into());
+        self
+    }
+
+    /// Dithering against the palette, it has no effect without a palette.
+    pub fn with_dithering(mut self, dithering: Dithering) -> Self {
+        self.dithering = dithering;
+        self
+    }
+
+    pub fn palette(&self) -> Option<&Palette> {
+        self.palette.as_ref()
+    }
+
+    pub fn dithering(&self) -> Dithering {
+        self.dithering
+    }
+
+    /// Maps `H` * `W` pixels of rgba, row by row, to colors of the palette.
+    fn map_colors<const H: usize, const W: usize>(
+        &self,
+        pixels: &[[u8; 4]],
+    ) -> Vec<Option<PixelColor>> {
+        let Some(palette) = &self.palette else {
+            return pixels
+                .iter()
+                .map(|[r, g, b, a]| (*a >= ALPHA_THRESHOLD).then(|| PixelColor::new(*r, *g, *b)))
+                .collect();
+        };
+
+        // Ordered dithering spreads around as much as the average gap between colors.
+        let spread = 255. / (palette.len() as f32).cbrt();
+        let mut diffusion = ErrorDiffusion::new(W, H);
+        let mut result = Vec::with_capacity(H * W);
+
+        for (i, [r, g, b, a]) in pixels.iter().enumerate() {
+            if *a < ALPHA_THRESHOLD {
+                result.push(None);
+                continue;
+            }
+
+            let (row, column) = (i / W, i % W);
+            let mut wanted = [*r as f32, *g as f32, *b as f32];
+            match self.dithering {
+                Dithering::None => {}
+                Dithering::FloydSteinberg => wanted = diffusion.adjust(i, wanted),
+                Dithering::Ordered => {
+                    let threshold = (BAYER_4X4[row % 4][column % 4] as f32 + 0.5) / 16. - 0.5;
+                    for value in wanted.iter_mut() {
+                        *value += threshold * spread;
+                    }
+                }
+            }
+
+            let got = palette.nearest(clamp_rgb(wanted));
+            result.push(Some(got));
+
+            if self.dithering == Dithering::FloydSteinberg {
+                diffusion.spread(i, wanted, &got);
+            }
+        }
+
+        result
+    }
+}
+
+impl<const H: usize, const W: usize, P> PixelCanvas<H, W, P>
+where
+    P: PixelMutInterface + PixelInitializer + PartialEq + Clone + Default,
+    P::ColorType: Default + Clone + From<PixelColor>,
+{
+    /// Creates a canvas from a picture, which is resized to `H` * `W` first.
+    ///
+    /// Mostly transparent pixels of the picture keep the default color.
+    pub fn from_image(image: &DynamicImage, options: &ImportOptions) -> Self {
+        let resized = imageops::resize(
+            &image.to_rgba8(),
+            W as u32,
+            H as u32,
+            imageops::FilterType::Triangle,
+        );
+        let pixels: Vec<[u8; 4]> = resized.pixels().map(|pixel| pixel.0).collect();
+
+        let mut canvas = Self::default();
+        for (i, color) in options.map_colors::<H, W>(&pixels).into_iter().enumerate() {
+            if let Some(color) = color {
+                canvas.get_pixel_mut((i / W, i % W)).update_color(color);
+            }
+        }
+        canvas
+    }
+
+    /// Opens a picture from the path and imports it, see [`PixelCanvas::from_image`].
+    pub fn open<Q: AsRef<Path>>(path: Q, options: &ImportOptions) -> ImageResult<Self> {
+        Ok(Self::from_image(&image::open(path)?, options))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{Rgba, RgbaImage};
+
+    use crate::{pixels::color::palettes::GAME_BOY, prelude::*};
+
+    use super::*;
+
+    #[test]
+    fn test_import() {
+        // Horizontal gray ramp with a transparent first row.
+        let image = RgbaImage::from_fn(8, 4, |x, y| {
+            let value = (x * 255 / 7) as u8;
+            Rgba([value, value, value, if y == 0 { 0 } else { 255 }])
+        });
+        let image = DynamicImage::ImageRgba8(image);
+
+        let plain = MaybePixelCanvas::<4, 8>::from_image(&image, &ImportOptions::new());
+        assert_eq!(plain.color_at(TOP_LEFT), None);
+        assert_eq!(plain.color_at(BOTTOM_RIGHT), Some(WHITE));
+
+        for dithering in [
+            Dithering::None,
+            Dithering::FloydSteinberg,
+            Dithering::Ordered,
+        ] {
+            let options = ImportOptions::new()
+                .with_palette(GAME_BOY)
+                .with_dithering(dithering);
+            let canvas = PixelCanvas::<4, 8>::from_image(&image, &options);
+
+            assert_eq!(canvas.color_at(TOP_LEFT), WHITE);
+            if dithering == Dithering::None {
+                assert_eq!(canvas.color_at(BOTTOM_LEFT), GAME_BOY[0]);
+                assert_eq!(canvas.color_at(BOTTOM_RIGHT), GAME_BOY[3]);
+            }
+            for row in 1..4 {
+                for column in 0..8 {
+                    assert!(GAME_BOY.contains(canvas.get_pixel((row, column)).color()));
+                }
+            }
+        }
+    }
+}
@@ -20,7 +20,11 @@ use crate::{
 };
 
 #[cfg(feature = "viewer")]
-use crate::viewer::ViewResult;
+use crate::viewer::{ViewEntry, ViewResult};
+
+pub mod import;
+pub mod snapshot;
+pub mod strip;
 
 pub type DefaultImageBuffer = ImageBuffer<Rgba<u8>, Vec<u8>>;
 
@@ -57,6 +61,87 @@ impl PixelImageStyle {
         self.border_width *= scale;
         self
     }
+
+    /// Width and height of the image of a `height` * `width` canvas.
+    pub(crate) fn image_size(&self, height: usize, width: usize) -> (usize, usize) {
+        let separator_pixel_length = self.border_width;
+
+        // How many pixels in height for blocks
+        let blocks_pixel_in_height = height * self.pixel_width;
+        let separators_count_in_height = height + 1;
+        // How many pixels in height for separator
+        let separators_pixel_in_height = separators_count_in_height * separator_pixel_length;
+        let image_height = blocks_pixel_in_height + separators_pixel_in_height;
+
+        let blocks_pixel_in_width = width * self.pixel_width;
+        let separators_count_in_width = width + 1;
+        let separators_pixel_in_width = separators_count_in_width * separator_pixel_length;
+        let image_width = blocks_pixel_in_width + separators_pixel_in_width;
+
+        (image_width, image_height)
+    }
+
+    /// Size of a pixel with one of its borders, the distance between two pixels on the image.
+    #[cfg(feature = "viewer")]
+    pub(crate) fn cell_size(&self) -> usize {
+        self.pixel_width + self.border_width
+    }
+
+    /// Draws the pixel at `row` and `column` with its border.
+    pub(crate) fn draw_pixel(
+        &self,
+        image: &mut DefaultImageBuffer,
+        row: usize,
+        column: usize,
+        color: Rgba<u8>,
+    ) {
+        let start_row = (row * self.border_width) + (row * self.pixel_width);
+        let start_column = (column * self.border_width) + (column * self.pixel_width);
+
+        let bw = self.border_width;
+        let pw = self.pixel_width;
+        let bpw = bw + pw;
+
+        draw_filled_rect_mut(
+            image,
+            Rect::at(start_column as i32, start_row as i32).of_size((bpw) as u32, bw as u32),
+            self.border_color,
+        );
+
+        draw_filled_rect_mut(
+            image,
+            Rect::at((start_column + bpw) as i32, start_row as i32)
+                .of_size(bw as u32, (bpw) as u32),
+            self.border_color,
+        );
+
+        draw_filled_rect_mut(
+            image,
+            Rect::at(start_column as i32, (start_row + bw) as i32).of_size(bw as u32, (bpw) as u32),
+            self.border_color,
+        );
+
+        draw_filled_rect_mut(
+            image,
+            Rect::at((start_column + bw) as i32, (start_row + bpw) as i32)
+                .of_size((bpw) as u32, bw as u32),
+            self.border_color,
+        );
+
+        // Draw the pixel
+        let start_x_pixel = start_row + bw;
+        let start_y_pixel = start_column + bw;
+
+        for i in 0..self.pixel_width {
+            for j in 0..self.pixel_width {
+                image.draw_pixel(
+                    (i + start_y_pixel) as u32,
+                    (j + start_x_pixel) as u32,
+                    color,
+                )
+            }
+        }
+    }
 }
 
 /// A type which can help generating [`ImageBuffer`] from a [`PixelCanvasInterface`].
@@ -99,33 +184,24 @@ where
         }
     }
 
+    /// Width and height of the resulting image.
+    fn dimensions(&self) -> (usize, usize) {
+        self.style.image_size(H, W)
+    }
+
     fn get_pixel_paper_image(&self) -> DefaultImageBuffer
     where
         P::ColorType: RgbaInterface + Default,
     {
-        let separator_pixel_length = self.style.border_width;
+        let (width, height) = self.dimensions();
 
-        // How many pixels in height for blocks
-        let blocks_pixel_in_height = H * self.style.pixel_width;
-        let separators_count_in_height = H + 1;
-        // How many pixels in height for separator
-        let separators_pixel_in_height = separators_count_in_height * separator_pixel_length;
-        let height = blocks_pixel_in_height + separators_pixel_in_height;
-
-        let blocks_pixel_in_width = W * self.style.pixel_width;
-        let separators_count_in_width = W + 1;
-        let separators_pixel_in_width = separators_count_in_width * separator_pixel_length;
-        let width = blocks_pixel_in_width + separators_pixel_in_width;
-
-        let image = if P::TRANSPARENT {
+        if P::TRANSPARENT {
             // Transparent image, no need to fill with any color (just empty).
             ImageBuffer::new(width as u32, height as u32)
         } else {
             // Filled with default color.
             ImageBuffer::from_pixel(width as u32, height as u32, P::ColorType::default().rgba())
-        };
-
-        image
+        }
     }
 
     /// Draws a pixel with its border.
@@ -133,56 +209,8 @@ where
     where
         P::ColorType: RgbaInterface,
     {
-        // Draw pixel border
-        let row = pos.row();
-        let column = pos.column();
-
-        let start_row = (row * self.style.border_width) + (row * self.style.pixel_width);
-        let start_column = (column * self.style.border_width) + (column * self.style.pixel_width);
-
-        let bw = self.style.border_width;
-        let pw = self.style.pixel_width;
-        let bpw = bw + pw;
-
-        draw_filled_rect_mut(
-            image,
-            Rect::at(start_column as i32, start_row as i32).of_size((bpw) as u32, bw as u32),
-            self.style.border_color,
-        );
-
-        draw_filled_rect_mut(
-            image,
-            Rect::at((start_column + bpw) as i32, start_row as i32)
-                .of_size(bw as u32, (bpw) as u32),
-            self.style.border_color,
-        );
-
-        draw_filled_rect_mut(
-            image,
-            Rect::at(start_column as i32, (start_row + bw) as i32).of_size(bw as u32, (bpw) as u32),
-            self.style.border_color,
-        );
-
-        draw_filled_rect_mut(
-            image,
-            Rect::at((start_column + bw) as i32, (start_row + bpw) as i32)
-                .of_size((bpw) as u32, bw as u32),
-            self.style.border_color,
-        );
-
-        // Draw the pixel
-        let start_x_pixel = start_row + bw;
-        let start_y_pixel = start_column + bw;
-
-        for i in 0..self.style.pixel_width {
-            for j in 0..self.style.pixel_width {
-                image.draw_pixel(
-                    (i + start_y_pixel) as u32,
-                    (j + start_x_pixel) as u32,
-                    pixel.color().rgba(),
-                )
-            }
-        }
+        self.style
+            .draw_pixel(image, pos.row(), pos.column(), pixel.color().rgba());
     }
 
     /// Draws the associated [`PixelCanvasInterface`] to an image buffer.
@@ -196,7 +224,7 @@ where
         // So, we only need to draw the pixels that are real, because everything else is default.
         for ((row, column), pixel) in table.real_items() {
             if pixel.has_color() {
-                self.draw_pixel_on_image(PixelPosition::new(*row, *column), pixel, image)
+                self.draw_pixel_on_image(PixelPosition::new(row, column), pixel, image)
             }
         }
     }
@@ -222,6 +250,70 @@ where
         image.save(path)
     }
 
+    /// Returns an SVG document of the canvas, where every pixel (and its border) is a `<rect>`.
+    ///
+    /// Unlike [`PixelImageBuilder::get_image`], this stays crisp at any zoom level.
+    pub fn get_svg(&self) -> String
+    where
+        P::ColorType: RgbaInterface + Default,
+    {
+        let (width, height) = self.dimensions();
+        let bw = self.style.border_width;
+        let pw = self.style.pixel_width;
+
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" shape-rendering="crispEdges">"#
+        );
+        svg.push('\n');
+
+        if !P::TRANSPARENT {
+            svg_rect(
+                &mut svg,
+                0,
+                0,
+                width,
+                height,
+                P::ColorType::default().rgba(),
+            );
+        }
+
+        for ((row, column), pixel) in self.canvas_ref.table().real_items() {
+            if pixel.has_color() {
+                let y = row * (bw + pw);
+                let x = column * (bw + pw);
+                if bw > 0 {
+                    svg_rect(
+                        &mut svg,
+                        x,
+                        y,
+                        pw + 2 * bw,
+                        pw + 2 * bw,
+                        self.style.border_color,
+                    );
+                }
+                svg_rect(&mut svg, x + bw, y + bw, pw, pw, pixel.color().rgba());
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Saves the SVG document from [`PixelImageBuilder::get_svg`] to a file at specified path.
+    pub fn save_svg<Q>(&self, path: Q) -> std::io::Result<()>
+    where
+        P::ColorType: RgbaInterface + Default,
+        Q: AsRef<Path>,
+    {
+        std::fs::write(path, self.get_svg())
+    }
+
+    /// An entry of the viewer whose grid matches pixels of the canvas.
+    #[cfg(feature = "viewer")]
+    fn view_entry(&self, frames: impl IntoIterator<Item = DefaultImageBuffer>) -> ViewEntry {
+        ViewEntry::new(frames).with_grid_cell(self.style.cell_size() as u32)
+    }
+
     #[cfg(feature = "viewer")]
     /// View the image inside a window.
     pub fn view(&self) -> ViewResult
@@ -229,7 +321,7 @@ where
         P::ColorType: RgbaInterface + Default,
     {
         let image = self.get_image();
-        crate::viewer::view([[image]])
+        crate::viewer::view([self.view_entry([image])])
     }
 
     #[cfg(feature = "viewer")]
@@ -239,8 +331,8 @@ where
         P::ColorType: RgbaInterface + Default,
         T: IntoIterator<Item = DefaultImageBuffer>,
     {
-        let first_image = vec![self.get_image()];
-        let others: Vec<Vec<_>> = others.into_iter().map(|f| vec![f]).collect();
+        let first_image = self.view_entry([self.get_image()]);
+        let others = others.into_iter().map(|f| ViewEntry::new([f]));
         crate::viewer::view([first_image].into_iter().chain(others))
     }
 
@@ -253,8 +345,19 @@ where
     {
         let image = self.get_image();
         let images: Vec<_> = [image].into_iter().chain(others).collect();
-        crate::viewer::view([images])
+        crate::viewer::view([self.view_entry(images)])
+    }
+}
+
+fn svg_rect(svg: &mut String, x: usize, y: usize, width: usize, height: usize, color: Rgba<u8>) {
+    let Rgba([r, g, b, a]) = color;
+    svg.push_str(&format!(
+        r##"<rect x="{x}" y="{y}" width="{width}" height="{height}" fill="#{r:02x}{g:02x}{b:02x}""##
+    ));
+    if a != u8::MAX {
+        svg.push_str(&format!(r#" fill-opacity="{:.3}""#, a as f32 / 255.));
     }
+    svg.push_str("/>\n");
 }
 
 #[cfg(test)]
@@ -294,4 +397,21 @@ mod tests {
             .save("arts/image_1.png")
             .unwrap();
     }
+
+    #[test]
+    fn svg_test() {
+        let mut canvas = MaybePixelCanvas::<3>::default();
+        canvas
+            .iter_pixels_mut()
+            .filter_position(|(row, column)| column == row)
+            .update_colors(PixelColor::MAGENTA);
+
+        let svg = canvas.default_image_builder().get_svg();
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="34" height="34""#)
+        );
+        // A border and the pixel itself for each of the 3 colored pixels.
+        assert_eq!(svg.matches("<rect").count(), 6);
+        assert!(svg.contains(r##"<rect x="12" y="12" width="10" height="10" fill="#ff00ff"/>"##));
+    }
 }
@@ -0,0 +1,218 @@
+//! Headless rendering and image comparison, to write golden-image tests without a window.
+//!
+
+use std::path::{Path, PathBuf};
+
+use image::ImageError;
+use thiserror::Error;
+
+use crate::pixels::{canvas::PixelCanvasInterface, color::RgbaInterface, PixelInterface};
+
+use super::{DefaultImageBuffer, PixelImageBuilder, PixelImageStyle};
+
+/// Renders the canvas to an image using the given style.
+pub fn render_to_buffer<const H: usize, const W: usize, P, I>(
+    canvas: &I,
+    style: PixelImageStyle,
+) -> DefaultImageBuffer
+where
+    I: PixelCanvasInterface<H, W, P>,
+    P: PixelInterface + Default,
+    P::ColorType: RgbaInterface + Default,
+{
+    PixelImageBuilder::new(canvas, style).get_image()
+}
+
+/// Why two images didn't match in [`compare_images`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ImageMismatch {
+    #[error("Expected an image of {expected:?} (width, height) but got {actual:?}.")]
+    Size {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+
+    #[error("{count} pixels are different, the first one at {first:?} (x, y), by up to {max_difference}.")]
+    Pixels {
+        /// Number of pixels which differ more than the tolerance.
+        count: usize,
+        first: (u32, u32),
+        /// The largest difference of a single channel.
+        max_difference: u8,
+    },
+}
+
+/// Compares two images pixel by pixel.
+///
+/// Each channel (including alpha) of a pixel may differ by up to `tolerance`.
+///
+/// ## Example
+/// ```rust
+/// # use pixelart::{image::{snapshot::*, PixelImageStyle}, prelude::*};
+/// let mut canvas = PixelCanvas::<3>::default();
+/// canvas.fill(BLUE);
+/// let expected = render_to_buffer(&canvas, PixelImageStyle::default());
+///
+/// canvas.update_color_at(CENTER, PixelColor::new(0, 0, 250));
+/// let actual = render_to_buffer(&canvas, PixelImageStyle::default());
+///
+/// assert!(compare_images(&expected, &actual, 0).is_err());
+/// assert!(compare_images(&expected, &actual, 5).is_ok());
+/// ```
+pub fn compare_images(
+    expected: &DefaultImageBuffer,
+    actual: &DefaultImageBuffer,
+    tolerance: u8,
+) -> Result<(), ImageMismatch> {
+    if expected.dimensions() != actual.dimensions() {
+        return Err(ImageMismatch::Size {
+            expected: expected.dimensions(),
+            actual: actual.dimensions(),
+        });
+    }
+
+    let mut count = 0;
+    let mut first = None;
+    let mut max_difference = 0;
+    for ((x, y, expected), actual) in expected.enumerate_pixels().zip(actual.pixels()) {
+        let difference = expected
+            .0
+            .iter()
+            .zip(actual.0)
+            .map(|(e, a)| e.abs_diff(a))
+            .max()
+            .unwrap_or_default();
+        if difference > tolerance {
+            count += 1;
+            first.get_or_insert((x, y));
+            max_difference = max_difference.max(difference);
+        }
+    }
+
+    match first {
+        Some(first) => Err(ImageMismatch::Pixels {
+            count,
+            first,
+            max_difference,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Error of [`match_snapshot`].
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("Can't read or write the snapshot: {0}")]
+    Image(#[from] ImageError),
+
+    #[error("The image doesn't match the snapshot: {0}")]
+    Mismatch(#[from] ImageMismatch),
+
+    #[error("There's no snapshot at {0:?}, set {UPDATE_SNAPSHOTS_VAR} to record it.")]
+    Missing(PathBuf),
+}
+
+/// Environment variable which makes [`match_snapshot`] record snapshots instead of comparing.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "PIXELART_UPDATE_SNAPSHOTS";
+
+/// Compares the image with a golden image file, see [`compare_images`].
+///
+/// A missing snapshot is an error. Run with [`UPDATE_SNAPSHOTS_VAR`] set to save the image
+/// as the snapshot instead, whether the file exists or not.
+pub fn match_snapshot(
+    actual: &DefaultImageBuffer,
+    path: impl AsRef<Path>,
+    tolerance: u8,
+) -> Result<(), SnapshotError> {
+    let update = std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some();
+    match_snapshot_inner(actual, path.as_ref(), tolerance, update)
+}
+
+fn match_snapshot_inner(
+    actual: &DefaultImageBuffer,
+    path: &Path,
+    tolerance: u8,
+    update: bool,
+) -> Result<(), SnapshotError> {
+    if update {
+        actual.save(path)?;
+        return Ok(());
+    }
+
+    if !path.exists() {
+        return Err(SnapshotError::Missing(path.to_path_buf()));
+    }
+
+    let expected = image::open(path)?.into_rgba8();
+    compare_images(&expected, actual, tolerance)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_compare_images() {
+        let mut canvas = PixelCanvas::<3>::default();
+        canvas.fill(PixelColor::new(200, 200, 200));
+        let expected = render_to_buffer(&canvas, PixelImageStyle::default());
+        assert_eq!(expected.dimensions(), (34, 34));
+        assert_eq!(compare_images(&expected, &expected, 0), Ok(()));
+
+        canvas.update_color_at(TOP_LEFT, PixelColor::new(200, 190, 200));
+        let actual = render_to_buffer(&canvas, PixelImageStyle::default());
+        assert_eq!(
+            compare_images(&expected, &actual, 2),
+            Err(ImageMismatch::Pixels {
+                count: 100,
+                first: (1, 1),
+                max_difference: 10,
+            })
+        );
+        assert_eq!(compare_images(&expected, &actual, 10), Ok(()));
+
+        let scaled = render_to_buffer(&canvas, PixelImageStyle::default().with_scale(2));
+        assert_eq!(
+            compare_images(&expected, &scaled, 255),
+            Err(ImageMismatch::Size {
+                expected: (34, 34),
+                actual: (68, 68),
+            })
+        );
+    }
+
+    #[test]
+    fn test_match_snapshot() {
+        std::fs::create_dir_all("arts/test").unwrap();
+        let path = Path::new("arts/test/snapshot.png");
+        let _ = std::fs::remove_file(path);
+
+        let mut canvas = PixelCanvas::<3>::default();
+        canvas.update_color_at(CENTER, RED);
+        let image = render_to_buffer(&canvas, PixelImageStyle::default());
+        assert!(matches!(
+            match_snapshot_inner(&image, path, 0, false),
+            Err(SnapshotError::Missing(missing)) if missing == path
+        ));
+        assert!(!path.exists());
+
+        match_snapshot_inner(&image, path, 0, true).unwrap();
+        match_snapshot_inner(&image, path, 0, false).unwrap();
+
+        canvas.update_color_at(CENTER, BLUE);
+        let updated = render_to_buffer(&canvas, PixelImageStyle::default());
+        assert!(matches!(
+            match_snapshot_inner(&updated, path, 0, false),
+            Err(SnapshotError::Mismatch(ImageMismatch::Pixels {
+                count: 100,
+                ..
+            }))
+        ));
+
+        match_snapshot_inner(&updated, path, 0, true).unwrap();
+        match_snapshot_inner(&updated, path, 0, false).unwrap();
+    }
+}
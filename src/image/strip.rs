@@ -0,0 +1,153 @@
+//! Exports a group of related images (e.g. all rotations of a sprite) as a single
+//! horizontal strip or a multi-page TIFF.
+//!
+
+use std::{fs::File, io::BufWriter, path::Path};
+
+use image::{
+    error::{EncodingError, ImageFormatHint},
+    GenericImage, ImageError, ImageFormat, ImageResult, Rgba,
+};
+use tiff::encoder::{colortype::RGBA8, TiffEncoder};
+
+use crate::{
+    pixels::{canvas::PixelCanvasInterface, color::RgbaInterface, PixelInterface},
+    prelude::PixelColor,
+};
+
+use super::{DefaultImageBuffer, PixelImageBuilder, PixelImageStyle};
+
+/// A sequence of images which are exported together.
+#[derive(Debug, Clone)]
+pub struct ImageStrip {
+    images: Vec<DefaultImageBuffer>,
+    spacing: u32,
+    background: Rgba<u8>,
+}
+
+impl Default for ImageStrip {
+    fn default() -> Self {
+        Self::new([])
+    }
+}
+
+impl ImageStrip {
+    pub fn new(images: impl IntoIterator<Item = DefaultImageBuffer>) -> Self {
+        Self {
+            images: images.into_iter().collect(),
+            spacing: 0,
+            background: Rgba([0, 0, 0, 0]),
+        }
+    }
+
+    /// Empty pixels between two images of the strip.
+    pub fn with_spacing(mut self, spacing: u32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Color of spacing and of the area below shorter images, transparent by default.
+    pub fn with_background(mut self, color: impl Into<PixelColor>) -> Self {
+        self.background = color.into().rgba();
+        self
+    }
+
+    pub fn push_image(&mut self, image: DefaultImageBuffer) -> &mut Self {
+        self.images.push(image);
+        self
+    }
+
+    /// Adds image of a canvas, drawn using the given style.
+    pub fn push_canvas<
+        const H: usize,
+        const W: usize,
+        P: PixelInterface + Default,
+        I: PixelCanvasInterface<H, W, P>,
+    >(
+        &mut self,
+        canvas: &I,
+        style: PixelImageStyle,
+    ) -> &mut Self
+    where
+        P::ColorType: RgbaInterface + Default,
+    {
+        self.push_image(PixelImageBuilder::new(canvas, style).get_image())
+    }
+
+    pub fn images(&self) -> &[DefaultImageBuffer] {
+        &self.images
+    }
+
+    /// Puts all images next to each other, top aligned.
+    pub fn get_image(&self) -> DefaultImageBuffer {
+        let height = self
+            .images
+            .iter()
+            .map(|image| image.height())
+            .max()
+            .unwrap_or_default();
+        let width = self.images.iter().map(|image| image.width()).sum::<u32>()
+            + self.spacing * (self.images.len().max(1) as u32 - 1);
+
+        let mut strip = DefaultImageBuffer::from_pixel(width, height, self.background);
+        let mut x = 0;
+        for image in self.images.iter() {
+            strip
+                .copy_from(image, x, 0)
+                .expect("The strip is large enough for all images.");
+            x += image.width() + self.spacing;
+        }
+        strip
+    }
+
+    /// Saves the strip as a single image, the format is deduced from the path (usually png).
+    pub fn save<Q: AsRef<Path>>(&self, path: Q) -> ImageResult<()> {
+        self.get_image().save(path)
+    }
+
+    /// Saves every image as a separate page of a TIFF file.
+    pub fn save_tiff<Q: AsRef<Path>>(&self, path: Q) -> ImageResult<()> {
+        let tiff_error = |e| {
+            ImageError::Encoding(EncodingError::new(
+                ImageFormatHint::Exact(ImageFormat::Tiff),
+                e,
+            ))
+        };
+
+        let mut encoder =
+            TiffEncoder::new(BufWriter::new(File::create(path)?)).map_err(tiff_error)?;
+        for image in self.images.iter() {
+            encoder
+                .write_image::<RGBA8>(image.width(), image.height(), image.as_raw())
+                .map_err(tiff_error)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_strip() {
+        let mut strip = ImageStrip::default().with_spacing(3).with_background(BLACK);
+        strip
+            .push_canvas(&PixelCanvas::<2>::default(), PixelImageStyle::default())
+            .push_canvas(&PixelCanvas::<3>::default(), PixelImageStyle::default());
+
+        let image = strip.get_image();
+        let (first, second) = (
+            strip.images()[0].dimensions(),
+            strip.images()[1].dimensions(),
+        );
+        assert_eq!(image.width(), first.0 + 3 + second.0);
+        assert_eq!(image.height(), second.1);
+        assert_eq!(image.get_pixel(first.0 + 1, 0), &BLACK.rgba());
+
+        std::fs::create_dir_all("arts/test").unwrap();
+        strip.save_tiff("arts/test/strip.tiff").unwrap();
+    }
+}
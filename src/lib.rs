@@ -12,7 +12,7 @@ let mut canvas = PixelCanvas::<5>::default();
 let pos = StrictPositions::TopRight;
 
 // Update color of a pixel.
-let prev_color = canvas[pos].update_color(BLACK);
+let prev_color = canvas.get_pixel_mut(pos).update_color(BLACK);
 assert_eq!(prev_color, WHITE);
 assert_eq!(canvas[pos].color(), &BLACK);
 
@@ -21,7 +21,7 @@ canvas
     // Iterate over all pixels row by row
     .iter_pixels_mut()
     // Filter main diagonal only pixels.
-    .filter_position(|p| p.column() == p.row())
+    .filter_position(|(row, column)| row == column)
     // Update the pixel color for each item in iterator.
     .update_colors(RED);
 
@@ -41,6 +41,7 @@ pub mod growth;
 pub mod image;
 pub mod pixels;
 pub mod prelude;
+pub mod terminal;
 
 #[cfg(feature = "viewer")]
 pub mod viewer;
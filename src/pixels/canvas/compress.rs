@@ -0,0 +1,218 @@
+//! Compact representations of canvas content, as horizontal runs or rectangles of a single color.
+//!
+
+use crate::pixels::{PixelInitializer, PixelInterface, PixelMutInterface};
+
+use super::{PixelCanvas, PixelCanvasInterface};
+
+/// A horizontal span of `length` pixels with the same color, starting at `(row, column)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorRun<C> {
+    pub row: usize,
+    pub column: usize,
+    pub length: usize,
+    pub color: C,
+}
+
+/// A `height` * `width` rectangle of pixels with the same color, starting at `(row, column)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorRect<C> {
+    pub row: usize,
+    pub column: usize,
+    pub height: usize,
+    pub width: usize,
+    pub color: C,
+}
+
+pub(crate) fn to_runs<const H: usize, const W: usize, P, I>(
+    canvas: &I,
+) -> Vec<ColorRun<P::ColorType>>
+where
+    P: PixelInterface + Default,
+    P::ColorType: Clone + PartialEq,
+    I: PixelCanvasInterface<H, W, P>,
+{
+    let table = canvas.table();
+    let mut runs = Vec::new();
+
+    for row in 0..H {
+        let mut column = 0;
+        while column < W {
+            let color = table.get_pixel((row, column)).color().clone();
+            let mut length = 1;
+            while column + length < W && table.get_pixel((row, column + length)).color() == &color {
+                length += 1;
+            }
+
+            runs.push(ColorRun {
+                row,
+                column,
+                length,
+                color,
+            });
+            column += length;
+        }
+    }
+
+    runs
+}
+
+pub(crate) fn to_rects<const H: usize, const W: usize, P, I>(
+    canvas: &I,
+) -> Vec<ColorRect<P::ColorType>>
+where
+    P: PixelInterface + Default,
+    P::ColorType: Clone + PartialEq,
+    I: PixelCanvasInterface<H, W, P>,
+{
+    let table = canvas.table();
+    let mut covered = vec![false; H * W];
+    let mut rects = Vec::new();
+    let same = |covered: &[bool], row: usize, column: usize, color: &P::ColorType| {
+        !covered[row * W + column] && table.get_pixel((row, column)).color() == color
+    };
+
+    for row in 0..H {
+        for column in 0..W {
+            if covered[row * W + column] {
+                continue;
+            }
+
+            // Greedy: as wide as possible first, then as tall as the whole width allows.
+            let color = table.get_pixel((row, column)).color().clone();
+            let mut width = 1;
+            while column + width < W && same(&covered, row, column + width, &color) {
+                width += 1;
+            }
+
+            let mut height = 1;
+            while row + height < H
+                && (column..column + width).all(|c| same(&covered, row + height, c, &color))
+            {
+                height += 1;
+            }
+
+            for r in row..row + height {
+                for c in column..column + width {
+                    covered[r * W + c] = true;
+                }
+            }
+
+            rects.push(ColorRect {
+                row,
+                column,
+                height,
+                width,
+                color,
+            });
+        }
+    }
+
+    rects
+}
+
+impl<const H: usize, const W: usize, P> PixelCanvas<H, W, P>
+where
+    P: PixelMutInterface + PixelInitializer + PartialEq + Clone + Default,
+    P::ColorType: Default + Clone,
+{
+    /// Creates a canvas from runs created by [`to_runs`](super::SharedPixelCanvasExt::to_runs).
+    ///
+    /// Pixels which are not covered by any run keep the default color,
+    /// parts of the runs out of bounds are ignored.
+    pub fn from_runs(runs: impl IntoIterator<Item = ColorRun<P::ColorType>>) -> Self {
+        let mut canvas = Self::default();
+        for run in runs.into_iter().filter(|run| run.row < H) {
+            for column in run.column..(run.column + run.length).min(W) {
+                canvas
+                    .get_pixel_mut((run.row, column))
+                    .update_color(run.color.clone());
+            }
+        }
+        canvas
+    }
+
+    /// Creates a canvas from rectangles created by [`to_rects`](super::SharedPixelCanvasExt::to_rects).
+    ///
+    /// Pixels which are not covered by any rectangle keep the default color,
+    /// parts of the rectangles out of bounds are ignored.
+    pub fn from_rects(rects: impl IntoIterator<Item = ColorRect<P::ColorType>>) -> Self {
+        let mut canvas = Self::default();
+        for rect in rects {
+            for row in rect.row..(rect.row + rect.height).min(H) {
+                for column in rect.column..(rect.column + rect.width).min(W) {
+                    canvas
+                        .get_pixel_mut((row, column))
+                        .update_color(rect.color.clone());
+                }
+            }
+        }
+        canvas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pixels::canvas::templates::square, prelude::*};
+
+    use super::*;
+
+    #[test]
+    fn test_runs_and_rects() {
+        let mut canvas = PixelCanvas::<6>::default();
+        canvas.draw(TOP_LEFT, square::<6>(BLACK));
+        canvas.fill_inside(RED, CENTER);
+
+        let runs = canvas.to_runs();
+        assert_eq!(runs.len(), 2 + 4 * 3);
+        assert_eq!(
+            PixelCanvas::<6>::from_runs(runs).to_runs(),
+            canvas.to_runs()
+        );
+
+        let rects = canvas.to_rects();
+        // Top row, left and right columns, bottom row and the filled center.
+        assert_eq!(rects.len(), 5);
+        assert!(rects.contains(&ColorRect {
+            row: 1,
+            column: 1,
+            height: 4,
+            width: 4,
+            color: RED
+        }));
+        assert_eq!(
+            PixelCanvas::<6>::from_rects(rects).to_runs(),
+            canvas.to_runs()
+        );
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let canvas = PixelCanvas::<2>::from_runs([
+            ColorRun {
+                row: 2,
+                column: 0,
+                length: 2,
+                color: RED,
+            },
+            ColorRun {
+                row: 1,
+                column: 1,
+                length: 5,
+                color: BLUE,
+            },
+        ]);
+        assert_eq!(canvas.to_runs().len(), 3);
+        assert_eq!(canvas.color_at(BOTTOM_RIGHT), BLUE);
+
+        let canvas = PixelCanvas::<2>::from_rects([ColorRect {
+            row: 1,
+            column: 1,
+            height: 3,
+            width: 3,
+            color: RED,
+        }]);
+        assert_eq!(canvas.color_at(BOTTOM_RIGHT), RED);
+        assert_eq!(canvas.color_at(TOP_RIGHT), PixelColor::default());
+    }
+}
@@ -0,0 +1,160 @@
+//! A [`PixelCanvas`] which keeps a slot for every pixel, see [`DensePixelCanvas`].
+//!
+
+use crate::pixels::{Pixel, PixelInterface, PixelMutInterface};
+
+use super::{table::PixelTable, PixelCanvas, PixelCanvasInterface, PixelCanvasMutInterface};
+
+/// A [`PixelCanvas`] whose pixels are found by their position instead of hashing it.
+///
+/// It's faster for small canvases (like sprites and icons) which are mostly filled, while
+/// big sparse canvases are cheaper as a plain [`PixelCanvas`].
+/// It derefs to a [`PixelCanvas`] and implements the same interfaces, so it works anywhere
+/// a canvas does.
+pub struct DensePixelCanvas<const H: usize, const W: usize = H, P: PixelInterface + Default = Pixel>
+{
+    canvas: PixelCanvas<H, W, P>,
+}
+
+/// A [`DensePixelCanvas`] of [`MaybePixel`](crate::pixels::maybe::MaybePixel)s.
+pub type DenseMaybePixelCanvas<const H: usize, const W: usize = H> =
+    DensePixelCanvas<H, W, crate::pixels::maybe::MaybePixel>;
+
+impl<const H: usize, const W: usize, P: PixelInterface + Default> DensePixelCanvas<H, W, P> {
+    pub fn into_inner(self) -> PixelCanvas<H, W, P> {
+        self.canvas
+    }
+}
+
+impl<const H: usize, const W: usize, P: PixelInterface + Default + Clone> Default
+    for DensePixelCanvas<H, W, P>
+{
+    fn default() -> Self {
+        Self {
+            canvas: PixelCanvas::dense(),
+        }
+    }
+}
+
+impl<const H: usize, const W: usize, P: PixelInterface + Default + Clone> Clone
+    for DensePixelCanvas<H, W, P>
+where
+    <P as PixelInterface>::ColorType: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            canvas: self.canvas.clone(),
+        }
+    }
+}
+
+impl<const H: usize, const W: usize, P: PixelInterface + Default + std::fmt::Debug> std::fmt::Debug
+    for DensePixelCanvas<H, W, P>
+where
+    <P as PixelInterface>::ColorType: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DensePixelCanvas")
+            .field("canvas", &self.canvas)
+            .finish()
+    }
+}
+
+impl<const H: usize, const W: usize, P: PixelInterface + Default> From<DensePixelCanvas<H, W, P>>
+    for PixelCanvas<H, W, P>
+{
+    fn from(value: DensePixelCanvas<H, W, P>) -> Self {
+        value.into_inner()
+    }
+}
+
+impl<const H: usize, const W: usize, P: PixelInterface + Default> std::ops::Deref
+    for DensePixelCanvas<H, W, P>
+{
+    type Target = PixelCanvas<H, W, P>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.canvas
+    }
+}
+
+impl<const H: usize, const W: usize, P: PixelInterface + Default> std::ops::DerefMut
+    for DensePixelCanvas<H, W, P>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.canvas
+    }
+}
+
+impl<const H: usize, const W: usize, P: PixelInterface + Default> PixelCanvasInterface<H, W, P>
+    for DensePixelCanvas<H, W, P>
+{
+    fn table(&self) -> &PixelTable<H, W, P> {
+        self.canvas.table()
+    }
+}
+
+impl<const H: usize, const W: usize, P: PixelInterface + Default> PixelCanvasInterface<H, W, P>
+    for &DensePixelCanvas<H, W, P>
+{
+    fn table(&self) -> &PixelTable<H, W, P> {
+        self.canvas.table()
+    }
+}
+
+impl<const H: usize, const W: usize, P: PixelInterface + Default> PixelCanvasInterface<H, W, P>
+    for &mut DensePixelCanvas<H, W, P>
+{
+    fn table(&self) -> &PixelTable<H, W, P> {
+        self.canvas.table()
+    }
+}
+
+impl<const H: usize, const W: usize, P: PixelMutInterface + Default>
+    PixelCanvasMutInterface<H, W, P> for DensePixelCanvas<H, W, P>
+{
+    fn table_mut(&mut self) -> &mut PixelTable<H, W, P> {
+        self.canvas.table_mut()
+    }
+}
+
+impl<const H: usize, const W: usize, P: PixelMutInterface + Default>
+    PixelCanvasMutInterface<H, W, P> for &mut DensePixelCanvas<H, W, P>
+{
+    fn table_mut(&mut self) -> &mut PixelTable<H, W, P> {
+        self.canvas.table_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pixelart_table_abs::IllusionBackend;
+
+    use crate::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_dense_canvas() {
+        let mut dense = DenseMaybePixelCanvas::<5>::default();
+        let mut sparse = MaybePixelCanvas::<5>::default();
+        for canvas in [&mut *dense, &mut sparse] {
+            canvas.fill_region(TOP_LEFT, CENTER, RED);
+            canvas.update_color_at(BOTTOM_RIGHT, BLUE);
+            canvas.flip_x();
+        }
+
+        assert_eq!(dense.backend(), IllusionBackend::Dense);
+        assert_eq!(dense.table(), sparse.table());
+        assert_eq!(dense.filled_len(), 10);
+        assert_eq!(dense.clone().into_inner().backend(), IllusionBackend::Dense);
+
+        // Canvases made out of it keep the backend.
+        assert_eq!(dense.rotate_90_cw().backend(), IllusionBackend::Dense);
+        assert_eq!(dense.resampled::<2, 3>().backend(), IllusionBackend::Dense);
+        assert_eq!(
+            dense.cropped::<2, 2>(CENTER).backend(),
+            IllusionBackend::Dense
+        );
+    }
+}
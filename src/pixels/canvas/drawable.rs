@@ -1,9 +1,18 @@
+use thiserror::Error;
+
 use crate::pixels::{
-    position::{IntoPixelStrictPosition, PixelStrictPositionInterface, StrictPositions},
+    position::{
+        IntoPixelStrictPosition, OutOfBoundsError, OutOfBoundsPolicy, PixelStrictPositionInterface,
+        StrictPositions,
+    },
     PixelInterface, PixelMutInterface,
 };
 
-use super::{table::PixelTable, PixelCanvas, PixelCanvasInterface, PixelCanvasMutInterface};
+use super::{
+    grid::{self, TableGrid},
+    table::PixelTable,
+    PixelCanvas, PixelCanvasInterface, PixelCanvasMutInterface,
+};
 
 /// Something that can later be drawn on a [`PixelCanvas`].
 pub trait Drawable<const H: usize, const W: usize, MP>
@@ -44,6 +53,35 @@ where
     }
 }
 
+/// What to do when a drawable doesn't fit where it's asked to be drawn,
+/// see [`SharedMutPixelCanvasExt::try_draw_exact`](super::SharedMutPixelCanvasExt::try_draw_exact).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AutoFit {
+    /// Report a [`DimensionMismatchError`].
+    #[default]
+    None,
+
+    /// Ignore the start position and put the drawable at center of the canvas.
+    ///
+    /// It's still an error if the drawable is larger than the canvas.
+    Recenter,
+
+    /// Ignore the start position and resample the drawable to cover the whole canvas.
+    Resample,
+}
+
+/// A drawable doesn't fit in the space it's drawn to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error(
+    "Expected a drawable of at most ({expected_height}, {expected_width}), but it's ({actual_height}, {actual_width})."
+)]
+pub struct DimensionMismatchError {
+    pub expected_height: usize,
+    pub expected_width: usize,
+    pub actual_height: usize,
+    pub actual_width: usize,
+}
+
 pub fn draw_canvas_on<
     const H: usize,
     const W: usize,
@@ -64,39 +102,43 @@ pub fn draw_canvas_on<
     MP::ColorType: Clone,
     P::ColorType: TryFrom<MP::ColorType, Error = E>,
 {
-    let start_pos = start_pos.into_pixel_strict_position();
-    if MP::TRANSPARENT {
-        // If the pixel is transparent, we can skip the empty pixels (which is None).
-        for ((row, column), pixel) in me.real_items() {
-            if let Ok(Ok(pos_on_canvas)) = start_pos
-                .checked_down(*row)
-                .map(|res| res.checked_right(*column))
-            {
-                if let Ok(color) = P::ColorType::try_from(pixel.color().clone()) {
-                    canvas
-                        .table_mut()
-                        .get_pixel_mut(pos_on_canvas)
-                        .update_color(color);
-                }
-            }
-        }
-    } else {
-        // If the pixel is not transparent, we can't skip the empty pixels (which is withe by default).
-        for pixel in me.iter_pixels() {
-            let (row, column) = pixel.index();
-            if let Ok(Ok(pos_on_canvas)) = start_pos
-                .checked_down(row)
-                .map(|res| res.checked_right(column))
-            {
-                if let Ok(color) = P::ColorType::try_from(pixel.color().clone()) {
-                    canvas
-                        .table_mut()
-                        .get_pixel_mut(pos_on_canvas)
-                        .update_color(color);
-                }
-            }
-        }
-    }
+    // Clipping never fails.
+    let _ = draw_canvas_on_with_policy(me, start_pos, canvas, OutOfBoundsPolicy::Clip);
+}
+
+/// As same as [`draw_canvas_on`] but pixels going out of the canvas are handled
+/// based on the given [`OutOfBoundsPolicy`].
+///
+/// Using [`OutOfBoundsPolicy::Error`], nothing is drawn if any pixel is out of bounds.
+pub fn draw_canvas_on_with_policy<
+    const H: usize,
+    const W: usize,
+    const HC: usize,
+    const WC: usize,
+    P,
+    C,
+    MP,
+    E,
+>(
+    me: &PixelTable<H, W, MP>,
+    start_pos: impl IntoPixelStrictPosition<HC, WC>,
+    canvas: &mut C,
+    policy: OutOfBoundsPolicy,
+) -> Result<(), OutOfBoundsError>
+where
+    MP: PixelInterface + Default,
+    P: PixelMutInterface + PartialEq + Clone + Default,
+    C: PixelCanvasMutInterface<HC, WC, P>,
+    MP::ColorType: Clone,
+    P::ColorType: TryFrom<MP::ColorType, Error = E>,
+{
+    let start_pos = start_pos.into_pixel_strict_position().expand();
+    grid::draw_grid_on_with_policy(
+        &TableGrid(me),
+        start_pos,
+        &mut TableGrid(canvas.table_mut()),
+        policy,
+    )
 }
 
 impl<const H: usize, const W: usize, MP: PixelInterface + Default> Drawable<H, W, MP>
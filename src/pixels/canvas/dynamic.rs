@@ -0,0 +1,400 @@
+//! A canvas with runtime dimensions, see [`DynPixelCanvas`].
+//!
+
+use std::borrow::{Borrow, BorrowMut};
+
+use crate::pixels::{Pixel, PixelInitializer, PixelInterface, PixelMutInterface};
+
+use super::{
+    drawable::DimensionMismatchError,
+    grid::{PixelGridInterface, PixelGridMutExt, PixelGridMutInterface},
+    pen::{DynAttachedMarker, Pen},
+    table::PixelTable,
+    PixelCanvas, PixelCanvasInterface, PixelCanvasMutInterface,
+};
+
+/// A canvas whose `height` and `width` are only known at runtime, e.g. coming from
+/// command line arguments or a loaded file.
+///
+/// Shapes, fills and drawables come from [`PixelGridMutExt`] (as same as canvases of a known
+/// size use under the hood), and pens work on it directly. For anything else, take a
+/// [partition](Self::partition_mut) of a known size, or convert it from/into a [`PixelCanvas`]
+/// once sizes are known. Tags are not kept on these canvases.
+///
+/// [`PixelGridMutExt`]: super::grid::PixelGridMutExt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynPixelCanvas<P: PixelInterface = Pixel> {
+    height: usize,
+    width: usize,
+    pixels: Vec<P>,
+}
+
+/// A [`DynPixelCanvas`] of [`MaybePixel`](crate::pixels::maybe::MaybePixel)s.
+pub type DynMaybePixelCanvas = DynPixelCanvas<crate::pixels::maybe::MaybePixel>;
+
+impl<P> DynPixelCanvas<P>
+where
+    P: PixelInterface + Default + Clone,
+{
+    /// Creates a `height` * `width` canvas filled with default pixels.
+    pub fn new(height: usize, width: usize) -> Self {
+        Self {
+            height,
+            width,
+            pixels: vec![P::default(); height * width],
+        }
+    }
+
+    pub fn from_fill_color(height: usize, width: usize, color: impl Into<P::ColorType>) -> Self
+    where
+        P: PixelInitializer,
+    {
+        Self {
+            height,
+            width,
+            pixels: vec![P::new(color); height * width],
+        }
+    }
+}
+
+impl<P: PixelInterface> DynPixelCanvas<P> {
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    fn index(&self, (row, column): (usize, usize)) -> Option<usize> {
+        (row < self.height && column < self.width).then_some(row * self.width + column)
+    }
+
+    /// Gets the pixel at given position mutably, none if it's out of bounds.
+    pub fn get_pixel_mut(&mut self, pos: (usize, usize)) -> Option<&mut P> {
+        self.index(pos).map(|index| &mut self.pixels[index])
+    }
+
+    /// All pixels and their `(row, column)`s, row by row.
+    pub fn iter_pixels(&self) -> impl Iterator<Item = ((usize, usize), &P)> {
+        let width = self.width;
+        self.pixels
+            .iter()
+            .enumerate()
+            .map(move |(index, pixel)| ((index / width, index % width), pixel))
+    }
+}
+
+impl<P: PixelInterface> PixelGridInterface<P> for DynPixelCanvas<P> {
+    fn size(&self) -> (usize, usize) {
+        (self.height, self.width)
+    }
+
+    fn get_pixel(&self, pos: (usize, usize)) -> Option<&P> {
+        self.index(pos).map(|index| &self.pixels[index])
+    }
+
+    fn real_pixels<'a>(&'a self) -> impl Iterator<Item = ((usize, usize), &'a P)>
+    where
+        P: 'a,
+    {
+        self.iter_pixels()
+    }
+}
+
+impl<P: PixelMutInterface> PixelGridMutInterface<P> for DynPixelCanvas<P> {
+    fn set_color(&mut self, pos: (usize, usize), color: P::ColorType) -> Option<P::ColorType> {
+        self.get_pixel_mut(pos)
+            .map(|pixel| pixel.update_color(color))
+    }
+}
+
+impl<P> DynPixelCanvas<P>
+where
+    P: PixelMutInterface + Default + Clone,
+    P::ColorType: Clone,
+{
+    /// Attaches a new [`Pen`] to this canvas, see [`Pen::attach_dyn`].
+    pub fn attach_new_pen(
+        &mut self,
+        color: impl Into<P::ColorType>,
+        start_pos: (usize, usize),
+    ) -> Pen<DynAttachedMarker<'_, P>> {
+        Pen::<super::pen::CanvasUnattachedMarker<P::ColorType>>::new(color)
+            .attach_dyn(self, start_pos)
+    }
+}
+
+impl<P> DynPixelCanvas<P>
+where
+    P: PixelMutInterface + PixelInitializer + PartialEq + Clone + Default,
+    P::ColorType: Clone + Default,
+{
+    /// A `MH` * `MW` partition of this canvas starting at `top_left`, which can use
+    /// everything a [`PixelCanvas`] can.
+    ///
+    /// Parts of the partition out of this canvas are left default.
+    pub fn partition<const MH: usize, const MW: usize>(
+        &self,
+        top_left: (usize, usize),
+    ) -> DynCanvasPartition<MH, MW, &Self, P> {
+        DynCanvasPartition::new(top_left, self)
+    }
+
+    /// As same as [`Self::partition`], but changes can be written back to this canvas using
+    /// [`DynCanvasPartition::write_source`].
+    pub fn partition_mut<const MH: usize, const MW: usize>(
+        &mut self,
+        top_left: (usize, usize),
+    ) -> DynCanvasPartition<MH, MW, &mut Self, P> {
+        DynCanvasPartition::new(top_left, self)
+    }
+}
+
+/// A partition of known size taken from a [`DynPixelCanvas`], see
+/// [`DynPixelCanvas::partition_mut`].
+///
+/// It's a [`PixelCanvasInterface`] itself, so pens, shapes, drawables and the rest of the
+/// canvas extensions work on it.
+pub struct DynCanvasPartition<const MH: usize, const MW: usize, I, P>
+where
+    P: PixelInterface + Default,
+    I: Borrow<DynPixelCanvas<P>>,
+{
+    position: (usize, usize),
+    source: I,
+    canvas: PixelCanvas<MH, MW, P>,
+}
+
+impl<const MH: usize, const MW: usize, I, P> DynCanvasPartition<MH, MW, I, P>
+where
+    P: PixelMutInterface + PixelInitializer + PartialEq + Clone + Default,
+    P::ColorType: Clone + Default,
+    I: Borrow<DynPixelCanvas<P>>,
+{
+    pub fn new(position: (usize, usize), source: I) -> Self {
+        let mut canvas = PixelCanvas::<MH, MW, P>::default();
+        for ((row, column), source_pos) in Self::included_positions(position, source.borrow()) {
+            if let Some(pixel) = source.borrow().get_pixel(source_pos) {
+                if pixel != &P::default() {
+                    canvas
+                        .get_pixel_mut((row, column))
+                        .update_color(pixel.color().clone());
+                }
+            }
+        }
+
+        Self {
+            position,
+            source,
+            canvas,
+        }
+    }
+
+    /// Positions of the partition and their positions on the source, if inside of it.
+    fn included_positions(
+        (top, left): (usize, usize),
+        source: &DynPixelCanvas<P>,
+    ) -> impl Iterator<Item = ((usize, usize), (usize, usize))> {
+        let rows = MH.min(source.height.saturating_sub(top));
+        let columns = MW.min(source.width.saturating_sub(left));
+        (0..rows).flat_map(move |row| {
+            (0..columns).map(move |column| ((row, column), (top + row, left + column)))
+        })
+    }
+
+    /// Top left corner of the partition on the source.
+    pub fn position(&self) -> (usize, usize) {
+        self.position
+    }
+
+    pub fn source(&self) -> &DynPixelCanvas<P> {
+        self.source.borrow()
+    }
+
+    /// Copies the colored pixels of the partition back to the source.
+    pub fn write_source(&mut self)
+    where
+        I: BorrowMut<DynPixelCanvas<P>>,
+    {
+        let positions: Vec<_> =
+            Self::included_positions(self.position, self.source.borrow()).collect();
+        for (my_pos, source_pos) in positions {
+            let pixel = self.canvas.table().get_pixel(my_pos);
+            if pixel.has_color() {
+                self.source
+                    .borrow_mut()
+                    .update_color_at(source_pos, pixel.color().clone());
+            }
+        }
+    }
+}
+
+impl<const MH: usize, const MW: usize, I, P> PixelCanvasInterface<MH, MW, P>
+    for DynCanvasPartition<MH, MW, I, P>
+where
+    P: PixelInterface + Default,
+    I: Borrow<DynPixelCanvas<P>>,
+{
+    fn table(&self) -> &PixelTable<MH, MW, P> {
+        self.canvas.table()
+    }
+}
+
+impl<const MH: usize, const MW: usize, I, P> PixelCanvasMutInterface<MH, MW, P>
+    for DynCanvasPartition<MH, MW, I, P>
+where
+    P: PixelMutInterface + Default,
+    I: Borrow<DynPixelCanvas<P>>,
+{
+    fn table_mut(&mut self) -> &mut PixelTable<MH, MW, P> {
+        self.canvas.table_mut()
+    }
+}
+
+impl<const H: usize, const W: usize, P> From<&PixelCanvas<H, W, P>> for DynPixelCanvas<P>
+where
+    P: PixelInterface + Default + Clone,
+{
+    fn from(value: &PixelCanvas<H, W, P>) -> Self {
+        let mut canvas = Self::new(H, W);
+        for ((row, column), pixel) in value.table().real_items() {
+            canvas.pixels[row * W + column] = pixel.clone();
+        }
+        canvas
+    }
+}
+
+impl<const H: usize, const W: usize, P> TryFrom<&DynPixelCanvas<P>> for PixelCanvas<H, W, P>
+where
+    P: PixelMutInterface + PixelInitializer + PartialEq + Clone + Default,
+    P::ColorType: Clone + Default,
+{
+    type Error = DimensionMismatchError;
+
+    /// Fails if the dynamic canvas isn't exactly `H` * `W`.
+    fn try_from(value: &DynPixelCanvas<P>) -> Result<Self, Self::Error> {
+        if (value.height, value.width) != (H, W) {
+            return Err(DimensionMismatchError {
+                expected_height: H,
+                expected_width: W,
+                actual_height: value.height,
+                actual_width: value.width,
+            });
+        }
+
+        let mut canvas = Self::default();
+        for ((row, column), pixel) in value.iter_pixels() {
+            if pixel != &P::default() {
+                canvas
+                    .get_pixel_mut((row, column))
+                    .update_color(pixel.color().clone());
+            }
+        }
+        Ok(canvas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        image::PixelImageStyle,
+        pixels::position::{Direction, OutOfBoundsPolicy},
+        prelude::*,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_dyn_canvas() {
+        let (height, width) = ("3".parse().unwrap(), "4".parse().unwrap());
+        let mut canvas = DynMaybePixelCanvas::new(height, width);
+        assert_eq!(canvas.update_color_at((1, 3), RED), Some(None));
+        assert_eq!(canvas.update_color_at((3, 0), RED), None);
+        assert_eq!(canvas.color_at((1, 3)), Some(Some(RED)));
+
+        let fixed = MaybePixelCanvas::<3, 4>::try_from(&canvas).unwrap();
+        assert_eq!(fixed.color_at(pos!(1, 3; 3, 4)), Some(RED));
+        assert_eq!(DynPixelCanvas::from(&fixed), canvas);
+        assert_eq!(
+            MaybePixelCanvas::<4>::try_from(&canvas).unwrap_err(),
+            DimensionMismatchError {
+                expected_height: 4,
+                expected_width: 4,
+                actual_height: 3,
+                actual_width: 4,
+            }
+        );
+
+        assert_eq!(
+            canvas.get_image(&PixelImageStyle::default()),
+            fixed.default_image_builder().get_image()
+        );
+    }
+
+    #[test]
+    fn test_dyn_canvas_drawing() {
+        let mut canvas = DynMaybePixelCanvas::new(5, 6);
+        canvas.draw_line((0, 0), (0, 9), RED);
+        assert_eq!(canvas.color_at((0, 5)), Some(Some(RED)));
+
+        canvas.draw_filled_circle((2, 2), 1, BLUE);
+        assert_eq!(canvas.color_at((2, 2)), Some(Some(BLUE)));
+        assert_eq!(canvas.color_at((4, 4)), Some(None));
+
+        let mut filled = canvas.clone();
+        filled.fill_inside(GREEN, (4, 5));
+        assert_eq!(filled.color_at((1, 5)), Some(Some(GREEN)));
+        assert_eq!(filled.color_at((2, 2)), Some(Some(BLUE)));
+
+        canvas.boundary_fill((4, 5), GREEN, BLUE);
+        assert_eq!(canvas.color_at((0, 5)), Some(Some(GREEN)));
+
+        let mut sprite = MaybePixelCanvas::<2>::default();
+        sprite.fill(YELLOW);
+        let mut drawn = DynMaybePixelCanvas::new(3, 3);
+        assert!(drawn
+            .draw_with_policy((2, 2), sprite.clone(), OutOfBoundsPolicy::Error)
+            .is_err());
+        assert_eq!(drawn.color_at((2, 2)), Some(None));
+        drawn.draw((2, 2), sprite);
+        assert_eq!(drawn.color_at((2, 2)), Some(Some(YELLOW)));
+    }
+
+    #[test]
+    fn test_dyn_canvas_pen() {
+        let mut canvas = DynMaybePixelCanvas::new(3, 4);
+        canvas
+            .attach_new_pen(RED, (2, 0))
+            .start()
+            .right(10)
+            .up(1)
+            .branch(|pen| pen.up_left(1));
+        assert_eq!(canvas.color_at((2, 3)), Some(Some(RED)));
+        assert_eq!(canvas.color_at((1, 3)), Some(Some(RED)));
+        assert_eq!(canvas.color_at((0, 2)), Some(Some(RED)));
+
+        let mut pen = canvas.attach_new_pen(BLUE, (0, 0));
+        assert!(pen.start().try_go_direction(Direction::Up, 1).is_err());
+        assert_eq!(pen.current_position(), (0, 0));
+
+        let mut pen = pen.with_policy(OutOfBoundsPolicy::Wrap);
+        pen.left(1);
+        assert_eq!(pen.current_position(), (0, 3));
+    }
+
+    #[test]
+    fn test_dyn_canvas_partition() {
+        let mut canvas = DynMaybePixelCanvas::new(4, 5);
+        canvas.update_color_at((3, 4), RED);
+
+        let mut partition = canvas.partition_mut::<2, 2>((3, 3));
+        assert_eq!(partition.color_at(pos!(0, 1; 2, 2)), Some(RED));
+        partition.fill(BLUE);
+        partition.write_source();
+
+        assert_eq!(canvas.color_at((3, 3)), Some(Some(BLUE)));
+        assert_eq!(canvas.color_at((3, 4)), Some(Some(BLUE)));
+        assert_eq!(canvas.color_at((2, 3)), Some(None));
+    }
+}
@@ -0,0 +1,438 @@
+//! Tables whose size may only be known at runtime, see [`PixelGridInterface`].
+//!
+
+use std::path::Path;
+
+use image::ImageBuffer;
+
+use crate::{
+    image::{DefaultImageBuffer, PixelImageStyle},
+    pixels::{
+        color::{PixelColor, RgbaInterface},
+        position::{Connectivity, OutOfBoundsError, OutOfBoundsPolicy},
+        PixelInterface, PixelMutInterface,
+    },
+};
+
+use super::{
+    drawable::Drawable, shapes, table::PixelTable, MaybePixelCanvas, PixelCanvasInterface,
+};
+
+/// Interface of a table of pixels whose size may only be known at runtime, like a
+/// [`DynPixelCanvas`](super::dynamic::DynPixelCanvas).
+///
+/// Using this we can have access to [`PixelGridExt`] and [`PixelGridMutExt`], which are also
+/// used under the hood by canvases of a known size, so shapes, fills and drawables are
+/// implemented once for both.
+pub trait PixelGridInterface<P: PixelInterface> {
+    /// `(height, width)` of the table.
+    fn size(&self) -> (usize, usize);
+
+    /// The pixel at `(row, column)`, none if it's out of bounds.
+    fn get_pixel(&self, pos: (usize, usize)) -> Option<&P>;
+
+    /// Pixels which may not be the default one with their `(row, column)`s, in no particular
+    /// order. The others are default.
+    fn real_pixels<'a>(&'a self) -> impl Iterator<Item = ((usize, usize), &'a P)>
+    where
+        P: 'a;
+}
+
+/// Interface of a mutable [`PixelGridInterface`].
+pub trait PixelGridMutInterface<P: PixelMutInterface>: PixelGridInterface<P> {
+    /// Updates color of the pixel at `(row, column)`, returning the previous one.
+    ///
+    /// Positions out of bounds are ignored and return none.
+    fn set_color(&mut self, pos: (usize, usize), color: P::ColorType) -> Option<P::ColorType>;
+}
+
+/// The [`PixelTable`] of a canvas of a known size (or a reference to it), as a
+/// [`PixelGridInterface`].
+pub(crate) struct TableGrid<T>(pub(crate) T);
+
+macro_rules! impl_table_grid {
+    ($($table:ty),*) => {$(
+        impl<const H: usize, const W: usize, P: PixelInterface + Default> PixelGridInterface<P>
+            for TableGrid<$table>
+        {
+            fn size(&self) -> (usize, usize) {
+                (H, W)
+            }
+
+            fn get_pixel(&self, pos: (usize, usize)) -> Option<&P> {
+                self.0.inner.item(pos)
+            }
+
+            fn real_pixels<'a>(&'a self) -> impl Iterator<Item = ((usize, usize), &'a P)>
+    where
+        P: 'a,
+    {
+                self.0.real_items()
+            }
+        }
+    )*};
+}
+
+impl_table_grid!(&PixelTable<H, W, P>, &mut PixelTable<H, W, P>);
+
+impl<const H: usize, const W: usize, P> PixelGridMutInterface<P>
+    for TableGrid<&mut PixelTable<H, W, P>>
+where
+    P: PixelMutInterface + PartialEq + Clone + Default,
+{
+    fn set_color(
+        &mut self,
+        (row, column): (usize, usize),
+        color: P::ColorType,
+    ) -> Option<P::ColorType> {
+        (row < H && column < W).then(|| self.0.get_pixel_mut((row, column)).update_color(color))
+    }
+}
+
+/// Colors all positions inside the grid, the others are ignored.
+pub(crate) fn draw_points<P, G>(
+    grid: &mut G,
+    points: impl IntoIterator<Item = (isize, isize)>,
+    color: P::ColorType,
+) where
+    P: PixelMutInterface,
+    P::ColorType: Clone,
+    G: PixelGridMutInterface<P> + ?Sized,
+{
+    let size = grid.size();
+    for point in points {
+        if let Ok(Some(pos)) = OutOfBoundsPolicy::Clip.resolve_in(point, size) {
+            grid.set_color(pos, color.clone());
+        }
+    }
+}
+
+/// Draws pixels of the `source` on the `target` with its top left corner at `start_pos`,
+/// pixels going out of the target are handled based on the given [`OutOfBoundsPolicy`].
+///
+/// Using [`OutOfBoundsPolicy::Error`], nothing is drawn if any pixel is out of bounds.
+pub(crate) fn draw_grid_on_with_policy<MP, P, S, G, E>(
+    source: &S,
+    (top, left): (usize, usize),
+    target: &mut G,
+    policy: OutOfBoundsPolicy,
+) -> Result<(), OutOfBoundsError>
+where
+    MP: PixelInterface,
+    MP::ColorType: Clone,
+    P: PixelMutInterface,
+    P::ColorType: TryFrom<MP::ColorType, Error = E>,
+    S: PixelGridInterface<MP> + ?Sized,
+    G: PixelGridMutInterface<P> + ?Sized,
+{
+    let items: Vec<((usize, usize), MP::ColorType)> = if MP::TRANSPARENT {
+        // If the pixel is transparent, we can skip the empty pixels (which is None).
+        source
+            .real_pixels()
+            .map(|(index, pixel)| (index, pixel.color().clone()))
+            .collect()
+    } else {
+        // If the pixel is not transparent, we can't skip the empty pixels (which is withe by default).
+        let (height, width) = source.size();
+        (0..height)
+            .flat_map(|row| (0..width).map(move |column| (row, column)))
+            .filter_map(|index| {
+                source
+                    .get_pixel(index)
+                    .map(|pixel| (index, pixel.color().clone()))
+            })
+            .collect()
+    };
+
+    let size = target.size();
+    let mut resolved = Vec::with_capacity(items.len());
+    for ((row, column), color) in items {
+        let position = ((top + row) as isize, (left + column) as isize);
+        if let Some(pos) = policy.resolve_in(position, size)? {
+            resolved.push((pos, color));
+        }
+    }
+
+    for (pos, color) in resolved {
+        if let Ok(color) = P::ColorType::try_from(color) {
+            target.set_color(pos, color);
+        }
+    }
+    Ok(())
+}
+
+/// Extensions for any type that implements [`PixelGridInterface`].
+pub trait PixelGridExt<P: PixelInterface>: PixelGridInterface<P> {
+    /// Gets the color of a pixel at given position, none if it's out of bounds.
+    fn color_at(&self, pos: (usize, usize)) -> Option<P::ColorType>
+    where
+        P::ColorType: Clone,
+    {
+        self.get_pixel(pos).map(|pixel| pixel.color().clone())
+    }
+
+    /// Returns an image of the grid using the [`PixelImageStyle`], like
+    /// [`PixelImageBuilder`](crate::image::PixelImageBuilder).
+    fn get_image(&self, style: &PixelImageStyle) -> DefaultImageBuffer
+    where
+        P::ColorType: RgbaInterface + Default,
+    {
+        let (height, width) = self.size();
+        let (image_width, image_height) = style.image_size(height, width);
+        let mut image = if P::TRANSPARENT {
+            ImageBuffer::new(image_width as u32, image_height as u32)
+        } else {
+            ImageBuffer::from_pixel(
+                image_width as u32,
+                image_height as u32,
+                P::ColorType::default().rgba(),
+            )
+        };
+
+        for ((row, column), pixel) in self.real_pixels() {
+            if pixel.has_color() {
+                style.draw_pixel(&mut image, row, column, pixel.color().rgba());
+            }
+        }
+        image
+    }
+
+    /// Saves the image from [`PixelGridExt::get_image`] to a file at specified path.
+    fn save<Q>(&self, path: Q, style: &PixelImageStyle) -> Result<(), image::ImageError>
+    where
+        P::ColorType: RgbaInterface + Default,
+        Q: AsRef<Path>,
+    {
+        self.get_image(style).save(path)
+    }
+}
+
+impl<P: PixelInterface, T: PixelGridInterface<P> + ?Sized> PixelGridExt<P> for T {}
+
+/// Extensions for any type that implements [`PixelGridMutInterface`].
+///
+/// Canvases of a known size use these under the hood, see
+/// [`SharedMutPixelCanvasExt`](super::SharedMutPixelCanvasExt).
+pub trait PixelGridMutExt<P: PixelMutInterface>: PixelGridMutInterface<P> {
+    /// Updates color of the pixel at given position, returning the previous one.
+    ///
+    /// Positions out of bounds are ignored and return none.
+    fn update_color_at(
+        &mut self,
+        pos: (usize, usize),
+        color: impl Into<P::ColorType>,
+    ) -> Option<P::ColorType> {
+        self.set_color(pos, color.into())
+    }
+
+    /// Fills the whole grid with the given color.
+    fn fill(&mut self, color: impl Into<P::ColorType>)
+    where
+        P::ColorType: Clone,
+    {
+        let color = color.into();
+        let (height, width) = self.size();
+        for row in 0..height {
+            for column in 0..width {
+                self.set_color((row, column), color.clone());
+            }
+        }
+    }
+
+    /// Keep filling pixels with new color until we encounter a new color.
+    ///
+    /// Nothing is filled if `point_inside` is out of bounds.
+    fn fill_inside(&mut self, color: impl Into<P::ColorType>, point_inside: (usize, usize))
+    where
+        P::ColorType: PartialEq + Clone,
+    {
+        self.fill_inside_with(color, point_inside, Connectivity::Four)
+    }
+
+    /// Same as [`PixelGridMutExt::fill_inside`], using the given [`Connectivity`] so the fill
+    /// can cross diagonal gaps.
+    fn fill_inside_with(
+        &mut self,
+        color: impl Into<P::ColorType>,
+        point_inside: (usize, usize),
+        connectivity: Connectivity,
+    ) where
+        P::ColorType: PartialEq + Clone,
+    {
+        let Some(base_color) = self.color_at(point_inside) else {
+            return;
+        };
+
+        let (height, width) = self.size();
+        let mut visited = vec![false; height * width];
+        let mut filled = vec![];
+        let mut stack = vec![point_inside];
+        while let Some(pos) = stack.pop() {
+            if std::mem::replace(&mut visited[pos.0 * width + pos.1], true)
+                || self.color_at(pos).as_ref() != Some(&base_color)
+            {
+                continue;
+            }
+
+            filled.push(pos);
+            stack.extend(neighbors(pos, (height, width), connectivity));
+        }
+
+        let color = color.into();
+        for pos in filled {
+            self.set_color(pos, color.clone());
+        }
+    }
+
+    /// Keep filling pixels with `fill_color` until we hit pixels of `boundary_color`,
+    /// no matter what color other pixels have. Like a paint bucket inside an outline.
+    fn boundary_fill(
+        &mut self,
+        point: (usize, usize),
+        fill_color: impl Into<P::ColorType>,
+        boundary_color: impl Into<P::ColorType>,
+    ) where
+        P::ColorType: PartialEq + Clone,
+    {
+        let (fill_color, boundary_color) = (fill_color.into(), boundary_color.into());
+        let size = self.size();
+        let mut stack = vec![point];
+
+        while let Some(pos) = stack.pop() {
+            let Some(current) = self.color_at(pos) else {
+                continue;
+            };
+            // Pixels already filled are skipped too, otherwise we'd never stop.
+            if current != boundary_color && current != fill_color {
+                self.set_color(pos, fill_color.clone());
+                stack.extend(neighbors(pos, size, Connectivity::Four));
+            }
+        }
+    }
+
+    /// Draws a straight line between two positions (both included), parts out of the grid
+    /// are ignored.
+    fn draw_line(
+        &mut self,
+        from: (usize, usize),
+        to: (usize, usize),
+        color: impl Into<P::ColorType>,
+    ) where
+        P::ColorType: Clone,
+    {
+        let points = shapes::line(
+            (from.0 as isize, from.1 as isize),
+            (to.0 as isize, to.1 as isize),
+        );
+        draw_points(self, points, color.into());
+    }
+
+    /// Draws outline of a circle around the center, parts out of the grid are ignored.
+    fn draw_circle(&mut self, center: (usize, usize), radius: usize, color: impl Into<P::ColorType>)
+    where
+        P::ColorType: Clone,
+    {
+        self.draw_ellipse(center, radius, radius, color)
+    }
+
+    /// As same as [`PixelGridMutExt::draw_circle`] but the inside is colored too.
+    fn draw_filled_circle(
+        &mut self,
+        center: (usize, usize),
+        radius: usize,
+        color: impl Into<P::ColorType>,
+    ) where
+        P::ColorType: Clone,
+    {
+        self.draw_filled_ellipse(center, radius, radius, color)
+    }
+
+    /// Draws outline of an ellipse around the center with horizontal radius `rx` and
+    /// vertical radius `ry`, parts out of the grid are ignored.
+    fn draw_ellipse(
+        &mut self,
+        center: (usize, usize),
+        rx: usize,
+        ry: usize,
+        color: impl Into<P::ColorType>,
+    ) where
+        P::ColorType: Clone,
+    {
+        let points = shapes::ellipse((center.0 as isize, center.1 as isize), rx, ry, false);
+        draw_points(self, points, color.into());
+    }
+
+    /// As same as [`PixelGridMutExt::draw_ellipse`] but the inside is colored too.
+    fn draw_filled_ellipse(
+        &mut self,
+        center: (usize, usize),
+        rx: usize,
+        ry: usize,
+        color: impl Into<P::ColorType>,
+    ) where
+        P::ColorType: Clone,
+    {
+        let points = shapes::ellipse((center.0 as isize, center.1 as isize), rx, ry, true);
+        draw_points(self, points, color.into());
+    }
+
+    /// Draws the drawable with its top left corner at `start_pos`, parts out of the grid
+    /// are ignored.
+    fn draw<const HD: usize, const WD: usize, MP: PixelInterface, E>(
+        &mut self,
+        start_pos: (usize, usize),
+        drawable: impl Drawable<HD, WD, MP>,
+    ) where
+        MP::ColorType: Clone,
+        Option<PixelColor>: TryFrom<MP::ColorType>,
+        P::ColorType: TryFrom<Option<PixelColor>, Error = E>,
+    {
+        // Clipping never fails.
+        let _ = self.draw_with_policy(start_pos, drawable, OutOfBoundsPolicy::Clip);
+    }
+
+    /// As same as [`PixelGridMutExt::draw`] but pixels going out of the grid are handled
+    /// based on the given [`OutOfBoundsPolicy`].
+    ///
+    /// Using [`OutOfBoundsPolicy::Error`], nothing is drawn if any pixel is out of bounds.
+    fn draw_with_policy<const HD: usize, const WD: usize, MP: PixelInterface, E>(
+        &mut self,
+        start_pos: (usize, usize),
+        drawable: impl Drawable<HD, WD, MP>,
+        policy: OutOfBoundsPolicy,
+    ) -> Result<(), OutOfBoundsError>
+    where
+        MP::ColorType: Clone,
+        Option<PixelColor>: TryFrom<MP::ColorType>,
+        P::ColorType: TryFrom<Option<PixelColor>, Error = E>,
+    {
+        // Render the drawable on its own first, so we know where exactly the pixels are.
+        let mut scratch = MaybePixelCanvas::<HD, WD>::default();
+        drawable.draw_on_exact_abs(&mut scratch);
+        draw_grid_on_with_policy(&TableGrid(scratch.table()), start_pos, self, policy)
+    }
+}
+
+impl<P: PixelMutInterface, T: PixelGridMutInterface<P> + ?Sized> PixelGridMutExt<P> for T {}
+
+/// Neighbors of the position inside a grid of `(height, width)`, based on the
+/// [`Connectivity`].
+fn neighbors(
+    (row, column): (usize, usize),
+    size: (usize, usize),
+    connectivity: Connectivity,
+) -> impl Iterator<Item = (usize, usize)> {
+    connectivity
+        .directions()
+        .iter()
+        .filter_map(move |direction| {
+            let (row_step, column_step) = direction.delta();
+            OutOfBoundsPolicy::Clip
+                .resolve_in(
+                    (row as isize + row_step, column as isize + column_step),
+                    size,
+                )
+                .ok()
+                .flatten()
+        })
+}
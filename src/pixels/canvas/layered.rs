@@ -1,20 +1,160 @@
 use thiserror::Error;
 
 use crate::{
+    animation::subpixel::{SampleGrid, SubpixelOffset, Supersample},
     pixels::{
-        position::{IntoPixelStrictPosition, PixelStrictPosition},
+        color::RgbaInterface,
+        position::{IntoPixelStrictPosition, PixelStrictPosition, PixelStrictPositionInterface},
         Pixel, PixelInitializer, PixelInterface, PixelMutInterface,
     },
     prelude::{Drawable, MaybePixel, PixelColor},
 };
 
-use super::PixelCanvas;
+#[cfg(feature = "serde")]
+pub use super::serialize::ProjectError;
 
-#[derive(Debug, Default)]
+use super::{PixelCanvas, PixelCanvasInterface, PixelCanvasMutInterface, SharedPixelCanvasExt};
+
+/// How colors of a layer are combined with the colors below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlendMode {
+    /// The layer color replaces the one below.
+    #[default]
+    Normal,
+
+    /// Darkens, useful for shadows.
+    Multiply,
+
+    /// Lightens, the opposite of [`BlendMode::Multiply`].
+    Screen,
+
+    /// Adds the channels, useful for glows and lights.
+    Additive,
+}
+
+impl BlendMode {
+    /// The color of `over` drawn on `under` with this mode.
+    pub fn blend(&self, under: PixelColor, over: PixelColor) -> PixelColor {
+        let channel = |under: u8, over: u8| -> u8 {
+            let (under, over) = (under as u16, over as u16);
+            match self {
+                BlendMode::Normal => over as u8,
+                BlendMode::Multiply => (under * over / 255) as u8,
+                BlendMode::Screen => (255 - (255 - under) * (255 - over) / 255) as u8,
+                BlendMode::Additive => (under + over).min(255) as u8,
+            }
+        };
+        PixelColor::new(
+            channel(under.r, over.r),
+            channel(under.g, over.g),
+            channel(under.b, over.b),
+        )
+    }
+}
+
+/// Where the top left of a layer goes on the canvas, may be negative or out of the canvas
+/// so the layer is only partially (or not at all) visible.
+///
+/// The layer may also sit between pixels by a [`SubpixelOffset`], which is resolved by
+/// supersampling in [`LayeredCanvas::get_resulting_canvas`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayerOffset {
+    pub row: isize,
+    pub column: isize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub subpixel: SubpixelOffset,
+}
+
+impl LayerOffset {
+    pub const fn new(row: isize, column: isize) -> Self {
+        Self {
+            row,
+            column,
+            subpixel: SubpixelOffset::ZERO,
+        }
+    }
+
+    /// Offset at a fractional `(row, column)`, snapped to the nearest [`SubpixelOffset::STEPS`].
+    pub fn fractional(row: f32, column: f32) -> Self {
+        let ((row, column), subpixel) = SubpixelOffset::split((row, column));
+        Self {
+            row,
+            column,
+            subpixel,
+        }
+    }
+
+    /// `(row, column)` of the offset including its [`SubpixelOffset`].
+    pub fn to_fractional(&self) -> (f32, f32) {
+        let (row, column) = self.subpixel.fraction();
+        (self.row as f32 + row, self.column as f32 + column)
+    }
+
+    /// This offset moved by `rows` and `columns`.
+    pub const fn translated(&self, rows: isize, columns: isize) -> Self {
+        Self {
+            row: self.row + rows,
+            column: self.column + columns,
+            subpixel: self.subpixel,
+        }
+    }
+
+    /// Position of the offset on a canvas, `None` if it's outside.
+    pub fn to_strict_position<const H: usize, const W: usize>(
+        &self,
+    ) -> Option<PixelStrictPosition<H, W>> {
+        let row = usize::try_from(self.row).ok()?;
+        let column = usize::try_from(self.column).ok()?;
+        PixelStrictPosition::new(row, column).ok()
+    }
+
+    /// Position on the canvas of the pixel at `(row, column)` of the layer, `None` if it's outside.
+    pub fn apply<const H: usize, const W: usize>(
+        &self,
+        (row, column): (usize, usize),
+    ) -> Option<PixelStrictPosition<H, W>> {
+        self.translated(row as isize, column as isize)
+            .to_strict_position()
+    }
+}
+
+impl From<(isize, isize)> for LayerOffset {
+    fn from((row, column): (isize, isize)) -> Self {
+        Self::new(row, column)
+    }
+}
+
+impl From<(f32, f32)> for LayerOffset {
+    fn from((row, column): (f32, f32)) -> Self {
+        Self::fractional(row, column)
+    }
+}
+
+impl<const H: usize, const W: usize> From<PixelStrictPosition<H, W>> for LayerOffset {
+    fn from(value: PixelStrictPosition<H, W>) -> Self {
+        let (row, column) = value.expand();
+        Self::new(row as isize, column as isize)
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct LayerData<const H: usize, const W: usize> {
     layer_tag: Option<String>,
-    pub drawing_position: PixelStrictPosition<H, W>,
+    pub offset: LayerOffset,
     pub canvas: PixelCanvas<H, W, MaybePixel>,
+    /// How much the layer covers the layers below, from `0` (invisible) to `u8::MAX` (opaque).
+    pub opacity: u8,
+    pub blend_mode: BlendMode,
+    /// Hidden layers are skipped when composing, see [`LayeredCanvas::get_resulting_canvas`].
+    pub visible: bool,
+}
+
+impl<const H: usize, const W: usize> Default for LayerData<H, W> {
+    fn default() -> Self {
+        Self::new_without_tag(PixelCanvas::default())
+    }
 }
 
 impl<const H: usize, const W: usize> LayerData<H, W> {
@@ -23,9 +163,12 @@ impl<const H: usize, const W: usize> LayerData<H, W> {
         canvas: PixelCanvas<H, W, MaybePixel>,
     ) -> Self {
         Self {
-            drawing_position: PixelStrictPosition::new(0, 0).unwrap(),
+            offset: LayerOffset::default(),
             layer_tag: layer_tag.into(),
             canvas,
+            opacity: u8::MAX,
+            blend_mode: BlendMode::Normal,
+            visible: true,
         }
     }
 
@@ -40,9 +183,12 @@ impl<const H: usize, const W: usize> LayerData<H, W> {
         let mut canvas = PixelCanvas::default();
         canvas_builder(&mut canvas);
         Self {
-            drawing_position: PixelStrictPosition::new(0, 0).unwrap(),
+            offset: LayerOffset::default(),
             layer_tag: layer_tag.into(),
             canvas,
+            opacity: u8::MAX,
+            blend_mode: BlendMode::Normal,
+            visible: true,
         }
     }
 
@@ -60,7 +206,39 @@ impl<const H: usize, const W: usize> LayerData<H, W> {
         mut self,
         start_position: impl IntoPixelStrictPosition<H, W>,
     ) -> Self {
-        self.drawing_position = start_position.into_pixel_strict_position();
+        self.offset = start_position.into_pixel_strict_position().into();
+        self
+    }
+
+    pub fn with_offset(mut self, offset: impl Into<LayerOffset>) -> Self {
+        self.offset = offset.into();
+        self
+    }
+
+    /// The offset as a position on the canvas, clamped into it.
+    #[deprecated(note = "Layers may be offset outside of the canvas, use the `offset` instead.")]
+    pub fn drawing_position(&self) -> PixelStrictPosition<H, W> {
+        self.clamped_offset()
+    }
+
+    fn clamped_offset(&self) -> PixelStrictPosition<H, W> {
+        let clamp = |value: isize, max: usize| value.clamp(0, max as isize - 1) as usize;
+        PixelStrictPosition::new(clamp(self.offset.row, H), clamp(self.offset.column, W))
+            .expect("Clamped position is inside the canvas.")
+    }
+
+    pub fn with_opacity(mut self, opacity: u8) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    pub fn with_blend_mode(mut self, blend_mode: BlendMode) -> Self {
+        self.blend_mode = blend_mode;
+        self
+    }
+
+    pub fn with_visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
         self
     }
 
@@ -77,11 +255,24 @@ impl<const H: usize, const W: usize> LayerData<H, W> {
         self
     }
 
+    /// Moves the layer as much as the `updater` moves the offset clamped into the canvas.
+    ///
+    /// The offset itself is never clamped, so a layer partially outside of the canvas stays
+    /// so. See [`Self::update_offset`] to move it freely.
     pub fn update_drawing_position(
         &mut self,
         updater: impl FnOnce(&PixelStrictPosition<H, W>) -> PixelStrictPosition<H, W>,
     ) {
-        self.drawing_position = updater(&self.drawing_position);
+        let current = self.clamped_offset();
+        let (row, column) = updater(&current).expand();
+        self.offset = self.offset.translated(
+            row as isize - current.row() as isize,
+            column as isize - current.column() as isize,
+        );
+    }
+
+    pub fn update_offset(&mut self, updater: impl FnOnce(LayerOffset) -> LayerOffset) {
+        self.offset = updater(self.offset);
     }
 }
 
@@ -91,7 +282,16 @@ pub enum AddLayerError {
     LayerTagDuplicated,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Error)]
+pub enum LayerError {
+    #[error("There's no layer {0:?}")]
+    NotFound(TopLayerId),
+
+    #[error(transparent)]
+    Add(#[from] AddLayerError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TopLayerId {
     Tag(String),
     Index(usize),
@@ -118,16 +318,22 @@ impl From<&'static str> for TopLayerId {
 pub struct LayeredCanvas<const H: usize, const W: usize = H, P: PixelInterface + Default = Pixel> {
     pub(crate) base_layer: PixelCanvas<H, W, P>,
     pub(crate) top_layers: Vec<LayerData<H, W>>, // Top layers are all using maybe (transparent) pixel
+    pub(crate) supersample: Supersample,
 }
 
 impl<const H: usize, const W: usize, P: PixelInterface + Default> LayeredCanvas<H, W, P> {
+    /// Samples per pixel used for layers with a [`SubpixelOffset`].
+    pub fn supersample(&self) -> Supersample {
+        self.supersample
+    }
+
+    pub fn set_supersample(&mut self, supersample: Supersample) {
+        self.supersample = supersample;
+    }
+
     pub fn new_layer(&mut self, layer_data: LayerData<H, W>) -> Result<usize, AddLayerError> {
         if let Some(tag) = &layer_data.layer_tag {
-            if self
-                .top_layers
-                .iter()
-                .any(|x| x.layer_tag.as_ref().is_some_and(|x| x == tag))
-            {
+            if self.is_tag_used(tag) {
                 return Err(AddLayerError::LayerTagDuplicated);
             }
         }
@@ -137,18 +343,211 @@ impl<const H: usize, const W: usize, P: PixelInterface + Default> LayeredCanvas<
         Ok(self.top_layers.len() - 1)
     }
 
+    fn is_tag_used(&self, tag: &str) -> bool {
+        self.top_layers
+            .iter()
+            .any(|x| x.layer_tag.as_ref().is_some_and(|x| x == tag))
+    }
+
+    /// Number of top layers (without the base layer).
+    pub fn layer_count(&self) -> usize {
+        self.top_layers.len()
+    }
+
+    /// Index of a top layer, layers with higher indexes are drawn later (on top).
+    pub fn layer_index(&self, layer_id: impl Into<TopLayerId>) -> Option<usize> {
+        match layer_id.into() {
+            TopLayerId::Tag(tag) => self
+                .top_layers
+                .iter()
+                .position(|x| x.layer_tag.as_ref().is_some_and(|x| x == &tag)),
+            TopLayerId::Index(index) => (index < self.top_layers.len()).then_some(index),
+        }
+    }
+
+    fn existing_index(&self, layer_id: impl Into<TopLayerId>) -> Result<usize, LayerError> {
+        let layer_id = layer_id.into();
+        self.layer_index(layer_id.clone())
+            .ok_or(LayerError::NotFound(layer_id))
+    }
+
+    /// Removes a top layer, layers above it move one index down.
+    pub fn remove_layer(
+        &mut self,
+        layer_id: impl Into<TopLayerId>,
+    ) -> Result<LayerData<H, W>, LayerError> {
+        let index = self.existing_index(layer_id)?;
+        Ok(self.top_layers.remove(index))
+    }
+
+    /// Shows or hides a top layer.
+    pub fn set_visible(
+        &mut self,
+        layer_id: impl Into<TopLayerId>,
+        visible: bool,
+    ) -> Result<(), LayerError> {
+        let index = self.existing_index(layer_id)?;
+        self.top_layers[index].visible = visible;
+        Ok(())
+    }
+
+    /// Shows only this top layer (over the base layer) and hides all others.
+    ///
+    /// Use [`Self::show_all`] to bring the others back.
+    pub fn solo(&mut self, layer_id: impl Into<TopLayerId>) -> Result<(), LayerError> {
+        let index = self.existing_index(layer_id)?;
+        for (other, layer) in self.top_layers.iter_mut().enumerate() {
+            layer.visible = other == index;
+        }
+        Ok(())
+    }
+
+    pub fn show_all(&mut self) {
+        for layer in self.top_layers.iter_mut() {
+            layer.visible = true;
+        }
+    }
+
+    /// Copies a top layer right above the original one, returns index of the copy.
+    pub fn duplicate_layer(
+        &mut self,
+        layer_id: impl Into<TopLayerId>,
+        new_tag: impl Into<Option<String>>,
+    ) -> Result<usize, LayerError> {
+        let index = self.existing_index(layer_id)?;
+        let new_tag = new_tag.into();
+        if new_tag.as_ref().is_some_and(|tag| self.is_tag_used(tag)) {
+            return Err(AddLayerError::LayerTagDuplicated.into());
+        }
+
+        let mut copy = self.top_layers[index].clone();
+        copy.layer_tag = new_tag;
+        self.top_layers.insert(index + 1, copy);
+        Ok(index + 1)
+    }
+
+    /// Moves a top layer to `new_index` (clamped to the last layer), shifting the ones in between.
+    pub fn move_layer(
+        &mut self,
+        layer_id: impl Into<TopLayerId>,
+        new_index: usize,
+    ) -> Result<usize, LayerError> {
+        let index = self.existing_index(layer_id)?;
+        let new_index = new_index.min(self.top_layers.len() - 1);
+        let layer = self.top_layers.remove(index);
+        self.top_layers.insert(new_index, layer);
+        Ok(new_index)
+    }
+
+    /// Draws the visible top layers over the base layer, in order.
+    ///
+    /// Layers which are not fully opaque, have a [`BlendMode`] other than normal or sit
+    /// between pixels are blended over what's below them.
     pub fn get_resulting_canvas<E>(&self) -> PixelCanvas<H, W, P>
     where
         P: PartialEq + Clone + PixelMutInterface,
-        P::ColorType: Clone + TryFrom<Option<PixelColor>, Error = E>,
+        P::ColorType: Clone + RgbaInterface + TryFrom<Option<PixelColor>, Error = E>,
     {
         let mut base = self.base_layer.clone();
-        for top in self.top_layers.iter() {
-            top.canvas.draw_on_exact(top.drawing_position, &mut base);
+        for top in self.top_layers.iter().filter(|top| top.visible) {
+            Self::compose_layer(top, &mut base, self.supersample);
         }
         base
     }
 
+    /// Bakes a top layer into the base layer and removes it, as if it was drawn right on the
+    /// base (its visibility is ignored).
+    ///
+    /// Layers between the base and this one are on top of its pixels afterwards.
+    pub fn merge_down<E>(
+        &mut self,
+        layer_id: impl Into<TopLayerId>,
+    ) -> Result<LayerData<H, W>, LayerError>
+    where
+        P: PartialEq + Clone + PixelMutInterface,
+        P::ColorType: Clone + RgbaInterface + TryFrom<Option<PixelColor>, Error = E>,
+    {
+        let layer = self.remove_layer(layer_id)?;
+        Self::compose_layer(&layer, &mut self.base_layer, self.supersample);
+        Ok(layer)
+    }
+
+    /// Bakes all visible top layers into the base layer, and removes all top layers.
+    pub fn flatten<E>(&mut self)
+    where
+        P: PartialEq + Clone + PixelMutInterface,
+        P::ColorType: Clone + RgbaInterface + TryFrom<Option<PixelColor>, Error = E>,
+    {
+        for top in self.top_layers.drain(..).filter(|top| top.visible) {
+            Self::compose_layer(&top, &mut self.base_layer, self.supersample);
+        }
+    }
+
+    fn compose_layer<E>(
+        top: &LayerData<H, W>,
+        base: &mut PixelCanvas<H, W, P>,
+        supersample: Supersample,
+    ) where
+        P: PartialEq + Clone + PixelMutInterface,
+        P::ColorType: Clone + RgbaInterface + TryFrom<Option<PixelColor>, Error = E>,
+    {
+        match (top.opacity, top.blend_mode, top.offset.to_strict_position()) {
+            (0, _, _) => {}
+            (u8::MAX, BlendMode::Normal, Some(start)) if top.offset.subpixel.is_zero() => {
+                top.canvas.draw_on_exact(start, base)
+            }
+            _ => Self::blend_layer(top, base, supersample),
+        }
+    }
+
+    fn blend_layer<E>(
+        top: &LayerData<H, W>,
+        base: &mut PixelCanvas<H, W, P>,
+        supersample: Supersample,
+    ) where
+        P: PartialEq + Clone + PixelMutInterface,
+        P::ColorType: Clone + RgbaInterface + TryFrom<Option<PixelColor>, Error = E>,
+    {
+        let colors = top
+            .canvas
+            .table()
+            .real_items()
+            .filter_map(|(position, pixel)| pixel.color().map(|color| (position, color)));
+
+        // Each covered position with its color and how much of it is covered.
+        let covered: Vec<(PixelStrictPosition<H, W>, PixelColor, f32)> =
+            if top.offset.subpixel.is_zero() {
+                colors
+                    .filter_map(|(position, color)| Some((top.offset.apply(position)?, color, 1.)))
+                    .collect()
+            } else {
+                let mut grid = SampleGrid::new((H, W), supersample);
+                grid.place(top.offset.to_fractional(), colors);
+                grid.resolve()
+                    .filter_map(|((row, column), color, coverage)| {
+                        Some((PixelStrictPosition::new(row, column).ok()?, color, coverage))
+                    })
+                    .collect()
+            };
+
+        for (pos, color, coverage) in covered {
+            let under = base.color_at(pos).rgba();
+            // Nothing to blend with on transparent pixels.
+            let blended = if under[3] == 0 {
+                color
+            } else {
+                let under = PixelColor::new(under[0], under[1], under[2]);
+                under.lerp(
+                    top.blend_mode.blend(under, color),
+                    top.opacity as f32 / u8::MAX as f32 * coverage,
+                )
+            };
+            if let Ok(blended) = P::ColorType::try_from(Some(blended)) {
+                base.table_mut().get_pixel_mut(pos).update_color(blended);
+            }
+        }
+    }
+
     pub fn base_layer(&self) -> &PixelCanvas<H, W, P> {
         &self.base_layer
     }
@@ -158,28 +557,16 @@ impl<const H: usize, const W: usize, P: PixelInterface + Default> LayeredCanvas<
     }
 
     pub fn top_layer(&self, layer_id: impl Into<TopLayerId>) -> Option<&LayerData<H, W>> {
-        let layer_id: TopLayerId = layer_id.into();
-        match layer_id {
-            TopLayerId::Tag(tag) => self
-                .top_layers
-                .iter()
-                .find(|x| x.layer_tag.as_ref().is_some_and(|x| x == &tag)),
-            TopLayerId::Index(index) => self.top_layers.get(index),
-        }
+        self.layer_index(layer_id)
+            .map(|index| &self.top_layers[index])
     }
 
     pub fn top_layer_mut(
         &mut self,
         layer_id: impl Into<TopLayerId>,
     ) -> Option<&mut LayerData<H, W>> {
-        let layer_id: TopLayerId = layer_id.into();
-        match layer_id {
-            TopLayerId::Tag(tag) => self
-                .top_layers
-                .iter_mut()
-                .find(|x| x.layer_tag.as_ref().is_some_and(|x| x == &tag)),
-            TopLayerId::Index(index) => self.top_layers.get_mut(index),
-        }
+        self.layer_index(layer_id)
+            .map(|index| &mut self.top_layers[index])
     }
 }
 
@@ -193,6 +580,7 @@ where
         Self {
             base_layer: PixelCanvas::default(),
             top_layers: Vec::new(),
+            supersample: Supersample::default(),
         }
     }
 }
@@ -207,7 +595,10 @@ mod tests {
             },
             position::{Direction, PixelStrictPositionInterface},
         },
-        prelude::{CENTER, MAGENTA, TOP_LEFT},
+        pos,
+        prelude::{
+            BLACK, BLUE, BOTTOM_RIGHT, CENTER, GREEN, MAGENTA, RED, TOP_LEFT, TOP_RIGHT, WHITE,
+        },
     };
 
     use super::*;
@@ -245,4 +636,220 @@ mod tests {
             .save("arts/layered_0.png")
             .unwrap()
     }
+
+    #[test]
+    fn test_opacity() {
+        let mut layered = LayeredCanvas::<2>::default();
+        layered.base_layer_mut().fill(BLACK);
+
+        let half = LayerData::build_new_without_tag(|canvas| {
+            canvas.update_color_at(TOP_LEFT, WHITE);
+        })
+        .with_opacity(128);
+        let hidden = LayerData::build_new_without_tag(|canvas| {
+            canvas.update_color_at(BOTTOM_RIGHT, RED);
+        })
+        .with_opacity(0);
+        assert_eq!(LayerData::<2, 2>::default().opacity, u8::MAX);
+
+        layered.new_layer(half).unwrap();
+        layered.new_layer(hidden).unwrap();
+        let result = layered.get_resulting_canvas();
+        assert_eq!(result.color_at(TOP_LEFT), PixelColor::new(128, 128, 128));
+        assert_eq!(result.color_at(BOTTOM_RIGHT), BLACK);
+    }
+
+    #[test]
+    fn test_layer_management() {
+        let mut layered = LayeredCanvas::<2>::default();
+        for tag in ["a", "b", "c"] {
+            layered
+                .new_layer(LayerData::default().with_layer_tag(tag))
+                .unwrap();
+        }
+        let tags = |layered: &LayeredCanvas<2>| {
+            layered
+                .top_layers
+                .iter()
+                .map(|layer| layer.layer_tag().cloned().unwrap_or_default())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(layered.duplicate_layer("a", "a2".to_string()).unwrap(), 1);
+        assert!(matches!(
+            layered.duplicate_layer("b", "c".to_string()),
+            Err(LayerError::Add(AddLayerError::LayerTagDuplicated))
+        ));
+        assert_eq!(tags(&layered), ["a", "a2", "b", "c"]);
+
+        assert_eq!(layered.move_layer("a", 10).unwrap(), 3);
+        assert_eq!(tags(&layered), ["a2", "b", "c", "a"]);
+        assert_eq!(layered.move_layer(3, 1).unwrap(), 1);
+        assert_eq!(tags(&layered), ["a2", "a", "b", "c"]);
+
+        assert_eq!(
+            layered.remove_layer("b").unwrap().layer_tag(),
+            Some(&"b".to_string())
+        );
+        assert!(matches!(
+            layered.remove_layer("b"),
+            Err(LayerError::NotFound(TopLayerId::Tag(tag))) if tag == "b"
+        ));
+        assert!(matches!(
+            layered.move_layer(5, 0),
+            Err(LayerError::NotFound(TopLayerId::Index(5)))
+        ));
+        assert_eq!(layered.layer_count(), 3);
+        assert_eq!(layered.layer_index("c"), Some(2));
+    }
+
+    #[test]
+    fn test_visibility() {
+        let mut layered = LayeredCanvas::<2>::default();
+        for (tag, pos) in [("a", TOP_LEFT), ("b", BOTTOM_RIGHT)] {
+            layered
+                .new_layer(
+                    LayerData::build_new_without_tag(|canvas| {
+                        canvas.update_color_at(pos, RED);
+                    })
+                    .with_layer_tag(tag),
+                )
+                .unwrap();
+        }
+
+        layered.set_visible("a", false).unwrap();
+        let result = layered.get_resulting_canvas();
+        assert_eq!(result.color_at(TOP_LEFT), WHITE);
+        assert_eq!(result.color_at(BOTTOM_RIGHT), RED);
+
+        layered.solo("a").unwrap();
+        let result = layered.get_resulting_canvas();
+        assert_eq!(result.color_at(TOP_LEFT), RED);
+        assert_eq!(result.color_at(BOTTOM_RIGHT), WHITE);
+
+        layered.show_all();
+        assert!(layered.top_layers.iter().all(|layer| layer.visible));
+        assert!(matches!(layered.solo("c"), Err(LayerError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_merging() {
+        let mut layered = LayeredCanvas::<2>::default();
+        for (tag, pos, color) in [
+            ("a", TOP_LEFT, RED),
+            ("b", TOP_LEFT, BLUE),
+            ("c", BOTTOM_RIGHT, GREEN),
+        ] {
+            layered
+                .new_layer(
+                    LayerData::build_new_without_tag(|canvas| {
+                        canvas.update_color_at(pos, color);
+                    })
+                    .with_layer_tag(tag),
+                )
+                .unwrap();
+        }
+        let before = layered.get_resulting_canvas();
+
+        layered.merge_down("a").unwrap();
+        assert_eq!(layered.base_layer().color_at(TOP_LEFT), RED);
+        assert_eq!(layered.layer_count(), 2);
+        assert!(matches!(
+            layered.merge_down("a"),
+            Err(LayerError::NotFound(_))
+        ));
+
+        layered.set_visible("c", false).unwrap();
+        layered.flatten();
+        assert_eq!(layered.layer_count(), 0);
+        assert_eq!(layered.base_layer().color_at(TOP_LEFT), BLUE);
+        assert_eq!(layered.base_layer().color_at(BOTTOM_RIGHT), WHITE);
+        assert_ne!(layered.base_layer().table(), before.table());
+    }
+
+    #[test]
+    fn test_offsets() {
+        let sprite = LayerData::<3, 3>::build_new_without_tag(|canvas| {
+            canvas.fill(RED);
+        });
+        let mut layered = LayeredCanvas::<3>::default();
+        layered
+            .new_layer(sprite.clone().with_offset((-2, 1)))
+            .unwrap();
+        let result = layered.get_resulting_canvas();
+        assert_eq!(result.color_at(TOP_LEFT), WHITE);
+        assert_eq!(result.color_at(TOP_RIGHT), RED);
+        assert_eq!(result.color_at(pos!(1, 1; 3, 3)), WHITE);
+
+        let layer = layered.top_layer_mut(0).unwrap();
+        #[allow(deprecated)]
+        let drawing_position = layer.drawing_position();
+        assert_eq!(drawing_position, pos!(0, 1; 3, 3));
+        layer.update_offset(|offset| offset.translated(4, -5));
+        assert_eq!(layer.offset, LayerOffset::new(2, -4));
+        // Only the movement is applied, the column stays outside.
+        layer.update_drawing_position(|pos| pos.bounding_up(1));
+        assert_eq!(layer.offset, LayerOffset::new(1, -4));
+        assert!(LayerOffset::new(0, 3)
+            .to_strict_position::<3, 3>()
+            .is_none());
+    }
+
+    #[test]
+    fn test_subpixel_offsets() {
+        let sprite = LayerData::<1, 3>::build_new_without_tag(|canvas| {
+            canvas.update_color_at(TOP_LEFT, BLACK);
+        });
+        let mut layered = LayeredCanvas::<1, 3>::default();
+        layered.new_layer(sprite.with_offset((0.0, 0.5))).unwrap();
+
+        let offset = layered.top_layer(0).unwrap().offset;
+        assert_eq!(offset, LayerOffset::fractional(0.0, 0.5));
+        assert_eq!(offset.to_fractional(), (0.0, 0.5));
+        assert_eq!(LayerOffset::fractional(-0.25, 1.0).row, -1);
+
+        let result = layered.get_resulting_canvas();
+        assert_eq!(result.color_at(TOP_LEFT), PixelColor::splat(128));
+        assert_eq!(result.color_at(pos!(0, 1; 1, 3)), PixelColor::splat(128));
+        assert_eq!(result.color_at(TOP_RIGHT), WHITE);
+
+        layered.top_layer_mut(0).unwrap().offset = LayerOffset::fractional(0.0, 0.25);
+        layered.set_supersample(Supersample::X4);
+        let result = layered.get_resulting_canvas();
+        assert_eq!(result.color_at(TOP_LEFT), PixelColor::splat(64));
+        assert_eq!(result.color_at(pos!(0, 1; 1, 3)), PixelColor::splat(191));
+    }
+
+    #[test]
+    fn test_blend_modes() {
+        let under = PixelColor::new(100, 200, 0);
+        let over = PixelColor::new(200, 100, 255);
+        assert_eq!(BlendMode::Normal.blend(under, over), over);
+        assert_eq!(
+            BlendMode::Multiply.blend(under, over),
+            PixelColor::new(78, 78, 0)
+        );
+        assert_eq!(
+            BlendMode::Screen.blend(under, over),
+            PixelColor::new(222, 222, 255)
+        );
+        assert_eq!(
+            BlendMode::Additive.blend(under, over),
+            PixelColor::new(255, 255, 255)
+        );
+
+        let mut layered = LayeredCanvas::<2>::default();
+        layered.base_layer_mut().fill(under);
+        layered
+            .new_layer(
+                LayerData::build_new_without_tag(|canvas| {
+                    canvas.update_color_at(TOP_LEFT, PixelColor::new(128, 128, 128));
+                })
+                .with_blend_mode(BlendMode::Multiply),
+            )
+            .unwrap();
+        let result = layered.get_resulting_canvas();
+        assert_eq!(result.color_at(TOP_LEFT), PixelColor::new(50, 100, 0));
+        assert_eq!(result.color_at(BOTTOM_RIGHT), under);
+    }
 }
@@ -0,0 +1,155 @@
+//! Masks decide which pixels of a canvas an edit may touch, see [`Mask`].
+//!
+
+use crate::pixels::position::{
+    IntoPixelStrictPosition, PixelStrictPosition, PixelStrictPositionInterface,
+};
+
+use super::selection::{all_positions, Selection};
+
+const BITS: usize = u64::BITS as usize;
+
+/// A bitset of the allowed positions of a `H` * `W` canvas.
+///
+/// Masked edits like [`SharedMutPixelCanvasExt::fill_masked`] only affect allowed pixels,
+/// e.g. to protect finished areas of a piece.
+///
+/// [`SharedMutPixelCanvasExt::fill_masked`]: super::SharedMutPixelCanvasExt::fill_masked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mask<const H: usize, const W: usize> {
+    bits: Vec<u64>,
+}
+
+impl<const H: usize, const W: usize> Default for Mask<H, W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const H: usize, const W: usize> Mask<H, W> {
+    /// A mask where nothing is allowed.
+    pub fn new() -> Self {
+        Self {
+            bits: vec![0; (H * W).div_ceil(BITS)],
+        }
+    }
+
+    /// A mask where everything is allowed.
+    pub fn full() -> Self {
+        Self::new().inverted()
+    }
+
+    fn bit(pos: impl IntoPixelStrictPosition<H, W>) -> (usize, u64) {
+        let (row, column) = pos.into_pixel_strict_position().expand();
+        let index = row * W + column;
+        (index / BITS, 1 << (index % BITS))
+    }
+
+    /// Allows the position, returns `false` if it was already allowed.
+    pub fn allow(&mut self, pos: impl IntoPixelStrictPosition<H, W>) -> bool {
+        let (word, bit) = Self::bit(pos);
+        let was_allowed = self.bits[word] & bit != 0;
+        self.bits[word] |= bit;
+        !was_allowed
+    }
+
+    /// Disallows the position, returns `false` if it was not allowed anyway.
+    pub fn deny(&mut self, pos: impl IntoPixelStrictPosition<H, W>) -> bool {
+        let (word, bit) = Self::bit(pos);
+        let was_allowed = self.bits[word] & bit != 0;
+        self.bits[word] &= !bit;
+        was_allowed
+    }
+
+    pub fn is_allowed(&self, pos: impl IntoPixelStrictPosition<H, W>) -> bool {
+        let (word, bit) = Self::bit(pos);
+        self.bits[word] & bit != 0
+    }
+
+    /// Number of allowed positions.
+    pub fn len(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|word| *word == 0)
+    }
+
+    /// A mask allowing exactly what this one doesn't.
+    pub fn inverted(&self) -> Self {
+        let mut mask = Self {
+            bits: self.bits.iter().map(|word| !word).collect(),
+        };
+        // Keep the unused bits of the last word off, so len and eq stay correct.
+        let used = (H * W) % BITS;
+        if let (Some(last), true) = (mask.bits.last_mut(), used != 0) {
+            *last &= (1 << used) - 1;
+        }
+        mask
+    }
+
+    /// Allowed positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = PixelStrictPosition<H, W>> + '_ {
+        all_positions::<H, W>().filter(|pos| self.is_allowed(*pos))
+    }
+}
+
+impl<const H: usize, const W: usize> From<&Selection<H, W>> for Mask<H, W> {
+    fn from(value: &Selection<H, W>) -> Self {
+        value.iter().collect()
+    }
+}
+
+impl<const H: usize, const W: usize> FromIterator<PixelStrictPosition<H, W>> for Mask<H, W> {
+    fn from_iter<T: IntoIterator<Item = PixelStrictPosition<H, W>>>(iter: T) -> Self {
+        let mut mask = Self::new();
+        for pos in iter {
+            mask.allow(pos);
+        }
+        mask
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_mask() {
+        let mut mask = Mask::<3, 30>::new();
+        assert!(mask.is_empty());
+        assert!(mask.allow(TOP_LEFT));
+        assert!(!mask.allow(TOP_LEFT));
+        assert!(mask.allow(BOTTOM_RIGHT));
+        assert_eq!(mask.len(), 2);
+
+        let inverted = mask.inverted();
+        assert_eq!(inverted.len(), 88);
+        assert!(!inverted.is_allowed(BOTTOM_RIGHT));
+        assert_eq!(Mask::<3, 30>::full().len(), 90);
+
+        assert!(mask.deny(TOP_LEFT));
+        assert_eq!(
+            mask.iter().collect::<Vec<_>>(),
+            vec![BOTTOM_RIGHT.into_pixel_strict_position()]
+        );
+
+        let mut canvas = PixelCanvas::<3>::default();
+        let mut mask = Mask::<3, 3>::full();
+        mask.deny(CENTER);
+        canvas.fill_masked(RED, &mask);
+        assert_eq!(canvas.color_at(TOP_LEFT), RED);
+        assert_eq!(canvas.color_at(CENTER), WHITE);
+
+        let mut square = MaybePixelCanvas::<2>::default();
+        square.fill(BLUE);
+        canvas.draw_masked(CENTER, square, &mask);
+        assert_eq!(canvas.color_at(CENTER), WHITE);
+        assert_eq!(canvas.color_at(BOTTOM_RIGHT), BLUE);
+    }
+}
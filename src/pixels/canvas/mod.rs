@@ -1,28 +1,50 @@
 //! Module contains types related to a [`PixelCanvas`].
 
-use std::fmt::Debug;
+use std::{collections::HashMap, fmt::Debug};
 
 use partition::CanvasPartition;
+use pixelart_table_abs::table::IllusionTableStats;
 
 use crate::image::{PixelImageBuilder, PixelImageStyle};
 
-use self::{drawable::Drawable, pen::Pen, table::PixelTable};
+use self::{
+    compress::{ColorRect, ColorRun},
+    drawable::{AutoFit, DimensionMismatchError, Drawable},
+    grid::{PixelGridMutExt, TableGrid},
+    mask::Mask,
+    pen::Pen,
+    selection::Selection,
+    table::PixelTable,
+    text::Font,
+};
 
 use super::{
-    color::PixelColor,
+    color::{palettes::Palette, quantize::median_cut, ColorTolerance, PixelColor, RgbaInterface},
     maybe::MaybePixel,
     position::{
-        IntoPixelStrictPosition, PixelStrictPositionInterface, SingleCycle, MAIN_DIRECTIONS,
+        Connectivity, Direction, IntoPixelStrictPosition, OutOfBoundsError, OutOfBoundsPolicy,
+        PixelStrictPosition, PixelStrictPositionInterface, ShiftMode,
     },
     Pixel, PixelInitializer, PixelInterface, PixelMutInterface,
 };
 
+pub mod compress;
+pub mod dense;
 pub mod drawable;
+pub mod dynamic;
+pub mod grid;
 pub mod layered;
+pub mod mask;
 pub mod partition;
 pub mod pen;
+pub mod selection;
+#[cfg(feature = "serde")]
+mod serialize;
+pub mod shapes;
 pub mod table;
 pub mod templates;
+pub mod text;
+pub mod unbounded;
 
 /// Interface that any read_only pixel canvas may want to implement.
 ///
@@ -88,14 +110,118 @@ where
         canvas
     }
 
-    pub fn flip_x(&mut self) -> &mut PixelCanvas<H, W, P> {
-        for row in 0..H {
-            for col in 0..W / 2 {
-                let opposite_col = W - col - 1;
-                self.table.swap((row, col), (row, opposite_col));
+    /// An empty canvas backed by a dense table, see [`PixelTable::dense`] and
+    /// [`DensePixelCanvas`](dense::DensePixelCanvas).
+    pub fn dense() -> Self
+    where
+        P: Clone,
+    {
+        Self {
+            table: PixelTable::dense(),
+        }
+    }
+
+    /// An empty `NH` * `NW` canvas using the same backend as this one.
+    fn empty_like<const NH: usize, const NW: usize>(&self) -> PixelCanvas<NH, NW, P>
+    where
+        P: Clone,
+    {
+        PixelCanvas {
+            table: PixelTable::with_backend(self.table.backend()),
+        }
+    }
+
+    /// Reports how many pixels are actually stored and roughly how much memory they take.
+    ///
+    /// Pixels having the default color aren't stored, so huge canvases are cheap while sparse.
+    pub fn memory_report(&self) -> IllusionTableStats {
+        self.table.stats()
+    }
+
+    /// Copies pixels of `source` from `top_left` to `bottom_right` (both included) onto this
+    /// canvas, so that `top_left` lands on `destination`.
+    ///
+    /// Canvases may have any size, pixels out of bounds of either one are ignored.
+    /// Only stored pixels are copied, so default (e.g. transparent) ones don't cover this
+    /// canvas. Tags of the copied pixels are copied along.
+    pub fn blit<const SH: usize, const SW: usize>(
+        &mut self,
+        source: &impl PixelCanvasInterface<SH, SW, P>,
+        top_left: impl IntoPixelStrictPosition<SH, SW>,
+        bottom_right: impl IntoPixelStrictPosition<SH, SW>,
+        destination: impl IntoPixelStrictPosition<H, W>,
+    ) -> &mut PixelCanvas<H, W, P>
+    where
+        P: Clone + PartialEq,
+    {
+        // Clipping never fails.
+        let _ = self.blit_with_policy(
+            source,
+            top_left,
+            bottom_right,
+            destination,
+            OutOfBoundsPolicy::Clip,
+        );
+        self
+    }
+
+    /// As same as [`PixelCanvas::blit`] but pixels going out of this canvas are handled based
+    /// on the given [`OutOfBoundsPolicy`].
+    ///
+    /// Nothing is copied if the policy fails.
+    pub fn blit_with_policy<const SH: usize, const SW: usize>(
+        &mut self,
+        source: &impl PixelCanvasInterface<SH, SW, P>,
+        top_left: impl IntoPixelStrictPosition<SH, SW>,
+        bottom_right: impl IntoPixelStrictPosition<SH, SW>,
+        destination: impl IntoPixelStrictPosition<H, W>,
+        policy: OutOfBoundsPolicy,
+    ) -> Result<&mut PixelCanvas<H, W, P>, OutOfBoundsError>
+    where
+        P: Clone + PartialEq,
+    {
+        let (top_left, bottom_right) = (
+            top_left.into_pixel_strict_position().expand(),
+            bottom_right.into_pixel_strict_position().expand(),
+        );
+        let (destination_row, destination_column) =
+            destination.into_pixel_strict_position().expand();
+        let target = |(row, column): (usize, usize)| {
+            policy.resolve::<H, W>((
+                (row - top_left.0 + destination_row) as isize,
+                (column - top_left.1 + destination_column) as isize,
+            ))
+        };
+
+        let source = source.table();
+        if policy == OutOfBoundsPolicy::Error {
+            for ((row, column), _) in source.inner.real_items_in(top_left, bottom_right) {
+                target((row, column))?;
             }
         }
 
+        let mut copied = Vec::new();
+        self.table
+            .inner
+            .copy_from_with(&source.inner, top_left, bottom_right, |index, pixel| {
+                let position = target(index).ok().flatten()?;
+                copied.push((index, position));
+                Some((position.expand(), pixel.clone()))
+            });
+        for (index, position) in copied {
+            self.table
+                .tags_mut()
+                .set(position.expand(), source.tags().get(index));
+        }
+
+        Ok(self)
+    }
+
+    pub fn flip_x(&mut self) -> &mut PixelCanvas<H, W, P> {
+        for col in 0..W / 2 {
+            self.table.swap_columns(col, W - col - 1);
+        }
+
         self
     }
 
@@ -111,10 +237,7 @@ where
 
     pub fn flip_y(&mut self) -> &mut PixelCanvas<H, W, P> {
         for row in 0..H / 2 {
-            for col in 0..W {
-                let opposite_row = H - row - 1;
-                self.table.swap((row, col), (opposite_row, col));
-            }
+            self.table.swap_rows(row, H - row - 1);
         }
 
         self
@@ -129,6 +252,219 @@ where
         canvas.flip_y();
         canvas
     }
+
+    /// Creates a `W` * `H` copy of this canvas with rows and columns swapped.
+    pub fn transposed(&self) -> PixelCanvas<W, H, P>
+    where
+        P: Clone,
+    {
+        PixelCanvas {
+            table: self.table.transposed(),
+        }
+    }
+
+    /// Rotates the canvas by 180 degrees in place, same as flipping on both axes.
+    pub fn rotate_180(&mut self) -> &mut PixelCanvas<H, W, P> {
+        self.flip_x().flip_y()
+    }
+
+    /// Creates a copy of this canvas rotated by 90 degrees clockwise, so it's `W` * `H`.
+    pub fn rotate_90_cw(&self) -> PixelCanvas<W, H, P>
+    where
+        P: PixelMutInterface + PixelInitializer + PartialEq + Clone,
+        P::ColorType: Default + Clone,
+    {
+        self.remapped(|row, column| (H - column - 1, row))
+    }
+
+    /// Creates a copy of this canvas rotated by 90 degrees counterclockwise, so it's `W` * `H`.
+    pub fn rotate_90_ccw(&self) -> PixelCanvas<W, H, P>
+    where
+        P: PixelMutInterface + PixelInitializer + PartialEq + Clone,
+        P::ColorType: Default + Clone,
+    {
+        self.remapped(|row, column| (column, W - row - 1))
+    }
+
+    /// Creates a `NH` * `NW` canvas, where each pixel (and its tag) is copied from
+    /// the position of this canvas which `source` returns for it.
+    fn remapped<const NH: usize, const NW: usize>(
+        &self,
+        source: impl Fn(usize, usize) -> (usize, usize),
+    ) -> PixelCanvas<NH, NW, P>
+    where
+        P: PixelMutInterface + PixelInitializer + PartialEq + Clone,
+        P::ColorType: Default + Clone,
+    {
+        let mut canvas = self.empty_like::<NH, NW>();
+        for row in 0..NH {
+            for column in 0..NW {
+                let from = source(row, column);
+                canvas
+                    .get_pixel_mut((row, column))
+                    .update_color(self.get_pixel(from).color().clone());
+                canvas.tags.set((row, column), self.tags.get(from));
+            }
+        }
+        canvas
+    }
+
+    /// Scales this canvas up to `NH` * `NW` as actual canvas pixels, e.g. blowing a 16x16 sprite
+    /// up to 64x64 for further editing at the new resolution.
+    ///
+    /// New sizes must be whole multiples of the current ones, so each pixel becomes an exact
+    /// block. Use [`PixelCanvas::resampled`] for any other size.
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use pixelart::prelude::*;
+    /// let canvas = PixelCanvas::<16>::default();
+    /// let larger = canvas.scaled::<64, 32>();
+    /// ```
+    ///
+    /// This won't compile since 24 isn't a multiple of 16.
+    /// ```compile_fail
+    /// # use pixelart::prelude::*;
+    /// let canvas = PixelCanvas::<16>::default();
+    /// let larger = canvas.scaled::<24, 24>();
+    /// ```
+    pub fn scaled<const NH: usize, const NW: usize>(&self) -> PixelCanvas<NH, NW, P>
+    where
+        P: PixelMutInterface + PixelInitializer + PartialEq + Clone,
+        P::ColorType: Default + Clone,
+    {
+        let (row_factor, column_factor) = const {
+            assert!(
+                H > 0 && W > 0 && NH.is_multiple_of(H) && NW.is_multiple_of(W),
+                "New sizes must be whole multiples of the canvas size."
+            );
+            (NH / H, NW / W)
+        };
+        self.remapped(|row, column| (row / row_factor, column / column_factor))
+    }
+
+    /// Creates a `NH` * `NW` copy of this canvas using nearest-neighbor resampling.
+    ///
+    /// Sizes don't need to be multiples of each other, e.g. a 34x40 art can be fitted
+    /// into a 17x20 slot (or the other way around).
+    pub fn resampled<const NH: usize, const NW: usize>(&self) -> PixelCanvas<NH, NW, P>
+    where
+        P: PixelMutInterface + PixelInitializer + PartialEq + Clone,
+        P::ColorType: Default + Clone,
+    {
+        // Center of the new pixel, mapped back onto this canvas.
+        self.remapped(|row, column| {
+            (
+                (2 * row + 1) * H / (2 * NH),
+                (2 * column + 1) * W / (2 * NW),
+            )
+        })
+    }
+}
+
+impl<const H: usize, const W: usize> MaybePixelCanvas<H, W> {
+    /// Combines coverage of two canvases pixel by pixel, `op` gets both colors and
+    /// returns the resulting one.
+    fn combined(
+        &self,
+        other: &MaybePixelCanvas<H, W>,
+        op: impl Fn(Option<PixelColor>, Option<PixelColor>) -> Option<PixelColor>,
+    ) -> MaybePixelCanvas<H, W> {
+        let mut canvas = self.empty_like::<H, W>();
+        for pos in selection::all_positions::<H, W>() {
+            if let Some(color) = op(self.color_at(pos), other.color_at(pos)) {
+                canvas.update_color_at(pos, color);
+            }
+        }
+        canvas
+    }
+
+    /// Pixels covered by either canvas, this canvas' colors win where both are covered.
+    pub fn union(&self, other: &MaybePixelCanvas<H, W>) -> MaybePixelCanvas<H, W> {
+        self.combined(other, |a, b| a.or(b))
+    }
+
+    /// Pixels covered by both canvases, using colors of this canvas.
+    pub fn intersect(&self, other: &MaybePixelCanvas<H, W>) -> MaybePixelCanvas<H, W> {
+        self.combined(other, |a, b| b.and(a))
+    }
+
+    /// Pixels of this canvas which are not covered by the other one.
+    pub fn subtract(&self, other: &MaybePixelCanvas<H, W>) -> MaybePixelCanvas<H, W> {
+        self.combined(other, |a, b| if b.is_some() { None } else { a })
+    }
+
+    /// Makes all pixels from `top_left` to `bottom_right` (both included) transparent.
+    ///
+    /// Stored pixels are dropped at once, instead of updating pixels one by one.
+    pub fn erase_region(
+        &mut self,
+        top_left: impl IntoPixelStrictPosition<H, W>,
+        bottom_right: impl IntoPixelStrictPosition<H, W>,
+    ) {
+        self.table.inner.clear_region(
+            top_left.into_pixel_strict_position().expand(),
+            bottom_right.into_pixel_strict_position().expand(),
+        );
+    }
+
+    /// Keeps only colored pixels for which `f` returns `true`, the others become transparent.
+    pub fn retain_pixels(
+        &mut self,
+        mut f: impl FnMut(PixelStrictPosition<H, W>, &PixelColor) -> bool,
+    ) {
+        self.table.inner.retain(|pos, pixel| match &pixel.color {
+            Some(color) => f(pos.into_pixel_strict_position(), color),
+            None => false,
+        });
+    }
+
+    /// Size of the smallest canvas which holds this one rotated by `angle` degrees,
+    /// see [`MaybePixelCanvas::rotated_by_degrees`].
+    pub fn rotated_size(angle: f32) -> (usize, usize) {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let (sin, cos) = (sin.abs(), cos.abs());
+        // Small epsilon, so right angles don't grow by float errors.
+        let fit = |a: usize, b: usize| (a as f32 * cos + b as f32 * sin - 1e-3).ceil() as usize;
+        (fit(H, W), fit(W, H))
+    }
+
+    /// Creates a copy of this canvas rotated clockwise by `angle` degrees around its center,
+    /// using nearest-neighbor sampling. The result is centered on a `NH` * `NW` canvas,
+    /// and anything outside of it is clipped.
+    ///
+    /// Use [`MaybePixelCanvas::rotated_size`] to find out a size which holds the whole result,
+    /// a canvas as big as the diagonal fits every angle (e.g. 23x23 for a 16x16 sprite).
+    pub fn rotated_by_degrees<const NH: usize, const NW: usize>(
+        &self,
+        angle: f32,
+    ) -> MaybePixelCanvas<NH, NW> {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let mut canvas = self.empty_like::<NH, NW>();
+        for row in 0..NH {
+            for column in 0..NW {
+                // Center of the new pixel relative to the new center, rotated back.
+                let y = row as f32 + 0.5 - NH as f32 / 2.;
+                let x = column as f32 + 0.5 - NW as f32 / 2.;
+                let source_row = (-x * sin + y * cos + H as f32 / 2.).floor();
+                let source_column = (x * cos + y * sin + W as f32 / 2.).floor();
+                if source_row < 0.
+                    || source_column < 0.
+                    || source_row >= H as f32
+                    || source_column >= W as f32
+                {
+                    continue;
+                }
+
+                let from = (source_row as usize, source_column as usize);
+                canvas
+                    .get_pixel_mut((row, column))
+                    .update_color(self.get_pixel(from).color);
+                canvas.tags.set((row, column), self.tags.get(from));
+            }
+        }
+        canvas
+    }
 }
 
 impl<const H: usize, const W: usize, P> Default for PixelCanvas<H, W, P>
@@ -161,6 +497,55 @@ impl<const H: usize, const W: usize, P: PixelInterface + Default> std::ops::Dere
     }
 }
 
+/// Indexing with positions gives the pixel itself.
+///
+/// There's no `IndexMut`, use [`PixelTable::get_pixel_mut`] to change a pixel. Its handle
+/// drops the pixel when it's left with the default color, which a plain `&mut` can't do.
+///
+/// ```
+/// # use pixelart::prelude::*;
+/// let mut canvas = PixelCanvas::<5>::default();
+/// canvas.get_pixel_mut(CENTER).update_color(BLUE);
+///
+/// assert_eq!(canvas[CENTER].color(), &BLUE);
+/// ```
+impl<const H: usize, const W: usize, P, T> std::ops::Index<T> for PixelCanvas<H, W, P>
+where
+    P: PixelInterface + Default,
+    T: PixelStrictPositionInterface<H, W>,
+{
+    type Output = P;
+
+    fn index(&self, index: T) -> &Self::Output {
+        &self[index.expand()]
+    }
+}
+
+/// Indexing with `(row, column)` tuples.
+///
+/// # Panics
+/// Panics if the position is out of bounds.
+///
+/// ```
+/// # use pixelart::prelude::*;
+/// let mut canvas = PixelCanvas::<5>::default();
+/// canvas.update_color_at(pos!(0, 1; 5, 5), RED);
+///
+/// assert_eq!(canvas[(0, 1)].color(), &RED);
+/// ```
+impl<const H: usize, const W: usize, P> std::ops::Index<(usize, usize)> for PixelCanvas<H, W, P>
+where
+    P: PixelInterface + Default,
+{
+    type Output = P;
+
+    fn index(&self, (row, column): (usize, usize)) -> &Self::Output {
+        self.table.inner.item((row, column)).unwrap_or_else(|| {
+            panic!("Position ({row}, {column}) is out of bounds of a {H}x{W} canvas.")
+        })
+    }
+}
+
 impl<const H: usize, const W: usize, P: PixelInterface + Default> PixelCanvasInterface<H, W, P>
     for PixelCanvas<H, W, P>
 {
@@ -201,39 +586,6 @@ impl<const H: usize, const W: usize, P: PixelMutInterface + Default>
     }
 }
 
-fn _fill_inside<
-    const H: usize,
-    const W: usize,
-    P: PixelMutInterface + Default,
-    I: SharedMutPixelCanvasExt<H, W, P>,
->(
-    canvas: &mut I,
-    base_color: Option<P::ColorType>,
-    color: impl Into<P::ColorType> + Clone,
-    point_inside: impl IntoPixelStrictPosition<H, W>,
-) where
-    P: PartialEq + Clone,
-    P::ColorType: PartialEq + Clone + Default,
-{
-    let mut stack = vec![point_inside.into_pixel_strict_position()];
-    let base_color = base_color.unwrap_or_else(|| canvas.color_at(stack[0]).clone());
-    let color = color.into();
-
-    while let Some(pos) = stack.pop() {
-        if canvas.color_at(pos) == base_color {
-            canvas.update_color_at(pos, color.clone());
-
-            for dir in SingleCycle::new(super::position::Direction::Up)
-                .filter(|dir| MAIN_DIRECTIONS.contains(dir))
-            {
-                if let Ok(new_pos) = pos.checked_direction(dir, 1) {
-                    stack.push(new_pos);
-                }
-            }
-        }
-    }
-}
-
 /// Extensions for any type that implements [`PixelCanvasInterface`].
 ///
 /// This trait is implemented for any canvas of [`PixelInterface`].
@@ -241,7 +593,7 @@ pub trait SharedPixelCanvasExt<const H: usize, const W: usize, P: PixelInterface
     PixelCanvasInterface<H, W, P>
 {
     /// Get an [`PixelImageBuilder`] based on this canvas with [`PixelImageStyle`] specified.
-    fn image_builder(&self, style: PixelImageStyle) -> PixelImageBuilder<H, W, P, Self>
+    fn image_builder(&self, style: PixelImageStyle) -> PixelImageBuilder<'_, H, W, P, Self>
     where
         Self: Sized,
     {
@@ -249,7 +601,7 @@ pub trait SharedPixelCanvasExt<const H: usize, const W: usize, P: PixelInterface
     }
 
     /// Get an [`PixelImageBuilder`] based on this canvas with default [`PixelImageStyle`].
-    fn default_image_builder(&self) -> PixelImageBuilder<H, W, P, Self>
+    fn default_image_builder(&self) -> PixelImageBuilder<'_, H, W, P, Self>
     where
         Self: Sized,
     {
@@ -264,6 +616,120 @@ pub trait SharedPixelCanvasExt<const H: usize, const W: usize, P: PixelInterface
         self.table().get_pixel(pos).color().clone()
     }
 
+    /// Renders the canvas as ANSI escaped text, see [`crate::terminal`].
+    fn to_ansi(&self) -> String
+    where
+        Self: Sized,
+        P::ColorType: RgbaInterface,
+    {
+        crate::terminal::ansi_string(self)
+    }
+
+    /// Prints the canvas to the standard output using 24-bit terminal colors.
+    fn print_ansi(&self)
+    where
+        Self: Sized,
+        P::ColorType: RgbaInterface,
+    {
+        print!("{}", self.to_ansi())
+    }
+
+    /// Gets the tag of a pixel at given position, if it's tagged.
+    fn tag_at(&self, pos: impl PixelStrictPositionInterface<H, W>) -> Option<u16> {
+        self.table().tags().get(pos.expand())
+    }
+
+    /// Positions of all pixels having the given tag, row by row.
+    fn iter_by_tag<'a>(&'a self, tag: u16) -> impl Iterator<Item = PixelStrictPosition<H, W>> + 'a
+    where
+        P: 'a,
+    {
+        self.table()
+            .tags()
+            .iter_by_tag(tag)
+            .map(IntoPixelStrictPosition::into_pixel_strict_position)
+    }
+
+    /// Selects all positions where `predicate` returns `true` for the position and its color.
+    fn find_pixels(
+        &self,
+        mut predicate: impl FnMut(PixelStrictPosition<H, W>, &P::ColorType) -> bool,
+    ) -> Selection<H, W> {
+        selection::all_positions::<H, W>()
+            .filter(|pos| predicate(*pos, self.table().get_pixel(*pos).color()))
+            .collect()
+    }
+
+    /// Selects the region of same colored pixels connected to `pos` (like a magic wand).
+    fn select_connected(&self, pos: impl IntoPixelStrictPosition<H, W>) -> Selection<H, W>
+    where
+        P::ColorType: PartialEq,
+    {
+        let start = pos.into_pixel_strict_position();
+        let table = self.table();
+        let base_pixel = table.get_pixel(start);
+        let base_color = base_pixel.color();
+        selection::flood(start, Connectivity::Four, |pos| {
+            table.get_pixel(pos).color() == base_color
+        })
+    }
+
+    /// Run-length encodes each row into spans of identical colors.
+    fn to_runs(&self) -> Vec<ColorRun<P::ColorType>>
+    where
+        Self: Sized,
+        P::ColorType: Clone + PartialEq,
+    {
+        compress::to_runs(self)
+    }
+
+    /// Greedily decomposes the canvas into rectangles of identical colors.
+    fn to_rects(&self) -> Vec<ColorRect<P::ColorType>>
+    where
+        Self: Sized,
+        P::ColorType: Clone + PartialEq,
+    {
+        compress::to_rects(self)
+    }
+
+    /// The first position (from top left, row by row) where `predicate` returns `true`.
+    fn first_pixel_where(
+        &self,
+        mut predicate: impl FnMut(PixelStrictPosition<H, W>, &P::ColorType) -> bool,
+    ) -> Option<PixelStrictPosition<H, W>> {
+        selection::all_positions::<H, W>()
+            .find(|pos| predicate(*pos, self.table().get_pixel(*pos).color()))
+    }
+
+    /// Copies the `CH` * `CW` region starting at `top_left` into a new canvas.
+    ///
+    /// Parts of the region which fall outside of this canvas keep the default color.
+    fn cropped<const CH: usize, const CW: usize>(
+        &self,
+        top_left: impl IntoPixelStrictPosition<H, W>,
+    ) -> PixelCanvas<CH, CW, P>
+    where
+        P: PixelMutInterface + PixelInitializer + PartialEq + Clone,
+        P::ColorType: Clone + Default,
+    {
+        let (top, left) = top_left.into_pixel_strict_position().expand();
+        let mut canvas = PixelCanvas::<CH, CW, P> {
+            table: PixelTable::with_backend(self.table().backend()),
+        };
+        for row in 0..CH.min(H - top) {
+            for column in 0..CW.min(W - left) {
+                let from = (top + row, left + column);
+                canvas
+                    .get_pixel_mut((row, column))
+                    .update_color(self.table().get_pixel(from).color().clone());
+                canvas
+                    .tags
+                    .set((row, column), self.table().tags().get(from));
+            }
+        }
+        canvas
+    }
+
     fn any_partition<'a, const MH: usize, const MW: usize, MP>(
         &'a self,
         top_left: impl IntoPixelStrictPosition<H, W>,
@@ -315,6 +781,19 @@ where
 {
 }
 
+/// Colors all positions inside the canvas, the others are ignored.
+fn draw_points<const H: usize, const W: usize, P, C>(
+    canvas: &mut C,
+    points: impl IntoIterator<Item = (isize, isize)>,
+    color: P::ColorType,
+) where
+    P: PixelMutInterface + PartialEq + Clone + Default,
+    P::ColorType: Clone,
+    C: PixelCanvasMutInterface<H, W, P>,
+{
+    grid::draw_points(&mut TableGrid(canvas.table_mut()), points, color);
+}
+
 /// Extensions for any type that implements [`PixelCanvasInterface`].
 ///
 /// This trait is implemented for any canvas of [`PixelInterface`].
@@ -325,7 +804,7 @@ pub trait SharedMutPixelCanvasExt<const H: usize, const W: usize, P: PixelMutInt
         &mut self,
         color: impl Into<P::ColorType>,
         start_pos: impl IntoPixelStrictPosition<H, W>,
-    ) -> Pen<pen::CanvasAttachedMarker<H, W, P, Self>>
+    ) -> Pen<pen::CanvasAttachedMarker<'_, H, W, P, Self>>
     where
         Self: Sized,
         <P as PixelInterface>::ColorType: From<PixelColor>,
@@ -356,6 +835,53 @@ pub trait SharedMutPixelCanvasExt<const H: usize, const W: usize, P: PixelMutInt
         drawable.draw_on(start_pos, self)
     }
 
+    /// As same as [`SharedMutPixelCanvasExt::draw`] but pixels going out of the canvas are
+    /// handled based on the given [`OutOfBoundsPolicy`].
+    fn draw_with_policy<const HD: usize, const WD: usize, MP: PixelInterface, E>(
+        &mut self,
+        start_pos: impl IntoPixelStrictPosition<H, W>,
+        drawable: impl Drawable<HD, WD, MP>,
+        policy: OutOfBoundsPolicy,
+    ) -> Result<(), OutOfBoundsError>
+    where
+        Self: Sized,
+        P: PartialEq + Clone,
+        <MP as PixelInterface>::ColorType: Clone,
+        Option<PixelColor>: TryFrom<MP::ColorType>,
+        P::ColorType: TryFrom<Option<PixelColor>, Error = E>,
+    {
+        let start_pos = start_pos.into_pixel_strict_position().expand();
+        TableGrid(self.table_mut()).draw_with_policy(start_pos, drawable, policy)
+    }
+
+    /// As same as [`SharedMutPixelCanvasExt::draw`] but only pixels allowed by the
+    /// [`Mask`] are drawn.
+    fn draw_masked<const HD: usize, const WD: usize, MP: PixelInterface, E>(
+        &mut self,
+        start_pos: impl IntoPixelStrictPosition<H, W>,
+        drawable: impl Drawable<HD, WD, MP>,
+        mask: &Mask<H, W>,
+    ) where
+        Self: Sized,
+        P: PartialEq + Clone,
+        <MP as PixelInterface>::ColorType: Clone,
+        Option<PixelColor>: TryFrom<MP::ColorType>,
+        P::ColorType: TryFrom<Option<PixelColor>, Error = E>,
+    {
+        // Draw it on a transparent canvas of the same size first, then copy allowed pixels.
+        let mut scratch = MaybePixelCanvas::<H, W>::default();
+        drawable.draw_on(start_pos, &mut scratch);
+
+        for ((row, column), pixel) in scratch.real_items() {
+            let pos = (row, column);
+            if pixel.color.is_some() && mask.is_allowed(pos) {
+                if let Ok(color) = P::ColorType::try_from(pixel.color) {
+                    self.table_mut().get_pixel_mut(pos).update_color(color);
+                }
+            }
+        }
+    }
+
     fn draw_exact<MP: PixelInterface, E>(
         &mut self,
         start_pos: impl IntoPixelStrictPosition<H, W>,
@@ -369,6 +895,54 @@ pub trait SharedMutPixelCanvasExt<const H: usize, const W: usize, P: PixelMutInt
         drawable.draw_on_exact(start_pos, self)
     }
 
+    /// Checked version of [`SharedMutPixelCanvasExt::draw_exact`], for drawables of any size.
+    ///
+    /// If the drawable doesn't fit from `start_pos` to the bottom right corner, it's either
+    /// fitted based on the given [`AutoFit`] or a [`DimensionMismatchError`] is returned and
+    /// nothing is drawn.
+    fn try_draw_exact<const HD: usize, const WD: usize, MP: PixelInterface, E>(
+        &mut self,
+        start_pos: impl IntoPixelStrictPosition<H, W>,
+        drawable: impl Drawable<HD, WD, MP>,
+        auto_fit: AutoFit,
+    ) -> Result<(), DimensionMismatchError>
+    where
+        Self: Sized,
+        P: PartialEq + Clone,
+        <MP as PixelInterface>::ColorType: Clone,
+        Option<PixelColor>: TryFrom<MP::ColorType>,
+        P::ColorType: TryFrom<Option<PixelColor>, Error = E>,
+    {
+        let mut scratch = MaybePixelCanvas::<HD, WD>::default();
+        drawable.draw_on_exact_abs(&mut scratch);
+
+        let start_pos = start_pos.into_pixel_strict_position();
+        let (expected_height, expected_width) = match auto_fit {
+            AutoFit::None => (H - start_pos.row(), W - start_pos.column()),
+            AutoFit::Recenter => (H, W),
+            AutoFit::Resample => {
+                scratch.resampled::<H, W>().draw_on_exact_abs(self);
+                return Ok(());
+            }
+        };
+
+        if HD > expected_height || WD > expected_width {
+            return Err(DimensionMismatchError {
+                expected_height,
+                expected_width,
+                actual_height: HD,
+                actual_width: WD,
+            });
+        }
+
+        let start_pos = match auto_fit {
+            AutoFit::Recenter => ((H - HD) / 2, (W - WD) / 2).into_pixel_strict_position(),
+            _ => start_pos,
+        };
+        drawable::draw_canvas_on(scratch.table(), start_pos, self);
+        Ok(())
+    }
+
     fn draw_exact_abs<MP: PixelInterface, E>(&mut self, drawable: impl Drawable<H, W, MP>)
     where
         Self: Sized,
@@ -391,6 +965,85 @@ pub trait SharedMutPixelCanvasExt<const H: usize, const W: usize, P: PixelMutInt
         })
     }
 
+    /// Like [`SharedMutPixelCanvasExt::fill`], but rows are filled in parallel (`rayon` feature).
+    #[cfg(feature = "rayon")]
+    fn par_fill(&mut self, color: impl Into<P::ColorType>)
+    where
+        P: PartialEq + Clone + Send + Sync,
+        P::ColorType: Clone + Sync,
+    {
+        let color = color.into();
+        self.table_mut().par_for_each_pixel_mut(|_, pixel| {
+            pixel.update_color(color.clone());
+        });
+    }
+
+    /// Replaces the color of every pixel with `f(color)`, rows are processed in parallel
+    /// (`rayon` feature).
+    #[cfg(feature = "rayon")]
+    fn par_map_colors<F>(&mut self, f: F)
+    where
+        P: PartialEq + Clone + Send + Sync,
+        F: Fn(&P::ColorType) -> P::ColorType + Sync,
+    {
+        self.table_mut().par_for_each_pixel_mut(|_, pixel| {
+            let color = f(pixel.color());
+            pixel.update_color(color);
+        });
+    }
+
+    /// Moves all pixels (and their tags) `amount` steps toward the `direction`.
+    ///
+    /// Using [`ShiftMode::Clip`] the space left behind takes the default color.
+    fn shift(&mut self, direction: Direction, amount: usize, mode: ShiftMode)
+    where
+        P: PartialEq + Clone,
+        P::ColorType: Clone + Default,
+    {
+        let (delta_row, delta_column) = direction.delta();
+        let (delta_row, delta_column) =
+            (delta_row * amount as isize, delta_column * amount as isize);
+        let policy = OutOfBoundsPolicy::from(mode);
+
+        let table = self.table();
+        let moved: Vec<_> = selection::all_positions::<H, W>()
+            .map(|pos| {
+                let (row, column) = pos.expand();
+                let target = policy
+                    .resolve::<H, W>((row as isize + delta_row, column as isize + delta_column))
+                    .ok()
+                    .flatten();
+                (
+                    target,
+                    table.get_pixel(pos).color().clone(),
+                    table.tags().get((row, column)),
+                )
+            })
+            .collect();
+
+        self.fill(P::ColorType::default());
+        let table = self.table_mut();
+        table.tags_mut().clear();
+        for (target, color, tag) in moved {
+            if let Some(target) = target {
+                table.get_pixel_mut(target).update_color(color);
+                table.tags_mut().set(target.expand(), tag);
+            }
+        }
+    }
+
+    /// Fills only the pixels allowed by the [`Mask`] with the color.
+    fn fill_masked(&mut self, color: impl Into<P::ColorType>, mask: &Mask<H, W>)
+    where
+        P: PartialEq + Clone,
+        P::ColorType: Clone,
+    {
+        let color = color.into();
+        for pos in mask.iter() {
+            self.update_color_at(pos, color.clone());
+        }
+    }
+
     /// Keep filling pixels with new color until we encounter a new color.
     fn fill_inside(
         &mut self,
@@ -401,7 +1054,83 @@ pub trait SharedMutPixelCanvasExt<const H: usize, const W: usize, P: PixelMutInt
         P: PartialEq + Clone + Default,
         <P as PixelInterface>::ColorType: PartialEq + Clone + Default,
     {
-        _fill_inside::<H, W, P, _>(self, None, color, point_inside)
+        self.fill_inside_with(color, point_inside, Connectivity::Four)
+    }
+
+    /// Same as [`SharedMutPixelCanvasExt::fill_inside`], using the given [`Connectivity`]
+    /// so the fill can cross diagonal gaps.
+    fn fill_inside_with(
+        &mut self,
+        color: impl Into<P::ColorType> + std::clone::Clone,
+        point_inside: impl IntoPixelStrictPosition<H, W>,
+        connectivity: Connectivity,
+    ) where
+        Self: Sized,
+        P: PartialEq + Clone + Default,
+        <P as PixelInterface>::ColorType: PartialEq + Clone + Default,
+    {
+        let point_inside = point_inside.into_pixel_strict_position().expand();
+        TableGrid(self.table_mut()).fill_inside_with(color, point_inside, connectivity)
+    }
+
+    /// Like [`SharedMutPixelCanvasExt::fill_inside`], but also fills through pixels whose
+    /// colors are close enough to the color at `point_inside`.
+    ///
+    /// Transparent pixels only match other transparent pixels.
+    fn fill_inside_with_tolerance(
+        &mut self,
+        color: impl Into<P::ColorType>,
+        point_inside: impl IntoPixelStrictPosition<H, W>,
+        tolerance: ColorTolerance,
+    ) where
+        Self: Sized,
+        P: PartialEq + Clone + Default,
+        P::ColorType: Clone + Into<Option<PixelColor>>,
+    {
+        let start = point_inside.into_pixel_strict_position();
+        let color_of = |canvas: &Self, pos| -> Option<PixelColor> { canvas.color_at(pos).into() };
+        let base_color = color_of(self, start);
+
+        selection::flood(start, Connectivity::Four, |pos| {
+            match (&base_color, color_of(self, pos)) {
+                (Some(base), Some(other)) => tolerance.matches(base, &other),
+                (base, other) => base.is_none() && other.is_none(),
+            }
+        })
+        .fill_on(self, color);
+    }
+
+    /// Determines the region like [`SharedMutPixelCanvasExt::fill_inside`], but paints it
+    /// by tiling the `pattern` (e.g. checkerboards, stripes or textures).
+    ///
+    /// See [`Selection::fill_pattern_on`] to paint any other region.
+    fn fill_pattern<const PH: usize, const PW: usize>(
+        &mut self,
+        point_inside: impl IntoPixelStrictPosition<H, W>,
+        pattern: &MaybePixelCanvas<PH, PW>,
+    ) where
+        Self: Sized,
+        P: PartialEq + Clone + Default,
+        P::ColorType: PartialEq + From<PixelColor>,
+    {
+        self.select_connected(point_inside)
+            .fill_pattern_on(self, pattern);
+    }
+
+    /// Keep filling pixels with `fill_color` until we hit pixels of `boundary_color`,
+    /// no matter what color other pixels have. Like a paint bucket inside an outline.
+    fn boundary_fill(
+        &mut self,
+        point: impl IntoPixelStrictPosition<H, W>,
+        fill_color: impl Into<P::ColorType>,
+        boundary_color: impl Into<P::ColorType>,
+    ) where
+        Self: Sized,
+        P: PartialEq + Clone + Default,
+        <P as PixelInterface>::ColorType: PartialEq + Clone,
+    {
+        let point = point.into_pixel_strict_position().expand();
+        TableGrid(self.table_mut()).boundary_fill(point, fill_color, boundary_color)
     }
 
     /// Update color of a pixel at the given position.
@@ -416,6 +1145,251 @@ pub trait SharedMutPixelCanvasExt<const H: usize, const W: usize, P: PixelMutInt
         self.table_mut().get_pixel_mut(pos).update_color(color)
     }
 
+    /// Fills the canvas with colors interpolated from `start_color` at `from_pos` to
+    /// `end_color` at `to_pos`.
+    ///
+    /// Pixels are colored based on their projection on the line between the two positions,
+    /// so anything before `from_pos` or after `to_pos` gets the start or end color.
+    fn fill_linear_gradient(
+        &mut self,
+        from_pos: impl IntoPixelStrictPosition<H, W>,
+        to_pos: impl IntoPixelStrictPosition<H, W>,
+        start_color: impl Into<PixelColor>,
+        end_color: impl Into<PixelColor>,
+    ) where
+        P: PartialEq + Clone,
+        P::ColorType: From<PixelColor>,
+    {
+        let (from, to) = (
+            from_pos.into_pixel_strict_position(),
+            to_pos.into_pixel_strict_position(),
+        );
+        let (start_color, end_color) = (start_color.into(), end_color.into());
+        let direction = (
+            to.row() as f32 - from.row() as f32,
+            to.column() as f32 - from.column() as f32,
+        );
+        let length_squared = direction.0 * direction.0 + direction.1 * direction.1;
+
+        for pos in selection::all_positions::<H, W>() {
+            let t = if length_squared == 0. {
+                0.
+            } else {
+                ((pos.row() as f32 - from.row() as f32) * direction.0
+                    + (pos.column() as f32 - from.column() as f32) * direction.1)
+                    / length_squared
+            };
+            self.update_color_at(pos, start_color.lerp(end_color, t));
+        }
+    }
+
+    /// Fills the canvas with colors interpolated from `inner_color` at `center` to
+    /// `outer_color` at `radius` pixels away from it (and beyond).
+    fn fill_radial_gradient(
+        &mut self,
+        center: impl IntoPixelStrictPosition<H, W>,
+        radius: f32,
+        inner_color: impl Into<PixelColor>,
+        outer_color: impl Into<PixelColor>,
+    ) where
+        P: PartialEq + Clone,
+        P::ColorType: From<PixelColor>,
+    {
+        let center = center.into_pixel_strict_position();
+        let (inner_color, outer_color) = (inner_color.into(), outer_color.into());
+
+        for pos in selection::all_positions::<H, W>() {
+            let distance = (pos.row() as f32 - center.row() as f32)
+                .hypot(pos.column() as f32 - center.column() as f32);
+            let t = if radius <= 0. { 1. } else { distance / radius };
+            self.update_color_at(pos, inner_color.lerp(outer_color, t));
+        }
+    }
+
+    /// Draws a straight line between two positions (both included).
+    fn draw_line(
+        &mut self,
+        from: impl IntoPixelStrictPosition<H, W>,
+        to: impl IntoPixelStrictPosition<H, W>,
+        color: impl Into<P::ColorType>,
+    ) where
+        Self: Sized,
+        P: PartialEq + Clone,
+        P::ColorType: Clone,
+    {
+        let (from, to) = (
+            from.into_pixel_strict_position().expand(),
+            to.into_pixel_strict_position().expand(),
+        );
+        TableGrid(self.table_mut()).draw_line(from, to, color)
+    }
+
+    /// Draws outline of a circle around the center, parts out of the canvas are ignored.
+    fn draw_circle(
+        &mut self,
+        center: impl IntoPixelStrictPosition<H, W>,
+        radius: usize,
+        color: impl Into<P::ColorType>,
+    ) where
+        Self: Sized,
+        P: PartialEq + Clone,
+        P::ColorType: Clone,
+    {
+        self.draw_ellipse(center, radius, radius, color)
+    }
+
+    /// As same as [`SharedMutPixelCanvasExt::draw_circle`] but the inside is colored too.
+    fn draw_filled_circle(
+        &mut self,
+        center: impl IntoPixelStrictPosition<H, W>,
+        radius: usize,
+        color: impl Into<P::ColorType>,
+    ) where
+        Self: Sized,
+        P: PartialEq + Clone,
+        P::ColorType: Clone,
+    {
+        self.draw_filled_ellipse(center, radius, radius, color)
+    }
+
+    /// Draws outline of an ellipse around the center with horizontal radius `rx` and
+    /// vertical radius `ry`, parts out of the canvas are ignored.
+    fn draw_ellipse(
+        &mut self,
+        center: impl IntoPixelStrictPosition<H, W>,
+        rx: usize,
+        ry: usize,
+        color: impl Into<P::ColorType>,
+    ) where
+        Self: Sized,
+        P: PartialEq + Clone,
+        P::ColorType: Clone,
+    {
+        let center = center.into_pixel_strict_position().expand();
+        TableGrid(self.table_mut()).draw_ellipse(center, rx, ry, color)
+    }
+
+    /// As same as [`SharedMutPixelCanvasExt::draw_ellipse`] but the inside is colored too.
+    fn draw_filled_ellipse(
+        &mut self,
+        center: impl IntoPixelStrictPosition<H, W>,
+        rx: usize,
+        ry: usize,
+        color: impl Into<P::ColorType>,
+    ) where
+        Self: Sized,
+        P: PartialEq + Clone,
+        P::ColorType: Clone,
+    {
+        let center = center.into_pixel_strict_position().expand();
+        TableGrid(self.table_mut()).draw_filled_ellipse(center, rx, ry, color)
+    }
+
+    /// Draws the text using the default (3x5) [`Font`], see
+    /// [`SharedMutPixelCanvasExt::draw_text_with_font`].
+    fn draw_text(
+        &mut self,
+        start_pos: impl IntoPixelStrictPosition<H, W>,
+        text: &str,
+        color: impl Into<P::ColorType>,
+    ) -> Option<(PixelStrictPosition<H, W>, PixelStrictPosition<H, W>)>
+    where
+        Self: Sized,
+        P: PartialEq + Clone,
+        P::ColorType: Clone,
+    {
+        self.draw_text_with_font(start_pos, text, color, Font::default())
+    }
+
+    /// Draws the text with its top left at `start_pos`, lines are separated by `\n`.
+    ///
+    /// Returns the top left and bottom right of the area the text occupies on the canvas,
+    /// or `None` if it's empty.
+    fn draw_text_with_font(
+        &mut self,
+        start_pos: impl IntoPixelStrictPosition<H, W>,
+        text: &str,
+        color: impl Into<P::ColorType>,
+        font: Font,
+    ) -> Option<(PixelStrictPosition<H, W>, PixelStrictPosition<H, W>)>
+    where
+        Self: Sized,
+        P: PartialEq + Clone,
+        P::ColorType: Clone,
+    {
+        let start_pos = start_pos.into_pixel_strict_position();
+        let (top, left) = (start_pos.row(), start_pos.column());
+        let points = font
+            .text_points(text)
+            .into_iter()
+            .map(|(row, column)| ((top + row) as isize, (left + column) as isize));
+        draw_points(self, points, color.into());
+
+        let (height, width) = font.text_size(text);
+        if width == 0 {
+            return None;
+        }
+        Some((
+            start_pos,
+            ((top + height - 1).min(H - 1), (left + width - 1).min(W - 1))
+                .into_pixel_strict_position(),
+        ))
+    }
+
+    /// Replaces color of every (non-transparent) pixel with its nearest color in the palette.
+    fn quantize_to_palette(&mut self, palette: &Palette)
+    where
+        P: PartialEq + Clone,
+        P::ColorType: Clone + Into<Option<PixelColor>> + From<PixelColor>,
+    {
+        for pos in selection::all_positions::<H, W>() {
+            let color: Option<PixelColor> = self.table().get_pixel(pos).color().clone().into();
+            if let Some(color) = color {
+                self.update_color_at(pos, palette.nearest(color));
+            }
+        }
+    }
+
+    /// Reduces the canvas to at most `n_colors` colors, picked using median cut.
+    ///
+    /// Returns the palette which is used.
+    fn quantize(&mut self, n_colors: usize) -> Palette
+    where
+        P: PartialEq + Clone,
+        P::ColorType: Clone + Into<Option<PixelColor>> + From<PixelColor>,
+    {
+        let mut histogram = HashMap::<[u8; 3], u32>::new();
+        for pos in selection::all_positions::<H, W>() {
+            let color: Option<PixelColor> = self.table().get_pixel(pos).color().clone().into();
+            if let Some(color) = color {
+                *histogram.entry([color.r, color.g, color.b]).or_default() += 1;
+            }
+        }
+
+        let mut colors = median_cut(&histogram, n_colors.max(1));
+        if colors.is_empty() {
+            // Nothing to quantize, any color will do.
+            colors.push([u8::MAX; 3]);
+        }
+        colors.sort_unstable();
+
+        let palette = Palette::new(colors);
+        self.quantize_to_palette(&palette);
+        palette
+    }
+
+    /// Tags a pixel (or removes its tag using `None`) and returns the previous tag.
+    ///
+    /// Tags are kept alongside colors and move with pixels on transforms like
+    /// [`PixelCanvas::flip_x`] or when moving a partition.
+    fn set_tag(
+        &mut self,
+        pos: impl PixelStrictPositionInterface<H, W>,
+        tag: impl Into<Option<u16>>,
+    ) -> Option<u16> {
+        self.table_mut().tags_mut().set(pos.expand(), tag.into())
+    }
+
     fn any_partition_mut<'a, const MH: usize, const MW: usize, MP>(
         &'a mut self,
         top_left: impl IntoPixelStrictPosition<H, W>,
@@ -505,7 +1479,10 @@ impl<const H: usize, const W: usize, T> PixelCanvasMutExt<H, W> for T where
 mod tests {
 
     use crate::{
-        pixels::{color::PixelColorExt, position::StrictPositions, PixelIterMutExt},
+        pixels::{
+            canvas::templates::square, color::PixelColorExt, position::StrictPositions,
+            PixelIterMutExt,
+        },
         prelude::*,
     };
 
@@ -527,6 +1504,67 @@ mod tests {
         image_builder.save("arts/fill_inside.png").unwrap();
     }
 
+    #[test]
+    fn test_boundary_fill() {
+        let mut canvas = PixelCanvas::<5>::default();
+        canvas.draw(
+            TOP_LEFT,
+            crate::pixels::canvas::templates::square::<5>(BLACK),
+        );
+        canvas.update_color_at(CENTER, RED);
+        canvas.update_color_at(pos!(1, 2; 5, 5), BLUE);
+
+        canvas.boundary_fill(CENTER.bounding_left(1), YELLOW, BLACK);
+
+        assert_eq!(canvas.color_at(TOP_LEFT), BLACK);
+        assert_eq!(canvas.color_at(CENTER), YELLOW);
+        assert_eq!(canvas.color_at(pos!(1, 2; 5, 5)), YELLOW);
+        assert_eq!(canvas.iter_pixels().filter_color(YELLOW).count(), 9);
+    }
+
+    #[test]
+    fn test_find_pixels() {
+        let mut canvas = PixelCanvas::<5>::default();
+        canvas.update_color_at(BOTTOM_LEFT, RED);
+        canvas.update_color_at(CENTER, RED);
+        canvas.update_color_at(TOP_RIGHT, BLUE);
+
+        let reds = canvas.find_pixels(|_, color| color == &RED);
+        assert_eq!(reds.len(), 2);
+        assert_eq!(reds.topmost(), Some(CENTER.into_pixel_strict_position()));
+        assert_eq!(reds.bounds(), Some((pos!(2, 0; 5, 5), pos!(4, 2; 5, 5))));
+
+        assert_eq!(
+            canvas.first_pixel_where(|_, color| color != &WHITE),
+            Some(TOP_RIGHT.into_pixel_strict_position())
+        );
+        assert_eq!(canvas.first_pixel_where(|_, color| color == &GREEN), None);
+    }
+
+    #[test]
+    fn test_draw_with_policy() {
+        use crate::pixels::{canvas::templates::square, position::OutOfBoundsPolicy};
+
+        let mut canvas = PixelCanvas::<5>::default();
+        canvas
+            .draw_with_policy(BOTTOM_RIGHT, square::<2>(RED), OutOfBoundsPolicy::Wrap)
+            .unwrap();
+
+        assert_eq!(canvas.iter_pixels().filter_color(RED).count(), 4);
+        assert_eq!(canvas.color_at(TOP_LEFT), RED);
+
+        let mut canvas = PixelCanvas::<5>::default();
+        assert!(canvas
+            .draw_with_policy(BOTTOM_RIGHT, square::<2>(RED), OutOfBoundsPolicy::Error)
+            .is_err());
+        assert_eq!(canvas.iter_pixels().filter_color(RED).count(), 0);
+
+        canvas
+            .draw_with_policy(BOTTOM_RIGHT, square::<2>(RED), OutOfBoundsPolicy::Clip)
+            .unwrap();
+        assert_eq!(canvas.iter_pixels().filter_color(RED).count(), 1);
+    }
+
     #[test]
     fn test_swap() {
         let mut canvas = PixelCanvas::<5>::default();
@@ -546,4 +1584,448 @@ mod tests {
             .save("arts/swap_0.png")
             .unwrap();
     }
+
+    #[test]
+    fn test_resampled() {
+        let mut canvas = PixelCanvas::<2, 3>::default();
+        canvas.update_color_at(pos!(0, 0; 2, 3), RED);
+        canvas.update_color_at(pos!(1, 2; 2, 3), BLUE);
+
+        let larger = canvas.resampled::<4, 7>();
+        assert_eq!(larger.color_at(pos!(1, 1; 4, 7)), RED);
+        assert_eq!(larger.color_at(pos!(0, 2; 4, 7)), WHITE);
+        assert_eq!(larger.color_at(pos!(3, 6; 4, 7)), BLUE);
+
+        let back = larger.resampled::<2, 3>();
+        assert_eq!(back.to_runs(), canvas.to_runs());
+    }
+
+    #[test]
+    fn test_scaled() {
+        let mut canvas = PixelCanvas::<2>::default();
+        canvas.update_color_at(pos!(0, 1; 2, 2), RED);
+
+        let scaled = canvas.scaled::<8, 8>();
+        for row in 0..8 {
+            for column in 0..8 {
+                let expected = if row < 4 && column >= 4 { RED } else { WHITE };
+                assert_eq!(*scaled.get_pixel((row, column)).color(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cropped() {
+        let mut canvas = PixelCanvas::<4>::default();
+        canvas.update_color_at(pos!(1, 2; 4, 4), RED);
+        canvas.update_color_at(BOTTOM_RIGHT, BLUE);
+        canvas.set_tag(BOTTOM_RIGHT, Some(3));
+
+        let cropped = canvas.cropped::<2, 3>(pos!(1, 1; 4, 4));
+        assert_eq!(cropped.color_at(pos!(0, 1; 2, 3)), RED);
+        assert_eq!(cropped.color_at(pos!(1, 1; 2, 3)), WHITE);
+
+        let edge = canvas.cropped::<3, 3>(pos!(3, 3; 4, 4));
+        assert_eq!(edge.color_at(TOP_LEFT), BLUE);
+        assert_eq!(edge.tag_at(TOP_LEFT), Some(3));
+        assert_eq!(edge.color_at(BOTTOM_RIGHT), WHITE);
+    }
+
+    #[test]
+    fn test_shift() {
+        let mut canvas = MaybePixelCanvas::<3>::default();
+        canvas.update_color_at(TOP_LEFT, RED);
+        canvas.update_color_at(BOTTOM_RIGHT, BLUE);
+        canvas.set_tag(BOTTOM_RIGHT, Some(1));
+
+        let mut wrapped = canvas.clone();
+        wrapped.shift(Direction::Right, 1, ShiftMode::Wrap);
+        assert_eq!(wrapped.color_at(pos!(0, 1; 3, 3)), Some(RED));
+        assert_eq!(wrapped.color_at(BOTTOM_LEFT), Some(BLUE));
+        assert_eq!(wrapped.tag_at(BOTTOM_LEFT), Some(1));
+        assert_eq!(wrapped.color_at(TOP_LEFT), None);
+
+        let mut clipped = canvas.clone();
+        clipped.shift(Direction::DownRight, 2, ShiftMode::Clip);
+        assert_eq!(clipped.color_at(BOTTOM_RIGHT), Some(RED));
+        assert_eq!(clipped.tag_at(BOTTOM_RIGHT), None);
+        assert_eq!(clipped.filled_len(), 1);
+    }
+
+    #[test]
+    fn test_transposed() {
+        let mut canvas = PixelCanvas::<2, 3>::default();
+        canvas.update_color_at(pos!(0, 2; 2, 3), RED);
+        canvas.set_tag(pos!(1, 0; 2, 3), Some(4));
+
+        let transposed = canvas.transposed();
+        assert_eq!(transposed.color_at(pos!(2, 0; 3, 2)), RED);
+        assert_eq!(transposed.tag_at(pos!(0, 1; 3, 2)), Some(4));
+        assert_eq!(transposed.transposed().to_runs(), canvas.to_runs());
+    }
+
+    #[test]
+    fn test_fill_inside_with_tolerance() {
+        let mut canvas = PixelCanvas::<1, 4>::default();
+        canvas.update_color_at(pos!(0, 1; 1, 4), PixelColor::new(250, 252, 255));
+        canvas.update_color_at(pos!(0, 2; 1, 4), PixelColor::new(200, 200, 200));
+
+        let mut exact = canvas.clone();
+        exact.fill_inside_with_tolerance(RED, TOP_LEFT, ColorTolerance::Exact);
+        assert_eq!(
+            exact.color_at(pos!(0, 1; 1, 4)),
+            PixelColor::new(250, 252, 255)
+        );
+
+        let mut close = canvas.clone();
+        close.fill_inside_with_tolerance(RED, TOP_LEFT, ColorTolerance::PerChannel(5));
+        assert_eq!(close.color_at(pos!(0, 1; 1, 4)), RED);
+        assert_eq!(close.color_at(TOP_RIGHT), WHITE);
+
+        canvas.fill_inside_with_tolerance(RED, TOP_LEFT, ColorTolerance::Euclidean(100.));
+        assert_eq!(canvas.color_at(TOP_RIGHT), RED);
+    }
+
+    #[test]
+    fn test_fill_inside_with() {
+        // A diagonal wall, which only blocks 4-connected fills.
+        let mut canvas = PixelCanvas::<3>::default();
+        for i in 0..3 {
+            canvas.update_color_at((i, 2 - i).into_pixel_strict_position(), BLACK);
+        }
+
+        let mut four = canvas.clone();
+        four.fill_inside_with(RED, TOP_LEFT, Connectivity::Four);
+        assert_eq!(four.color_at(TOP_LEFT), RED);
+        assert_eq!(four.color_at(BOTTOM_RIGHT), WHITE);
+
+        canvas.fill_inside_with(RED, TOP_LEFT, Connectivity::Eight);
+        assert_eq!(canvas.color_at(BOTTOM_RIGHT), RED);
+        assert_eq!(canvas.color_at(CENTER), BLACK);
+    }
+
+    #[test]
+    fn test_fill_pattern() {
+        let mut canvas = PixelCanvas::<4>::default();
+        for row in 0..4 {
+            canvas.update_color_at((row, 2).into_pixel_strict_position(), BLACK);
+        }
+
+        let mut checkerboard = MaybePixelCanvas::<2>::default();
+        checkerboard.update_color_at(TOP_LEFT, RED);
+        checkerboard.update_color_at(BOTTOM_RIGHT, RED);
+
+        canvas.fill_pattern(TOP_LEFT, &checkerboard);
+        assert_eq!(canvas.color_at(TOP_LEFT), RED);
+        assert_eq!(canvas.color_at(pos!(0, 1; 4, 4)), WHITE);
+        assert_eq!(canvas.color_at(pos!(1, 1; 4, 4)), RED);
+        assert_eq!(canvas.color_at(pos!(0, 2; 4, 4)), BLACK);
+        assert_eq!(canvas.color_at(pos!(1, 3; 4, 4)), WHITE);
+    }
+
+    #[test]
+    fn test_boolean_operations() {
+        let mut left = MaybePixelCanvas::<1, 3>::default();
+        left.update_color_at(pos!(0, 0; 1, 3), RED);
+        left.update_color_at(pos!(0, 1; 1, 3), RED);
+        let mut right = MaybePixelCanvas::<1, 3>::default();
+        right.update_color_at(pos!(0, 1; 1, 3), BLUE);
+        right.update_color_at(pos!(0, 2; 1, 3), BLUE);
+
+        let colors = |canvas: &MaybePixelCanvas<1, 3>| {
+            (0..3)
+                .map(|column| canvas.color_at((0, column).into_pixel_strict_position()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            colors(&left.union(&right)),
+            [Some(RED), Some(RED), Some(BLUE)]
+        );
+        assert_eq!(colors(&left.intersect(&right)), [None, Some(RED), None]);
+        assert_eq!(colors(&left.subtract(&right)), [Some(RED), None, None]);
+    }
+
+    #[test]
+    fn test_erase_and_retain() {
+        let mut canvas = MaybePixelCanvas::<4, 4>::default();
+        canvas.fill(RED);
+        canvas.update_color_at(BOTTOM_RIGHT, BLUE);
+
+        canvas.erase_region(TOP_LEFT, pos!(1, 3; 4, 4));
+        assert_eq!(canvas.filled_len(), 8);
+        assert_eq!(canvas.color_at(pos!(1, 2; 4, 4)), None);
+
+        canvas.retain_pixels(|pos, color| color == &BLUE || pos.column() == 0);
+        assert_eq!(canvas.filled_len(), 3);
+        assert_eq!(canvas.color_at(BOTTOM_RIGHT), Some(BLUE));
+        assert_eq!(canvas.color_at(pos!(2, 0; 4, 4)), Some(RED));
+    }
+
+    #[test]
+    fn test_rotations() {
+        let mut canvas = PixelCanvas::<2, 3>::default();
+        canvas.update_color_at(pos!(0, 0; 2, 3), RED);
+        canvas.update_color_at(pos!(1, 2; 2, 3), BLUE);
+        canvas.set_tag(pos!(0, 0; 2, 3), Some(1));
+
+        let cw = canvas.rotate_90_cw();
+        assert_eq!(cw.color_at(pos!(0, 1; 3, 2)), RED);
+        assert_eq!(cw.color_at(pos!(2, 0; 3, 2)), BLUE);
+        assert_eq!(cw.tag_at(pos!(0, 1; 3, 2)), Some(1));
+
+        let ccw = canvas.rotate_90_ccw();
+        assert_eq!(ccw.color_at(pos!(2, 0; 3, 2)), RED);
+        assert_eq!(ccw.color_at(pos!(0, 1; 3, 2)), BLUE);
+        assert_eq!(ccw.rotate_90_cw().to_runs(), canvas.to_runs());
+
+        let mut half = canvas.clone();
+        half.rotate_180();
+        assert_eq!(half.color_at(pos!(1, 2; 2, 3)), RED);
+        assert_eq!(half.color_at(pos!(0, 0; 2, 3)), BLUE);
+        assert_eq!(half.to_runs(), cw.rotate_90_cw().to_runs());
+    }
+
+    #[test]
+    fn test_rotated_by_degrees() {
+        let mut canvas = MaybePixelCanvas::<2, 4>::default();
+        canvas.update_color_at(pos!(0, 3; 2, 4), RED);
+        canvas.update_color_at(pos!(1, 0; 2, 4), BLUE);
+
+        assert_eq!(MaybePixelCanvas::<2, 4>::rotated_size(90.), (4, 2));
+        assert_eq!(MaybePixelCanvas::<2, 4>::rotated_size(45.), (5, 5));
+
+        let right = canvas.rotated_by_degrees::<4, 2>(90.);
+        assert_eq!(right.to_runs(), canvas.rotate_90_cw().to_runs());
+
+        let same = canvas.rotated_by_degrees::<2, 4>(360.);
+        assert_eq!(same.to_runs(), canvas.to_runs());
+
+        let diagonal =
+            MaybePixelCanvas::<4>::from_fill_color(BLACK).rotated_by_degrees::<6, 6>(45.);
+        assert_eq!(diagonal.color_at(pos!(0, 0; 6, 6)), None);
+        assert_eq!(diagonal.color_at(pos!(3, 3; 6, 6)), Some(BLACK));
+        assert_eq!(diagonal.color_at(pos!(1, 2; 6, 6)), Some(BLACK));
+    }
+
+    #[test]
+    fn test_try_draw_exact() {
+        let mut canvas = PixelCanvas::<5>::default();
+
+        let error = canvas
+            .try_draw_exact((2, 2), square::<4>(BLACK), AutoFit::None)
+            .unwrap_err();
+        assert_eq!((error.expected_height, error.expected_width), (3, 3));
+        assert_eq!(canvas.to_runs().len(), 5);
+
+        canvas
+            .try_draw_exact((2, 2), square::<3>(BLACK), AutoFit::None)
+            .unwrap();
+        assert_eq!(canvas.color_at(BOTTOM_RIGHT), BLACK);
+
+        canvas.clear();
+        canvas
+            .try_draw_exact(BOTTOM_RIGHT, square::<3>(BLACK), AutoFit::Recenter)
+            .unwrap();
+        assert_eq!(canvas.color_at(pos!(1, 1; 5, 5)), BLACK);
+        assert_eq!(canvas.color_at(TOP_LEFT), WHITE);
+        assert!(canvas
+            .try_draw_exact(TOP_LEFT, square::<6>(BLACK), AutoFit::Recenter)
+            .is_err());
+
+        canvas.clear();
+        canvas
+            .try_draw_exact(
+                CENTER,
+                MaybePixelCanvas::<10>::from_fill_color(BLACK),
+                AutoFit::Resample,
+            )
+            .unwrap();
+        assert_eq!(canvas.color_at(TOP_LEFT), BLACK);
+        assert_eq!(canvas.color_at(BOTTOM_RIGHT), BLACK);
+    }
+
+    #[test]
+    fn test_tags() {
+        let mut canvas = PixelCanvas::<3>::default();
+        canvas.update_color_at(TOP_LEFT, RED);
+        canvas.set_tag(TOP_LEFT, 1);
+        canvas.set_tag(BOTTOM_RIGHT, 2);
+        assert_eq!(canvas.tag_at(TOP_LEFT), Some(1));
+
+        canvas.flip_x();
+        assert_eq!(canvas.tag_at(TOP_RIGHT), Some(1));
+        assert_eq!(canvas.tag_at(BOTTOM_LEFT), Some(2));
+        assert_eq!(canvas.resampled::<6, 6>().iter_by_tag(1).count(), 4);
+
+        {
+            let mut part = canvas.maybe_partition_mut::<1, 1>(TOP_RIGHT);
+            part.crop_to(CENTER);
+        }
+        assert_eq!(canvas.tag_at(TOP_RIGHT), None);
+        assert_eq!(
+            canvas.iter_by_tag(1).collect::<Vec<_>>(),
+            vec![CENTER.into_pixel_strict_position()]
+        );
+
+        assert_eq!(canvas.set_tag(CENTER, None), Some(1));
+        assert_eq!(canvas.iter_by_tag(1).count(), 0);
+    }
+
+    #[test]
+    fn test_gradients() {
+        let mut canvas = PixelCanvas::<3, 5>::default();
+        canvas.fill_linear_gradient(TOP_LEFT, TOP_RIGHT, BLACK, WHITE);
+        assert_eq!(canvas.color_at(BOTTOM_LEFT), BLACK);
+        assert_eq!(canvas.color_at(pos!(1, 2; 3, 5)), PixelColor::splat(128));
+        assert_eq!(canvas.color_at(BOTTOM_RIGHT), WHITE);
+
+        canvas.fill_radial_gradient(pos!(1, 2; 3, 5), 2., RED, BLUE);
+        assert_eq!(canvas.color_at(pos!(1, 2; 3, 5)), RED);
+        assert_eq!(canvas.color_at(pos!(1, 3; 3, 5)), RED.lerp(BLUE, 0.5));
+        assert_eq!(canvas.color_at(TOP_LEFT), BLUE);
+    }
+
+    #[test]
+    fn test_quantize() {
+        let mut canvas = MaybePixelCanvas::<2, 4>::default();
+        canvas.update_color_at(TOP_LEFT, PixelColor::new(250, 10, 10));
+        canvas.update_color_at(pos!(0, 1; 2, 4), PixelColor::new(240, 0, 20));
+        canvas.update_color_at(pos!(0, 2; 2, 4), PixelColor::new(10, 10, 240));
+        canvas.update_color_at(BOTTOM_RIGHT, PixelColor::new(0, 20, 250));
+
+        let mut reduced = canvas.clone();
+        let palette = reduced.quantize(2);
+        assert_eq!(palette.len(), 2);
+        assert_eq!(
+            reduced.color_at(TOP_LEFT),
+            reduced.color_at(pos!(0, 1; 2, 4))
+        );
+        assert_eq!(
+            reduced.color_at(BOTTOM_RIGHT),
+            reduced.color_at(pos!(0, 2; 2, 4))
+        );
+        assert_eq!(reduced.color_at(BOTTOM_LEFT), None);
+
+        canvas.quantize_to_palette(&Palette::new([RED, BLUE]));
+        assert_eq!(canvas.color_at(TOP_LEFT), Some(RED));
+        assert_eq!(canvas.color_at(BOTTOM_RIGHT), Some(BLUE));
+    }
+
+    #[test]
+    fn test_draw_text() {
+        let mut canvas = PixelCanvas::<7, 10>::default();
+        let (top_left, bottom_right) = canvas.draw_text(pos!(1, 1; 7, 10), "hi!", RED).unwrap();
+        assert_eq!((top_left.row(), top_left.column()), (1, 1));
+        assert_eq!((bottom_right.row(), bottom_right.column()), (5, 9));
+        assert_eq!(canvas.color_at(pos!(1, 1; 7, 10)), RED);
+        assert_eq!(canvas.color_at(pos!(2, 2; 7, 10)), WHITE);
+
+        assert!(canvas.draw_text(TOP_LEFT, "", RED).is_none());
+    }
+
+    #[test]
+    fn test_memory_report() {
+        let mut canvas = MaybePixelCanvas::<1000, 2000>::default();
+        canvas.draw(CENTER, square::<10>(BLUE));
+
+        let report = canvas.memory_report();
+        assert_eq!(report.filled_len, 36);
+        assert_eq!(report.stored_rows, 10);
+        assert_eq!(report.filled_per_row.len(), 1000);
+        assert!(report.approx_bytes < 1000 * 2000);
+    }
+
+    #[test]
+    fn test_blit() {
+        let mut sprite = MaybePixelCanvas::<3>::default();
+        sprite.fill_region(TOP_LEFT, BOTTOM_RIGHT, RED);
+        sprite.update_color_at(CENTER, None);
+
+        let mut canvas = MaybePixelCanvas::<5, 4>::from_fill_color(BLUE);
+        canvas.blit(&sprite, TOP_LEFT, BOTTOM_RIGHT, (3, 2));
+
+        // Only the top left corner of the sprite fits and its empty center is skipped.
+        assert_eq!(canvas.color_at(pos!(3, 2; 5, 4)), Some(RED));
+        assert_eq!(canvas.color_at(pos!(4, 2; 5, 4)), Some(RED));
+        assert_eq!(canvas.color_at(pos!(4, 3; 5, 4)), Some(BLUE));
+        assert_eq!(canvas.color_at(pos!(2, 2; 5, 4)), Some(BLUE));
+        assert_eq!(canvas.filled_len(), 20);
+    }
+
+    #[test]
+    fn test_blit_with_policy() {
+        let mut sprite = MaybePixelCanvas::<2>::default();
+        sprite.fill_region(TOP_LEFT, pos!(1, 1; 2, 2), RED);
+        sprite.set_tag(TOP_LEFT, 7);
+
+        let mut canvas = MaybePixelCanvas::<5>::default();
+        canvas
+            .blit_with_policy(
+                &sprite,
+                TOP_LEFT,
+                pos!(1, 1; 2, 2),
+                BOTTOM_RIGHT,
+                OutOfBoundsPolicy::Wrap,
+            )
+            .unwrap();
+        assert_eq!(canvas.filled_len(), 4);
+        assert_eq!(canvas.color_at(TOP_LEFT), Some(RED));
+        assert_eq!(canvas.tag_at(BOTTOM_RIGHT), Some(7));
+        assert_eq!(canvas.tag_at(TOP_LEFT), None);
+
+        let mut canvas = MaybePixelCanvas::<5>::default();
+        assert!(canvas
+            .blit_with_policy(
+                &sprite,
+                TOP_LEFT,
+                pos!(1, 1; 2, 2),
+                BOTTOM_RIGHT,
+                OutOfBoundsPolicy::Error
+            )
+            .is_err());
+        assert_eq!(canvas.filled_len(), 0);
+        assert_eq!(canvas.tag_at(BOTTOM_RIGHT), None);
+
+        canvas.blit(&sprite, TOP_LEFT, pos!(1, 1; 2, 2), CENTER);
+        assert_eq!(canvas.tag_at(CENTER), Some(7));
+    }
+
+    #[test]
+    fn test_index() {
+        let mut canvas = PixelCanvas::<5>::default();
+        canvas.update_color_at(CENTER, BLUE);
+        canvas.update_color_at(pos!(0, 1; 5, 5), RED);
+        assert_eq!(canvas[(2, 2)].color(), &BLUE);
+        assert_eq!(canvas[pos!(0, 1; 5, 5)].color(), &RED);
+
+        // Reading a pixel doesn't store it.
+        assert_eq!(canvas[TOP_LEFT].color(), &WHITE);
+        assert_eq!(canvas.filled_len(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_out_of_bounds() {
+        let canvas = PixelCanvas::<5>::default();
+        let _ = &canvas[(5, 0)];
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_fill() {
+        let mut canvas = MaybePixelCanvas::<20, 30>::default();
+        canvas.par_fill(RED);
+        assert_eq!(canvas.filled_len(), 600);
+
+        canvas.update_color_at(TOP_LEFT, BLUE);
+        canvas.par_map_colors(|color| color.filter(|color| color == &RED));
+        assert_eq!(canvas.color_at(TOP_LEFT), None);
+        assert_eq!(canvas.color_at(BOTTOM_RIGHT), Some(RED));
+        assert_eq!(canvas.filled_len(), 599);
+
+        let mut expected = MaybePixelCanvas::<20, 30>::default();
+        expected.fill(RED);
+        expected.update_color_at(TOP_LEFT, None);
+        assert_eq!(canvas.table(), expected.table());
+    }
 }
@@ -1,15 +1,18 @@
-use std::marker::PhantomData;
+use std::{collections::BTreeMap, marker::PhantomData};
 
 use crate::{
+    animation::subpixel::{SampleGrid, SubpixelOffset, Supersample},
     pixels::{
+        color::RgbaInterface,
         position::{IntoPixelStrictPosition, PixelStrictPosition, PixelStrictPositionInterface},
         PixelInitializer, PixelInterface, PixelMutInterface,
     },
-    prelude::{Drawable, MaybePixel},
+    prelude::{Drawable, MaybePixel, PixelColor},
 };
 
 use super::{
-    table::PixelTable, PixelCanvasInterface, PixelCanvasMutInterface, SharedMutPixelCanvasExt,
+    mask::Mask, table::PixelTable, PixelCanvasInterface, PixelCanvasMutInterface,
+    SharedMutPixelCanvasExt,
 };
 
 #[derive(Debug, Clone)]
@@ -28,6 +31,29 @@ impl<const H: usize, const W: usize> BoxIndicator<H, W> {
             bottom_right: bottom_right.into_pixel_strict_position(),
         }
     }
+
+    pub fn top_left(&self) -> PixelStrictPosition<H, W> {
+        self.top_left
+    }
+
+    pub fn bottom_right(&self) -> PixelStrictPosition<H, W> {
+        self.bottom_right
+    }
+
+    /// Whether the position is inside the box (edges included).
+    pub fn contains(&self, pos: impl IntoPixelStrictPosition<H, W>) -> bool {
+        let (row, column) = pos.into_pixel_strict_position().expand();
+        (self.top_left.row()..=self.bottom_right.row()).contains(&row)
+            && (self.top_left.column()..=self.bottom_right.column()).contains(&column)
+    }
+
+    /// Whether the two boxes share any position.
+    pub fn intersects(&self, other: &BoxIndicator<H, W>) -> bool {
+        self.top_left.row() <= other.bottom_right.row()
+            && other.top_left.row() <= self.bottom_right.row()
+            && self.top_left.column() <= other.bottom_right.column()
+            && other.top_left.column() <= self.bottom_right.column()
+    }
 }
 
 impl<const H: usize, const W: usize> IntoIterator for BoxIndicator<H, W> {
@@ -90,9 +116,15 @@ pub struct CanvasPartition<
     I: PixelCanvasInterface<SH, SW, SP>,
 {
     position: PixelStrictPosition<SH, SW>,
+    /// `(height, width)` of the captured window, at most `(MH, MW)`.
+    size: (usize, usize),
+    /// Positions of the window which are part of the partition, all of them if none.
+    shape: Option<Mask<MH, MW>>,
     source_table: I,
     partition_table: PixelTable<MH, MW, MP>,
     partition_snapshot_table: PixelTable<MH, MW, MP>,
+    /// Fraction of a pixel the partition is moved by when captured, see [`Self::resolve_subpixel_on`].
+    subpixel: SubpixelOffset,
     _phantom: PhantomData<SP>,
 }
 
@@ -113,9 +145,11 @@ where
         C: PixelCanvasMutInterface<HC, WC, P>,
         P::ColorType: TryFrom<MP::ColorType, Error = E>,
     {
-        for (my_position, source_position) in
-            Self::_included_positions::<MH, MW, HC, WC>(start_pos.into_pixel_strict_position())
-        {
+        for (my_position, source_position) in Self::_included_positions::<MH, MW, HC, WC>(
+            start_pos.into_pixel_strict_position(),
+            self.size,
+            self.shape.as_ref(),
+        ) {
             let my_color = self.partition_table.get_pixel(my_position).color().clone();
             if let Ok(my_color) = P::ColorType::try_from(my_color) {
                 canvas
@@ -159,19 +193,23 @@ where
     I: PixelCanvasInterface<SH, SW, SP>,
 {
     fn _included_positions<
+        'a,
         const MMH: usize,
         const MMW: usize,
         const SSH: usize,
         const SSW: usize,
     >(
         start_position: PixelStrictPosition<SSH, SSW>,
-    ) -> impl Iterator<Item = (PixelStrictPosition<MMH, MMW>, PixelStrictPosition<SSH, SSW>)> {
-        (0..MMH).flat_map(move |row_offset| {
-            (0..MMW).filter_map(move |column_offset| {
+        (height, width): (usize, usize),
+        shape: Option<&'a Mask<MMH, MMW>>,
+    ) -> impl Iterator<Item = (PixelStrictPosition<MMH, MMW>, PixelStrictPosition<SSH, SSW>)> + 'a
+    {
+        let positions = (0..height.min(MMH)).flat_map(move |row_offset| {
+            (0..width.min(MMW)).filter_map(move |column_offset| {
                 start_position
-                    .checked_right(row_offset)
+                    .checked_down(row_offset)
                     .ok()
-                    .and_then(|f| f.checked_down(column_offset).ok())
+                    .and_then(|f| f.checked_right(column_offset).ok())
                     .map(|f| {
                         (
                             PixelStrictPosition::<MMH, MMW>::new(row_offset, column_offset)
@@ -180,12 +218,17 @@ where
                         )
                     })
             })
+        });
+        positions.filter(move |(my_position, _)| {
+            shape.is_none_or(|shape| shape.is_allowed(*my_position))
         })
     }
 
     fn _read_source<const MMH: usize, const MMW: usize>(
         source_table: &I,
         position: PixelStrictPosition<SH, SW>,
+        size: (usize, usize),
+        shape: Option<&Mask<MMH, MMW>>,
     ) -> PixelTable<MMH, MMW, MP>
     where
         MP: PixelMutInterface + PixelInitializer + Clone + PartialEq,
@@ -194,7 +237,7 @@ where
         MP::ColorType: From<SP::ColorType>,
     {
         let mut partition_table = PixelTable::<MMH, MMW, MP>::default();
-        for (my_position, source_position) in Self::_included_positions(position) {
+        for (my_position, source_position) in Self::_included_positions(position, size, shape) {
             let source_color = source_table
                 .table()
                 .get_pixel(source_position)
@@ -203,6 +246,10 @@ where
             partition_table
                 .get_pixel_mut(my_position)
                 .update_color(source_color);
+            partition_table.tags.set(
+                my_position.expand(),
+                source_table.table().tags().get(source_position.expand()),
+            );
         }
         partition_table
     }
@@ -214,7 +261,12 @@ where
         SP::ColorType: Clone,
         MP::ColorType: From<SP::ColorType>,
     {
-        Self::_read_source(&self.source_table, self.position)
+        Self::_read_source(
+            &self.source_table,
+            self.position,
+            self.size,
+            self.shape.as_ref(),
+        )
     }
 
     fn set_source_color<E>(&mut self, color: impl Into<Option<SP::ColorType>>)
@@ -225,8 +277,19 @@ where
         SP::ColorType: TryFrom<MP::ColorType, Error = E> + Clone,
     {
         let chosen_color: Option<SP::ColorType> = color.into();
-        for (part_position, source_position) in self.included_positions() {
+        // Fields are borrowed one by one, so the source can be changed meanwhile.
+        for (part_position, source_position) in
+            Self::_included_positions(self.position, self.size, self.shape.as_ref())
+        {
             if self.partition_table.get_pixel(part_position).has_color() {
+                if chosen_color.is_none() {
+                    // The partition is leaving, so do its tags.
+                    self.source_table
+                        .table_mut()
+                        .tags
+                        .set(source_position.expand(), None);
+                }
+
                 if let Some(color) = &chosen_color {
                     self.source_table
                         .table_mut()
@@ -255,27 +318,74 @@ where
         MP: PixelMutInterface + PartialEq + Clone,
         MP::ColorType: From<SP::ColorType> + Clone,
     {
-        for (my_position, source_position) in self.included_positions() {
-            if self.partition_table.get_pixel(my_position).has_color() {
-                let new_color = self.partition_table.get_pixel(my_position).color().clone();
+        let included: BTreeMap<_, _> = self
+            .included_positions()
+            .map(|(my_position, source_position)| (my_position.expand(), source_position))
+            .collect();
+
+        // Remember what's under the partition, so it can be put back when the partition moves.
+        for (&my_position, source_position) in &included {
+            let pixel = self.partition_table.get_pixel(my_position);
+            if pixel.has_color() && SP::ColorType::try_from(pixel.color().clone()).is_ok() {
                 let source_current_color = self
                     .source_table
                     .table()
-                    .get_pixel(source_position)
+                    .get_pixel(*source_position)
                     .color()
                     .clone();
+                self.partition_snapshot_table
+                    .get_pixel_mut(my_position)
+                    .update_color(source_current_color);
+            }
+        }
 
-                if let Ok(new_color) = SP::ColorType::try_from(new_color) {
-                    self.partition_snapshot_table
-                        .get_pixel_mut(my_position)
-                        .update_color(source_current_color);
-                    self.source_table
-                        .table_mut()
-                        .get_pixel_mut(source_position)
-                        .update_color(new_color);
+        // Default pixels aren't stored, but they still cover the source if they've a color.
+        let default_pixel = MP::default();
+        if default_pixel.has_color() {
+            if let Ok(color) = SP::ColorType::try_from(default_pixel.color().clone()) {
+                for (&my_position, source_position) in &included {
+                    let source = self.source_table.table_mut();
+                    source
+                        .get_pixel_mut(*source_position)
+                        .update_color(color.clone());
+                    source.tags.set(
+                        source_position.expand(),
+                        self.partition_table.tags().get(my_position),
+                    );
                 }
             }
         }
+
+        let (height, width) = self.visible_size();
+        if height == 0 || width == 0 {
+            return;
+        }
+
+        let mut written = Vec::new();
+        self.source_table.table_mut().inner.copy_from_with(
+            &self.partition_table.inner,
+            (0, 0),
+            (height - 1, width - 1),
+            |my_position, pixel| {
+                let source_position = included.get(&my_position)?;
+                if !pixel.has_color() {
+                    return None;
+                }
+
+                let color = SP::ColorType::try_from(pixel.color().clone()).ok()?;
+                let mut new_pixel = SP::default();
+                new_pixel.update_color(color);
+                written.push((my_position, *source_position));
+                Some((source_position.expand(), new_pixel))
+            },
+        );
+        for (my_position, source_position) in written {
+            let tag = self.partition_table.tags().get(my_position);
+            self.source_table
+                .table_mut()
+                .tags
+                .set(source_position.expand(), tag);
+        }
     }
 
     pub fn new(
@@ -289,20 +399,125 @@ where
     {
         let start_position = position.into_pixel_strict_position();
         CanvasPartition::<MH, MW, SH, SW, I, SP, MP> {
-            partition_table: Self::_read_source(&source_table, start_position),
+            partition_table: Self::_read_source(&source_table, start_position, (MH, MW), None),
             position: start_position,
+            size: (MH, MW),
+            shape: None,
             source_table,
             partition_snapshot_table: Default::default(),
+            subpixel: SubpixelOffset::ZERO,
             _phantom: PhantomData,
         }
     }
 
     pub fn included_positions(
         &self,
-    ) -> impl Iterator<Item = (PixelStrictPosition<MH, MW>, PixelStrictPosition<SH, SW>)> {
-        Self::_included_positions(self.position)
+    ) -> impl Iterator<Item = (PixelStrictPosition<MH, MW>, PixelStrictPosition<SH, SW>)> + '_ {
+        Self::_included_positions(self.position, self.size, self.shape.as_ref())
     }
 
+    /// Limits the partition to the allowed positions of `shape` (relative to the window) and
+    /// captures the source again.
+    ///
+    /// The rest of the window is never read from or written to the source, so irregular sprites
+    /// can move without their neighbors.
+    pub fn with_shape(mut self, shape: Mask<MH, MW>) -> Self
+    where
+        MP: PixelMutInterface + PixelInitializer + PartialEq + Clone,
+        MP::ColorType: Clone + Default + From<SP::ColorType>,
+        SP::ColorType: Clone,
+    {
+        self.shape = Some(shape);
+        self.partition_table = self.read_source();
+        self
+    }
+
+    /// A partition of the allowed positions of a [`Mask`] of the source, e.g. one made from a
+    /// [`Selection`](super::selection::Selection).
+    ///
+    /// The window starts at the top left of the mask's bounding box, and positions which don't
+    /// fit into `MH` * `MW` are left out. Returns none if the mask is empty.
+    pub fn from_mask(mask: &Mask<SH, SW>, source_table: I) -> Option<Self>
+    where
+        MP: PixelMutInterface + PixelInitializer + PartialEq + Clone,
+        MP::ColorType: Clone + Default + From<SP::ColorType>,
+        SP::ColorType: Clone,
+    {
+        let top = mask.iter().map(|pos| pos.row()).min()?;
+        let left = mask.iter().map(|pos| pos.column()).min()?;
+        let shape = mask
+            .iter()
+            .filter_map(|pos| PixelStrictPosition::new(pos.row() - top, pos.column() - left).ok())
+            .collect();
+        Some(Self::new((top, left), source_table).with_shape(shape))
+    }
+
+    pub fn shape(&self) -> Option<&Mask<MH, MW>> {
+        self.shape.as_ref()
+    }
+
+    /// The box of the source covered by the window (the part inside the source),
+    /// none if the window is empty.
+    pub fn source_bounds(&self) -> Option<BoxIndicator<SH, SW>> {
+        let (height, width) = self.visible_size();
+        if height == 0 || width == 0 {
+            return None;
+        }
+
+        let (row, column) = self.position.expand();
+        Some(BoxIndicator::new(
+            self.position,
+            (row + height - 1, column + width - 1),
+        ))
+    }
+
+    /// Whether the partition covers the source position, respecting its shape.
+    pub fn contains(&self, pos: impl IntoPixelStrictPosition<SH, SW>) -> bool {
+        let pos = pos.into_pixel_strict_position();
+        let Some(bounds) = self.source_bounds() else {
+            return false;
+        };
+        if !bounds.contains(pos) {
+            return false;
+        }
+
+        let offset = (
+            pos.row() - self.position.row(),
+            pos.column() - self.position.column(),
+        );
+        self.shape
+            .as_ref()
+            .is_none_or(|shape| shape.is_allowed(offset))
+    }
+
+    /// Whether the two partitions (of the same source size) cover any shared position,
+    /// e.g. two moving sprites colliding.
+    pub fn overlaps<const OH: usize, const OW: usize, OI, OSP, OMP>(
+        &self,
+        other: &CanvasPartition<OH, OW, SH, SW, OI, OSP, OMP>,
+    ) -> bool
+    where
+        OSP: PixelInterface + Default,
+        OMP: PixelInterface + Default,
+        OI: PixelCanvasInterface<SH, SW, OSP>,
+    {
+        let (Some(bounds), Some(other_bounds)) = (self.source_bounds(), other.source_bounds())
+        else {
+            return false;
+        };
+        if !bounds.intersects(&other_bounds) {
+            return false;
+        }
+
+        match (&self.shape, &other.shape) {
+            (None, None) => true,
+            _ => self
+                .included_positions()
+                .any(|(_, source_position)| other.contains(source_position)),
+        }
+    }
+
+    /// Moves the window to a new position and captures the source there again.
     pub fn update_position(&mut self, new_position: impl IntoPixelStrictPosition<SH, SW>)
     where
         MP: PixelMutInterface + PixelInitializer + PartialEq + Clone,
@@ -311,7 +526,38 @@ where
         MP::ColorType: From<SP::ColorType>,
     {
         self.position = new_position.into_pixel_strict_position();
-        self.read_source();
+        self.partition_table = self.read_source();
+    }
+
+    /// Current `(height, width)` of the captured window, which is `(MH, MW)` at first.
+    pub fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    /// Grows or shrinks the captured window (up to `(MH, MW)`) and captures the source again.
+    ///
+    /// Changes which are not written to the source yet are lost, and pixels outside of the
+    /// window stay default.
+    pub fn resize(&mut self, height: usize, width: usize)
+    where
+        MP: PixelMutInterface + PixelInitializer + PartialEq + Clone,
+        MP::ColorType: Clone + Default,
+        SP::ColorType: Clone,
+        MP::ColorType: From<SP::ColorType>,
+    {
+        self.size = (height.min(MH), width.min(MW));
+        self.partition_table = self.read_source();
+    }
+
+    /// `(height, width)` of the part of the window which is inside the source.
+    pub fn visible_size(&self) -> (usize, usize) {
+        let (row, column) = self.position.expand();
+        (self.size.0.min(SH - row), self.size.1.min(SW - column))
+    }
+
+    /// Whether the window goes past the edges of the source, so some of it isn't captured.
+    pub fn is_clipped(&self) -> bool {
+        self.visible_size() != self.size
     }
 
     pub fn update_color<E>(&mut self, color: impl Into<MP::ColorType> + Clone)
@@ -353,6 +599,105 @@ where
         self.write_source();
     }
 
+    /// Replaces the contents of the window with `source(row, column)` of the current ones,
+    /// using `(height, width)` as the new window size, then writes it back to the source.
+    fn remap_contents<E>(
+        &mut self,
+        (height, width): (usize, usize),
+        source: impl Fn(usize, usize) -> (usize, usize),
+    ) where
+        MP: PixelMutInterface + PixelInitializer + PartialEq + Clone,
+        MP::ColorType: From<SP::ColorType> + Clone + Default,
+        SP::ColorType: TryFrom<MP::ColorType, Error = E> + Clone + Default,
+        SP: PixelMutInterface + PartialEq + Clone,
+        I: PixelCanvasMutInterface<SH, SW, SP>,
+    {
+        // Bring back what was under the old contents, like moving with `crop_to`.
+        self.set_source_color(None);
+
+        self.size = (height.min(MH), width.min(MW));
+        if let Some(shape) = &self.shape {
+            let (height, width) = self.size;
+            let remapped = (0..height)
+                .flat_map(|row| (0..width).map(move |column| (row, column)))
+                .filter(|&(row, column)| shape.is_allowed(source(row, column)))
+                .map(|index| index.into_pixel_strict_position())
+                .collect();
+            self.shape = Some(remapped);
+        }
+
+        let mut table = PixelTable::<MH, MW, MP>::default();
+        for row in 0..self.size.0 {
+            for column in 0..self.size.1 {
+                let from = source(row, column);
+                table
+                    .get_pixel_mut((row, column))
+                    .update_color(self.partition_table.get_pixel(from).color().clone());
+                table
+                    .tags
+                    .set((row, column), self.partition_table.tags().get(from));
+            }
+        }
+        self.partition_table = table;
+        self.write_source();
+    }
+
+    /// Rotates the contents of the window 90 degrees clockwise and writes them back.
+    ///
+    /// The window becomes `(width, height)`, anything past `(MH, MW)` is cut.
+    pub fn rotate_cw<E>(&mut self)
+    where
+        MP: PixelMutInterface + PixelInitializer + PartialEq + Clone,
+        MP::ColorType: From<SP::ColorType> + Clone + Default,
+        SP::ColorType: TryFrom<MP::ColorType, Error = E> + Clone + Default,
+        SP: PixelMutInterface + PartialEq + Clone,
+        I: PixelCanvasMutInterface<SH, SW, SP>,
+    {
+        let (height, width) = self.size;
+        self.remap_contents((width, height), |row, column| (height - 1 - column, row));
+    }
+
+    /// Rotates the contents of the window 90 degrees counter clockwise and writes them back.
+    ///
+    /// The window becomes `(width, height)`, anything past `(MH, MW)` is cut.
+    pub fn rotate_ccw<E>(&mut self)
+    where
+        MP: PixelMutInterface + PixelInitializer + PartialEq + Clone,
+        MP::ColorType: From<SP::ColorType> + Clone + Default,
+        SP::ColorType: TryFrom<MP::ColorType, Error = E> + Clone + Default,
+        SP: PixelMutInterface + PartialEq + Clone,
+        I: PixelCanvasMutInterface<SH, SW, SP>,
+    {
+        let (height, width) = self.size;
+        self.remap_contents((width, height), |row, column| (column, width - 1 - row));
+    }
+
+    /// Mirrors the contents of the window horizontally and writes them back.
+    pub fn flip_x<E>(&mut self)
+    where
+        MP: PixelMutInterface + PixelInitializer + PartialEq + Clone,
+        MP::ColorType: From<SP::ColorType> + Clone + Default,
+        SP::ColorType: TryFrom<MP::ColorType, Error = E> + Clone + Default,
+        SP: PixelMutInterface + PartialEq + Clone,
+        I: PixelCanvasMutInterface<SH, SW, SP>,
+    {
+        let (_, width) = self.size;
+        self.remap_contents(self.size, |row, column| (row, width - 1 - column));
+    }
+
+    /// Mirrors the contents of the window vertically and writes them back.
+    pub fn flip_y<E>(&mut self)
+    where
+        MP: PixelMutInterface + PixelInitializer + PartialEq + Clone,
+        MP::ColorType: From<SP::ColorType> + Clone + Default,
+        SP::ColorType: TryFrom<MP::ColorType, Error = E> + Clone + Default,
+        SP: PixelMutInterface + PartialEq + Clone,
+        I: PixelCanvasMutInterface<SH, SW, SP>,
+    {
+        let (height, _) = self.size;
+        self.remap_contents(self.size, |row, column| (height - 1 - row, column));
+    }
+
     /// Returns a mutable reference to the partition table of this [`CanvasPartition<SH, SW, MH, MW, I, SP, MP>`].
     pub fn partition_table_mut(&mut self) -> &mut PixelTable<MH, MW, MP> {
         &mut self.partition_table
@@ -374,14 +719,105 @@ where
     pub fn source_table_mut(&mut self) -> &mut I {
         &mut self.source_table
     }
+
+    pub fn subpixel_offset(&self) -> SubpixelOffset {
+        self.subpixel
+    }
+
+    /// Moves the partition by a fraction of a pixel further than its position, the source
+    /// itself doesn't change. See [`Self::resolve_subpixel_on`].
+    pub fn set_subpixel_offset(&mut self, subpixel: SubpixelOffset) {
+        self.subpixel = subpixel;
+    }
+
+    /// Moves the partition on `canvas`, a copy of the source where the partition is written,
+    /// by its [`SubpixelOffset`]. Pixels it partially covers are blended with what's below.
+    ///
+    /// This is how animations capture partitions which sit between pixels.
+    pub fn resolve_subpixel_on<C, E>(&self, canvas: &mut C, supersample: Supersample)
+    where
+        C: PixelCanvasMutInterface<SH, SW, SP>,
+        SP: PixelMutInterface + PartialEq + Clone,
+        SP::ColorType: TryFrom<MP::ColorType, Error = E> + TryFrom<Option<PixelColor>> + Clone,
+        SP::ColorType: RgbaInterface,
+        MP::ColorType: RgbaInterface + Clone,
+    {
+        if self.subpixel.is_zero() {
+            return;
+        }
+
+        let mut colors = Vec::new();
+        for (my_position, source_position) in self.included_positions() {
+            let [r, g, b, a] = self.partition_table.get_pixel(my_position).color().rgba().0;
+            if a == 0 {
+                continue;
+            }
+
+            // Put back what the partition covers, it's drawn again at the fractional position.
+            if let Ok(under) = SP::ColorType::try_from(
+                self.partition_snapshot_table
+                    .get_pixel(my_position)
+                    .color()
+                    .clone(),
+            ) {
+                canvas
+                    .table_mut()
+                    .get_pixel_mut(source_position)
+                    .update_color(under);
+            }
+            colors.push((my_position.expand(), PixelColor::new(r, g, b)));
+        }
+
+        let (row, column) = self.position.expand();
+        let (row_fraction, column_fraction) = self.subpixel.fraction();
+        let mut grid = SampleGrid::new((SH, SW), supersample);
+        grid.place(
+            (row as f32 + row_fraction, column as f32 + column_fraction),
+            colors,
+        );
+        for ((row, column), color, coverage) in grid.resolve() {
+            let Ok(pos) = PixelStrictPosition::new(row, column) else {
+                continue;
+            };
+            let under = canvas.table().get_pixel(pos).color().rgba();
+            // Nothing to blend with on transparent pixels.
+            let blended = if under[3] == 0 {
+                color
+            } else {
+                PixelColor::new(under[0], under[1], under[2]).lerp(color, coverage)
+            };
+            if let Ok(blended) = SP::ColorType::try_from(Some(blended)) {
+                canvas.table_mut().get_pixel_mut(pos).update_color(blended);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::prelude::*;
+    use crate::{
+        animation::subpixel::{SubpixelOffset, Supersample},
+        pixels::canvas::mask::Mask,
+        prelude::*,
+    };
 
     use super::CanvasPartition;
 
+    #[test]
+    fn test_non_square_window() {
+        let mut canvas = PixelCanvas::<4, 6>::default();
+        canvas.update_color_at(pos!(1, 3; 4, 6), RED);
+
+        // Rows and columns of a non-square window are not swapped.
+        let mut part = canvas.maybe_partition_mut::<2, 4>(TOP_LEFT);
+        assert_eq!(part.color_at(pos!(1, 3; 2, 4)), Some(RED));
+
+        // Moving the window captures the source at its new position.
+        part.update_position(pos!(1, 2; 4, 6));
+        assert_eq!(part.color_at(pos!(0, 1; 2, 4)), Some(RED));
+        assert_eq!(part.color_at(pos!(1, 3; 2, 4)), Some(WHITE));
+    }
+
     #[test]
     fn feature_1() {
         let mut canvas = PixelCanvas::<5>::default();
@@ -447,4 +883,133 @@ mod tests {
             .save("arts/partition_crop_2.png")
             .unwrap()
     }
+
+    #[test]
+    fn test_resize() {
+        let mut canvas = PixelCanvas::<4, 6>::default();
+        canvas.update_color_at(pos!(1, 3; 4, 6), RED);
+
+        let mut part = canvas.maybe_partition_mut::<2, 4>(TOP_LEFT);
+        assert_eq!(part.size(), (2, 4));
+        assert!(!part.is_clipped());
+        assert_eq!(part.color_at(pos!(1, 3; 2, 4)), Some(RED));
+
+        part.resize(1, 10);
+        assert_eq!(part.size(), (1, 4));
+        assert_eq!(part.color_at(pos!(1, 3; 2, 4)), None);
+        assert_eq!(part.included_positions().count(), 4);
+
+        part.update_position(pos!(1, 3; 4, 6));
+        assert!(part.is_clipped());
+        assert_eq!(part.visible_size(), (1, 3));
+        assert_eq!(part.color_at(TOP_LEFT), Some(RED));
+    }
+
+    #[test]
+    fn test_rotate_and_flip() {
+        let mut canvas = PixelCanvas::<4>::default();
+        let mut part = canvas.maybe_partition_mut::<3, 3>(TOP_LEFT);
+        // An L shape
+        part.update_color_at(TOP_LEFT, RED);
+        part.update_color_at(pos!(1, 0; 3, 3), RED);
+        part.update_color_at(pos!(2, 0; 3, 3), RED);
+        part.update_color_at(BOTTOM_CENTER, BLUE);
+        part.write_source();
+
+        part.rotate_cw();
+        let source = part.source_table();
+        assert_eq!(source.color_at(TOP_LEFT), RED);
+        assert_eq!(source.color_at(pos!(0, 2; 4, 4)), RED);
+        assert_eq!(source.color_at(pos!(1, 0; 4, 4)), BLUE);
+        assert_eq!(source.color_at(pos!(2, 0; 4, 4)), WHITE);
+
+        part.rotate_ccw();
+        part.flip_x();
+        let source = part.source_table();
+        assert_eq!(source.color_at(pos!(0, 2; 4, 4)), RED);
+        assert_eq!(source.color_at(pos!(2, 1; 4, 4)), BLUE);
+        assert_eq!(source.color_at(TOP_LEFT), WHITE);
+
+        part.flip_y();
+        let source = part.source_table();
+        assert_eq!(source.color_at(pos!(0, 2; 4, 4)), RED);
+        assert_eq!(source.color_at(pos!(0, 1; 4, 4)), BLUE);
+        assert_eq!(source.color_at(pos!(2, 1; 4, 4)), WHITE);
+    }
+
+    #[test]
+    fn test_shaped_partition() {
+        let mut canvas = PixelCanvas::<4>::default();
+        canvas.update_color_at(pos!(1, 1; 4, 4), RED);
+        canvas.update_color_at(pos!(2, 1; 4, 4), RED);
+        canvas.update_color_at(pos!(2, 2; 4, 4), RED);
+        canvas.update_color_at(pos!(1, 2; 4, 4), BLUE);
+
+        let mask = Mask::from(&canvas.select_connected(pos!(1, 1; 4, 4)));
+        let mut part =
+            CanvasPartition::<2, 2, 4, 4, _, _, MaybePixel>::from_mask(&mask, &mut canvas).unwrap();
+        assert_eq!(part.position(), pos!(1, 1; 4, 4));
+        assert_eq!(part.included_positions().count(), 3);
+        assert_eq!(part.color_at(TOP_RIGHT), None);
+
+        part.crop_to(TOP_LEFT);
+        let source = part.source_table();
+        assert_eq!(source.color_at(TOP_LEFT), RED);
+        assert_eq!(source.color_at(pos!(1, 1; 4, 4)), RED);
+        assert_eq!(source.color_at(pos!(0, 1; 4, 4)), WHITE);
+        // Not part of the shape, so it's left alone.
+        assert_eq!(source.color_at(pos!(1, 2; 4, 4)), BLUE);
+
+        part.rotate_cw();
+        assert_eq!(part.shape().map(|shape| shape.len()), Some(3));
+        assert!(!part.shape().unwrap().is_allowed(BOTTOM_RIGHT));
+
+        assert!(
+            CanvasPartition::<2, 2, 4, 4, _, _, MaybePixel>::from_mask(&Mask::new(), &canvas)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_bounds_and_overlaps() {
+        let canvas = PixelCanvas::<5>::default();
+        let a = canvas.maybe_partition::<2, 3>(TOP_LEFT);
+        let b = canvas.maybe_partition::<3, 3>(CENTER);
+        let c = canvas.maybe_partition::<2, 2>(pos!(1, 2; 5, 5));
+
+        let bounds = b.source_bounds().unwrap();
+        assert_eq!(bounds.top_left(), pos!(2, 2; 5, 5));
+        assert_eq!(bounds.bottom_right(), pos!(4, 4; 5, 5));
+        assert!(b.contains(BOTTOM_RIGHT));
+        assert!(!b.contains(TOP_LEFT));
+
+        assert!(!a.overlaps(&b));
+        assert!(a.overlaps(&c));
+        assert!(b.overlaps(&c));
+
+        let shape = [pos!(0, 1; 2, 2), pos!(1, 1; 2, 2)]
+            .into_iter()
+            .collect::<Mask<2, 2>>();
+        let c = c.with_shape(shape);
+        assert!(!c.contains(pos!(1, 2; 5, 5)));
+        assert!(!a.overlaps(&c));
+        assert!(b.overlaps(&c));
+    }
+
+    #[test]
+    fn test_subpixel_offset() {
+        let mut canvas = PixelCanvas::<1, 3>::default();
+        let mut part = canvas.maybe_partition_mut::<1, 1>(TOP_LEFT);
+        part.update_color(BLACK);
+        part.set_subpixel_offset(SubpixelOffset::split((0.0, 0.5)).1);
+
+        let mut frame = PixelCanvas::clone(part.source_table());
+        part.resolve_subpixel_on(&mut frame, Supersample::X2);
+        assert_eq!(frame.color_at(TOP_LEFT), PixelColor::splat(128));
+        assert_eq!(frame.color_at(pos!(0, 1; 1, 3)), PixelColor::splat(128));
+        assert_eq!(frame.color_at(TOP_RIGHT), WHITE);
+
+        // The source keeps the partition at its whole pixel position.
+        assert_eq!(part.source_table().color_at(TOP_LEFT), BLACK);
+    }
 }
@@ -1,16 +1,26 @@
-use std::marker::PhantomData;
+use std::{
+    fmt::Display,
+    iter::Peekable,
+    marker::PhantomData,
+    str::{CharIndices, FromStr},
+};
+
+use thiserror::Error;
 
 use crate::{
     pixels::{
         position::{
-            Direction, IntoPixelStrictPosition, PixelStrictPosition, PixelStrictPositionInterface,
+            Direction, IntoPixelStrictPosition, OutOfBoundsError, OutOfBoundsPolicy,
+            PixelStrictPosition, PixelStrictPositionInterface, ALL_DIRECTIONS,
         },
         PixelInterface, PixelMutInterface,
     },
     prelude::PixelColor,
 };
 
-use super::PixelCanvasMutInterface;
+use super::{
+    dynamic::DynPixelCanvas, shapes::line, unbounded::UnboundedCanvas, PixelCanvasMutInterface,
+};
 
 pub trait CanvasAttachment {
     type CanvasType;
@@ -25,6 +35,7 @@ pub struct CanvasAttachedMarker<
     C: PixelCanvasMutInterface<H, W, P>,
 > {
     current_pos: PixelStrictPosition<H, W>,
+    last_error: Option<OutOfBoundsError>,
     _phantom: PhantomData<&'c (P, C)>,
 }
 
@@ -38,6 +49,7 @@ impl<
     pub fn new(current_pos: impl IntoPixelStrictPosition<H, W>) -> Self {
         Self {
             current_pos: current_pos.into_pixel_strict_position(),
+            last_error: None,
             _phantom: PhantomData,
         }
     }
@@ -55,6 +67,29 @@ impl<
     type ColorType = P::ColorType;
 }
 
+/// Marker of a pen attached to an [`UnboundedCanvas`].
+pub struct UnboundedAttachedMarker<'c> {
+    current_pos: (isize, isize),
+    _phantom: PhantomData<&'c UnboundedCanvas>,
+}
+
+impl<'c> CanvasAttachment for UnboundedAttachedMarker<'c> {
+    type CanvasType = &'c mut UnboundedCanvas;
+    type ColorType = PixelColor;
+}
+
+/// Marker of a pen attached to a [`DynPixelCanvas`].
+pub struct DynAttachedMarker<'c, P: PixelInterface> {
+    current_pos: (usize, usize),
+    last_error: Option<OutOfBoundsError>,
+    _phantom: PhantomData<&'c DynPixelCanvas<P>>,
+}
+
+impl<'c, P: PixelInterface + 'c> CanvasAttachment for DynAttachedMarker<'c, P> {
+    type CanvasType = &'c mut DynPixelCanvas<P>;
+    type ColorType = P::ColorType;
+}
+
 pub struct CanvasUnattachedMarker<Co = PixelColor>(PhantomData<Co>);
 
 impl<Co> CanvasAttachment for CanvasUnattachedMarker<Co> {
@@ -88,14 +123,84 @@ pub struct Pen<M: CanvasAttachment = CanvasUnattachedMarker> {
     canvas: M::CanvasType,
     color: M::ColorType,
     pub drawing: bool,
+    /// What happens when the pen is moved past the edges of the canvas.
+    ///
+    /// Using [`OutOfBoundsPolicy::Clip`] (default) the pen stays at the edge. It's ignored on
+    /// an [`UnboundedCanvas`], which has no edges, but kept for when the pen is detached.
+    pub policy: OutOfBoundsPolicy,
+    /// How the line is drawn as the pen moves, see [`StrokeStyle`].
+    pub stroke: StrokeStyle,
+    /// Pixels visited since the pen was started, used by the [`StrokeStyle`].
+    stroke_step: usize,
+    /// Whether the pen paints or erases, see [`PenMode`].
+    pub mode: PenMode,
     attachment: M,
 }
 
+/// What a [`Pen`] does to the pixels it draws on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PenMode {
+    /// Pixels take the color of the pen.
+    #[default]
+    Paint,
+    /// Pixels are reset to the canvas default, which is `None` on a
+    /// [`MaybePixelCanvas`](super::MaybePixelCanvas).
+    Erase,
+}
+
+/// The pattern of the line a [`Pen`] leaves behind while drawing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StrokeStyle {
+    /// Every pixel on the way is drawn.
+    #[default]
+    Solid,
+    /// `on` pixels are drawn, then `off` pixels are skipped, and so on.
+    Dashed { on: usize, off: usize },
+    /// Every other pixel is drawn.
+    Dotted,
+}
+
+impl StrokeStyle {
+    /// Whether the `step`th pixel since the pen was started is drawn.
+    pub fn is_drawn(&self, step: usize) -> bool {
+        match *self {
+            StrokeStyle::Solid => true,
+            StrokeStyle::Dashed { on, off } => on + off == 0 || step % (on + off) < on,
+            StrokeStyle::Dotted => step.is_multiple_of(2),
+        }
+    }
+}
+
 impl<M: CanvasAttachment> Pen<M> {
     pub fn stop(&mut self) -> &mut Pen<M> {
         self.drawing = false;
         self
     }
+
+    /// Sets the [`OutOfBoundsPolicy`] used for pen movements.
+    pub fn with_policy(mut self, policy: OutOfBoundsPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Sets the [`PenMode`], e.g. to erase using an existing pen.
+    pub fn with_mode(mut self, mode: PenMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the [`StrokeStyle`], e.g. to draw dashed borders.
+    pub fn with_stroke(mut self, stroke: StrokeStyle) -> Self {
+        self.stroke = stroke;
+        self
+    }
+
+    /// Advances the stroke pattern, returns whether the current pixel is drawn.
+    fn next_stroke_step(&mut self) -> bool {
+        let drawn = self.stroke.is_drawn(self.stroke_step);
+        self.stroke_step += 1;
+        drawn
+    }
 }
 
 impl<Co> Pen<CanvasUnattachedMarker<Co>> {
@@ -104,11 +209,22 @@ impl<Co> Pen<CanvasUnattachedMarker<Co>> {
             canvas: (),
             color: color.into(),
             drawing: false,
+            policy: OutOfBoundsPolicy::default(),
+            stroke: StrokeStyle::default(),
+            stroke_step: 0,
+            mode: PenMode::default(),
             attachment: CanvasUnattachedMarker::<Co>(PhantomData),
         }
     }
 }
 
+impl<Co: Default> Pen<CanvasUnattachedMarker<Co>> {
+    /// A pen in [`PenMode::Erase`], which cleans up pixels instead of painting them.
+    pub fn eraser() -> Pen<CanvasUnattachedMarker<Co>> {
+        Self::new(Co::default()).with_mode(PenMode::Erase)
+    }
+}
+
 pub type PixelPen = Pen<CanvasUnattachedMarker<PixelColor>>;
 
 impl<Co> Pen<CanvasUnattachedMarker<Co>> {
@@ -130,149 +246,653 @@ impl<Co> Pen<CanvasUnattachedMarker<Co>> {
             canvas,
             color: self.color.into(),
             drawing: false,
+            policy: self.policy,
+            stroke: self.stroke,
+            stroke_step: 0,
+            mode: self.mode,
             attachment: CanvasAttachedMarker::new(start_pos),
         }
     }
 }
 
+impl<Co> Pen<CanvasUnattachedMarker<Co>> {
+    /// Attach this pen to an [`UnboundedCanvas`], where moves are never clamped.
+    #[must_use = "This function returns a new attached pen."]
+    pub fn attach_unbounded(
+        self,
+        canvas: &mut UnboundedCanvas,
+        start_pos: (isize, isize),
+    ) -> Pen<UnboundedAttachedMarker<'_>>
+    where
+        PixelColor: From<Co>,
+    {
+        Pen {
+            canvas,
+            color: self.color.into(),
+            drawing: false,
+            policy: self.policy,
+            stroke: self.stroke,
+            stroke_step: 0,
+            mode: self.mode,
+            attachment: UnboundedAttachedMarker {
+                current_pos: start_pos,
+                _phantom: PhantomData,
+            },
+        }
+    }
+}
+
+impl<Co> Pen<CanvasUnattachedMarker<Co>> {
+    /// Attach this pen to a [`DynPixelCanvas`].
+    ///
+    /// A start position out of bounds is clamped to the nearest edge.
+    #[must_use = "This function returns a new attached pen."]
+    pub fn attach_dyn<P>(
+        self,
+        canvas: &mut DynPixelCanvas<P>,
+        start_pos: (usize, usize),
+    ) -> Pen<DynAttachedMarker<'_, P>>
+    where
+        P: PixelInterface,
+        P::ColorType: From<Co>,
+    {
+        let current_pos = clamp_in(start_pos, (canvas.height(), canvas.width()));
+        Pen {
+            canvas,
+            color: self.color.into(),
+            drawing: false,
+            policy: self.policy,
+            stroke: self.stroke,
+            stroke_step: 0,
+            mode: self.mode,
+            attachment: DynAttachedMarker {
+                current_pos,
+                last_error: None,
+                _phantom: PhantomData,
+            },
+        }
+    }
+}
+
+/// The nearest position to `(row, column)` inside `(height, width)`.
+fn clamp_in((row, column): (usize, usize), (height, width): (usize, usize)) -> (usize, usize) {
+    (
+        row.min(height.saturating_sub(1)),
+        column.min(width.saturating_sub(1)),
+    )
+}
+
+/// A canvas attachment which a [`Pen`] can move on and draw to.
+///
+/// Movements of attached pens (like [`Pen::go_direction`], [`Pen::branch`] and
+/// [`Pen::follow`]) are implemented once on top of this, using signed `(row, column)`s.
+pub trait PenAttachment: CanvasAttachment {
+    /// Current `(row, column)` of the pen.
+    fn position(&self) -> (isize, isize);
+
+    /// Sets the position of the pen, which is already resolved to be on the canvas.
+    fn set_position(&mut self, pos: (isize, isize));
+
+    /// `(height, width)` of the canvas, none if it has no edges.
+    fn size(canvas: &Self::CanvasType) -> Option<(usize, usize)>;
+
+    /// Paints or erases the pixel under the pen.
+    fn draw(&self, canvas: &mut Self::CanvasType, color: &Self::ColorType, mode: PenMode);
+
+    /// Keeps the error of the last movement, see [`Pen::take_error`].
+    fn set_error(&mut self, error: Option<OutOfBoundsError>);
+
+    /// Takes the error of the last movement, see [`Pen::take_error`].
+    fn take_error(&mut self) -> Option<OutOfBoundsError>;
+}
+
 impl<
+        'c,
         const H: usize,
         const W: usize,
-        P: PixelMutInterface + Default,
+        P: PixelMutInterface + PartialEq + Clone + Default,
         C: PixelCanvasMutInterface<H, W, P>,
-    > Pen<CanvasAttachedMarker<'_, H, W, P, C>>
+    > PenAttachment for CanvasAttachedMarker<'c, H, W, P, C>
+where
+    P::ColorType: Clone,
+{
+    fn position(&self) -> (isize, isize) {
+        let (row, column) = self.current_pos.expand();
+        (row as isize, column as isize)
+    }
+
+    fn set_position(&mut self, (row, column): (isize, isize)) {
+        self.current_pos = (row as usize, column as usize).into_pixel_strict_position();
+    }
+
+    fn size(_canvas: &Self::CanvasType) -> Option<(usize, usize)> {
+        Some((H, W))
+    }
+
+    fn draw(&self, canvas: &mut Self::CanvasType, color: &Self::ColorType, mode: PenMode) {
+        let mut pixel = canvas.table_mut().get_pixel_mut(self.current_pos);
+        match mode {
+            PenMode::Paint => {
+                pixel.update_color(color.clone());
+            }
+            PenMode::Erase => *pixel = P::default(),
+        }
+    }
+
+    fn set_error(&mut self, error: Option<OutOfBoundsError>) {
+        self.last_error = error;
+    }
+
+    fn take_error(&mut self) -> Option<OutOfBoundsError> {
+        self.last_error.take()
+    }
+}
+
+/// An [`UnboundedCanvas`] has no edges, so the pen never goes out of bounds.
+impl<'c> PenAttachment for UnboundedAttachedMarker<'c> {
+    fn position(&self) -> (isize, isize) {
+        self.current_pos
+    }
+
+    fn set_position(&mut self, pos: (isize, isize)) {
+        self.current_pos = pos;
+    }
+
+    fn size(_canvas: &Self::CanvasType) -> Option<(usize, usize)> {
+        None
+    }
+
+    fn draw(&self, canvas: &mut Self::CanvasType, color: &Self::ColorType, mode: PenMode) {
+        match mode {
+            PenMode::Paint => {
+                canvas.update_color_at(self.current_pos, *color);
+            }
+            PenMode::Erase => {
+                canvas.clear_pixel(self.current_pos);
+            }
+        }
+    }
+
+    fn set_error(&mut self, _error: Option<OutOfBoundsError>) {}
+
+    fn take_error(&mut self) -> Option<OutOfBoundsError> {
+        None
+    }
+}
+
+impl<'c, P> PenAttachment for DynAttachedMarker<'c, P>
+where
+    P: PixelMutInterface + Default + Clone + 'c,
+    P::ColorType: Clone,
 {
+    fn position(&self) -> (isize, isize) {
+        let (row, column) = self.current_pos;
+        (row as isize, column as isize)
+    }
+
+    fn set_position(&mut self, (row, column): (isize, isize)) {
+        self.current_pos = (row as usize, column as usize);
+    }
+
+    fn size(canvas: &Self::CanvasType) -> Option<(usize, usize)> {
+        Some((canvas.height(), canvas.width()))
+    }
+
+    fn draw(&self, canvas: &mut Self::CanvasType, color: &Self::ColorType, mode: PenMode) {
+        if let Some(pixel) = canvas.get_pixel_mut(self.current_pos) {
+            match mode {
+                PenMode::Paint => {
+                    pixel.update_color(color.clone());
+                }
+                PenMode::Erase => *pixel = P::default(),
+            }
+        }
+    }
+
+    fn set_error(&mut self, error: Option<OutOfBoundsError>) {
+        self.last_error = error;
+    }
+
+    fn take_error(&mut self) -> Option<OutOfBoundsError> {
+        self.last_error.take()
+    }
+}
+
+impl<M: PenAttachment> Pen<M> {
     #[must_use = "This function returns a new unattached pen."]
-    pub fn detach(self) -> Pen<CanvasUnattachedMarker<P::ColorType>> {
+    pub fn detach(self) -> Pen<CanvasUnattachedMarker<M::ColorType>> {
         Pen {
             canvas: (),
             color: self.color,
             drawing: self.drawing,
+            policy: self.policy,
+            stroke: self.stroke,
+            stroke_step: 0,
+            mode: self.mode,
             attachment: CanvasUnattachedMarker(PhantomData),
         }
     }
 
-    fn draw(&mut self) -> &mut Self
-    where
-        P: PartialEq + Clone + Default,
-        <P as PixelInterface>::ColorType: From<PixelColor> + Clone,
-    {
-        if self.drawing {
-            self.canvas
-                .table_mut()
-                .get_pixel_mut(self.attachment.current_pos)
-                .update_color(self.color.clone());
+    /// Jumps to the position, only the target pixel is drawn (if the pen is started).
+    fn jump(&mut self, pos: (isize, isize)) -> &mut Self {
+        self.attachment.set_position(pos);
+        self.draw()
+    }
+
+    /// Moves to the position in a straight line, drawing the way even if the pen is stopped.
+    fn draw_line_to(&mut self, pos: (isize, isize)) -> &mut Self {
+        let drawing = std::mem::replace(&mut self.drawing, true);
+        // The current pixel is already drawn if the pen was started.
+        let skip = usize::from(drawing);
+        for point in line(self.attachment.position(), pos).into_iter().skip(skip) {
+            self.attachment.set_position(point);
+            self.draw();
         }
+        self.drawing = drawing;
         self
     }
 
-    pub fn start(&mut self) -> &mut Self
-    where
-        P: PartialEq + Clone + Default,
-        <P as PixelInterface>::ColorType: From<PixelColor> + Clone,
-    {
+    /// Takes the error of the last movement that went out of bounds, if any.
+    ///
+    /// Only used with [`OutOfBoundsPolicy::Error`], where the pen stops moving at the edge.
+    pub fn take_error(&mut self) -> Option<OutOfBoundsError> {
+        self.attachment.take_error()
+    }
+
+    fn draw(&mut self) -> &mut Self {
+        if self.drawing && self.next_stroke_step() {
+            self.attachment
+                .draw(&mut self.canvas, &self.color, self.mode);
+        }
+        self
+    }
+
+    pub fn start(&mut self) -> &mut Self {
         self.drawing = true;
+        self.stroke_step = 0;
         self.draw()
     }
 
-    fn go_direction_once(&mut self, dir: Direction) -> &mut Self
-    where
-        P: PartialEq + Clone + Default,
-        <P as PixelInterface>::ColorType: From<PixelColor> + Clone,
-    {
-        self.attachment.current_pos = self.attachment.current_pos.bounding_direction(dir, 1);
-        self.draw()
+    /// Moves the pen a single step, returns `false` if it couldn't move because of an error.
+    fn go_direction_once(&mut self, dir: Direction) -> bool {
+        let (row, column) = self.attachment.position();
+        let (row_step, column_step) = dir.delta();
+        let target = (row + row_step, column + column_step);
+        let next = match M::size(&self.canvas) {
+            None => Some(target),
+            Some(size) if self.policy == OutOfBoundsPolicy::Clip => {
+                let (row, column) =
+                    clamp_in((target.0.max(0) as usize, target.1.max(0) as usize), size);
+                Some((row as isize, column as isize))
+            }
+            Some(size) => match self.policy.resolve_in(target, size) {
+                Ok(next) => next.map(|(row, column)| (row as isize, column as isize)),
+                Err(e) => {
+                    self.attachment.set_error(Some(e));
+                    return false;
+                }
+            },
+        };
+        if let Some(next) = next {
+            self.attachment.set_position(next);
+        }
+        self.draw();
+        true
     }
 
-    pub fn go_direction(&mut self, dir: Direction, how_many: usize) -> &mut Self
-    where
-        P: PartialEq + Clone + Default,
-        <P as PixelInterface>::ColorType: From<PixelColor> + Clone,
-    {
+    /// As same as [`Pen::go_direction`] but returns an error if the pen goes out of bounds.
+    ///
+    /// The pen stays at the last valid position in case of error.
+    pub fn try_go_direction(
+        &mut self,
+        dir: Direction,
+        how_many: usize,
+    ) -> Result<&mut Self, OutOfBoundsError> {
+        let policy = std::mem::replace(&mut self.policy, OutOfBoundsPolicy::Error);
+        self.attachment.set_error(None);
+        self.go_direction(dir, how_many);
+        self.policy = policy;
+
+        match self.attachment.take_error() {
+            Some(e) => Err(e),
+            None => Ok(self),
+        }
+    }
+
+    pub fn go_direction(&mut self, dir: Direction, how_many: usize) -> &mut Self {
         for _ in 0..how_many {
-            self.go_direction_once(dir);
+            if !self.go_direction_once(dir) {
+                break;
+            }
         }
         self
     }
 
-    pub fn up(&mut self, how_many: usize) -> &mut Self
-    where
-        P: PartialEq + Clone + Default,
-        <P as PixelInterface>::ColorType: From<PixelColor> + Clone,
-    {
+    pub fn up(&mut self, how_many: usize) -> &mut Self {
         self.go_direction(Direction::Up, how_many)
     }
 
-    pub fn down(&mut self, how_many: usize) -> &mut Self
-    where
-        P: PartialEq + Clone + Default,
-        <P as PixelInterface>::ColorType: From<PixelColor> + Clone,
-    {
+    pub fn down(&mut self, how_many: usize) -> &mut Self {
         self.go_direction(Direction::Down, how_many)
     }
 
-    pub fn left(&mut self, how_many: usize) -> &mut Self
-    where
-        P: PartialEq + Clone + Default,
-        <P as PixelInterface>::ColorType: From<PixelColor> + Clone,
-    {
+    pub fn left(&mut self, how_many: usize) -> &mut Self {
         self.go_direction(Direction::Left, how_many)
     }
 
-    pub fn right(&mut self, how_many: usize) -> &mut Self
-    where
-        P: PartialEq + Clone + Default,
-        <P as PixelInterface>::ColorType: From<PixelColor> + Clone,
-    {
+    pub fn right(&mut self, how_many: usize) -> &mut Self {
         self.go_direction(Direction::Right, how_many)
     }
 
-    pub fn up_right(&mut self, how_many: usize) -> &mut Self
-    where
-        P: PartialEq + Clone + Default,
-        <P as PixelInterface>::ColorType: From<PixelColor> + Clone,
-    {
+    pub fn up_right(&mut self, how_many: usize) -> &mut Self {
         self.go_direction(Direction::UpRight, how_many)
     }
 
-    pub fn down_right(&mut self, how_many: usize) -> &mut Self
-    where
-        P: PartialEq + Clone + Default,
-        <P as PixelInterface>::ColorType: From<PixelColor> + Clone,
-    {
+    pub fn down_right(&mut self, how_many: usize) -> &mut Self {
         self.go_direction(Direction::DownRight, how_many)
     }
 
-    pub fn down_left(&mut self, how_many: usize) -> &mut Self
-    where
-        P: PartialEq + Clone + Default,
-        <P as PixelInterface>::ColorType: From<PixelColor> + Clone,
-    {
+    pub fn down_left(&mut self, how_many: usize) -> &mut Self {
         self.go_direction(Direction::DownLeft, how_many)
     }
 
-    pub fn up_left(&mut self, how_many: usize) -> &mut Self
-    where
-        P: PartialEq + Clone + Default,
-        <P as PixelInterface>::ColorType: From<PixelColor> + Clone,
-    {
+    pub fn up_left(&mut self, how_many: usize) -> &mut Self {
         self.go_direction(Direction::UpLeft, how_many)
     }
 
     pub fn branch<B: FnMut(&mut Self) -> &mut Self>(&mut self, mut b: B) -> &mut Self {
-        let pos_before_branching = self.attachment.current_pos;
+        let pos_before_branching = self.attachment.position();
         b(self);
-        self.attachment.current_pos = pos_before_branching;
+        self.attachment.set_position(pos_before_branching);
         self
     }
+
+    /// Runs the commands of a [`PenPath`] one by one.
+    pub fn follow(&mut self, path: &PenPath) -> &mut Self {
+        for command in path.commands() {
+            match command {
+                PenCommand::Start => self.start(),
+                PenCommand::Stop => self.stop(),
+                PenCommand::Move(direction, count) => self.go_direction(*direction, *count),
+                PenCommand::Branch(path) => self.branch(|pen| pen.follow(path)),
+            };
+        }
+        self
+    }
+}
+
+impl Pen<UnboundedAttachedMarker<'_>> {
+    /// Current signed `(row, column)` of the pen.
+    pub fn current_position(&self) -> (isize, isize) {
+        self.attachment.current_pos
+    }
+
+    /// Jumps to the position, only the target pixel is drawn (if the pen is started).
+    pub fn goto(&mut self, pos: (isize, isize)) -> &mut Self {
+        self.jump(pos)
+    }
+
+    /// Moves to the position in a straight line, drawing the way even if the pen is stopped.
+    pub fn move_to_drawing(&mut self, pos: (isize, isize)) -> &mut Self {
+        self.draw_line_to(pos)
+    }
+}
+
+impl<
+        const H: usize,
+        const W: usize,
+        P: PixelMutInterface + PartialEq + Clone + Default,
+        C: PixelCanvasMutInterface<H, W, P>,
+    > Pen<CanvasAttachedMarker<'_, H, W, P, C>>
+where
+    P::ColorType: Clone,
+{
+    /// Current position of the pen.
+    pub fn current_position(&self) -> PixelStrictPosition<H, W> {
+        self.attachment.current_pos
+    }
+
+    /// Jumps to the position, only the target pixel is drawn (if the pen is started).
+    pub fn goto(&mut self, pos: impl IntoPixelStrictPosition<H, W>) -> &mut Self {
+        let (row, column) = pos.into_pixel_strict_position().expand();
+        self.jump((row as isize, column as isize))
+    }
+
+    /// Moves to the position in a straight line, drawing the way even if the pen is stopped.
+    pub fn move_to_drawing(&mut self, pos: impl IntoPixelStrictPosition<H, W>) -> &mut Self {
+        let (row, column) = pos.into_pixel_strict_position().expand();
+        self.draw_line_to((row as isize, column as isize))
+    }
+}
+
+impl<P> Pen<DynAttachedMarker<'_, P>>
+where
+    P: PixelMutInterface + Default + Clone,
+    P::ColorType: Clone,
+{
+    /// Current `(row, column)` of the pen.
+    pub fn current_position(&self) -> (usize, usize) {
+        self.attachment.current_pos
+    }
+
+    /// Jumps to the position, only the target pixel is drawn (if the pen is started).
+    ///
+    /// Positions out of bounds are clamped to the nearest edge.
+    pub fn goto(&mut self, pos: (usize, usize)) -> &mut Self {
+        let (row, column) = clamp_in(pos, (self.canvas.height(), self.canvas.width()));
+        self.jump((row as isize, column as isize))
+    }
+
+    /// Moves to the position in a straight line, drawing the way even if the pen is stopped.
+    ///
+    /// Positions out of bounds are clamped to the nearest edge.
+    pub fn move_to_drawing(&mut self, pos: (usize, usize)) -> &mut Self {
+        let (row, column) = clamp_in(pos, (self.canvas.height(), self.canvas.width()));
+        self.draw_line_to((row as isize, column as isize))
+    }
+}
+
+/// A single step of a [`PenPath`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PenCommand {
+    /// Same as [`Pen::start`].
+    Start,
+    /// Same as [`Pen::stop`].
+    Stop,
+    /// Same as [`Pen::go_direction`].
+    Move(Direction, usize),
+    /// Same as [`Pen::branch`], following the inner path.
+    Branch(PenPath),
+}
+
+/**
+A list of [`PenCommand`]s, which can be written as text and followed by a pen later.
+
+Commands are separated by whitespace (or commas):
+- `U`, `D`, `L`, `R`, `UR`, `UL`, `DR` and `DL` followed by an optional count (default `1`)
+  move the pen, e.g. `R3`.
+- `S` starts and `X` stops drawing.
+- `B(...)` runs the commands inside as a branch.
+
+## Example
+```rust
+# use pixelart::prelude::*;
+use pixelart::pixels::canvas::pen::PenPath;
+
+let path: PenPath = "S U2 B(UL2) UR2".parse().unwrap();
+
+let mut canvas = PixelCanvas::<5>::default();
+canvas
+    .attach_new_pen(CYAN, StrictPositions::BottomCenter)
+    .follow(&path);
+assert_eq!(canvas.color_at(TOP_LEFT), CYAN);
+assert_eq!(canvas.color_at(TOP_RIGHT), CYAN);
+```
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct PenPath {
+    commands: Vec<PenCommand>,
+}
+
+impl PenPath {
+    pub fn new(commands: impl IntoIterator<Item = PenCommand>) -> Self {
+        Self {
+            commands: commands.into_iter().collect(),
+        }
+    }
+
+    /// Parses a path from its text form, see [`PenPath`].
+    pub fn parse(text: &str) -> Result<Self, ParsePenPathError> {
+        let mut chars = text.char_indices().peekable();
+        Ok(Self::new(parse_commands(&mut chars, false)?))
+    }
+
+    pub fn commands(&self) -> &[PenCommand] {
+        &self.commands
+    }
+
+    pub fn push(&mut self, command: PenCommand) {
+        self.commands.push(command);
+    }
+}
+
+impl FromStr for PenPath {
+    type Err = ParsePenPathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Writes the path back in its text form, which [`PenPath::parse`] accepts.
+impl Display for PenPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, command) in self.commands.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            match command {
+                PenCommand::Start => write!(f, "S")?,
+                PenCommand::Stop => write!(f, "X")?,
+                PenCommand::Move(direction, count) => {
+                    write!(f, "{}{count}", direction_name(*direction))?
+                }
+                PenCommand::Branch(path) => write!(f, "B({path})")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Errors of [`PenPath::parse`], positions are byte offsets in the text.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParsePenPathError {
+    #[error("Unknown pen command {name:?} at {at}.")]
+    UnknownCommand { name: String, at: usize },
+
+    #[error("Unexpected {character:?} at {at}.")]
+    UnexpectedCharacter { character: char, at: usize },
+
+    #[error("Count at {at} is too large.")]
+    InvalidCount { at: usize },
+
+    #[error("A branch is not closed.")]
+    UnclosedBranch,
+}
+
+fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Up => "U",
+        Direction::UpRight => "UR",
+        Direction::Right => "R",
+        Direction::DownRight => "DR",
+        Direction::Down => "D",
+        Direction::DownLeft => "DL",
+        Direction::Left => "L",
+        Direction::UpLeft => "UL",
+    }
+}
+
+fn parse_commands(
+    chars: &mut Peekable<CharIndices>,
+    in_branch: bool,
+) -> Result<Vec<PenCommand>, ParsePenPathError> {
+    let mut commands = Vec::new();
+    loop {
+        let Some(&(at, character)) = chars.peek() else {
+            return match in_branch {
+                true => Err(ParsePenPathError::UnclosedBranch),
+                false => Ok(commands),
+            };
+        };
+
+        if character.is_whitespace() || character == ',' {
+            chars.next();
+            continue;
+        }
+        if character == ')' && in_branch {
+            chars.next();
+            return Ok(commands);
+        }
+        if !character.is_ascii_alphabetic() {
+            return Err(ParsePenPathError::UnexpectedCharacter { character, at });
+        }
+
+        let mut name = String::new();
+        while let Some((_, character)) = chars.next_if(|(_, c)| c.is_ascii_alphabetic()) {
+            name.push(character.to_ascii_uppercase());
+        }
+
+        let command = match name.as_str() {
+            "S" => PenCommand::Start,
+            "X" => PenCommand::Stop,
+            "B" => match chars.next() {
+                Some((_, '(')) => PenCommand::Branch(PenPath::new(parse_commands(chars, true)?)),
+                Some((at, character)) => {
+                    return Err(ParsePenPathError::UnexpectedCharacter { character, at })
+                }
+                None => return Err(ParsePenPathError::UnclosedBranch),
+            },
+            _ => {
+                let direction = ALL_DIRECTIONS
+                    .into_iter()
+                    .find(|direction| direction_name(*direction) == name)
+                    .ok_or(ParsePenPathError::UnknownCommand { name, at })?;
+                PenCommand::Move(direction, parse_count(chars)?)
+            }
+        };
+        commands.push(command);
+    }
+}
+
+/// Digits right after a move command, `1` if there's none.
+fn parse_count(chars: &mut Peekable<CharIndices>) -> Result<usize, ParsePenPathError> {
+    let Some(&(at, _)) = chars.peek() else {
+        return Ok(1);
+    };
+
+    let mut digits = String::new();
+    while let Some((_, digit)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
+        digits.push(digit);
+    }
+    match digits.is_empty() {
+        true => Ok(1),
+        false => digits
+            .parse()
+            .map_err(|_| ParsePenPathError::InvalidCount { at }),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::pixels::color::PixelColorExt;
+    use crate::pos;
     use crate::{
         pixels::canvas::{SharedMutPixelCanvasExt, SharedPixelCanvasExt},
-        prelude::{PixelCanvas, StrictPositions},
+        prelude::{MaybePixelCanvas, PixelCanvas, StrictPositions},
     };
 
     use super::*;
@@ -304,6 +924,37 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn test_pen_policy() {
+        let mut canvas = PixelCanvas::<5>::default();
+
+        PixelPen::new(PixelColor::RED)
+            .with_policy(OutOfBoundsPolicy::Wrap)
+            .attach(&mut canvas, StrictPositions::TopRight)
+            .start()
+            .right(1);
+        assert_eq!(canvas.color_at(StrictPositions::TopLeft), PixelColor::RED);
+
+        let mut pen = PixelPen::new(PixelColor::BLUE).attach(&mut canvas, StrictPositions::TopLeft);
+        assert!(pen.start().try_go_direction(Direction::Up, 1).is_err());
+        assert!(pen.try_go_direction(Direction::Down, 4).is_ok());
+        assert!(pen.try_go_direction(Direction::Down, 1).is_err());
+        assert_eq!(
+            canvas.color_at(StrictPositions::BottomLeft),
+            PixelColor::BLUE
+        );
+
+        // An error which is not taken doesn't stop later moves.
+        let mut pen = PixelPen::new(PixelColor::GREEN)
+            .with_policy(OutOfBoundsPolicy::Error)
+            .attach(&mut canvas, StrictPositions::TopLeft);
+        pen.start().up(1).right(2).right(2);
+        assert_eq!(
+            canvas.color_at(StrictPositions::TopRight),
+            PixelColor::GREEN
+        );
+    }
+
     #[test]
     fn test_pen_branching() {
         let mut canvas = PixelCanvas::<5>::default();
@@ -321,4 +972,117 @@ mod tests {
             .save("arts/pen_0.png")
             .unwrap();
     }
+
+    #[test]
+    fn test_pen_stroke() {
+        let mut canvas = PixelCanvas::<1, 8>::default();
+        PixelPen::new(PixelColor::RED)
+            .with_stroke(StrokeStyle::Dashed { on: 2, off: 1 })
+            .attach(&mut canvas, StrictPositions::TopLeft)
+            .start()
+            .right(7);
+        let drawn: Vec<_> = canvas
+            .iter_pixels()
+            .map(|pixel| pixel.color() == &PixelColor::RED)
+            .collect();
+        assert_eq!(
+            drawn,
+            vec![true, true, false, true, true, false, true, true]
+        );
+
+        let mut canvas = PixelCanvas::<5>::default();
+        let mut pen = PixelPen::new(PixelColor::BLUE)
+            .with_stroke(StrokeStyle::Dotted)
+            .attach(&mut canvas, StrictPositions::TopLeft);
+        pen.start().down(2).stop().right(1);
+        pen.start().right(1);
+        assert_eq!(canvas.color_at(StrictPositions::TopLeft), PixelColor::BLUE);
+        assert_eq!(canvas.color_at(pos!(1, 0; 5, 5)), PixelColor::WHITE);
+        assert_eq!(canvas.color_at(pos!(2, 0; 5, 5)), PixelColor::BLUE);
+        assert_eq!(canvas.color_at(pos!(2, 1; 5, 5)), PixelColor::BLUE);
+        assert_eq!(canvas.color_at(pos!(2, 2; 5, 5)), PixelColor::WHITE);
+    }
+
+    #[test]
+    fn test_pen_path() {
+        let path = PenPath::parse("s r2, dr2 B(u2 X l) dl").unwrap();
+        assert_eq!(path.to_string(), "S R2 DR2 B(U2 X L1) DL1");
+        assert_eq!(path.to_string().parse(), Ok(path.clone()));
+        assert_eq!(path.commands().len(), 5);
+
+        let mut canvas = PixelCanvas::<5>::default();
+        canvas
+            .attach_new_pen(PixelColor::RED, StrictPositions::TopLeft)
+            .follow(&path);
+        assert_eq!(canvas.color_at(pos!(2, 4; 5, 5)), PixelColor::RED);
+        assert_eq!(canvas.color_at(pos!(0, 4; 5, 5)), PixelColor::RED);
+        // The branch stopped drawing, but only the position is restored afterwards.
+        assert_eq!(canvas.color_at(pos!(0, 3; 5, 5)), PixelColor::WHITE);
+        assert_eq!(canvas.color_at(pos!(3, 3; 5, 5)), PixelColor::WHITE);
+
+        assert_eq!(
+            PenPath::parse("R2 Q"),
+            Err(ParsePenPathError::UnknownCommand {
+                name: "Q".to_string(),
+                at: 3
+            })
+        );
+        assert_eq!(
+            PenPath::parse("B(R2"),
+            Err(ParsePenPathError::UnclosedBranch)
+        );
+        assert_eq!(
+            PenPath::parse("R2)"),
+            Err(ParsePenPathError::UnexpectedCharacter {
+                character: ')',
+                at: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_eraser() {
+        let mut canvas = MaybePixelCanvas::<3>::default();
+        canvas.fill(PixelColor::RED);
+
+        let mut eraser = PixelPen::eraser().attach(&mut canvas, StrictPositions::TopLeft);
+        eraser.start().right(1).stop().right(1);
+        assert_eq!(canvas.color_at(StrictPositions::TopLeft), None);
+        assert_eq!(canvas.color_at(pos!(0, 1; 3, 3)), None);
+        assert_eq!(
+            canvas.color_at(StrictPositions::TopRight),
+            Some(PixelColor::RED)
+        );
+
+        let mut canvas = PixelCanvas::<3>::from_fill_color(PixelColor::RED);
+        PixelPen::new(PixelColor::BLUE)
+            .with_mode(PenMode::Erase)
+            .attach(&mut canvas, StrictPositions::TopLeft)
+            .start();
+        assert_eq!(
+            canvas.color_at(StrictPositions::TopLeft),
+            PixelColor::default()
+        );
+    }
+
+    #[test]
+    fn test_pen_goto() {
+        let mut canvas = PixelCanvas::<5>::default();
+        let mut pen = PixelPen::new(PixelColor::RED).attach(&mut canvas, StrictPositions::TopLeft);
+        pen.goto(StrictPositions::Center);
+        assert_eq!(pen.current_position(), pos!(2, 2; 5, 5));
+
+        pen.move_to_drawing(StrictPositions::BottomRight);
+        assert!(!pen.drawing);
+        pen.start().goto(StrictPositions::TopRight).stop();
+        assert_eq!(canvas.color_at(StrictPositions::Center), PixelColor::RED);
+        assert_eq!(canvas.color_at(pos!(3, 3; 5, 5)), PixelColor::RED);
+        assert_eq!(
+            canvas.color_at(StrictPositions::BottomRight),
+            PixelColor::RED
+        );
+        assert_eq!(canvas.color_at(StrictPositions::TopRight), PixelColor::RED);
+        assert_eq!(canvas.color_at(pos!(1, 4; 5, 5)), PixelColor::WHITE);
+        assert_eq!(canvas.filled_len(), 4);
+    }
 }
@@ -0,0 +1,248 @@
+//! A set of positions picked from a canvas, see [`SharedPixelCanvasExt::find_pixels`].
+//!
+//! [`SharedPixelCanvasExt::find_pixels`]: super::SharedPixelCanvasExt::find_pixels
+
+use std::collections::BTreeSet;
+
+use crate::pixels::{
+    color::PixelColor,
+    position::{
+        Connectivity, IntoPixelStrictPosition, PixelStrictPosition, PixelStrictPositionInterface,
+    },
+    PixelInterface, PixelMutInterface,
+};
+
+use super::{MaybePixelCanvas, SharedMutPixelCanvasExt, SharedPixelCanvasExt};
+
+/// Positions of a `H` * `W` canvas, ordered from top left, row by row.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Selection<const H: usize, const W: usize> {
+    positions: BTreeSet<PixelStrictPosition<H, W>>,
+}
+
+impl<const H: usize, const W: usize> Selection<H, W> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn insert(&mut self, pos: impl IntoPixelStrictPosition<H, W>) -> bool {
+        self.positions.insert(pos.into_pixel_strict_position())
+    }
+
+    pub fn remove(&mut self, pos: impl IntoPixelStrictPosition<H, W>) -> bool {
+        self.positions.remove(&pos.into_pixel_strict_position())
+    }
+
+    pub fn contains(&self, pos: impl IntoPixelStrictPosition<H, W>) -> bool {
+        self.positions.contains(&pos.into_pixel_strict_position())
+    }
+
+    /// Selected positions, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = PixelStrictPosition<H, W>> + '_ {
+        self.positions.iter().copied()
+    }
+
+    /// The first selected position in the topmost row.
+    pub fn topmost(&self) -> Option<PixelStrictPosition<H, W>> {
+        self.positions.first().copied()
+    }
+
+    /// The last selected position in the bottommost row.
+    pub fn bottommost(&self) -> Option<PixelStrictPosition<H, W>> {
+        self.positions.last().copied()
+    }
+
+    /// The topmost selected position in the leftmost column.
+    pub fn leftmost(&self) -> Option<PixelStrictPosition<H, W>> {
+        self.iter().min_by_key(|pos| (pos.column(), pos.row()))
+    }
+
+    /// The bottommost selected position in the rightmost column.
+    pub fn rightmost(&self) -> Option<PixelStrictPosition<H, W>> {
+        self.iter().max_by_key(|pos| (pos.column(), pos.row()))
+    }
+
+    /// Top left and bottom right corners of the smallest rectangle containing the selection.
+    pub fn bounds(&self) -> Option<(PixelStrictPosition<H, W>, PixelStrictPosition<H, W>)> {
+        let top = self.topmost()?.row();
+        let bottom = self.bottommost()?.row();
+        let left = self.leftmost()?.column();
+        let right = self.rightmost()?.column();
+
+        Some((
+            (top, left).into_pixel_strict_position(),
+            (bottom, right).into_pixel_strict_position(),
+        ))
+    }
+
+    /// Positions selected in either this or the other selection.
+    pub fn union(&self, other: &Self) -> Self {
+        self.positions.union(&other.positions).copied().collect()
+    }
+
+    /// Positions selected in both this and the other selection.
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.positions
+            .intersection(&other.positions)
+            .copied()
+            .collect()
+    }
+
+    /// Positions which are not selected in this selection.
+    pub fn invert(&self) -> Self {
+        all_positions::<H, W>()
+            .filter(|pos| !self.positions.contains(pos))
+            .collect()
+    }
+
+    /// Updates color of all selected pixels on the canvas.
+    pub fn fill_on<P, C>(&self, canvas: &mut C, color: impl Into<P::ColorType>)
+    where
+        P: PixelMutInterface + Default + PartialEq + Clone,
+        P::ColorType: Clone,
+        C: SharedMutPixelCanvasExt<H, W, P>,
+    {
+        let color = color.into();
+        for pos in self.iter() {
+            canvas.update_color_at(pos, color.clone());
+        }
+    }
+
+    /// Paints the selected pixels by tiling the `pattern` from the top left of the canvas,
+    /// transparent pixels of the pattern leave the canvas untouched.
+    pub fn fill_pattern_on<P, C, const PH: usize, const PW: usize>(
+        &self,
+        canvas: &mut C,
+        pattern: &MaybePixelCanvas<PH, PW>,
+    ) where
+        P: PixelMutInterface + Default + PartialEq + Clone,
+        P::ColorType: From<PixelColor>,
+        C: SharedMutPixelCanvasExt<H, W, P>,
+    {
+        if PH == 0 || PW == 0 {
+            return;
+        }
+
+        for pos in self.iter() {
+            let (row, column) = pos.expand();
+            if let Some(color) = pattern.get_pixel((row % PH, column % PW)).color {
+                canvas.update_color_at(pos, color);
+            }
+        }
+    }
+
+    /// Resets all selected pixels on the canvas to the default color.
+    pub fn clear_on<P, C>(&self, canvas: &mut C)
+    where
+        P: PixelMutInterface + Default + PartialEq + Clone,
+        P::ColorType: Clone + Default,
+        C: SharedMutPixelCanvasExt<H, W, P>,
+    {
+        self.fill_on(canvas, P::ColorType::default());
+    }
+
+    /// Copies the selected pixels of `source` into a new [`MaybePixelCanvas`],
+    /// anything else is left transparent.
+    pub fn copy_to_canvas<P, C>(&self, source: &C) -> MaybePixelCanvas<H, W>
+    where
+        P: PixelInterface + Default,
+        P::ColorType: Clone + Into<Option<PixelColor>>,
+        C: SharedPixelCanvasExt<H, W, P>,
+    {
+        let mut canvas = MaybePixelCanvas::<H, W>::default();
+        for pos in self.iter() {
+            canvas.update_color_at(pos, source.color_at(pos).into());
+        }
+        canvas
+    }
+}
+
+impl<const H: usize, const W: usize> FromIterator<PixelStrictPosition<H, W>> for Selection<H, W> {
+    fn from_iter<T: IntoIterator<Item = PixelStrictPosition<H, W>>>(iter: T) -> Self {
+        Self {
+            positions: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<const H: usize, const W: usize> IntoIterator for Selection<H, W> {
+    type Item = PixelStrictPosition<H, W>;
+    type IntoIter = std::collections::btree_set::IntoIter<PixelStrictPosition<H, W>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.positions.into_iter()
+    }
+}
+
+/// All positions of a `H` * `W` canvas, row by row.
+pub(crate) fn all_positions<const H: usize, const W: usize>(
+) -> impl Iterator<Item = PixelStrictPosition<H, W>> {
+    (0..H).flat_map(|row| (0..W).map(move |column| (row, column).into_pixel_strict_position()))
+}
+
+/// Positions connected to `start` (based on the [`Connectivity`]), where `accept` returns
+/// `true` for all of them (including `start` itself).
+pub(crate) fn flood<const H: usize, const W: usize>(
+    start: PixelStrictPosition<H, W>,
+    connectivity: Connectivity,
+    mut accept: impl FnMut(PixelStrictPosition<H, W>) -> bool,
+) -> Selection<H, W> {
+    let mut selection = Selection::new();
+    let mut visited = BTreeSet::new();
+    let mut stack = vec![start];
+
+    while let Some(pos) = stack.pop() {
+        if !visited.insert(pos) || !accept(pos) {
+            continue;
+        }
+
+        selection.insert(pos);
+        stack.extend(pos.neighbors(connectivity));
+    }
+    selection
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_select_connected() {
+        let mut canvas = PixelCanvas::<3>::default();
+        canvas.update_color_at(pos!(0, 1; 3, 3), RED);
+        canvas.update_color_at(pos!(1, 1; 3, 3), RED);
+        canvas.update_color_at(BOTTOM_RIGHT, RED);
+
+        let selection = canvas.select_connected(TOP_CENTER);
+        assert_eq!(selection.len(), 2);
+        assert!(!selection.contains(BOTTOM_RIGHT));
+        assert_eq!(selection.invert().len(), 7);
+
+        let copied = selection.copy_to_canvas(&canvas);
+        assert_eq!(copied.color_at(CENTER), Some(RED));
+        assert_eq!(copied.color_at(BOTTOM_RIGHT), None);
+
+        let mut stripes = MaybePixelCanvas::<1, 2>::default();
+        stripes.update_color_at(TOP_LEFT, BLUE);
+        let mut striped = canvas.clone();
+        selection.invert().fill_pattern_on(&mut striped, &stripes);
+        assert_eq!(striped.color_at(TOP_LEFT), BLUE);
+        assert_eq!(striped.color_at(TOP_RIGHT), BLUE);
+        assert_eq!(striped.color_at(LEFT_CENTER), BLUE);
+        assert_eq!(striped.color_at(pos!(2, 1; 3, 3)), WHITE);
+        assert_eq!(striped.color_at(BOTTOM_RIGHT), BLUE);
+        assert_eq!(striped.color_at(CENTER), RED);
+
+        selection.clear_on(&mut canvas);
+        assert_eq!(canvas.color_at(CENTER), WHITE);
+        assert_eq!(canvas.color_at(BOTTOM_RIGHT), RED);
+    }
+}
@@ -0,0 +1,351 @@
+//! Serde support for tables, canvases and layers (`serde` feature).
+//!
+//! Tables are stored sparsely: the dimensions plus only the real items (and tags),
+//! so a mostly empty canvas takes almost no space as JSON or RON.
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+use pixelart_table_abs::IllusionBackend;
+
+use crate::{
+    animation::subpixel::{SubpixelOffset, Supersample},
+    pixels::{maybe::MaybePixel, PixelInterface},
+};
+
+use super::{
+    layered::{BlendMode, LayerData, LayerOffset, LayeredCanvas},
+    table::PixelTable,
+    PixelCanvas,
+};
+
+#[derive(Serialize, Deserialize)]
+struct TableRepr<P> {
+    height: usize,
+    width: usize,
+    pixels: Vec<((usize, usize), P)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<((usize, usize), u16)>,
+    /// Whether the table uses [`IllusionBackend::Dense`], sparse otherwise.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    dense: bool,
+}
+
+impl<const H: usize, const W: usize, P> Serialize for PixelTable<H, W, P>
+where
+    P: PixelInterface + Default + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut pixels: Vec<_> = self.real_items().collect();
+        // Real items are kept in hash maps, sorting makes the output stable.
+        pixels.sort_unstable_by_key(|(index, _)| *index);
+
+        TableRepr {
+            height: H,
+            width: W,
+            pixels,
+            tags: self.tags.iter().collect(),
+            dense: self.backend() == IllusionBackend::Dense,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, const H: usize, const W: usize, P> Deserialize<'de> for PixelTable<H, W, P>
+where
+    P: PixelInterface + Default + Clone + PartialEq + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = TableRepr::<P>::deserialize(deserializer)?;
+        if (repr.height, repr.width) != (H, W) {
+            return Err(D::Error::custom(format!(
+                "expected a ({H}, {W}) table, but it's ({}, {})",
+                repr.height, repr.width
+            )));
+        }
+
+        let mut table = PixelTable::<H, W, P>::with_backend(if repr.dense {
+            IllusionBackend::Dense
+        } else {
+            IllusionBackend::Sparse
+        });
+        for ((row, column), pixel) in repr.pixels {
+            if !table
+                .inner
+                .try_modify((row, column), |item| *item.get_mut() = pixel)
+            {
+                return Err(D::Error::custom(format!(
+                    "pixel ({row}, {column}) is out of the table"
+                )));
+            }
+        }
+        for (index, tag) in repr.tags {
+            table.tags.set(index, Some(tag));
+        }
+        Ok(table)
+    }
+}
+
+impl<const H: usize, const W: usize, P> Serialize for PixelCanvas<H, W, P>
+where
+    P: PixelInterface + Default + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.table.serialize(serializer)
+    }
+}
+
+impl<'de, const H: usize, const W: usize, P> Deserialize<'de> for PixelCanvas<H, W, P>
+where
+    P: PixelInterface + Default + Clone + PartialEq + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self {
+            table: PixelTable::deserialize(deserializer)?,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct LayerRepr<T, C> {
+    layer_tag: T,
+    /// Named after the old unsigned drawing position, to keep reading older files.
+    drawing_position: (isize, isize),
+    #[serde(default, skip_serializing_if = "SubpixelOffset::is_zero")]
+    subpixel: SubpixelOffset,
+    canvas: C,
+    #[serde(default = "opaque", skip_serializing_if = "is_opaque")]
+    opacity: u8,
+    #[serde(default, skip_serializing_if = "is_normal")]
+    blend_mode: BlendMode,
+    #[serde(default = "visible", skip_serializing_if = "is_visible")]
+    visible: bool,
+}
+
+fn opaque() -> u8 {
+    u8::MAX
+}
+
+fn is_opaque(opacity: &u8) -> bool {
+    *opacity == u8::MAX
+}
+
+fn is_normal(blend_mode: &BlendMode) -> bool {
+    *blend_mode == BlendMode::Normal
+}
+
+fn visible() -> bool {
+    true
+}
+
+fn is_visible(visible: &bool) -> bool {
+    *visible
+}
+
+impl<const H: usize, const W: usize> Serialize for LayerData<H, W> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        LayerRepr {
+            layer_tag: self.layer_tag(),
+            drawing_position: (self.offset.row, self.offset.column),
+            subpixel: self.offset.subpixel,
+            canvas: &self.canvas,
+            opacity: self.opacity,
+            blend_mode: self.blend_mode,
+            visible: self.visible,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, const H: usize, const W: usize> Deserialize<'de> for LayerData<H, W> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr =
+            LayerRepr::<Option<String>, PixelCanvas<H, W, MaybePixel>>::deserialize(deserializer)?;
+        Ok(LayerData::new(repr.layer_tag, repr.canvas)
+            .with_offset(LayerOffset {
+                subpixel: repr.subpixel,
+                ..repr.drawing_position.into()
+            })
+            .with_opacity(repr.opacity)
+            .with_blend_mode(repr.blend_mode)
+            .with_visible(repr.visible))
+    }
+}
+
+/// Version of the project files written by [`LayeredCanvas::save_project`].
+const PROJECT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct ProjectRepr<B, L> {
+    version: u32,
+    base_layer: B,
+    top_layers: Vec<L>,
+}
+
+impl<const H: usize, const W: usize, P> Serialize for LayeredCanvas<H, W, P>
+where
+    P: PixelInterface + Default + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ProjectRepr {
+            version: PROJECT_VERSION,
+            base_layer: &self.base_layer,
+            top_layers: self.top_layers.iter().collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, const H: usize, const W: usize, P> Deserialize<'de> for LayeredCanvas<H, W, P>
+where
+    P: PixelInterface + Default + Clone + PartialEq + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ProjectRepr::<PixelCanvas<H, W, P>, LayerData<H, W>>::deserialize(deserializer)?;
+        if repr.version > PROJECT_VERSION {
+            return Err(D::Error::custom(format!(
+                "project version {} is newer than the supported {PROJECT_VERSION}",
+                repr.version
+            )));
+        }
+
+        let mut layered = LayeredCanvas {
+            base_layer: repr.base_layer,
+            top_layers: Vec::with_capacity(repr.top_layers.len()),
+            supersample: Supersample::default(),
+        };
+        for layer in repr.top_layers {
+            layered.new_layer(layer).map_err(D::Error::custom)?;
+        }
+        Ok(layered)
+    }
+}
+
+/// Errors of saving and loading project files.
+#[derive(Debug, Error)]
+pub enum ProjectError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid project file: {0}")]
+    Format(#[from] serde_json::Error),
+}
+
+impl<const H: usize, const W: usize, P> LayeredCanvas<H, W, P>
+where
+    P: PixelInterface + Default,
+{
+    /// Saves the base layer and all top layers (with their tags, offsets, ...) as a JSON
+    /// project file, which can be opened again using [`Self::load_project`].
+    pub fn save_project(&self, path: impl AsRef<Path>) -> Result<(), ProjectError>
+    where
+        P: Serialize,
+    {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, self)?;
+        // Dropping the writer would flush it too, but without reporting errors.
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Loads a project file saved using [`Self::save_project`].
+    ///
+    /// Fails if the project is of another size than `H` * `W`.
+    pub fn load_project(path: impl AsRef<Path>) -> Result<Self, ProjectError>
+    where
+        P: Clone + PartialEq + for<'de> Deserialize<'de>,
+    {
+        Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_serde_round_trip() {
+        let mut canvas = MaybePixelCanvas::<3, 4>::default();
+        canvas.update_color_at(pos!(1, 2; 3, 4), RED);
+        canvas.update_color_at(BOTTOM_RIGHT, BLUE);
+        canvas.set_tag(TOP_LEFT, Some(9));
+
+        let json = serde_json::to_string(&canvas).unwrap();
+        assert_eq!(
+            json,
+            r#"{"height":3,"width":4,"pixels":[[[1,2],{"color":{"r":255,"g":0,"b":0}}],[[2,3],{"color":{"r":0,"g":0,"b":255}}]],"tags":[[[0,0],9]]}"#
+        );
+
+        let back: MaybePixelCanvas<3, 4> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.to_runs(), canvas.to_runs());
+        assert_eq!(back.tag_at(TOP_LEFT), Some(9));
+        assert!(serde_json::from_str::<MaybePixelCanvas<4>>(&json).is_err());
+
+        let layer = LayerData::new("hero".to_string(), canvas).with_offset((-0.5, 2.0));
+        let back: LayerData<3, 4> =
+            serde_json::from_str(&serde_json::to_string(&layer).unwrap()).unwrap();
+        assert_eq!(back.layer_tag().map(String::as_str), Some("hero"));
+        assert_eq!(back.offset, layer.offset);
+        assert_eq!(back.canvas.to_runs(), layer.canvas.to_runs());
+
+        let dense = MaybePixelCanvas::<3, 4>::dense();
+        let json = serde_json::to_string(&dense).unwrap();
+        assert!(json.ends_with(r#""dense":true}"#));
+        let back: MaybePixelCanvas<3, 4> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.backend(), IllusionBackend::Dense);
+    }
+
+    #[test]
+    fn test_project_round_trip() {
+        let mut layered = LayeredCanvas::<4>::default();
+        layered.base_layer_mut().fill(BLACK);
+        layered
+            .new_layer(
+                LayerData::build_new_without_tag(|canvas| {
+                    canvas.update_color_at(TOP_LEFT, RED);
+                })
+                .with_layer_tag("sprite")
+                .with_offset((-1, 2))
+                .with_opacity(100)
+                .with_visible(false),
+            )
+            .unwrap();
+        layered
+            .new_layer(LayerData::default().with_layer_tag("empty"))
+            .unwrap();
+
+        std::fs::create_dir_all("arts/test").unwrap();
+        let path = "arts/test/project.json";
+        layered.save_project(path).unwrap();
+
+        let loaded = LayeredCanvas::<4>::load_project(path).unwrap();
+        assert_eq!(loaded.layer_count(), 2);
+        assert_eq!(
+            loaded.base_layer().to_runs(),
+            layered.base_layer().to_runs()
+        );
+        let sprite = loaded.top_layer("sprite").unwrap();
+        assert_eq!(sprite.offset, (-1, 2).into());
+        assert_eq!(sprite.opacity, 100);
+        assert!(!sprite.visible);
+        assert_eq!(sprite.canvas.color_at(TOP_LEFT), Some(RED));
+        assert_eq!(loaded.layer_index("empty"), Some(1));
+
+        assert!(matches!(
+            LayeredCanvas::<5>::load_project(path),
+            Err(ProjectError::Format(_))
+        ));
+        assert!(matches!(
+            LayeredCanvas::<4>::load_project("arts/test/missing.json"),
+            Err(ProjectError::Io(_))
+        ));
+    }
+}
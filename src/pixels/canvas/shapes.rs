@@ -0,0 +1,179 @@
+//! Rasterization of basic shapes into pixel positions, used by shape drawing methods of
+//! [`SharedMutPixelCanvasExt`](super::SharedMutPixelCanvasExt).
+//!
+//! Positions are signed `(row, column)`s, so shapes can be partially outside of a canvas.
+
+/// Positions of a line from `from` to `to` (both included), using Bresenham's algorithm.
+pub fn line(from: (isize, isize), to: (isize, isize)) -> Vec<(isize, isize)> {
+    let (mut row, mut column) = from;
+    let delta_row = -(to.0 - from.0).abs();
+    let delta_column = (to.1 - from.1).abs();
+    let step_row = if from.0 < to.0 { 1 } else { -1 };
+    let step_column = if from.1 < to.1 { 1 } else { -1 };
+
+    let mut points = Vec::new();
+    let mut error = delta_column + delta_row;
+    loop {
+        points.push((row, column));
+        if (row, column) == to {
+            break;
+        }
+
+        let doubled = 2 * error;
+        if doubled >= delta_row {
+            error += delta_row;
+            column += step_column;
+        }
+        if doubled <= delta_column {
+            error += delta_column;
+            row += step_row;
+        }
+    }
+    points
+}
+
+/// Positions of an ellipse around `center` with horizontal radius `rx` and vertical radius `ry`,
+/// using the midpoint algorithm.
+///
+/// If `filled`, the inside positions are included too.
+pub fn ellipse(center: (isize, isize), rx: usize, ry: usize, filled: bool) -> Vec<(isize, isize)> {
+    let (rx, ry) = (rx as isize, ry as isize);
+    if rx == 0 || ry == 0 {
+        return line(
+            (center.0 - ry, center.1 - rx),
+            (center.0 + ry, center.1 + rx),
+        );
+    }
+
+    let mut points = Vec::new();
+    let mut plot = |x: isize, y: isize| {
+        for row in [center.0 - y, center.0 + y] {
+            if filled {
+                points.extend((center.1 - x..=center.1 + x).map(|column| (row, column)));
+            } else {
+                points.extend([(row, center.1 - x), (row, center.1 + x)]);
+            }
+        }
+    };
+
+    let (rx2, ry2) = (rx * rx, ry * ry);
+    let (mut x, mut y) = (0, ry);
+    let (mut px, mut py) = (0, 2 * rx2 * y);
+
+    // Where the slope is less than one, step on columns.
+    let mut p = ry2 - rx2 * ry + rx2 / 4;
+    while px < py {
+        plot(x, y);
+        x += 1;
+        px += 2 * ry2;
+        if p < 0 {
+            p += ry2 + px;
+        } else {
+            y -= 1;
+            py -= 2 * rx2;
+            p += ry2 + px - py;
+        }
+    }
+
+    // Then step on rows.
+    let mut p = ry2 as f64 * (x as f64 + 0.5).powi(2) + (rx2 * (y - 1) * (y - 1)) as f64
+        - (rx2 * ry2) as f64;
+    while y >= 0 {
+        plot(x, y);
+        y -= 1;
+        py -= 2 * rx2;
+        if p > 0. {
+            p += (rx2 - py) as f64;
+        } else {
+            x += 1;
+            px += 2 * ry2;
+            p += (rx2 - py + px) as f64;
+        }
+    }
+
+    points.sort_unstable();
+    points.dedup();
+    points
+}
+
+/// Positions inside a polygon (edges excluded), using even-odd scanline filling.
+///
+/// Draw the outline using [`line`]s between consecutive vertices to get the edges too.
+pub fn polygon_inside(vertices: &[(isize, isize)]) -> Vec<(isize, isize)> {
+    let Some(top) = vertices.iter().map(|(row, _)| *row).min() else {
+        return Vec::new();
+    };
+    let bottom = vertices.iter().map(|(row, _)| *row).max().unwrap_or(top);
+    let edges: Vec<_> = vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .filter(|(a, b)| a.0 != b.0)
+        .collect();
+
+    let mut points = Vec::new();
+    for row in top..=bottom {
+        // Half-open on rows, so shared vertices are counted once.
+        let mut crossings: Vec<f64> = edges
+            .iter()
+            .filter(|(a, b)| (a.0.min(b.0)..a.0.max(b.0)).contains(&row))
+            .map(|(a, b)| a.1 as f64 + (row - a.0) as f64 * (b.1 - a.1) as f64 / (b.0 - a.0) as f64)
+            .collect();
+        crossings.sort_by(f64::total_cmp);
+
+        for pair in crossings.chunks_exact(2) {
+            let (start, end) = (pair[0].ceil() as isize, pair[1].floor() as isize);
+            points.extend((start..=end).map(|column| (row, column)));
+        }
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line() {
+        assert_eq!(line((0, 0), (0, 3)), vec![(0, 0), (0, 1), (0, 2), (0, 3)]);
+        assert_eq!(line((2, 2), (0, 0)), vec![(2, 2), (1, 1), (0, 0)]);
+        assert_eq!(
+            line((0, 0), (1, 4)),
+            vec![(0, 0), (0, 1), (1, 2), (1, 3), (1, 4)]
+        );
+        assert_eq!(line((1, 1), (1, 1)), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn test_ellipse() {
+        let circle = ellipse((2, 2), 2, 2, false);
+        for point in [(0, 2), (2, 0), (4, 2), (2, 4)] {
+            assert!(circle.contains(&point));
+        }
+        assert!(!circle.contains(&(2, 2)));
+
+        let filled = ellipse((2, 2), 2, 2, true);
+        assert!(filled.contains(&(2, 2)));
+        assert!(circle.iter().all(|point| filled.contains(point)));
+
+        let wide = ellipse((1, 3), 3, 1, false);
+        assert!(wide.contains(&(1, 0)) && wide.contains(&(1, 6)));
+        assert!(wide.contains(&(0, 3)) && wide.contains(&(2, 3)));
+        assert!(wide.iter().all(|(row, _)| (0..=2).contains(row)));
+
+        assert_eq!(ellipse((0, 0), 2, 0, true), line((0, -2), (0, 2)));
+    }
+
+    #[test]
+    fn test_polygon_inside() {
+        let square = polygon_inside(&[(0, 0), (0, 4), (4, 4), (4, 0)]);
+        assert!(square.contains(&(2, 2)));
+        assert!(square
+            .iter()
+            .all(|(row, column)| (0..=4).contains(row) && (0..=4).contains(column)));
+
+        let triangle = polygon_inside(&[(0, 2), (4, 0), (4, 4)]);
+        assert!(triangle.contains(&(3, 2)));
+        assert!(!triangle.contains(&(1, 0)));
+        assert!(polygon_inside(&[]).is_empty());
+    }
+}
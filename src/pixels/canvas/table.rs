@@ -2,38 +2,146 @@
 //! [PixelCanvas](`super::PixelCanvas`)
 //!
 
-use pixelart_table_abs::table::{IllusionArray2DHandle, IllusionArray2DHandleMut, IllusionTable};
+use pixelart_table_abs::{
+    table::{IllusionArray2DHandle, IllusionArray2DHandleMut, IllusionTable, IllusionTableStats},
+    tags::TagLayer,
+    IllusionBackend,
+};
 
 use crate::pixels::{
-    position::{IntoPixelStrictPosition, PixelStrictPositionInterface},
+    position::{IntoPixelStrictPosition, PixelStrictPosition, PixelStrictPositionInterface},
     Pixel, PixelInitializer, PixelInterface, PixelMutInterface,
 };
 /// Represents a table of [`Pixel`]s. (A collection of [`PixelRow`]s).
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PixelTable<const H: usize, const W: usize = H, P: PixelInterface + Default = Pixel> {
     pub(crate) inner: IllusionTable<H, W, P>,
+
+    /// Optional `u16` tag per pixel (e.g. collision or region ids), which moves with the pixels.
+    pub(crate) tags: TagLayer<H, W>,
 }
 
 impl<const H: usize, const W: usize, P: PixelInterface + Default> PixelTable<H, W, P> {
+    /// An empty table which keeps a slot for every pixel instead of hashing their positions.
+    ///
+    /// Faster for small tables which are mostly filled, see [`IllusionBackend::Dense`].
+    pub fn dense() -> Self
+    where
+        P: Clone,
+    {
+        Self::with_backend(IllusionBackend::Dense)
+    }
+
+    /// An empty table using the given backend, see [`IllusionBackend`].
+    pub fn with_backend(backend: IllusionBackend) -> Self
+    where
+        P: Clone,
+    {
+        Self {
+            inner: IllusionTable::with_backend(P::default(), backend),
+            tags: TagLayer::default(),
+        }
+    }
+
+    pub fn backend(&self) -> IllusionBackend {
+        self.inner.backend()
+    }
+
     /// Returns actual existing elements, any other element will be the default value.
-    pub fn real_items(&self) -> impl Iterator<Item = ((&usize, &usize), &P)> {
+    pub fn real_items(&self) -> impl Iterator<Item = ((usize, usize), &P)> {
         self.inner.real_items()
     }
 
     /// Returns actual existing elements, any other element will be the default value.
-    pub fn real_items_mut(&mut self) -> impl Iterator<Item = ((&usize, &usize), &mut P)> {
+    pub fn real_items_mut(&mut self) -> impl Iterator<Item = ((usize, usize), &mut P)> {
         self.inner.real_items_mut()
     }
 
+    /// Returns actual existing elements from `top_left` to `bottom_right` (both included).
+    ///
+    /// Cheaper than filtering [`PixelTable::real_items`] when the box is small.
+    pub fn real_items_in_box(
+        &self,
+        top_left: impl IntoPixelStrictPosition<H, W>,
+        bottom_right: impl IntoPixelStrictPosition<H, W>,
+    ) -> impl Iterator<Item = ((usize, usize), &P)> {
+        self.inner.real_items_in(
+            top_left.into_pixel_strict_position().expand(),
+            bottom_right.into_pixel_strict_position().expand(),
+        )
+    }
+
     pub fn swap(
         &mut self,
         a: impl IntoPixelStrictPosition<H, W>,
         b: impl IntoPixelStrictPosition<H, W>,
     ) {
-        self.inner.swap(
+        let (a, b) = (
             a.into_pixel_strict_position().expand(),
             b.into_pixel_strict_position().expand(),
-        )
+        );
+        self.inner.swap(a, b);
+        self.tags.swap(a, b);
+    }
+
+    /// Swaps two whole rows of pixels (and tags), rows out of bounds are ignored.
+    ///
+    /// Much faster than swapping the pixels one by one.
+    pub fn swap_rows(&mut self, a: usize, b: usize) {
+        self.inner.swap_rows(a, b);
+        self.tags.swap_rows(a, b);
+    }
+
+    /// Swaps two whole columns of pixels (and tags), columns out of bounds are ignored.
+    pub fn swap_columns(&mut self, a: usize, b: usize) {
+        self.inner.swap_columns(a, b);
+        self.tags.swap_columns(a, b);
+    }
+
+    /// A `W` * `H` copy of this table with rows and columns (and tags) swapped.
+    pub fn transposed(&self) -> PixelTable<W, H, P>
+    where
+        P: Clone,
+    {
+        PixelTable {
+            inner: self.inner.transposed(),
+            tags: self.tags.transposed(),
+        }
+    }
+
+    /// Count of actually stored pixels (per row and in total) and an estimate of their memory.
+    pub fn stats(&self) -> IllusionTableStats {
+        self.inner.stats()
+    }
+
+    /// Count of changes made to pixels so far, see [`IllusionTable::version`].
+    pub fn version(&self) -> u64 {
+        self.inner.version()
+    }
+
+    /// Starts tracking which pixels are changed, see [`IllusionTable::track_changes`].
+    pub fn track_changes(&mut self) {
+        self.inner.track_changes();
+    }
+
+    /// Positions of pixels changed after the `version`, e.g. to only redraw them.
+    ///
+    /// Every pixel counts as changed unless [`PixelTable::track_changes`] is called first.
+    /// Changes of tags aren't tracked.
+    pub fn dirty_since(&self, version: u64) -> Vec<PixelStrictPosition<H, W>> {
+        self.inner
+            .dirty_since(version)
+            .into_iter()
+            .map(IntoPixelStrictPosition::into_pixel_strict_position)
+            .collect()
+    }
+
+    pub fn tags(&self) -> &TagLayer<H, W> {
+        &self.tags
+    }
+
+    pub fn tags_mut(&mut self) -> &mut TagLayer<H, W> {
+        &mut self.tags
     }
 }
 
@@ -43,6 +151,24 @@ impl<const H: usize, const W: usize, P: PixelInterface + PixelInitializer + Clon
     pub fn filled_len(&self) -> usize {
         self.inner.filled_len()
     }
+
+    /// Updates color of all pixels from `top_left` to `bottom_right` (both included).
+    ///
+    /// This is much faster than updating pixels one by one for large regions.
+    pub fn fill_region(
+        &mut self,
+        top_left: impl IntoPixelStrictPosition<H, W>,
+        bottom_right: impl IntoPixelStrictPosition<H, W>,
+        color: impl Into<P::ColorType>,
+    ) where
+        P: PartialEq,
+    {
+        self.inner.set_region(
+            top_left.into_pixel_strict_position().expand(),
+            bottom_right.into_pixel_strict_position().expand(),
+            P::new(color),
+        );
+    }
 }
 
 impl<const H: usize, const W: usize, P: PixelInterface + Default> PixelTable<H, W, P> {
@@ -57,7 +183,7 @@ impl<const H: usize, const W: usize, P: PixelInterface + Default> PixelTable<H,
     pub fn get_pixel_mut(
         &mut self,
         pos: impl IntoPixelStrictPosition<H, W>,
-    ) -> pixelart_table_abs::table::IllusionArray2DHandleMut<H, W, P>
+    ) -> pixelart_table_abs::table::IllusionArray2DHandleMut<'_, H, W, P>
     where
         P: PartialEq + Clone,
     {
@@ -76,7 +202,7 @@ impl<const H: usize, const W: usize, P: PixelInterface + Default> PixelTable<H,
     ///     println!("{:?}", pix)
     /// }
     /// ```
-    pub fn iter_pixels(&self) -> impl Iterator<Item = IllusionArray2DHandle<H, W, P>> {
+    pub fn iter_pixels(&self) -> impl Iterator<Item = IllusionArray2DHandle<'_, H, W, P>> {
         self.inner.iter()
     }
 
@@ -92,7 +218,7 @@ impl<const H: usize, const W: usize, P: PixelInterface + Default> PixelTable<H,
     ///     println!("{:?}", pix)
     /// }
     /// ```
-    pub fn iter_pixels_mut(&mut self) -> impl Iterator<Item = IllusionArray2DHandleMut<H, W, P>>
+    pub fn iter_pixels_mut(&mut self) -> impl Iterator<Item = IllusionArray2DHandleMut<'_, H, W, P>>
     where
         P: PartialEq + Clone,
     {
@@ -113,7 +239,40 @@ impl<const H: usize, const W: usize, P: PixelInterface + Default> PixelTable<H,
         P: PixelMutInterface + Clone + PartialEq,
         F: FnMut(IllusionArray2DHandleMut<H, W, P>) + Copy,
     {
-        self.iter_pixels_mut().for_each(f);
+        self.inner.for_each_mut(f);
+    }
+
+    /// Parallel version of [`PixelTable::iter_pixels`] (`rayon` feature).
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use pixelart::prelude::*;
+    /// use rayon::prelude::*;
+    ///
+    /// let canvas = PixelCanvas::<100>::from_fill_color(RED);
+    /// assert!(canvas.par_iter_pixels().all(|pix| pix.color() == &RED));
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn par_iter_pixels(
+        &self,
+    ) -> impl rayon::iter::IndexedParallelIterator<Item = IllusionArray2DHandle<'_, H, W, P>>
+    where
+        P: Sync,
+    {
+        self.inner.par_iter()
+    }
+
+    /// Parallel version of [`PixelTable::for_each_pixel_mut`], rows are processed in parallel
+    /// (`rayon` feature).
+    ///
+    /// The closure gets the `(row, column)` of each pixel as well.
+    #[cfg(feature = "rayon")]
+    pub fn par_for_each_pixel_mut<F>(&mut self, f: F)
+    where
+        P: Clone + PartialEq + Send + Sync,
+        F: Fn((usize, usize), &mut P) + Sync,
+    {
+        self.inner.par_for_each_mut(f);
     }
 
     pub fn iter(
@@ -140,6 +299,29 @@ impl<const H: usize, const W: usize, P: PixelInterface + Default> PixelTable<H,
         self.inner.get_row(row)
     }
 
+    pub fn get_column(
+        &self,
+        column: usize,
+    ) -> Option<pixelart_table_abs::table::IllusionColumnHandle<'_, H, W, P>> {
+        self.inner.get_column(column)
+    }
+
+    /// Returns an iterator over [`Pixel`]s of a column from top to bottom,
+    /// which is empty if the column is out of bounds.
+    pub fn iter_column(
+        &self,
+        column: usize,
+    ) -> impl Iterator<Item = IllusionArray2DHandle<'_, H, W, P>> {
+        self.inner.iter_column(column)
+    }
+
+    /// Returns an iterator over all columns, from left to right.
+    pub fn iter_columns(
+        &self,
+    ) -> impl Iterator<Item = pixelart_table_abs::table::IllusionColumnHandle<'_, H, W, P>> {
+        self.inner.iter_columns()
+    }
+
     pub fn get_row_mut(
         &mut self,
         row: usize,
@@ -161,6 +343,7 @@ where
     fn default() -> Self {
         Self {
             inner: IllusionTable::default(),
+            tags: TagLayer::default(),
         }
     }
 }
@@ -206,6 +389,87 @@ mod pixel_table_tests {
         }
     }
 
+    #[test]
+    fn test_columns() {
+        let mut table = PixelTable::<3, 2>::default();
+        table
+            .get_pixel_mut(PixelStrictPosition::new(1, 1).unwrap())
+            .update_color(PixelColor::BLACK);
+
+        let colors: Vec<_> = table.iter_column(1).map(|pixel| *pixel.color()).collect();
+        assert_eq!(
+            colors,
+            vec![PixelColor::WHITE, PixelColor::BLACK, PixelColor::WHITE]
+        );
+        assert_eq!(table.get_column(1).unwrap().filled_len(), 1);
+        assert!(table.get_column(2).is_none());
+        assert_eq!(table.iter_columns().count(), 2);
+    }
+
+    #[test]
+    fn test_dirty_since() {
+        let mut table = PixelTable::<3, 2>::default();
+        table.track_changes();
+        let version = table.version();
+        table
+            .get_pixel_mut(PixelStrictPosition::new(1, 1).unwrap())
+            .update_color(PixelColor::BLACK);
+        table.fill_region(
+            PixelStrictPosition::new(2, 0).unwrap(),
+            PixelStrictPosition::new(2, 0).unwrap(),
+            PixelColor::RED,
+        );
+
+        assert_eq!(
+            table.dirty_since(version),
+            vec![
+                PixelStrictPosition::new(1, 1).unwrap(),
+                PixelStrictPosition::new(2, 0).unwrap()
+            ]
+        );
+        assert!(table.dirty_since(table.version()).is_empty());
+    }
+
+    #[test]
+    fn test_fill_region() {
+        let mut table = PixelTable::<4, 6>::default();
+        table.fill_region(
+            PixelStrictPosition::new(1, 2).unwrap(),
+            PixelStrictPosition::new(2, 5).unwrap(),
+            PixelColor::BLACK,
+        );
+        assert_eq!(table.filled_len(), 8);
+        assert_eq!(
+            table
+                .get_pixel(PixelStrictPosition::new(2, 5).unwrap())
+                .color(),
+            &PixelColor::BLACK
+        );
+
+        table.fill_region(
+            PixelStrictPosition::new(0, 0).unwrap(),
+            PixelStrictPosition::new(3, 5).unwrap(),
+            PixelColor::WHITE,
+        );
+        assert_eq!(table.filled_len(), 0);
+    }
+
+    #[test]
+    fn test_real_items_in_box() {
+        let mut table = PixelTable::<5>::default();
+        table.fill_region(
+            PixelStrictPosition::new(0, 0).unwrap(),
+            PixelStrictPosition::new(4, 1).unwrap(),
+            PixelColor::RED,
+        );
+
+        let in_box = table.real_items_in_box(
+            PixelStrictPosition::new(1, 1).unwrap(),
+            PixelStrictPosition::new(3, 4).unwrap(),
+        );
+        assert_eq!(in_box.count(), 3);
+    }
+
     #[test]
     fn test_flip() {
         let mut canvas = PixelCanvas::<5>::default();
@@ -1,6 +1,7 @@
 use crate::pixels::canvas::SharedMutPixelCanvasExt;
+use crate::pos;
 use crate::{
-    pixels::{color::PixelColorExt, position::PixelStrictPositionInterface, PixelMutInterface},
+    pixels::{color::PixelColorExt, PixelMutInterface},
     prelude::{MaybePixel, PixelColor, StrictPositions},
 };
 
@@ -53,12 +54,15 @@ impl Template<17, 10> for HalfAlienMonster {
         let color = PixelColor::from_red(106).blue(127);
 
         canvas.fill_inside(color, StrictPositions::LeftCenter);
-        canvas.table_mut().get_pixel_mut((1, 5)).update_color(color);
-        canvas.fill_inside(color, (3, 5));
-        canvas.fill_inside(color, (5, 8));
         canvas
             .table_mut()
-            .get_pixel_mut((15, 5))
+            .get_pixel_mut(pos!(1, 5; 17, 10))
+            .update_color(color);
+        canvas.fill_inside(color, pos!(3, 5; 17, 10));
+        canvas.fill_inside(color, pos!(5, 8; 17, 10));
+        canvas
+            .table_mut()
+            .get_pixel_mut(pos!(15, 5; 17, 10))
             .update_color(color);
     }
 }
@@ -77,10 +81,7 @@ impl Template<17, 20> for AlienMonster {
             HalfAlienMonster.create().flipped_x(),
         );
         // Right side
-        canvas.draw(
-            StrictPositions::TopLeft.bounding_right(10),
-            HalfAlienMonster,
-        );
+        canvas.draw(pos!(0, 10; 17, 20), HalfAlienMonster);
     }
 }
 
@@ -0,0 +1,204 @@
+//! Sprites sketched as text, see [`from_ascii`] and [`pixel_template!`](crate::pixel_template).
+//!
+
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use crate::pixels::{
+    canvas::{MaybePixelCanvas, SharedMutPixelCanvasExt},
+    color::PixelColor,
+    position::IntoPixelStrictPosition,
+};
+
+/// Errors of [`from_ascii`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum AsciiArtError {
+    #[error("The art doesn't fit into {height}x{width}, ({row}, {column}) is outside.")]
+    OutOfBounds {
+        height: usize,
+        width: usize,
+        row: usize,
+        column: usize,
+    },
+
+    #[error("There's no color for {character:?} at ({row}, {column}).")]
+    UnknownCharacter {
+        character: char,
+        row: usize,
+        column: usize,
+    },
+}
+
+/// Turns a text sketch into a `H` * `W` canvas, each line of the `art` being a row.
+///
+/// Characters are colored using the `palette`, `.` and spaces which are not in it are left
+/// transparent. Lines may be shorter than `W`.
+///
+/// ## Example
+/// ```rust
+/// # use pixelart::{pixels::canvas::templates::ascii::from_ascii, prelude::*};
+/// let eye = from_ascii::<2, 4>(".XX.\nXooX", [('X', BLACK), ('o', RED)]).unwrap();
+/// assert_eq!(eye.color_at(TOP_LEFT), None);
+/// assert_eq!(eye.color_at(pos!(1, 1; 2, 4)), Some(RED));
+/// ```
+pub fn from_ascii<const H: usize, const W: usize>(
+    art: &str,
+    palette: impl IntoIterator<Item = (char, impl Into<PixelColor>)>,
+) -> Result<MaybePixelCanvas<H, W>, AsciiArtError> {
+    let palette: HashMap<_, _> = palette
+        .into_iter()
+        .map(|(character, color)| (character, color.into()))
+        .collect();
+
+    let mut canvas = MaybePixelCanvas::<H, W>::default();
+    for (row, line) in art.lines().enumerate() {
+        for (column, character) in line.chars().enumerate() {
+            let color = match palette.get(&character) {
+                Some(color) => *color,
+                None if character == '.' || character == ' ' => continue,
+                None => {
+                    return Err(AsciiArtError::UnknownCharacter {
+                        character,
+                        row,
+                        column,
+                    })
+                }
+            };
+
+            if row >= H || column >= W {
+                return Err(AsciiArtError::OutOfBounds {
+                    height: H,
+                    width: W,
+                    row,
+                    column,
+                });
+            }
+            canvas.update_color_at((row, column).into_pixel_strict_position(), color);
+        }
+    }
+    Ok(canvas)
+}
+
+/// Length of the longest row in characters, used by [`pixel_template!`](crate::pixel_template).
+#[doc(hidden)]
+pub const fn ascii_width(rows: &[&str]) -> usize {
+    let mut width = 0;
+    let mut index = 0;
+    while index < rows.len() {
+        let length = char_count(rows[index]);
+        if length > width {
+            width = length;
+        }
+        index += 1;
+    }
+    width
+}
+
+/// Number of characters in the text, `str::chars` can't be used in const fns.
+const fn char_count(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut count = 0;
+    let mut index = 0;
+    while index < bytes.len() {
+        // Continuation bytes of UTF-8 look like `10xxxxxx`.
+        if bytes[index] & 0b1100_0000 != 0b1000_0000 {
+            count += 1;
+        }
+        index += 1;
+    }
+    count
+}
+
+/// Creates a [`MaybePixelCanvas`] from rows of ascii art, sized to fit the art.
+///
+/// Rows are string literals, followed by the colors of characters (see [`from_ascii`]).
+///
+/// ## Example
+/// ```rust
+/// # use pixelart::{pixel_template, prelude::*};
+/// let eye = pixel_template! {
+///     ".XX."
+///     "XooX"
+///     ".XX.";
+///     'X' => BLACK,
+///     'o' => RED,
+/// };
+/// assert_eq!(eye.color_at(pos!(1, 2; 3, 4)), Some(RED));
+/// ```
+///
+/// ## Panics
+/// If a character other than `.` and space has no color.
+#[macro_export]
+macro_rules! pixel_template {
+    ($($row:literal)+; $($character:literal => $color:expr),* $(,)?) => {{
+        const ROWS: &[&str] = &[$($row),+];
+        $crate::pixels::canvas::templates::ascii::from_ascii::<
+            { ROWS.len() },
+            { $crate::pixels::canvas::templates::ascii::ascii_width(ROWS) },
+        >(
+            &ROWS.join("\n"),
+            [$(($character, $crate::pixels::color::PixelColor::from($color))),*],
+        )
+        .expect("Every character of the art should have a color.")
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_from_ascii() {
+        let art = "
+.X
+X.X";
+        let canvas = from_ascii::<3, 3>(art, [('X', RED)]).unwrap();
+        assert_eq!(canvas.color_at(pos!(1, 1; 3, 3)), Some(RED));
+        assert_eq!(canvas.color_at(BOTTOM_RIGHT), Some(RED));
+        assert_eq!(canvas.color_at(TOP_LEFT), None);
+
+        assert_eq!(
+            from_ascii::<3, 2>(art, [('X', RED)]).unwrap_err(),
+            AsciiArtError::OutOfBounds {
+                height: 3,
+                width: 2,
+                row: 2,
+                column: 2
+            }
+        );
+        assert_eq!(
+            from_ascii::<3, 3>(art, [('Y', RED)]).unwrap_err(),
+            AsciiArtError::UnknownCharacter {
+                character: 'X',
+                row: 1,
+                column: 1
+            }
+        );
+
+        let sprite: MaybePixelCanvas<2, 3> = crate::pixel_template! {
+            "ab"
+            "..b";
+            'a' => RED,
+            'b' => BLUE,
+        };
+        assert_eq!(sprite.color_at(TOP_LEFT), Some(RED));
+        assert_eq!(sprite.color_at(BOTTOM_RIGHT), Some(BLUE));
+    }
+
+    #[test]
+    fn test_multibyte_characters() {
+        assert_eq!(ascii_width(&["█▓", "é"]), 2);
+
+        let sprite: MaybePixelCanvas<2, 2> = crate::pixel_template! {
+            "█▓"
+            ".█";
+            '█' => BLACK,
+            '▓' => RED,
+        };
+        assert_eq!(sprite.color_at(TOP_RIGHT), Some(RED));
+        assert_eq!(sprite.color_at(BOTTOM_RIGHT), Some(BLACK));
+    }
+}
@@ -54,6 +54,7 @@ mod tests {
         canvas.get_pixel_mut(TOP_LEFT).update_color(RED);
         canvas.get_pixel_mut(TOP_LEFT).update_color(RED);
 
+        std::fs::create_dir_all("arts/test").unwrap();
         canvas
             .default_image_builder()
             .save("arts/test/duplicated.png")
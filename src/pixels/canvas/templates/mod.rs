@@ -1,16 +1,28 @@
+use std::f32::consts::PI;
+
 use crate::{
     pixels::{
         maybe::MaybePixel,
-        position::{IntoPixelStrictPosition, PixelStrictPositionInterface, StrictPositions},
+        position::{
+            Direction, IntoPixelStrictPosition, PixelStrictPositionInterface, StrictPositions,
+            ALL_DIRECTIONS,
+        },
         PixelInterface,
     },
     prelude::{PixelColor, TOP_RIGHT},
 };
 
-use super::{drawable::Drawable, PixelCanvas, PixelCanvasMutInterface, SharedMutPixelCanvasExt};
+use super::{
+    draw_points, drawable::Drawable, shapes, PixelCanvas, PixelCanvasMutInterface,
+    SharedMutPixelCanvasExt,
+};
+use polygon::Polygon;
 
 pub mod alien_monster;
+pub mod ascii;
 pub mod heart;
+pub mod polygon;
+pub mod registry;
 
 pub trait Template<const H: usize, const W: usize> {
     fn define<C: PixelCanvasMutInterface<H, W, MaybePixel>>(&self, canvas: &mut C);
@@ -86,11 +98,151 @@ pub fn square<const H: usize>(
     rectangle::<H, H>(color)
 }
 
+/// A filled `H` * `H` star with given number of `points`, the first one pointing up.
+pub fn star<const H: usize>(
+    points: usize,
+    color: impl Into<PixelColor>,
+) -> PixelCanvas<H, H, MaybePixel> {
+    let center = (H - 1) as f32 / 2.;
+    let (outer, inner) = (center, center / 2.);
+
+    let vertices = (0..points * 2).map(|index| {
+        let radius = if index % 2 == 0 { outer } else { inner };
+        let angle = -PI / 2. + index as f32 * PI / points as f32;
+        (
+            (center + radius * angle.sin()).round() as usize,
+            (center + radius * angle.cos()).round() as usize,
+        )
+    });
+    Polygon::<H>::new(vertices, color).filled().create()
+}
+
+/// A filled diamond, whose corners touch the middle of each edge of the `H` * `W` canvas.
+pub fn diamond<const H: usize, const W: usize>(
+    color: impl Into<PixelColor>,
+) -> PixelCanvas<H, W, MaybePixel> {
+    let (middle_row, middle_column) = ((H - 1) / 2, (W - 1) / 2);
+    Polygon::<H, W>::new(
+        [
+            (0, middle_column),
+            (middle_row, W - 1),
+            (H - 1, middle_column),
+            (middle_row, 0),
+        ],
+        color,
+    )
+    .filled()
+    .create()
+}
+
+/// An `H` * `H` arrow pointing toward the `direction`.
+pub fn arrow<const H: usize>(
+    direction: Direction,
+    color: impl Into<PixelColor>,
+) -> PixelCanvas<H, H, MaybePixel> {
+    let last = H as isize - 1;
+    let middle = last / 2;
+
+    // Steps of 45 degrees from up, clockwise.
+    let steps = ALL_DIRECTIONS
+        .iter()
+        .position(|dir| dir == &direction)
+        .expect("All directions are there.");
+    let lines = if steps % 2 == 0 {
+        // Pointing up
+        [
+            ((last, middle), (0, middle)),
+            ((0, middle), (middle, 0)),
+            ((0, middle), (middle, middle * 2)),
+        ]
+    } else {
+        // Pointing up right
+        [
+            ((last, 0), (0, last)),
+            ((0, last), (0, last - middle)),
+            ((0, last), (middle, last)),
+        ]
+    };
+
+    let mut canvas = PixelCanvas::<H, H, MaybePixel>::default();
+    let points = lines
+        .into_iter()
+        .flat_map(|(from, to)| shapes::line(from, to));
+    draw_points(&mut canvas, points, Some(color.into()));
+
+    for _ in 0..steps / 2 {
+        canvas = canvas.rotate_90_cw();
+    }
+    canvas
+}
+
+/// A `H` * `W` checkerboard of `cell` * `cell` squares, starting with `color_a` at the top left.
+pub fn checkerboard<const H: usize, const W: usize>(
+    cell: usize,
+    color_a: impl Into<PixelColor>,
+    color_b: impl Into<PixelColor>,
+) -> PixelCanvas<H, W, MaybePixel> {
+    let cell = cell.max(1);
+    let colors = [color_a.into(), color_b.into()];
+
+    let mut canvas = PixelCanvas::<H, W, MaybePixel>::default();
+    for row in 0..H {
+        for column in 0..W {
+            let color = colors[(row / cell + column / cell) % 2];
+            canvas.update_color_at((row, column).into_pixel_strict_position(), color);
+        }
+    }
+    canvas
+}
+
+/// Which way the [`stripes`] go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Orientation {
+    /// Stripes are rows.
+    #[default]
+    Horizontal,
+    /// Stripes are columns.
+    Vertical,
+    /// Stripes go from the bottom left toward the top right.
+    Diagonal,
+}
+
+/// A `H` * `W` canvas of `width` wide stripes, cycling through the `colors`.
+pub fn stripes<const H: usize, const W: usize>(
+    width: usize,
+    colors: impl IntoIterator<Item = impl Into<PixelColor>>,
+    orientation: Orientation,
+) -> PixelCanvas<H, W, MaybePixel> {
+    let width = width.max(1);
+    let colors: Vec<PixelColor> = colors.into_iter().map(Into::into).collect();
+
+    let mut canvas = PixelCanvas::<H, W, MaybePixel>::default();
+    if colors.is_empty() {
+        return canvas;
+    }
+    for row in 0..H {
+        for column in 0..W {
+            let offset = match orientation {
+                Orientation::Horizontal => row,
+                Orientation::Vertical => column,
+                Orientation::Diagonal => row + column,
+            };
+            let color = colors[offset / width % colors.len()];
+            canvas.update_color_at((row, column).into_pixel_strict_position(), color);
+        }
+    }
+    canvas
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::pixels::{
-        canvas::SharedPixelCanvasExt,
-        color::{PixelColor, PixelColorExt},
+    use crate::{
+        pixels::{
+            canvas::SharedPixelCanvasExt,
+            color::{PixelColor, PixelColorExt},
+        },
+        pos,
+        prelude::*,
     };
 
     use super::*;
@@ -105,4 +257,66 @@ mod tests {
         let image = canvas.default_image_builder().with_scale(5);
         image.save("arts/template_0.png").unwrap();
     }
+
+    #[test]
+    fn test_basic_shapes() {
+        let star = star::<9>(5, PixelColor::RED);
+        assert_eq!(star.color_at(TOP_CENTER), Some(PixelColor::RED));
+        assert_eq!(star.color_at(CENTER), Some(PixelColor::RED));
+        assert_eq!(star.color_at(TOP_LEFT), None);
+        assert_eq!(star.color_at(BOTTOM_CENTER), None);
+
+        let diamond = diamond::<5, 7>(PixelColor::BLUE);
+        assert_eq!(diamond.color_at(TOP_CENTER), Some(PixelColor::BLUE));
+        assert_eq!(diamond.color_at(RIGHT_CENTER), Some(PixelColor::BLUE));
+        assert_eq!(diamond.color_at(CENTER), Some(PixelColor::BLUE));
+        assert_eq!(diamond.color_at(BOTTOM_RIGHT), None);
+
+        let right = arrow::<5>(Direction::Right, PixelColor::BLACK);
+        assert_eq!(right.color_at(LEFT_CENTER), Some(PixelColor::BLACK));
+        assert_eq!(right.color_at(TOP_RIGHT), None);
+        assert_eq!(right.color_at(pos!(1, 3; 5, 5)), Some(PixelColor::BLACK));
+        assert_eq!(right.color_at(pos!(1, 1; 5, 5)), None);
+
+        let down_left = arrow::<5>(Direction::DownLeft, PixelColor::BLACK);
+        assert_eq!(down_left.color_at(BOTTOM_LEFT), Some(PixelColor::BLACK));
+        assert_eq!(down_left.color_at(TOP_RIGHT), Some(PixelColor::BLACK));
+        assert_eq!(
+            down_left.color_at(pos!(2, 0; 5, 5)),
+            Some(PixelColor::BLACK)
+        );
+        assert_eq!(
+            down_left.color_at(pos!(4, 2; 5, 5)),
+            Some(PixelColor::BLACK)
+        );
+        assert_eq!(down_left.color_at(TOP_LEFT), None);
+    }
+
+    #[test]
+    fn test_patterns() {
+        let board = checkerboard::<4, 6>(2, PixelColor::BLACK, PixelColor::WHITE);
+        assert_eq!(board.color_at(TOP_LEFT), Some(PixelColor::BLACK));
+        assert_eq!(board.color_at(pos!(1, 1; 4, 6)), Some(PixelColor::BLACK));
+        assert_eq!(board.color_at(pos!(0, 2; 4, 6)), Some(PixelColor::WHITE));
+        assert_eq!(board.color_at(BOTTOM_RIGHT), Some(PixelColor::WHITE));
+
+        let colors = [PixelColor::RED, PixelColor::GREEN, PixelColor::BLUE];
+        let horizontal = stripes::<4, 2>(1, colors, Orientation::Horizontal);
+        assert_eq!(horizontal.color_at(TOP_RIGHT), Some(PixelColor::RED));
+        assert_eq!(horizontal.color_at(BOTTOM_LEFT), Some(PixelColor::RED));
+        assert_eq!(
+            horizontal.color_at(pos!(2, 0; 4, 2)),
+            Some(PixelColor::BLUE)
+        );
+
+        let vertical = stripes::<2, 4>(2, colors, Orientation::Vertical);
+        assert_eq!(vertical.color_at(BOTTOM_RIGHT), Some(PixelColor::GREEN));
+
+        let diagonal = stripes::<3, 3>(1, colors, Orientation::Diagonal);
+        assert_eq!(diagonal.color_at(TOP_RIGHT), Some(PixelColor::BLUE));
+        assert_eq!(diagonal.color_at(BOTTOM_LEFT), Some(PixelColor::BLUE));
+
+        let empty = stripes::<3, 3>(1, Vec::<PixelColor>::new(), Orientation::Vertical);
+        assert_eq!(empty.filled_len(), 0);
+    }
 }
@@ -0,0 +1,96 @@
+use crate::{
+    pixels::{
+        canvas::{draw_points, shapes, PixelCanvasMutInterface},
+        maybe::MaybePixel,
+        position::{IntoPixelStrictPosition, PixelStrictPosition, PixelStrictPositionInterface},
+    },
+    prelude::PixelColor,
+};
+
+use super::Template;
+
+/// A closed shape through the given vertices, like triangles or stars.
+///
+/// ## Example
+/// ```rust
+/// # use pixelart::{pixels::canvas::templates::{polygon::Polygon, Template}, prelude::*};
+/// let triangle = Polygon::<5>::new([(0, 2), (4, 0), (4, 4)], BLACK).filled();
+/// let canvas = triangle.create();
+/// assert_eq!(canvas.color_at(pos!(3, 2; 5, 5)), Some(BLACK));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Polygon<const H: usize, const W: usize = H> {
+    vertices: Vec<PixelStrictPosition<H, W>>,
+    color: PixelColor,
+    filled: bool,
+}
+
+impl<const H: usize, const W: usize> Polygon<H, W> {
+    /// Create a new outlined [`Polygon`], the last vertex is connected to the first one.
+    pub fn new(
+        vertices: impl IntoIterator<Item = impl IntoPixelStrictPosition<H, W>>,
+        color: impl Into<PixelColor>,
+    ) -> Self {
+        Self {
+            vertices: vertices
+                .into_iter()
+                .map(IntoPixelStrictPosition::into_pixel_strict_position)
+                .collect(),
+            color: color.into(),
+            filled: false,
+        }
+    }
+
+    /// Fill the inside of the polygon (using scanlines) too.
+    pub fn filled(mut self) -> Self {
+        self.filled = true;
+        self
+    }
+
+    pub fn vertices(&self) -> &[PixelStrictPosition<H, W>] {
+        &self.vertices
+    }
+}
+
+impl<const H: usize, const W: usize> Template<H, W> for Polygon<H, W> {
+    fn define<C: PixelCanvasMutInterface<H, W, MaybePixel>>(&self, canvas: &mut C) {
+        let vertices: Vec<_> = self
+            .vertices
+            .iter()
+            .map(|pos| (pos.row() as isize, pos.column() as isize))
+            .collect();
+
+        let mut points: Vec<_> = vertices
+            .iter()
+            .zip(vertices.iter().cycle().skip(1))
+            .flat_map(|(from, to)| shapes::line(*from, *to))
+            .collect();
+        if self.filled {
+            points.extend(shapes::polygon_inside(&vertices));
+        }
+
+        draw_points(canvas, points, Some(self.color));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_polygon() {
+        let outline = Polygon::<5>::new([(0, 0), (0, 4), (4, 4), (4, 0)], RED).create();
+        assert_eq!(outline.color_at(TOP_RIGHT), Some(RED));
+        assert_eq!(outline.color_at(BOTTOM_LEFT), Some(RED));
+        assert_eq!(outline.color_at(CENTER), None);
+
+        let mut canvas = PixelCanvas::<5>::default();
+        canvas.draw(
+            TOP_LEFT,
+            Polygon::<5>::new([(0, 0), (0, 4), (4, 4), (4, 0)], RED).filled(),
+        );
+        assert_eq!(canvas.color_at(CENTER), RED);
+    }
+}
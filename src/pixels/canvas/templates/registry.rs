@@ -0,0 +1,200 @@
+//! Templates picked by name at runtime, see [`TemplateRegistry`].
+//!
+
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+
+use crate::pixels::{
+    canvas::{dynamic::DynMaybePixelCanvas, MaybePixelCanvas, PixelCanvasMutInterface},
+    color::PixelColor,
+    position::{IntoPixelStrictPosition, PixelStrictPosition, PixelStrictPositionInterface},
+    PixelInterface, PixelMutInterface,
+};
+
+use super::Template;
+
+/// A template whose size is only known at runtime.
+pub trait DynTemplate {
+    /// `(height, width)` of the created canvas.
+    fn size(&self) -> (usize, usize);
+
+    fn create_dyn(&self) -> DynMaybePixelCanvas;
+}
+
+/// A [`Template`] with its sizes kept, so it can be used as a [`DynTemplate`].
+struct SizedTemplate<const H: usize, const W: usize, T>(T);
+
+impl<const H: usize, const W: usize, T: Template<H, W>> DynTemplate for SizedTemplate<H, W, T> {
+    fn size(&self) -> (usize, usize) {
+        (H, W)
+    }
+
+    fn create_dyn(&self) -> DynMaybePixelCanvas {
+        DynMaybePixelCanvas::from(&self.0.create())
+    }
+}
+
+impl DynTemplate for DynMaybePixelCanvas {
+    fn size(&self) -> (usize, usize) {
+        (self.height(), self.width())
+    }
+
+    fn create_dyn(&self) -> DynMaybePixelCanvas {
+        self.clone()
+    }
+}
+
+/// No template is registered under the name.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("No template is registered as {0:?}.")]
+pub struct UnknownTemplateError(pub String);
+
+/// Templates (of any size) registered under names, e.g. stamps which users can pick from.
+///
+/// ## Example
+/// ```rust
+/// # use pixelart::{pixels::canvas::templates::{heart::Heart, registry::TemplateRegistry}, prelude::*};
+/// let mut registry = TemplateRegistry::new();
+/// registry.register("heart", Heart);
+///
+/// let mut canvas = PixelCanvas::<10>::default();
+/// registry.draw_on("heart", TOP_LEFT, &mut canvas).unwrap();
+/// assert!(registry.draw_on("alien", TOP_LEFT, &mut canvas).is_err());
+/// ```
+#[derive(Default)]
+pub struct TemplateRegistry {
+    templates: BTreeMap<String, Box<dyn DynTemplate>>,
+}
+
+impl TemplateRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a [`Template`], replacing any other one with the same name.
+    pub fn register<const H: usize, const W: usize>(
+        &mut self,
+        name: impl Into<String>,
+        template: impl Template<H, W> + 'static,
+    ) -> &mut Self {
+        self.register_dyn(name, SizedTemplate::<H, W, _>(template))
+    }
+
+    /// Registers a ready canvas, like a loaded sprite.
+    pub fn register_canvas<const H: usize, const W: usize>(
+        &mut self,
+        name: impl Into<String>,
+        canvas: &MaybePixelCanvas<H, W>,
+    ) -> &mut Self {
+        self.register_dyn(name, DynMaybePixelCanvas::from(canvas))
+    }
+
+    pub fn register_dyn(
+        &mut self,
+        name: impl Into<String>,
+        template: impl DynTemplate + 'static,
+    ) -> &mut Self {
+        self.templates.insert(name.into(), Box::new(template));
+        self
+    }
+
+    /// Removes a template, returns `false` if there was none.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.templates.remove(name).is_some()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn DynTemplate> {
+        self.templates.get(name).map(|template| template.as_ref())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.templates.contains_key(name)
+    }
+
+    /// Names of all templates, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.templates.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.templates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+
+    /// Creates a canvas from the template.
+    pub fn instantiate(&self, name: &str) -> Result<DynMaybePixelCanvas, UnknownTemplateError> {
+        self.get(name)
+            .map(DynTemplate::create_dyn)
+            .ok_or_else(|| UnknownTemplateError(name.to_string()))
+    }
+
+    /// Draws the template on a canvas with its top left at `start_pos`,
+    /// parts going out of the canvas are dropped.
+    pub fn draw_on<const H: usize, const W: usize, P, C>(
+        &self,
+        name: &str,
+        start_pos: impl IntoPixelStrictPosition<H, W>,
+        canvas: &mut C,
+    ) -> Result<(), UnknownTemplateError>
+    where
+        P: PixelMutInterface + PartialEq + Clone + Default,
+        P::ColorType: TryFrom<Option<PixelColor>>,
+        C: PixelCanvasMutInterface<H, W, P>,
+    {
+        let template = self.instantiate(name)?;
+        let (top, left) = start_pos.into_pixel_strict_position().expand();
+        for ((row, column), pixel) in template.iter_pixels() {
+            let Ok(pos) = PixelStrictPosition::<H, W>::new(top + row, left + column) else {
+                continue;
+            };
+            if let (true, Ok(color)) = (pixel.has_color(), P::ColorType::try_from(*pixel.color())) {
+                canvas.table_mut().get_pixel_mut(pos).update_color(color);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pixels::canvas::templates::heart::Heart, prelude::*};
+
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        let mut dot = MaybePixelCanvas::<1>::default();
+        dot.update_color_at(TOP_LEFT, RED);
+
+        let mut registry = TemplateRegistry::new();
+        registry
+            .register("heart", Heart)
+            .register_canvas("dot", &dot);
+        assert_eq!(registry.names().collect::<Vec<_>>(), vec!["dot", "heart"]);
+        assert_eq!(
+            registry.get("heart").map(|heart| heart.size()),
+            Some((6, 7))
+        );
+        assert_eq!(
+            registry.instantiate("heart").unwrap(),
+            DynPixelCanvas::from(&Heart.create())
+        );
+
+        let mut canvas = PixelCanvas::<3>::default();
+        registry.draw_on("heart", CENTER, &mut canvas).unwrap();
+        registry.draw_on("dot", BOTTOM_RIGHT, &mut canvas).unwrap();
+        assert_eq!(canvas.color_at(BOTTOM_RIGHT), RED);
+        assert_eq!(canvas.color_at(TOP_LEFT), WHITE);
+
+        assert!(registry.remove("dot"));
+        assert_eq!(
+            registry.instantiate("dot").unwrap_err(),
+            UnknownTemplateError("dot".to_string())
+        );
+        assert_eq!(registry.len(), 1);
+    }
+}
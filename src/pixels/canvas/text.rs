@@ -0,0 +1,305 @@
+//! Built-in bitmap fonts to draw text on canvases, see
+//! [`SharedMutPixelCanvasExt::draw_text`](super::SharedMutPixelCanvasExt::draw_text).
+//!
+//! Fonts have uppercase letters, digits and a few punctuations, lowercase letters are drawn
+//! as uppercase and unknown characters as `?`.
+
+/// Empty columns between two characters, and empty rows between two lines.
+const SPACING: usize = 1;
+
+/// A fixed size bitmap font.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Font {
+    /// 3x5 pixels characters.
+    #[default]
+    Small,
+
+    /// 5x7 pixels characters.
+    Regular,
+}
+
+impl Font {
+    pub fn glyph_width(&self) -> usize {
+        match self {
+            Font::Small => 3,
+            Font::Regular => 5,
+        }
+    }
+
+    pub fn glyph_height(&self) -> usize {
+        match self {
+            Font::Small => 5,
+            Font::Regular => 7,
+        }
+    }
+
+    /// Rows of the character, where bits of each row from left are pixels from left.
+    fn glyph(&self, c: char) -> &'static [u8] {
+        let c = c.to_ascii_uppercase();
+        let glyph = match self {
+            Font::Small => small_glyph,
+            Font::Regular => regular_glyph,
+        };
+        glyph(c).or_else(|| glyph('?')).unwrap_or_default()
+    }
+
+    /// Height and width of the text (lines are separated by `\n`).
+    pub fn text_size(&self, text: &str) -> (usize, usize) {
+        let lines = text.split('\n').count();
+        let longest = text
+            .split('\n')
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or_default();
+
+        let height = lines * (self.glyph_height() + SPACING) - SPACING;
+        let width = (longest * (self.glyph_width() + SPACING)).saturating_sub(SPACING);
+        (height, width)
+    }
+
+    /// `(row, column)`s of colored pixels of the text, relative to its top left.
+    pub(crate) fn text_points(&self, text: &str) -> Vec<(usize, usize)> {
+        let (glyph_height, glyph_width) = (self.glyph_height(), self.glyph_width());
+        let mut points = Vec::new();
+
+        for (line_index, line) in text.split('\n').enumerate() {
+            for (char_index, c) in line.chars().enumerate() {
+                let top = line_index * (glyph_height + SPACING);
+                let left = char_index * (glyph_width + SPACING);
+                for (row, bits) in self.glyph(c).iter().enumerate() {
+                    for column in 0..glyph_width {
+                        if bits >> (glyph_width - 1 - column) & 1 == 1 {
+                            points.push((top + row, left + column));
+                        }
+                    }
+                }
+            }
+        }
+        points
+    }
+}
+
+fn small_glyph(c: char) -> Option<&'static [u8]> {
+    Some(match c {
+        'A' => &[0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => &[0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => &[0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => &[0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => &[0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => &[0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => &[0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => &[0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => &[0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => &[0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => &[0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => &[0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => &[0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => &[0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => &[0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => &[0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => &[0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => &[0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => &[0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => &[0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => &[0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => &[0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => &[0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => &[0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => &[0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => &[0b111, 0b001, 0b010, 0b100, 0b111],
+        '0' => &[0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => &[0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => &[0b110, 0b001, 0b010, 0b100, 0b111],
+        '3' => &[0b110, 0b001, 0b010, 0b001, 0b110],
+        '4' => &[0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => &[0b111, 0b100, 0b110, 0b001, 0b110],
+        '6' => &[0b011, 0b100, 0b111, 0b101, 0b111],
+        '7' => &[0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => &[0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => &[0b111, 0b101, 0b111, 0b001, 0b110],
+        ' ' => &[0b000, 0b000, 0b000, 0b000, 0b000],
+        '.' => &[0b000, 0b000, 0b000, 0b000, 0b010],
+        ',' => &[0b000, 0b000, 0b000, 0b010, 0b100],
+        '!' => &[0b010, 0b010, 0b010, 0b000, 0b010],
+        '?' => &[0b110, 0b001, 0b010, 0b000, 0b010],
+        ':' => &[0b000, 0b010, 0b000, 0b010, 0b000],
+        '-' => &[0b000, 0b000, 0b111, 0b000, 0b000],
+        '+' => &[0b000, 0b010, 0b111, 0b010, 0b000],
+        '/' => &[0b001, 0b001, 0b010, 0b100, 0b100],
+        '\'' => &[0b010, 0b010, 0b000, 0b000, 0b000],
+        '(' => &[0b001, 0b010, 0b010, 0b010, 0b001],
+        ')' => &[0b100, 0b010, 0b010, 0b010, 0b100],
+        '=' => &[0b000, 0b111, 0b000, 0b111, 0b000],
+        _ => return None,
+    })
+}
+
+fn regular_glyph(c: char) -> Option<&'static [u8]> {
+    Some(match c {
+        'A' => &[
+            0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001,
+        ],
+        'B' => &[
+            0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110,
+        ],
+        'C' => &[
+            0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110,
+        ],
+        'D' => &[
+            0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100,
+        ],
+        'E' => &[
+            0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111,
+        ],
+        'F' => &[
+            0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000,
+        ],
+        'G' => &[
+            0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111,
+        ],
+        'H' => &[
+            0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001,
+        ],
+        'I' => &[
+            0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110,
+        ],
+        'J' => &[
+            0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100,
+        ],
+        'K' => &[
+            0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001,
+        ],
+        'L' => &[
+            0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111,
+        ],
+        'M' => &[
+            0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001,
+        ],
+        'N' => &[
+            0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001,
+        ],
+        'O' => &[
+            0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110,
+        ],
+        'P' => &[
+            0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000,
+        ],
+        'Q' => &[
+            0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101,
+        ],
+        'R' => &[
+            0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001,
+        ],
+        'S' => &[
+            0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110,
+        ],
+        'T' => &[
+            0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100,
+        ],
+        'U' => &[
+            0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110,
+        ],
+        'V' => &[
+            0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100,
+        ],
+        'W' => &[
+            0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010,
+        ],
+        'X' => &[
+            0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001,
+        ],
+        'Y' => &[
+            0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100,
+        ],
+        'Z' => &[
+            0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111,
+        ],
+        '0' => &[
+            0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110,
+        ],
+        '1' => &[
+            0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110,
+        ],
+        '2' => &[
+            0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111,
+        ],
+        '3' => &[
+            0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110,
+        ],
+        '4' => &[
+            0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010,
+        ],
+        '5' => &[
+            0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110,
+        ],
+        '6' => &[
+            0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110,
+        ],
+        '7' => &[
+            0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000,
+        ],
+        '8' => &[
+            0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110,
+        ],
+        '9' => &[
+            0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100,
+        ],
+        ' ' => &[
+            0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000,
+        ],
+        '.' => &[
+            0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100,
+        ],
+        ',' => &[
+            0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000,
+        ],
+        '!' => &[
+            0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100,
+        ],
+        '?' => &[
+            0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100,
+        ],
+        ':' => &[
+            0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000,
+        ],
+        '-' => &[
+            0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000,
+        ],
+        '+' => &[
+            0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000,
+        ],
+        '/' => &[
+            0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000,
+        ],
+        '\'' => &[
+            0b01100, 0b00100, 0b01000, 0b00000, 0b00000, 0b00000, 0b00000,
+        ],
+        '(' => &[
+            0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010,
+        ],
+        ')' => &[
+            0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000,
+        ],
+        '=' => &[
+            0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000,
+        ],
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_points() {
+        assert_eq!(Font::Small.text_size("HI"), (5, 7));
+        assert_eq!(Font::Regular.text_size("AB\nC"), (15, 11));
+        assert_eq!(Font::Small.text_size(""), (5, 0));
+
+        let points = Font::Small.text_points("i");
+        assert_eq!(points.len(), 3 + 1 + 1 + 1 + 3);
+        assert!(points.contains(&(0, 0)) && points.contains(&(2, 1)));
+        assert_eq!(Font::Small.glyph('~'), Font::Small.glyph('?'));
+    }
+}
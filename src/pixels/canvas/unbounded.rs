@@ -0,0 +1,187 @@
+//! A canvas without any bounds which grows as you draw on it.
+//!
+
+use std::collections::HashMap;
+
+use crate::{
+    pixels::{
+        position::{IntoPixelStrictPosition, PixelStrictPositionInterface},
+        PixelMutInterface,
+    },
+    prelude::{MaybePixelCanvas, PixelColor},
+};
+
+use super::pen::{CanvasUnattachedMarker, Pen, UnboundedAttachedMarker};
+
+/// The rectangle which contains every filled pixel of an [`UnboundedCanvas`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentBounds {
+    /// Signed `(row, column)` of the top left filled corner.
+    pub top_left: (isize, isize),
+    pub height: usize,
+    pub width: usize,
+}
+
+/// A sparse canvas of [`PixelColor`]s indexed by signed `(row, column)`s.
+///
+/// There are no edges, so nothing is ever clamped or clipped while drawing.
+/// Use [`UnboundedCanvas::cropped`] to get a regular canvas out of the content at the end.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnboundedCanvas {
+    pixels: HashMap<(isize, isize), PixelColor>,
+}
+
+impl UnboundedCanvas {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count of pixels that actually have a color.
+    pub fn filled_len(&self) -> usize {
+        self.pixels.len()
+    }
+
+    /// Gets the color of a pixel at given position, if any.
+    pub fn color_at(&self, pos: (isize, isize)) -> Option<PixelColor> {
+        self.pixels.get(&pos).copied()
+    }
+
+    /// Update color of a pixel at the given position, returning the previous one.
+    pub fn update_color_at(
+        &mut self,
+        pos: (isize, isize),
+        color: impl Into<PixelColor>,
+    ) -> Option<PixelColor> {
+        self.pixels.insert(pos, color.into())
+    }
+
+    /// Removes the color of a pixel at the given position.
+    pub fn clear_pixel(&mut self, pos: (isize, isize)) -> Option<PixelColor> {
+        self.pixels.remove(&pos)
+    }
+
+    /// Returns actual existing pixels, in no particular order.
+    pub fn real_items(&self) -> impl Iterator<Item = ((isize, isize), &PixelColor)> {
+        self.pixels.iter().map(|(pos, color)| (*pos, color))
+    }
+
+    /// The smallest rectangle containing all of the filled pixels, or `None` if empty.
+    pub fn content_bounds(&self) -> Option<ContentBounds> {
+        let mut positions = self.pixels.keys();
+        let &(first_row, first_column) = positions.next()?;
+
+        let (mut min_row, mut max_row) = (first_row, first_row);
+        let (mut min_column, mut max_column) = (first_column, first_column);
+        for &(row, column) in positions {
+            min_row = min_row.min(row);
+            max_row = max_row.max(row);
+            min_column = min_column.min(column);
+            max_column = max_column.max(column);
+        }
+
+        Some(ContentBounds {
+            top_left: (min_row, min_column),
+            height: (max_row - min_row) as usize + 1,
+            width: (max_column - min_column) as usize + 1,
+        })
+    }
+
+    /// Copies a `H` * `W` window of this canvas which starts at `top_left` into a [`MaybePixelCanvas`].
+    pub fn window<const H: usize, const W: usize>(
+        &self,
+        top_left: (isize, isize),
+    ) -> MaybePixelCanvas<H, W> {
+        let mut canvas = MaybePixelCanvas::<H, W>::default();
+        for (&(row, column), color) in self.pixels.iter() {
+            let (row, column) = (row - top_left.0, column - top_left.1);
+            if row >= 0 && column >= 0 && (row as usize) < H && (column as usize) < W {
+                canvas
+                    .get_pixel_mut((row as usize, column as usize))
+                    .update_color(*color);
+            }
+        }
+        canvas
+    }
+
+    /// Crops the content into a [`MaybePixelCanvas`], starting from the top left of the content bounds.
+    ///
+    /// Anything that doesn't fit in `H` * `W` is clipped, so use [`UnboundedCanvas::content_bounds`]
+    /// to find out the required size.
+    pub fn cropped<const H: usize, const W: usize>(&self) -> MaybePixelCanvas<H, W> {
+        match self.content_bounds() {
+            Some(bounds) => self.window(bounds.top_left),
+            None => MaybePixelCanvas::default(),
+        }
+    }
+
+    /// Draws a [`MaybePixelCanvas`] on this canvas, starting at `top_left`.
+    pub fn draw_canvas<const H: usize, const W: usize>(
+        &mut self,
+        top_left: (isize, isize),
+        canvas: &MaybePixelCanvas<H, W>,
+    ) {
+        for ((row, column), pixel) in canvas.real_items() {
+            if let Some(color) = pixel.color {
+                self.pixels.insert(
+                    (top_left.0 + row as isize, top_left.1 + column as isize),
+                    color,
+                );
+            }
+        }
+    }
+
+    /// Attach a new pen to this canvas at `start_pos`.
+    pub fn attach_new_pen(
+        &mut self,
+        color: impl Into<PixelColor>,
+        start_pos: (isize, isize),
+    ) -> Pen<UnboundedAttachedMarker<'_>> {
+        Pen::<CanvasUnattachedMarker<PixelColor>>::new(color).attach_unbounded(self, start_pos)
+    }
+}
+
+impl<const H: usize, const W: usize> From<&MaybePixelCanvas<H, W>> for UnboundedCanvas {
+    fn from(value: &MaybePixelCanvas<H, W>) -> Self {
+        let mut canvas = Self::new();
+        canvas.draw_canvas((0, 0), value);
+        canvas
+    }
+}
+
+/// Converts any strict position to a signed one, for use with [`UnboundedCanvas`].
+pub fn signed_position<const H: usize, const W: usize>(
+    pos: impl IntoPixelStrictPosition<H, W>,
+) -> (isize, isize) {
+    let pos = pos.into_pixel_strict_position();
+    (pos.row() as isize, pos.column() as isize)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_pen_never_sticks() {
+        let mut canvas = UnboundedCanvas::new();
+
+        canvas
+            .attach_new_pen(RED, (0, 0))
+            .start()
+            .up(3)
+            .left(2)
+            .branch(|pen| pen.down_left(2))
+            .stop()
+            .right(10)
+            .start();
+
+        let bounds = canvas.content_bounds().unwrap();
+        assert_eq!(bounds.top_left, (-3, -4));
+        assert_eq!((bounds.height, bounds.width), (4, 13));
+
+        let cropped = canvas.cropped::<4, 13>();
+        assert_eq!(cropped.filled_len(), canvas.filled_len());
+        assert_eq!(cropped.color_at(TOP_RIGHT), Some(RED));
+    }
+}
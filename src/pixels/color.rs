@@ -1,8 +1,11 @@
-use std::{error::Error, fmt::Display};
+use std::{error::Error, fmt::Display, str::FromStr};
 
 use image::{Rgb, Rgba};
 use thiserror::Error;
 
+pub mod palettes;
+pub(crate) mod quantize;
+
 pub mod colors {
     use super::PixelColor;
 
@@ -52,6 +55,7 @@ pub trait PixelColorInterface {
 ///
 /// The default value is White (`u8::MAX` for all) and not Black (`u8::MIN` for all).
 #[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PixelColor {
     pub r: u8,
     pub g: u8,
@@ -228,6 +232,18 @@ impl PixelColor {
         }
     }
 
+    /// Linearly interpolates each channel, `t` of `0.0` is this color and `1.0` is the other.
+    pub fn lerp(&self, other: impl Into<PixelColor>, t: f32) -> PixelColor {
+        let other = other.into();
+        let t = t.clamp(0., 1.);
+        let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Self {
+            r: channel(self.r, other.r),
+            g: channel(self.g, other.g),
+            b: channel(self.b, other.b),
+        }
+    }
+
     pub fn map_all(&self, mapper: impl FnOnce(u8) -> u8 + Copy) -> PixelColor {
         Self {
             r: mapper(self.r),
@@ -237,6 +253,164 @@ impl PixelColor {
     }
 }
 
+impl PixelColor {
+    /// Parses a hex color like `#1a2b3c` or the short form `#abc` (the `#` is optional).
+    ///
+    /// ## Example
+    /// ```rust
+    /// # use pixelart::prelude::*;
+    /// assert_eq!(PixelColor::from_hex("#ff0000"), Ok(RED));
+    /// assert_eq!(PixelColor::from_hex("0f0"), Ok(GREEN));
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Self, ParseHexColorError> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        let values = digits
+            .chars()
+            .map(|c| {
+                c.to_digit(16)
+                    .map(|d| d as u8)
+                    .ok_or(ParseHexColorError::InvalidDigit(c))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match values[..] {
+            [r, g, b] => Ok(Self::new(r * 17, g * 17, b * 17)),
+            [r1, r2, g1, g2, b1, b2] => Ok(Self::new(r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2)),
+            _ => Err(ParseHexColorError::InvalidLength(values.len())),
+        }
+    }
+}
+
+impl FromStr for PixelColor {
+    type Err = ParseHexColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
+/// Conversions between rgb and the hsl/hsv color models.
+///
+/// Hue is in degrees (wraps around 360), saturation, lightness and value are from (0.0 to 1.0).
+impl PixelColor {
+    /// Create a new [`PixelColor`] from hue, saturation and lightness.
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32) -> Self {
+        let saturation = saturation.clamp(0., 1.);
+        let lightness = lightness.clamp(0., 1.);
+
+        let chroma = (1. - (2. * lightness - 1.).abs()) * saturation;
+        Self::from_chroma(hue, chroma, lightness - chroma / 2.)
+    }
+
+    /// Create a new [`PixelColor`] from hue, saturation and value.
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let saturation = saturation.clamp(0., 1.);
+        let value = value.clamp(0., 1.);
+
+        let chroma = value * saturation;
+        Self::from_chroma(hue, chroma, value - chroma)
+    }
+
+    /// Hue, saturation and lightness of this color.
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let (hue, max, min) = self.hue_max_min();
+        let lightness = (max + min) / 2.;
+        let saturation = if max == min {
+            0.
+        } else {
+            (max - min) / (1. - (2. * lightness - 1.).abs())
+        };
+        (hue, saturation, lightness)
+    }
+
+    /// Hue, saturation and value of this color.
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let (hue, max, min) = self.hue_max_min();
+        let saturation = if max == 0. { 0. } else { (max - min) / max };
+        (hue, saturation, max)
+    }
+
+    /// Rotates the hue by given degrees, keeping saturation and lightness.
+    pub fn with_hue_shifted(&self, degrees: f32) -> PixelColor {
+        let (hue, saturation, lightness) = self.to_hsl();
+        Self::from_hsl(hue + degrees, saturation, lightness)
+    }
+
+    /// The complementary color, which is on the other side of the color wheel.
+    pub fn complementary(&self) -> PixelColor {
+        self.with_hue_shifted(180.)
+    }
+
+    fn from_chroma(hue: f32, chroma: f32, lightness_match: f32) -> Self {
+        let hue = hue.rem_euclid(360.) / 60.;
+        let x = chroma * (1. - (hue % 2. - 1.).abs());
+        let (r, g, b) = match hue as u8 {
+            0 => (chroma, x, 0.),
+            1 => (x, chroma, 0.),
+            2 => (0., chroma, x),
+            3 => (0., x, chroma),
+            4 => (x, 0., chroma),
+            _ => (chroma, 0., x),
+        };
+
+        let channel = |value: f32| ((value + lightness_match) * 255.).round().clamp(0., 255.) as u8;
+        Self::new(channel(r), channel(g), channel(b))
+    }
+
+    /// Hue in degrees, and the max and min of channels from (0.0 to 1.0).
+    fn hue_max_min(&self) -> (f32, f32, f32) {
+        let (r, g, b) = (
+            self.r as f32 / 255.,
+            self.g as f32 / 255.,
+            self.b as f32 / 255.,
+        );
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+
+        let hue = if delta == 0. {
+            0.
+        } else if max == r {
+            60. * ((g - b) / delta).rem_euclid(6.)
+        } else if max == g {
+            60. * ((b - r) / delta + 2.)
+        } else {
+            60. * ((r - g) / delta + 4.)
+        };
+        (hue, max, min)
+    }
+}
+
+/// How different two colors can be while still counted as the same, e.g. for fills
+/// on imported or anti-aliased images.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ColorTolerance {
+    /// Colors must be equal.
+    #[default]
+    Exact,
+
+    /// No channel differs more than this.
+    PerChannel(u8),
+
+    /// The euclidean distance of rgb values is at most this.
+    Euclidean(f32),
+}
+
+impl ColorTolerance {
+    /// If the two colors are the same within this tolerance.
+    pub fn matches(&self, a: &PixelColor, b: &PixelColor) -> bool {
+        match self {
+            ColorTolerance::Exact => a == b,
+            ColorTolerance::PerChannel(max) => {
+                a.r.abs_diff(b.r) <= *max && a.g.abs_diff(b.g) <= *max && a.b.abs_diff(b.b) <= *max
+            }
+            ColorTolerance::Euclidean(max) => {
+                (palettes::distance_squared(a, b) as f32).sqrt() <= *max
+            }
+        }
+    }
+}
+
 impl TryFrom<Option<PixelColor>> for PixelColor {
     type Error = ColorConversionError<NoInformation>;
 
@@ -339,6 +513,52 @@ mod pixel_color_tests {
 
         assert_eq!(PixelColor::default(), PixelColor::WHITE);
     }
+
+    #[test]
+    fn hsl_and_hsv() {
+        assert_eq!(PixelColor::from_hsl(0., 1., 0.5), PixelColor::RED);
+        assert_eq!(PixelColor::from_hsl(480., 1., 0.5), PixelColor::GREEN);
+        assert_eq!(PixelColor::from_hsv(240., 1., 1.), PixelColor::BLUE);
+        assert_eq!(PixelColor::from_hsv(0., 0., 0.), PixelColor::BLACK);
+
+        assert_eq!(PixelColor::YELLOW.to_hsl(), (60., 1., 0.5));
+        assert_eq!(PixelColor::CYAN.to_hsv(), (180., 1., 1.));
+        assert_eq!(PixelColor::WHITE.to_hsl(), (0., 0., 1.));
+
+        let color = PixelColor::new(30, 120, 200);
+        let (h, s, l) = color.to_hsl();
+        assert_eq!(PixelColor::from_hsl(h, s, l), color);
+        let (h, s, v) = color.to_hsv();
+        assert_eq!(PixelColor::from_hsv(h, s, v), color);
+
+        assert_eq!(PixelColor::RED.with_hue_shifted(120.), PixelColor::GREEN);
+        assert_eq!(PixelColor::BLUE.complementary(), PixelColor::YELLOW);
+    }
+
+    #[test]
+    fn hex_parsing() {
+        assert_eq!("#1a2b3c".parse(), Ok(PixelColor::new(0x1a, 0x2b, 0x3c)));
+        assert_eq!("1A2B3C".parse(), Ok(PixelColor::new(0x1a, 0x2b, 0x3c)));
+        assert_eq!(PixelColor::from_hex("#fff"), Ok(PixelColor::WHITE));
+        assert_eq!(
+            PixelColor::from_hex("#12345"),
+            Err(ParseHexColorError::InvalidLength(5))
+        );
+        assert_eq!(
+            PixelColor::from_hex("#12g"),
+            Err(ParseHexColorError::InvalidDigit('g'))
+        );
+    }
+}
+
+/// Errors of [`PixelColor::from_hex`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum ParseHexColorError {
+    #[error("Expected 3 or 6 hex digits, found {0}.")]
+    InvalidLength(usize),
+
+    #[error("{0:?} is not a hex digit.")]
+    InvalidDigit(char),
 }
 
 #[derive(Debug, Error)]
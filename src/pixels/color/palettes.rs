@@ -0,0 +1,236 @@
+//! Classic constrained palettes and a [`Palette`] type to snap colors to them.
+//!
+
+use std::borrow::Cow;
+
+use super::{quantize::nearest_index, PixelColor};
+
+const fn hex(rgb: u32) -> PixelColor {
+    PixelColor::new((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+}
+
+/// The 16 colors of PICO-8 fantasy console.
+pub const PICO_8: &[PixelColor] = &[
+    hex(0x000000),
+    hex(0x1D2B53),
+    hex(0x7E2553),
+    hex(0x008751),
+    hex(0xAB5236),
+    hex(0x5F574F),
+    hex(0xC2C3C7),
+    hex(0xFFF1E8),
+    hex(0xFF004D),
+    hex(0xFFA300),
+    hex(0xFFEC27),
+    hex(0x00E436),
+    hex(0x29ADFF),
+    hex(0x83769C),
+    hex(0xFF77A8),
+    hex(0xFFCCAA),
+];
+
+/// The 64 entries of the NES (2C02) palette, in hardware order (some are repeated blacks).
+pub const NES: &[PixelColor] = &[
+    hex(0x7C7C7C),
+    hex(0x0000FC),
+    hex(0x0000BC),
+    hex(0x4428BC),
+    hex(0x940084),
+    hex(0xA80020),
+    hex(0xA81000),
+    hex(0x881400),
+    hex(0x503000),
+    hex(0x007800),
+    hex(0x006800),
+    hex(0x005800),
+    hex(0x004058),
+    hex(0x000000),
+    hex(0x000000),
+    hex(0x000000),
+    hex(0xBCBCBC),
+    hex(0x0078F8),
+    hex(0x0058F8),
+    hex(0x6844FC),
+    hex(0xD800CC),
+    hex(0xE40058),
+    hex(0xF83800),
+    hex(0xE45C10),
+    hex(0xAC7C00),
+    hex(0x00B800),
+    hex(0x00A800),
+    hex(0x00A844),
+    hex(0x008888),
+    hex(0x000000),
+    hex(0x000000),
+    hex(0x000000),
+    hex(0xF8F8F8),
+    hex(0x3CBCFC),
+    hex(0x6888FC),
+    hex(0x9878F8),
+    hex(0xF878F8),
+    hex(0xF85898),
+    hex(0xF87858),
+    hex(0xFCA044),
+    hex(0xF8B800),
+    hex(0xB8F818),
+    hex(0x58D854),
+    hex(0x58F898),
+    hex(0x00E8D8),
+    hex(0x787878),
+    hex(0x000000),
+    hex(0x000000),
+    hex(0xFCFCFC),
+    hex(0xA4E4FC),
+    hex(0xB8B8F8),
+    hex(0xD8B8F8),
+    hex(0xF8B8F8),
+    hex(0xF8A4C0),
+    hex(0xF0D0B0),
+    hex(0xFCE0A8),
+    hex(0xF8D878),
+    hex(0xD8F878),
+    hex(0xB8F8B8),
+    hex(0xB8F8D8),
+    hex(0x00FCFC),
+    hex(0xF8D8F8),
+    hex(0x000000),
+    hex(0x000000),
+];
+
+/// The 4 shades of green of the original Game Boy, darkest first.
+pub const GAME_BOY: &[PixelColor] = &[hex(0x0F380F), hex(0x306230), hex(0x8BAC0F), hex(0x9BBC0F)];
+
+/// The 16 colors of CGA (and EGA default) graphics.
+pub const CGA: &[PixelColor] = &[
+    hex(0x000000),
+    hex(0x0000AA),
+    hex(0x00AA00),
+    hex(0x00AAAA),
+    hex(0xAA0000),
+    hex(0xAA00AA),
+    hex(0xAA5500),
+    hex(0xAAAAAA),
+    hex(0x555555),
+    hex(0x5555FF),
+    hex(0x55FF55),
+    hex(0x55FFFF),
+    hex(0xFF5555),
+    hex(0xFF55FF),
+    hex(0xFFFF55),
+    hex(0xFFFFFF),
+];
+
+/// A non-empty set of colors which other colors can be snapped to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    colors: Cow<'static, [PixelColor]>,
+}
+
+impl Palette {
+    /// Create a new [`Palette`] from the given colors.
+    ///
+    /// ## Panics
+    /// If there's no color.
+    pub fn new(colors: impl IntoIterator<Item = impl Into<PixelColor>>) -> Self {
+        Self::from_cow(Cow::Owned(colors.into_iter().map(Into::into).collect()))
+    }
+
+    /// Create a new [`Palette`] from static colors, like [`PICO_8`], without copying them.
+    ///
+    /// ## Panics
+    /// If there's no color.
+    pub const fn from_static(colors: &'static [PixelColor]) -> Self {
+        assert!(!colors.is_empty(), "A palette needs at least one color.");
+        Self {
+            colors: Cow::Borrowed(colors),
+        }
+    }
+
+    fn from_cow(colors: Cow<'static, [PixelColor]>) -> Self {
+        assert!(!colors.is_empty(), "A palette needs at least one color.");
+        Self { colors }
+    }
+
+    pub const fn pico_8() -> Self {
+        Self::from_static(PICO_8)
+    }
+
+    pub const fn nes() -> Self {
+        Self::from_static(NES)
+    }
+
+    pub const fn game_boy() -> Self {
+        Self::from_static(GAME_BOY)
+    }
+
+    pub const fn cga() -> Self {
+        Self::from_static(CGA)
+    }
+
+    pub fn colors(&self) -> &[PixelColor] {
+        &self.colors
+    }
+
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// Always `false`, since a palette has at least one color.
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    pub fn contains(&self, color: &PixelColor) -> bool {
+        self.colors.contains(color)
+    }
+
+    /// Index of the closest color of the palette (by rgb distance), the first one on ties.
+    pub fn nearest_index(&self, color: impl Into<PixelColor>) -> usize {
+        nearest_index(&self.colors, &color.into()).expect("A palette has at least one color.")
+    }
+
+    /// The closest color of the palette (by rgb distance).
+    pub fn nearest(&self, color: impl Into<PixelColor>) -> PixelColor {
+        self.colors[self.nearest_index(color)]
+    }
+}
+
+impl From<&'static [PixelColor]> for Palette {
+    fn from(colors: &'static [PixelColor]) -> Self {
+        Self::from_static(colors)
+    }
+}
+
+impl From<Vec<PixelColor>> for Palette {
+    fn from(colors: Vec<PixelColor>) -> Self {
+        Self::from_cow(Cow::Owned(colors))
+    }
+}
+
+pub(crate) fn distance_squared(a: &PixelColor, b: &PixelColor) -> u32 {
+    let channel = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    channel(a.r, b.r) + channel(a.g, b.g) + channel(a.b, b.b)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_nearest() {
+        assert_eq!(PICO_8.len(), 16);
+        assert_eq!(NES.len(), 64);
+        assert_eq!(CGA.len(), 16);
+
+        let game_boy = Palette::game_boy();
+        assert_eq!(game_boy.nearest(BLACK), GAME_BOY[0]);
+        assert_eq!(game_boy.nearest(WHITE), GAME_BOY[3]);
+
+        let palette = Palette::new([RED, BLUE]);
+        assert_eq!(palette.nearest_index(PixelColor::new(200, 10, 60)), 0);
+        assert_eq!(palette.nearest(PixelColor::new(20, 10, 160)), BLUE);
+        assert!(Palette::cga().contains(&WHITE));
+    }
+}
@@ -0,0 +1,170 @@
+//! Color reduction shared by canvases, imports and GIF export.
+//!
+
+use std::collections::HashMap;
+
+use super::{palettes::distance_squared, PixelColor};
+
+/// Index of the closest color (by rgb distance) in `colors`, the first one on ties.
+///
+/// None if there are no colors.
+pub(crate) fn nearest_index(colors: &[PixelColor], color: &PixelColor) -> Option<usize> {
+    colors
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, candidate)| distance_squared(color, candidate))
+        .map(|(index, _)| index)
+}
+
+/// Floyd–Steinberg dithering of a `width` * `height` image, whose pixels are visited row
+/// by row: the error of each pixel is spread to the neighbors which are not visited yet.
+pub(crate) struct ErrorDiffusion {
+    width: usize,
+    height: usize,
+    errors: Vec<[f32; 3]>,
+}
+
+impl ErrorDiffusion {
+    pub(crate) fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            errors: vec![[0.; 3]; width * height],
+        }
+    }
+
+    /// The rgb at `index` with the error spread to it so far.
+    pub(crate) fn adjust(&self, index: usize, rgb: [f32; 3]) -> [f32; 3] {
+        std::array::from_fn(|channel| rgb[channel] + self.errors[index][channel])
+    }
+
+    /// Spreads the difference of the `wanted` rgb at `index` and the color which is `got`.
+    pub(crate) fn spread(&mut self, index: usize, wanted: [f32; 3], got: &PixelColor) {
+        let error = [
+            wanted[0] - got.r as f32,
+            wanted[1] - got.g as f32,
+            wanted[2] - got.b as f32,
+        ];
+        let (row, column) = (index / self.width, index % self.width);
+        for (row_step, column_step, factor) in
+            [(0, 1isize, 7.), (1, -1, 3.), (1, 0, 5.), (1, 1, 1.)]
+        {
+            let (next_row, next_column) = (row + row_step, column as isize + column_step);
+            if next_row >= self.height || next_column < 0 || next_column as usize >= self.width {
+                continue;
+            }
+            let target = &mut self.errors[next_row * self.width + next_column as usize];
+            for channel in 0..3 {
+                target[channel] += error[channel] * factor / 16.;
+            }
+        }
+    }
+}
+
+/// Rounds the rgb to the nearest valid color.
+pub(crate) fn clamp_rgb(rgb: [f32; 3]) -> PixelColor {
+    let [r, g, b] = rgb.map(|value| value.round().clamp(0., 255.) as u8);
+    PixelColor::new(r, g, b)
+}
+
+/// Reduces weighted colors to at most `max_colors` using median cut.
+pub(crate) fn median_cut(colors: &HashMap<[u8; 3], u32>, max_colors: usize) -> Vec<[u8; 3]> {
+    if colors.len() <= max_colors {
+        return colors.keys().copied().collect();
+    }
+
+    let channel_range = |bucket: &[([u8; 3], u32)], channel: usize| {
+        let (min, max) = bucket
+            .iter()
+            .fold((u8::MAX, u8::MIN), |(min, max), (c, _)| {
+                (min.min(c[channel]), max.max(c[channel]))
+            });
+        max.saturating_sub(min)
+    };
+
+    let mut buckets: Vec<Vec<([u8; 3], u32)>> = vec![colors
+        .iter()
+        .map(|(color, count)| (*color, *count))
+        .collect()];
+
+    while buckets.len() < max_colors {
+        // Split the bucket with the widest channel range.
+        let Some((index, channel, _)) = buckets
+            .iter()
+            .enumerate()
+            .filter(|(_, bucket)| bucket.len() > 1)
+            .flat_map(|(index, bucket)| {
+                (0..3).map(move |channel| (index, channel, channel_range(bucket, channel)))
+            })
+            .max_by_key(|(_, _, range)| *range)
+        else {
+            break;
+        };
+
+        let mut bucket = buckets.swap_remove(index);
+        bucket.sort_unstable_by_key(|(color, _)| color[channel]);
+
+        let total: u64 = bucket.iter().map(|(_, count)| *count as u64).sum();
+        let mut seen = 0;
+        let split_at = bucket
+            .iter()
+            .position(|(_, count)| {
+                seen += *count as u64;
+                seen * 2 >= total
+            })
+            .unwrap_or(0)
+            .clamp(0, bucket.len() - 2)
+            + 1;
+
+        let other = bucket.split_off(split_at);
+        buckets.push(bucket);
+        buckets.push(other);
+    }
+
+    buckets
+        .iter()
+        .map(|bucket| {
+            let total: u64 = bucket.iter().map(|(_, count)| *count as u64).sum();
+            let mut sum = [0u64; 3];
+            for (color, count) in bucket {
+                for (channel, value) in sum.iter_mut().enumerate() {
+                    *value += color[channel] as u64 * *count as u64;
+                }
+            }
+            sum.map(|value| (value / total.max(1)) as u8)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_median_cut_limits_colors() {
+        let colors: HashMap<[u8; 3], u32> = (0..=255u8).map(|v| ([v, v, 255 - v], 1)).collect();
+
+        let palette = median_cut(&colors, 16);
+        assert_eq!(palette.len(), 16);
+
+        let few: HashMap<[u8; 3], u32> = [([1, 2, 3], 5), ([4, 5, 6], 1)].into();
+        assert_eq!(median_cut(&few, 16).len(), 2);
+    }
+
+    #[test]
+    fn test_error_diffusion() {
+        // A flat mid gray on black and white alternates between the two.
+        let colors = [PixelColor::splat(0), PixelColor::splat(255)];
+        let mut diffusion = ErrorDiffusion::new(4, 1);
+        let picked: Vec<usize> = (0..4)
+            .map(|index| {
+                let wanted = diffusion.adjust(index, [128.; 3]);
+                let got = nearest_index(&colors, &clamp_rgb(wanted)).unwrap();
+                diffusion.spread(index, wanted, &colors[got]);
+                got
+            })
+            .collect();
+        assert_eq!(picked, vec![1, 0, 1, 0]);
+        assert_eq!(nearest_index(&[], &colors[0]), None);
+    }
+}
@@ -10,6 +10,7 @@ use super::{
 
 /// A pixel that may not have any effect on the color at this position.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MaybePixel {
     pub color: Option<PixelColor>,
 }
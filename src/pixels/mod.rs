@@ -35,6 +35,7 @@ pub trait PixelMutInterface: PixelInterface {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pixel {
     pub color: PixelColor,
 }
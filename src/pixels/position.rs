@@ -16,6 +16,7 @@ pub mod strict {
     pub const LEFT_CENTER: StrictPositions = StrictPositions::LeftCenter;
 }
 
+/// The four straight [`Direction`]s, clockwise from [`Direction::Up`].
 pub const MAIN_DIRECTIONS: [Direction; 4] = [
     Direction::Up,
     Direction::Right,
@@ -23,6 +24,7 @@ pub const MAIN_DIRECTIONS: [Direction; 4] = [
     Direction::Left,
 ];
 
+/// The four diagonal [`Direction`]s, clockwise from [`Direction::UpRight`].
 pub const MINOR_DIRECTIONS: [Direction; 4] = [
     Direction::UpRight,
     Direction::DownRight,
@@ -30,6 +32,42 @@ pub const MINOR_DIRECTIONS: [Direction; 4] = [
     Direction::UpLeft,
 ];
 
+/// Same as [`MINOR_DIRECTIONS`].
+pub const DIAGONAL_DIRECTIONS: [Direction; 4] = MINOR_DIRECTIONS;
+
+/// All eight [`Direction`]s, clockwise from [`Direction::Up`].
+pub const ALL_DIRECTIONS: [Direction; 8] = [
+    Direction::Up,
+    Direction::UpRight,
+    Direction::Right,
+    Direction::DownRight,
+    Direction::Down,
+    Direction::DownLeft,
+    Direction::Left,
+    Direction::UpLeft,
+];
+
+/// Which neighbors of a pixel count as connected to it, e.g. while flood filling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Connectivity {
+    /// Only the [`MAIN_DIRECTIONS`], so fills stop at diagonal gaps.
+    #[default]
+    Four,
+
+    /// [`ALL_DIRECTIONS`], including diagonals.
+    Eight,
+}
+
+impl Connectivity {
+    /// Directions of the connected neighbors.
+    pub fn directions(&self) -> &'static [Direction] {
+        match self {
+            Connectivity::Four => &MAIN_DIRECTIONS,
+            Connectivity::Eight => &ALL_DIRECTIONS,
+        }
+    }
+}
+
 /// Interface for a pixel position.
 pub trait PixelPositionInterface {
     /// Row number of the position starting from 0.
@@ -52,18 +90,12 @@ pub trait PixelPositionInterface {
 
     /// Returns a [`PixelPosition`] above this one as far as possible (0).
     fn up(&self, amount: usize) -> PixelPosition {
-        PixelPosition::new(
-            self.row().checked_sub(amount).unwrap_or_default(),
-            self.column(),
-        )
+        PixelPosition::new(self.row().saturating_sub(amount), self.column())
     }
 
     /// Returns a [`PixelPosition`] at the left side of this one as far as possible (0).
     fn left(&self, amount: usize) -> PixelPosition {
-        PixelPosition::new(
-            self.row(),
-            self.column().checked_sub(amount).unwrap_or_default(),
-        )
+        PixelPosition::new(self.row(), self.column().saturating_sub(amount))
     }
 
     /// Returns a [`PixelPosition`] below this one as far as possible.
@@ -106,6 +138,21 @@ pub trait PixelStrictPositionInterface<const H: usize, const W: usize> {
         (self.row(), self.column())
     }
 
+    /// Positions next to this one which are inside the bounds, clockwise from the one above.
+    fn neighbors(
+        &self,
+        connectivity: Connectivity,
+    ) -> impl Iterator<Item = PixelStrictPosition<H, W>> {
+        let (row, column) = (self.row() as isize, self.column() as isize);
+        connectivity.directions().iter().filter_map(move |dir| {
+            let (delta_row, delta_column) = dir.delta();
+            OutOfBoundsPolicy::Clip
+                .resolve::<H, W>((row + delta_row, column + delta_column))
+                .ok()
+                .flatten()
+        })
+    }
+
     /// Convert this [`PixelStrictPosition`] to a [`PixelPosition`], breaking the bounds.
     fn unbound(&self) -> PixelPosition {
         PixelPosition::new(self.row(), self.column())
@@ -272,6 +319,94 @@ impl<const H: usize, const W: usize> PixelStrictPosition<H, W> {
     pub fn new(row: usize, column: usize) -> Result<Self, PixelPositionOutOfBoundError<H, W>> {
         PixelPositionOutOfBoundError::validate_position(row, column)
     }
+
+    /// Create a new [`PixelStrictPosition`] in a const context.
+    ///
+    /// # Panics
+    /// Panics if provided row or column are out of bound, which is a compile time error
+    /// when evaluated as a constant. See [`pos!`](crate::pos) for a shorthand.
+    pub const fn new_const(row: usize, column: usize) -> Self {
+        assert!(row < H, "The provided row is out of bound.");
+        assert!(column < W, "The provided column is out of bound.");
+
+        Self {
+            raw: PixelPosition::new(row, column),
+        }
+    }
+}
+
+impl<const H: usize, const W: usize> PixelStrictPosition<H, W> {
+    /// All positions of a `H` * `W` canvas in a clockwise square spiral going out of `center`,
+    /// e.g. for spiral wipes. Parts of the spiral outside of the canvas are skipped.
+    pub fn spiral_from(
+        center: impl IntoPixelStrictPosition<H, W>,
+    ) -> impl Iterator<Item = PixelStrictPosition<H, W>> {
+        let (row, column) = center.into_pixel_strict_position().expand();
+        let start = (row as isize, column as isize);
+
+        // Steps of the spiral: 1 right, 1 down, 2 left, 2 up, 3 right and so on.
+        let steps = (1..).flat_map(|length| {
+            let first = if length % 2 == 1 {
+                Direction::Right
+            } else {
+                Direction::Left
+            };
+            let second = first.rotate_cw(2);
+            std::iter::repeat_n(first, length).chain(std::iter::repeat_n(second, length))
+        });
+
+        std::iter::once(start)
+            .chain(steps.scan(start, |(row, column), dir| {
+                let (delta_row, delta_column) = dir.delta();
+                *row += delta_row;
+                *column += delta_column;
+                Some((*row, *column))
+            }))
+            .filter_map(|(row, column)| {
+                OutOfBoundsPolicy::Clip
+                    .resolve::<H, W>((row, column))
+                    .ok()
+                    .flatten()
+            })
+            .take(H * W)
+    }
+}
+
+/// Anti-diagonals of a `H` * `W` canvas starting from top left, each going from top right to
+/// bottom left. Positions of a diagonal have the same `row + column`, handy for diagonal reveals.
+pub fn diagonals<const H: usize, const W: usize>(
+) -> impl Iterator<Item = Vec<PixelStrictPosition<H, W>>> {
+    (0..(H + W).saturating_sub(1)).map(|sum| {
+        (sum.saturating_sub(W - 1)..H.min(sum + 1))
+            .map(|row| PixelStrictPosition::new_const(row, sum - row))
+            .collect()
+    })
+}
+
+/// Creates a [`PixelStrictPosition`] which is validated at compile time.
+///
+/// ## Example
+/// ```rust
+/// # use pixelart::prelude::*;
+/// # use pixelart::pixels::position::{PixelStrictPosition, PixelStrictPositionInterface};
+/// let pos: PixelStrictPosition<5, 5> = pos!(1, 3; 5, 5);
+/// assert_eq!(pos.expand(), (1, 3));
+/// ```
+///
+/// This won't compile since the row is out of bound.
+/// ```compile_fail
+/// # use pixelart::prelude::*;
+/// let pos = pos!(5, 3; 5, 5);
+/// ```
+#[macro_export]
+macro_rules! pos {
+    ($row:expr, $column:expr; $h:expr, $w:expr) => {
+        const {
+            $crate::pixels::position::PixelStrictPosition::<{ $h }, { $w }>::new_const(
+                $row, $column,
+            )
+        }
+    };
 }
 
 impl<const H: usize, const W: usize> PixelStrictPositionInterface<H, W>
@@ -335,6 +470,227 @@ impl<const H: usize, const W: usize> IntoPixelStrictPosition<H, W> for [usize; 2
     }
 }
 
+/// Decides what happens to things that go past the edges of a container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutOfBoundsPolicy {
+    /// Anything out of bounds is dropped (or stays at the edge for pens).
+    #[default]
+    Clip,
+
+    /// Wraps around to the other side of the container, useful for tiling.
+    Wrap,
+
+    /// Report an [`OutOfBoundsError`] instead.
+    Error,
+}
+
+impl OutOfBoundsPolicy {
+    /// Resolves a signed `(row, column)` based on this policy.
+    ///
+    /// Returns `Ok(None)` if the position should be clipped.
+    pub fn resolve<const H: usize, const W: usize>(
+        &self,
+        pos: (isize, isize),
+    ) -> Result<Option<PixelStrictPosition<H, W>>, OutOfBoundsError> {
+        Ok(self
+            .resolve_in(pos, (H, W))?
+            .and_then(|(row, column)| PixelStrictPosition::new(row, column).ok()))
+    }
+
+    /// As same as [`Self::resolve`], for containers of `(height, width)` only known at runtime.
+    pub fn resolve_in(
+        &self,
+        (row, column): (isize, isize),
+        (height, width): (usize, usize),
+    ) -> Result<Option<(usize, usize)>, OutOfBoundsError> {
+        if (0..height as isize).contains(&row) && (0..width as isize).contains(&column) {
+            return Ok(Some((row as usize, column as usize)));
+        }
+
+        match self {
+            OutOfBoundsPolicy::Clip => Ok(None),
+            // There's nothing to wrap into.
+            OutOfBoundsPolicy::Wrap if height == 0 || width == 0 => Ok(None),
+            OutOfBoundsPolicy::Wrap => Ok(Some((
+                row.rem_euclid(height as isize) as usize,
+                column.rem_euclid(width as isize) as usize,
+            ))),
+            OutOfBoundsPolicy::Error => Err(OutOfBoundsError {
+                row,
+                column,
+                height,
+                width,
+            }),
+        }
+    }
+}
+
+/// What happens to pixels which are shifted past the edges of a canvas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShiftMode {
+    /// Pixels come back from the other side, e.g. for scrolling backgrounds.
+    Wrap,
+
+    /// Pixels are dropped, the empty space takes the default color.
+    #[default]
+    Clip,
+}
+
+impl From<ShiftMode> for OutOfBoundsPolicy {
+    fn from(value: ShiftMode) -> Self {
+        match value {
+            ShiftMode::Wrap => OutOfBoundsPolicy::Wrap,
+            ShiftMode::Clip => OutOfBoundsPolicy::Clip,
+        }
+    }
+}
+
+/// Something went out of the bounds while using [`OutOfBoundsPolicy::Error`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("The position ({row}, {column}) is out of bound ({height}, {width}).")]
+pub struct OutOfBoundsError {
+    pub row: isize,
+    pub column: isize,
+    pub height: usize,
+    pub width: usize,
+}
+
+/// Starts a [`RelativePosition`] from an anchor, like [`StrictPositions::TopLeft`] or any
+/// previously resolved position.
+///
+/// ## Example
+/// ```rust
+/// # use pixelart::prelude::*;
+/// # use pixelart::pixels::position::{from, PixelStrictPosition, PixelStrictPositionInterface};
+/// let pos: PixelStrictPosition<5, 5> = from(TOP_LEFT).down(3).right(2).resolve().unwrap();
+/// assert_eq!(pos.expand(), (3, 2));
+/// ```
+pub fn from<const H: usize, const W: usize>(
+    anchor: impl IntoPixelStrictPosition<H, W>,
+) -> RelativePosition<H, W> {
+    RelativePosition::new(anchor)
+}
+
+/// A position described by an anchor and a set of moves from there.
+///
+/// Unlike `bounding_*` methods, moves are accumulated without any clamping and the bounds
+/// are only checked once the position is resolved. So going out and back in is fine.
+///
+/// It's not converted to a [`PixelStrictPosition`] implicitly, use [`RelativePosition::resolve`],
+/// [`RelativePosition::resolve_with_policy`] or [`RelativePosition::bounding`] to decide what
+/// happens to a target out of bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelativePosition<const H: usize, const W: usize> {
+    anchor: PixelStrictPosition<H, W>,
+    row_offset: isize,
+    column_offset: isize,
+}
+
+impl<const H: usize, const W: usize> RelativePosition<H, W> {
+    /// Create a new [`RelativePosition`] with no moves from the `anchor`.
+    pub fn new(anchor: impl IntoPixelStrictPosition<H, W>) -> Self {
+        Self {
+            anchor: anchor.into_pixel_strict_position(),
+            row_offset: 0,
+            column_offset: 0,
+        }
+    }
+
+    /// Moves `amount` rows up.
+    pub fn up(self, amount: usize) -> Self {
+        self.offset(-(amount as isize), 0)
+    }
+
+    /// Moves `amount` rows down.
+    pub fn down(self, amount: usize) -> Self {
+        self.offset(amount as isize, 0)
+    }
+
+    /// Moves `amount` columns to the left.
+    pub fn left(self, amount: usize) -> Self {
+        self.offset(0, -(amount as isize))
+    }
+
+    /// Moves `amount` columns to the right.
+    pub fn right(self, amount: usize) -> Self {
+        self.offset(0, amount as isize)
+    }
+
+    /// Moves `amount` steps toward the [`Direction`].
+    pub fn direction(self, dir: Direction, amount: usize) -> Self {
+        let (rows, columns) = dir.delta();
+        self.offset(rows * amount as isize, columns * amount as isize)
+    }
+
+    /// Moves by a signed `(row, column)` offset.
+    pub fn offset(self, rows: isize, columns: isize) -> Self {
+        Self {
+            row_offset: self.row_offset + rows,
+            column_offset: self.column_offset + columns,
+            ..self
+        }
+    }
+
+    /// The anchor this position is relative to.
+    pub fn anchor(&self) -> PixelStrictPosition<H, W> {
+        self.anchor
+    }
+
+    /// The signed `(row, column)` this position points to, which may be out of bounds.
+    pub fn target(&self) -> (isize, isize) {
+        (
+            self.anchor.row() as isize + self.row_offset,
+            self.anchor.column() as isize + self.column_offset,
+        )
+    }
+
+    /// Resolves to a [`PixelStrictPosition`] **IF** it's inside the bounds.
+    ///
+    /// Targets above or at the left side of the container are reported as row or column `0`
+    /// in the error.
+    pub fn resolve(&self) -> StrictPositionValidationResult<H, W> {
+        let (row, column) = self.target();
+        match (row < 0, column < 0) {
+            (false, false) => PixelStrictPosition::new(row as usize, column as usize),
+            (true, false) => Err(PixelPositionOutOfBoundError::InvalidRow(
+                PixelPosition::new(0, column as usize),
+            )),
+            (false, true) => Err(PixelPositionOutOfBoundError::InvalidColumn(
+                PixelPosition::new(row as usize, 0),
+            )),
+            (true, true) => Err(PixelPositionOutOfBoundError::InvalidBoth(
+                PixelPosition::new(0, 0),
+            )),
+        }
+    }
+
+    /// Resolves to a [`PixelStrictPosition`] using the [`OutOfBoundsPolicy`].
+    ///
+    /// Returns `Ok(None)` if the target is clipped.
+    pub fn resolve_with_policy(
+        &self,
+        policy: OutOfBoundsPolicy,
+    ) -> Result<Option<PixelStrictPosition<H, W>>, OutOfBoundsError> {
+        policy.resolve(self.target())
+    }
+
+    /// Resolves to the nearest [`PixelStrictPosition`] inside the bounds.
+    pub fn bounding(&self) -> PixelStrictPosition<H, W> {
+        let (row, column) = self.target();
+        PixelStrictPosition::new(
+            row.clamp(0, H as isize - 1) as usize,
+            column.clamp(0, W as isize - 1) as usize,
+        )
+        .unwrap()
+    }
+
+    /// Starts a new [`RelativePosition`] using the resolved position as anchor, see
+    /// [`RelativePosition::resolve`].
+    pub fn then(self) -> Result<Self, PixelPositionOutOfBoundError<H, W>> {
+        self.resolve().map(Self::new)
+    }
+}
+
 /// A set of common useful [`PixelStrictPosition`]s inside the container
 /// wrapped by square from `(H - 1, 0) -> bottom-left` to `(0, W - 1) -> top-right`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -401,6 +757,7 @@ impl<const H: usize, const W: usize> PixelStrictPositionInterface<H, W> for Stri
 
 /// Represents a direction.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Direction {
     /// Going up.
     Up,
@@ -427,6 +784,50 @@ pub enum Direction {
     UpLeft,
 }
 
+impl Direction {
+    /// Index of this direction inside [`ALL_DIRECTIONS`].
+    fn index(&self) -> usize {
+        ALL_DIRECTIONS
+            .iter()
+            .position(|dir| dir == self)
+            .expect("All directions are there.")
+    }
+
+    /// The direction pointing the other way, e.g. [`Direction::Left`] for [`Direction::Right`].
+    pub fn opposite(&self) -> Direction {
+        self.rotate_cw(4)
+    }
+
+    /// Rotates clockwise by `steps` of 45 degrees.
+    pub fn rotate_cw(&self, steps: usize) -> Direction {
+        ALL_DIRECTIONS[(self.index() + steps) % 8]
+    }
+
+    /// Rotates counter clockwise by `steps` of 45 degrees.
+    pub fn rotate_ccw(&self, steps: usize) -> Direction {
+        ALL_DIRECTIONS[(self.index() + 8 - steps % 8) % 8]
+    }
+
+    /// Change of `(row, column)` when taking one step toward this direction.
+    pub fn delta(&self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::UpRight => (-1, 1),
+            Direction::Right => (0, 1),
+            Direction::DownRight => (1, 1),
+            Direction::Down => (1, 0),
+            Direction::DownLeft => (1, -1),
+            Direction::Left => (0, -1),
+            Direction::UpLeft => (-1, -1),
+        }
+    }
+
+    /// If this is one of [`DIAGONAL_DIRECTIONS`].
+    pub fn is_diagonal(&self) -> bool {
+        MINOR_DIRECTIONS.contains(self)
+    }
+}
+
 impl Iterator for Direction {
     type Item = Direction;
 
@@ -484,6 +885,66 @@ impl Iterator for SingleCycle {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_neighbors() {
+        let corner = PixelStrictPosition::<3, 3>::new(0, 0).unwrap();
+        let neighbors = |pos: PixelStrictPosition<3, 3>, connectivity| {
+            pos.neighbors(connectivity)
+                .map(|pos| pos.expand())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(neighbors(corner, Connectivity::Four), vec![(0, 1), (1, 0)]);
+        assert_eq!(
+            neighbors(corner, Connectivity::Eight),
+            vec![(0, 1), (1, 1), (1, 0)]
+        );
+        assert_eq!(
+            PixelStrictPosition::<3, 3>::new(1, 1)
+                .unwrap()
+                .neighbors(Connectivity::Eight)
+                .count(),
+            8
+        );
+    }
+
+    #[test]
+    fn test_spiral_and_diagonals() {
+        let spiral: Vec<_> = PixelStrictPosition::<3, 3>::spiral_from(strict::CENTER)
+            .map(|pos| pos.expand())
+            .collect();
+        assert_eq!(
+            spiral,
+            vec![
+                (1, 1),
+                (1, 2),
+                (2, 2),
+                (2, 1),
+                (2, 0),
+                (1, 0),
+                (0, 0),
+                (0, 1),
+                (0, 2)
+            ]
+        );
+        assert_eq!(
+            PixelStrictPosition::<2, 5>::spiral_from(strict::TOP_LEFT).count(),
+            10
+        );
+
+        let diagonals: Vec<Vec<_>> = diagonals::<2, 3>()
+            .map(|diagonal| diagonal.iter().map(|pos| pos.expand()).collect())
+            .collect();
+        assert_eq!(
+            diagonals,
+            vec![
+                vec![(0, 0)],
+                vec![(0, 1), (1, 0)],
+                vec![(0, 2), (1, 1)],
+                vec![(1, 2)]
+            ]
+        );
+    }
+
     #[test]
     fn test_name() {
         let pos = PixelStrictPosition::<5, 5>::new(0, 0).unwrap();
@@ -526,6 +987,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_const_position() {
+        const POS: PixelStrictPosition<5, 3> = PixelStrictPosition::new_const(4, 2);
+
+        assert_eq!(POS, PixelStrictPosition::new(4, 2).unwrap());
+        assert_eq!(crate::pos!(1, 0; 2, 2).expand(), (1, 0));
+    }
+
+    #[test]
+    fn test_relative_position() {
+        let pos = from::<5, 5>(StrictPositions::TopLeft).down(3).right(2);
+        assert_eq!(pos.resolve().unwrap().expand(), (3, 2));
+
+        // Going out and back in is fine.
+        let pos = from::<5, 5>(StrictPositions::TopLeft).up(2).down(3);
+        assert_eq!(pos.resolve().unwrap().expand(), (1, 0));
+
+        let pos = from::<5, 5>(StrictPositions::BottomRight).right(2);
+        assert!(pos.resolve().is_err());
+        assert_eq!(pos.bounding().expand(), (4, 4));
+        assert_eq!(
+            pos.resolve_with_policy(OutOfBoundsPolicy::Error),
+            Err(OutOfBoundsError {
+                row: 4,
+                column: 6,
+                height: 5,
+                width: 5,
+            })
+        );
+        assert_eq!(pos.resolve_with_policy(OutOfBoundsPolicy::Clip), Ok(None));
+        assert_eq!(
+            pos.resolve_with_policy(OutOfBoundsPolicy::Wrap)
+                .unwrap()
+                .map(|p| p.expand()),
+            Some((4, 1))
+        );
+
+        let pos = from::<5, 5>(StrictPositions::Center)
+            .right(1)
+            .then()
+            .unwrap()
+            .down(1);
+        assert_eq!(pos.resolve().unwrap().expand(), (3, 3));
+        assert!(from::<5, 5>(StrictPositions::Center)
+            .left(10)
+            .then()
+            .is_err());
+    }
+
+    #[test]
+    fn test_out_of_bounds_policy() {
+        let inside = OutOfBoundsPolicy::Error.resolve::<3, 3>((1, 2)).unwrap();
+        assert_eq!(inside.map(|p| p.expand()), Some((1, 2)));
+
+        assert_eq!(
+            OutOfBoundsPolicy::Clip.resolve::<3, 3>((3, 0)).unwrap(),
+            None
+        );
+        assert_eq!(
+            OutOfBoundsPolicy::Wrap
+                .resolve::<3, 3>((-1, 4))
+                .unwrap()
+                .map(|p| p.expand()),
+            Some((2, 1))
+        );
+        assert!(OutOfBoundsPolicy::Error.resolve::<3, 3>((0, -1)).is_err());
+    }
+
     #[test]
     fn test_iter() {
         let mut pos = PixelStrictPosition::<2, 2>::new(0, 0).unwrap();
@@ -566,4 +1095,20 @@ mod tests {
         assert_eq!(Some(DownRight), single.next());
         assert_eq!(None, single.next());
     }
+
+    #[test]
+    fn test_direction_math() {
+        assert_eq!(Direction::Up.opposite(), Direction::Down);
+        assert_eq!(Direction::UpLeft.opposite(), Direction::DownRight);
+        assert_eq!(Direction::Up.rotate_cw(2), Direction::Right);
+        assert_eq!(Direction::Up.rotate_ccw(1), Direction::UpLeft);
+        assert_eq!(Direction::Left.rotate_cw(10), Direction::Up);
+
+        for dir in ALL_DIRECTIONS {
+            let (row, column) = dir.delta();
+            let (opposite_row, opposite_column) = dir.opposite().delta();
+            assert_eq!((row + opposite_row, column + opposite_column), (0, 0));
+            assert_eq!(dir.is_diagonal(), row != 0 && column != 0);
+        }
+    }
 }
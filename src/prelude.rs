@@ -1,13 +1,21 @@
 pub use crate::pixels::{
     canvas::{
-        drawable::Drawable, pen::PixelPen, MaybePixelCanvas, PixelCanvas, PixelCanvasExt as _,
-        PixelCanvasMutExt as _, SharedMutPixelCanvasExt as _, SharedPixelCanvasExt as _,
+        dense::DensePixelCanvas,
+        drawable::Drawable,
+        dynamic::DynPixelCanvas,
+        grid::{PixelGridExt as _, PixelGridInterface as _, PixelGridMutExt as _},
+        pen::{PenMode, PixelPen, StrokeStyle},
+        MaybePixelCanvas, PixelCanvas, PixelCanvasExt as _, PixelCanvasMutExt as _,
+        SharedMutPixelCanvasExt as _, SharedPixelCanvasExt as _,
     },
     color::{colors::*, PixelColor, PixelColorExt as _},
     maybe::MaybePixel,
     position::{strict::*, PixelPositionInterface as _, StrictPositions},
     Pixel, PixelInterface as _, PixelIterExt as _, PixelIterMutExt as _, PixelMutInterface as _,
 };
+pub use crate::pos;
 
 #[cfg(feature = "viewer")]
-pub use crate::viewer::ViewResult;
+pub use crate::viewer::{
+    render_viewer_frame_to_image, ViewResult, ViewerLayout, ViewerOptions, ViewerTheme,
+};
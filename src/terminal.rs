@@ -0,0 +1,93 @@
+//! Renders canvases in a terminal using 24-bit ANSI colors, handy for quick debugging.
+//!
+//! Every line of text shows two rows of pixels, using the upper half block character (`▀`)
+//! with the top pixel as foreground and the bottom one as background.
+//! Transparent pixels keep the terminal's own colors.
+
+use image::Rgba;
+
+use crate::pixels::{canvas::PixelCanvasInterface, color::RgbaInterface, PixelInterface};
+
+const UPPER_HALF_BLOCK: char = '▀';
+const LOWER_HALF_BLOCK: char = '▄';
+const RESET: &str = "\x1b[0m";
+
+fn is_transparent(color: Rgba<u8>) -> bool {
+    color.0[3] == 0
+}
+
+/// Renders the canvas as lines of ANSI escaped text, see the [module docs](self).
+pub fn ansi_string<const H: usize, const W: usize, P, I>(canvas: &I) -> String
+where
+    P: PixelInterface + Default,
+    P::ColorType: RgbaInterface,
+    I: PixelCanvasInterface<H, W, P>,
+{
+    let table = canvas.table();
+    let color_at = |row: usize, column: usize| {
+        if row < H {
+            table.get_pixel((row, column)).color().rgba()
+        } else {
+            // The missing row under odd heights.
+            Rgba([0, 0, 0, 0])
+        }
+    };
+
+    let mut result = String::new();
+    for row in (0..H).step_by(2) {
+        for column in 0..W {
+            let top = color_at(row, column);
+            let bottom = color_at(row + 1, column);
+
+            match (is_transparent(top), is_transparent(bottom)) {
+                (true, true) => result.push(' '),
+                (false, true) => {
+                    let Rgba([r, g, b, _]) = top;
+                    result.push_str(&format!("\x1b[38;2;{r};{g};{b}m{UPPER_HALF_BLOCK}"));
+                }
+                (true, false) => {
+                    // No foreground, so draw the bottom half as foreground instead.
+                    let Rgba([r, g, b, _]) = bottom;
+                    result.push_str(&format!("\x1b[38;2;{r};{g};{b}m{LOWER_HALF_BLOCK}"));
+                }
+                (false, false) => {
+                    let Rgba([tr, tg, tb, _]) = top;
+                    let Rgba([br, bg, bb, _]) = bottom;
+                    result.push_str(&format!(
+                        "\x1b[38;2;{tr};{tg};{tb}m\x1b[48;2;{br};{bg};{bb}m{UPPER_HALF_BLOCK}"
+                    ));
+                }
+            }
+            result.push_str(RESET);
+        }
+        result.push('\n');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_ansi_string() {
+        let mut canvas = MaybePixelCanvas::<3, 2>::default();
+        canvas.update_color_at(TOP_LEFT, RED);
+        canvas.update_color_at(pos!(1, 0; 3, 2), BLUE);
+        canvas.update_color_at(BOTTOM_RIGHT, BLACK);
+
+        let ansi = canvas.to_ansi();
+        let lines: Vec<_> = ansi.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            format!("\x1b[38;2;255;0;0m\x1b[48;2;0;0;255m{UPPER_HALF_BLOCK}{RESET} {RESET}")
+        );
+        assert_eq!(
+            lines[1],
+            format!(" {RESET}\x1b[38;2;0;0;0m{UPPER_HALF_BLOCK}{RESET}")
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Two images in one window to spot their differences, see [`compare`].
+//!
+
+use eframe::egui::{self, ColorImage, TextureHandle, TextureOptions};
+
+use crate::image::DefaultImageBuffer;
+
+use super::{ViewerOptions, PIXELART_ICON, TOOLBAR_HEIGHT, WINDOW_PADDING};
+
+/// What the comparison window shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum CompareMode {
+    /// The first image on the left of a draggable divider, and the second one on the right.
+    #[default]
+    Wipe,
+    OnlyA,
+    OnlyB,
+}
+
+/// Shows two images on top of each other in a single window, with a divider which can be
+/// dragged to reveal more of one or the other. The toolbar can switch to only one of them.
+///
+/// Canvases can be compared using their images, like `canvas.default_image_builder().get_image()`.
+pub fn compare(a: DefaultImageBuffer, b: DefaultImageBuffer) -> eframe::Result {
+    compare_with_options(a, b, ViewerOptions::default())
+}
+
+/// As same as [`compare`], using the given [`ViewerOptions`].
+pub fn compare_with_options(
+    a: DefaultImageBuffer,
+    b: DefaultImageBuffer,
+    viewer_options: ViewerOptions,
+) -> eframe::Result {
+    let size = egui::vec2(
+        a.width().max(b.width()) as f32,
+        a.height().max(b.height()) as f32,
+    ) * viewer_options.start_scale;
+    let title = viewer_options
+        .title
+        .clone()
+        .unwrap_or_else(|| "Pixelart (compare)".to_string());
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_title(title.clone())
+            .with_resizable(viewer_options.resizable)
+            .with_maximize_button(viewer_options.resizable)
+            .with_icon(PIXELART_ICON.clone())
+            .with_inner_size([
+                size.x + WINDOW_PADDING,
+                size.y + WINDOW_PADDING + TOOLBAR_HEIGHT,
+            ]),
+        ..Default::default()
+    };
+
+    eframe::run_native(
+        &title,
+        options,
+        Box::new(move |cc| {
+            viewer_options.apply_theme(&cc.egui_ctx);
+            let load = |name, image: DefaultImageBuffer| {
+                let scaled = egui::vec2(image.width() as f32, image.height() as f32)
+                    * viewer_options.start_scale;
+                let texture = cc.egui_ctx.load_texture(
+                    name,
+                    ColorImage::from_rgba_unmultiplied(
+                        [image.width() as usize, image.height() as usize],
+                        &image.into_raw(),
+                    ),
+                    TextureOptions::NEAREST,
+                );
+                (texture, scaled)
+            };
+            Ok(Box::new(CompareApp {
+                a: load("a", a),
+                b: load("b", b),
+                size,
+                divider: 0.5,
+                mode: CompareMode::default(),
+                options: viewer_options,
+            }))
+        }),
+    )
+}
+
+/// Position of the divider (`0.0` on the left to `1.0` on the right) under the pointer.
+fn divider_at(rect: egui::Rect, pointer_x: f32) -> f32 {
+    if rect.width() <= 0. {
+        return 0.5;
+    }
+    ((pointer_x - rect.min.x) / rect.width()).clamp(0., 1.)
+}
+
+struct CompareApp {
+    a: (TextureHandle, egui::Vec2),
+    b: (TextureHandle, egui::Vec2),
+    size: egui::Vec2,
+    divider: f32,
+    mode: CompareMode,
+    options: ViewerOptions,
+}
+
+impl eframe::App for CompareApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::TopBottomPanel::top("toolbar")
+            .exact_height(TOOLBAR_HEIGHT)
+            .show(ctx, |ui| {
+                ui.horizontal_centered(|ui| {
+                    ui.selectable_value(&mut self.mode, CompareMode::Wipe, "A | B");
+                    ui.selectable_value(&mut self.mode, CompareMode::OnlyA, "A");
+                    ui.selectable_value(&mut self.mode, CompareMode::OnlyB, "B");
+                    if ctx.input(|input| input.key_pressed(egui::Key::Space)) {
+                        self.mode = match self.mode {
+                            CompareMode::OnlyA => CompareMode::OnlyB,
+                            _ => CompareMode::OnlyA,
+                        };
+                    }
+                    ui.weak("Space toggles between A and B");
+                });
+            });
+
+        self.options.central_panel().show(ctx, |ui| {
+            let (rect, response) = ui.allocate_exact_size(self.size, egui::Sense::drag());
+            if let Some(pointer) = response.interact_pointer_pos() {
+                self.divider = divider_at(rect, pointer.x);
+            }
+
+            let uv = egui::Rect::from_min_max(egui::pos2(0., 0.), egui::pos2(1., 1.));
+            let paint = |painter: &egui::Painter, (texture, size): &(TextureHandle, egui::Vec2)| {
+                painter.image(
+                    texture.id(),
+                    egui::Rect::from_min_size(rect.min, *size),
+                    uv,
+                    egui::Color32::WHITE,
+                );
+            };
+            match self.mode {
+                CompareMode::OnlyA => paint(ui.painter(), &self.a),
+                CompareMode::OnlyB => paint(ui.painter(), &self.b),
+                CompareMode::Wipe => {
+                    let x = rect.min.x + rect.width() * self.divider;
+                    let (left, right) = rect.split_left_right_at_x(x);
+                    paint(&ui.painter().with_clip_rect(right), &self.b);
+                    paint(&ui.painter().with_clip_rect(left), &self.a);
+                    ui.painter()
+                        .vline(x, rect.y_range(), (2., ui.visuals().strong_text_color()));
+                    response.on_hover_cursor(egui::CursorIcon::ResizeHorizontal);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_divider_at() {
+        let rect = egui::Rect::from_min_size(egui::pos2(10., 0.), egui::vec2(100., 50.));
+        assert_eq!(divider_at(rect, 35.), 0.25);
+        assert_eq!(divider_at(rect, 0.), 0.);
+        assert_eq!(divider_at(rect, 200.), 1.);
+    }
+}
@@ -0,0 +1,185 @@
+//! A minimal pixel editor, see [`edit`].
+//!
+
+use std::sync::{Arc, Mutex};
+
+use eframe::egui::{self, ColorImage, TextureHandle, TextureOptions};
+
+use crate::pixels::{
+    canvas::{PixelCanvas, SharedMutPixelCanvasExt, SharedPixelCanvasExt},
+    color::{PixelColor, RgbaInterface},
+    position::PixelStrictPosition,
+    PixelMutInterface,
+};
+
+use super::{ViewerOptions, PIXELART_ICON, TOOLBAR_HEIGHT, WINDOW_PADDING};
+
+/// Opens the canvas in an editor window, returning the edited canvas when it's closed.
+///
+/// Left click paints with the color picked in the toolbar, right click erases
+/// (sets the default color of the pixel type).
+pub fn edit<const H: usize, const W: usize, P>(
+    canvas: PixelCanvas<H, W, P>,
+) -> eframe::Result<PixelCanvas<H, W, P>>
+where
+    P: PixelMutInterface + PartialEq + Clone + Default + 'static,
+    P::ColorType: RgbaInterface + Default + Clone + TryFrom<Option<PixelColor>>,
+{
+    edit_with_options(canvas, ViewerOptions::default())
+}
+
+/// As same as [`edit`], using the given [`ViewerOptions`].
+pub fn edit_with_options<const H: usize, const W: usize, P>(
+    canvas: PixelCanvas<H, W, P>,
+    viewer_options: ViewerOptions,
+) -> eframe::Result<PixelCanvas<H, W, P>>
+where
+    P: PixelMutInterface + PartialEq + Clone + Default + 'static,
+    P::ColorType: RgbaInterface + Default + Clone + TryFrom<Option<PixelColor>>,
+{
+    let (width, height) = canvas.default_image_builder().get_image().dimensions();
+    let scale = viewer_options.start_scale;
+    let title = viewer_options
+        .title
+        .clone()
+        .unwrap_or_else(|| "Pixelart editor".to_string());
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_title(title.clone())
+            .with_resizable(viewer_options.resizable)
+            .with_maximize_button(viewer_options.resizable)
+            .with_icon(PIXELART_ICON.clone())
+            .with_inner_size([
+                width as f32 * scale + WINDOW_PADDING,
+                height as f32 * scale + WINDOW_PADDING + TOOLBAR_HEIGHT,
+            ]),
+        ..Default::default()
+    };
+
+    let canvas = Arc::new(Mutex::new(canvas));
+    let app = EditorApp {
+        canvas: canvas.clone(),
+        texture: None,
+        color: [0, 0, 0],
+        size: egui::vec2(width as f32 * scale, height as f32 * scale),
+        options: viewer_options,
+    };
+    eframe::run_native(
+        &title,
+        options,
+        Box::new(|cc| {
+            app.options.apply_theme(&cc.egui_ctx);
+            Ok(Box::new(app))
+        }),
+    )?;
+
+    let edited = canvas.lock().unwrap().clone();
+    Ok(edited)
+}
+
+/// Position of the pixel under `pointer` (relative to the top left of the shown canvas).
+fn pixel_at<const H: usize, const W: usize>(
+    shown_size: egui::Vec2,
+    pointer: egui::Vec2,
+) -> Option<PixelStrictPosition<H, W>> {
+    if pointer.x < 0. || pointer.y < 0. || shown_size.x <= 0. || shown_size.y <= 0. {
+        return None;
+    }
+    let row = (pointer.y / shown_size.y * H as f32) as usize;
+    let column = (pointer.x / shown_size.x * W as f32) as usize;
+    PixelStrictPosition::new(row, column).ok()
+}
+
+struct EditorApp<const H: usize, const W: usize, P: PixelMutInterface + Default> {
+    canvas: Arc<Mutex<PixelCanvas<H, W, P>>>,
+    /// Image of the canvas, `None` when it needs to be created again.
+    texture: Option<TextureHandle>,
+    color: [u8; 3],
+    size: egui::Vec2,
+    options: ViewerOptions,
+}
+
+impl<const H: usize, const W: usize, P> eframe::App for EditorApp<H, W, P>
+where
+    P: PixelMutInterface + PartialEq + Clone + Default,
+    P::ColorType: RgbaInterface + Default + Clone + TryFrom<Option<PixelColor>>,
+{
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::TopBottomPanel::top("toolbar")
+            .exact_height(TOOLBAR_HEIGHT)
+            .show(ctx, |ui| {
+                ui.horizontal_centered(|ui| {
+                    ui.label("Color");
+                    ui.color_edit_button_srgb(&mut self.color);
+                    ui.weak("Left click to paint, right click to erase");
+                });
+            });
+
+        self.options.central_panel().show(ctx, |ui| {
+            let mut canvas = self.canvas.lock().unwrap();
+            let texture = self.texture.get_or_insert_with(|| {
+                let image = canvas.default_image_builder().get_image();
+                ctx.load_texture(
+                    "canvas",
+                    ColorImage::from_rgba_unmultiplied(
+                        [image.width() as usize, image.height() as usize],
+                        &image.into_raw(),
+                    ),
+                    TextureOptions::NEAREST,
+                )
+            });
+
+            let response = ui.add(
+                egui::Image::new(&*texture)
+                    .fit_to_exact_size(self.size)
+                    .sense(egui::Sense::click_and_drag()),
+            );
+            let Some(pointer) = response.interact_pointer_pos() else {
+                return;
+            };
+            let Some(pos) = pixel_at::<H, W>(response.rect.size(), pointer - response.rect.min)
+            else {
+                return;
+            };
+
+            let (paint, erase) =
+                ui.input(|input| (input.pointer.primary_down(), input.pointer.secondary_down()));
+            let color = if paint {
+                let [r, g, b] = self.color;
+                P::ColorType::try_from(Some(PixelColor::new(r, g, b))).ok()
+            } else if erase {
+                Some(P::ColorType::default())
+            } else {
+                None
+            };
+            if let Some(color) = color {
+                if canvas.color_at(pos).rgba() != color.rgba() {
+                    canvas.update_color_at(pos, color);
+                    self.texture = None;
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pos;
+
+    use super::*;
+
+    #[test]
+    fn test_pixel_at() {
+        let shown = egui::vec2(50., 20.);
+        assert_eq!(
+            pixel_at::<2, 5>(shown, egui::vec2(45., 15.)),
+            Some(pos!(1, 4; 2, 5))
+        );
+        assert_eq!(
+            pixel_at::<2, 5>(shown, egui::vec2(0., 0.)),
+            Some(pos!(0, 0; 2, 5))
+        );
+        assert_eq!(pixel_at::<2, 5>(shown, egui::vec2(50., 0.)), None);
+        assert_eq!(pixel_at::<2, 5>(shown, egui::vec2(-3., 0.)), None);
+    }
+}
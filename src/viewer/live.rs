@@ -0,0 +1,206 @@
+//! Showing frames while they're being created, see [`live`].
+//!
+
+use std::sync::{
+    mpsc::{self, Receiver, Sender},
+    Arc, Mutex,
+};
+
+use eframe::egui::{self, ColorImage, TextureHandle, TextureOptions};
+use thiserror::Error;
+
+use crate::{
+    image::DefaultImageBuffer,
+    pixels::{
+        canvas::{PixelCanvasInterface, SharedPixelCanvasExt},
+        color::RgbaInterface,
+        PixelInterface,
+    },
+};
+
+use super::{ViewerOptions, PIXELART_ICON, WINDOW_PADDING};
+
+/// The live viewer window is closed, so frames can't be sent anymore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("The live viewer is closed.")]
+pub struct ViewerClosedError;
+
+/// Sends frames to a [`LiveViewer`], can be moved to other threads.
+#[derive(Clone)]
+pub struct LiveSender {
+    sender: Sender<DefaultImageBuffer>,
+    /// Context of the window once it's open, to wake it up on new frames.
+    context: Arc<Mutex<Option<egui::Context>>>,
+}
+
+impl LiveSender {
+    /// Shows the image instead of the current one.
+    pub fn push(&self, image: DefaultImageBuffer) -> Result<(), ViewerClosedError> {
+        self.sender.send(image).map_err(|_| ViewerClosedError)?;
+        if let Some(context) = &*self.context.lock().unwrap() {
+            context.request_repaint();
+        }
+        Ok(())
+    }
+
+    /// Shows the canvas (using its default image) instead of the current frame.
+    pub fn push_canvas<const H: usize, const W: usize, P, I>(
+        &self,
+        canvas: &I,
+    ) -> Result<(), ViewerClosedError>
+    where
+        P: PixelInterface + Default,
+        P::ColorType: RgbaInterface + Default,
+        I: PixelCanvasInterface<H, W, P>,
+    {
+        self.push(canvas.default_image_builder().get_image())
+    }
+}
+
+/// A window showing the latest frame sent by its [`LiveSender`]s.
+pub struct LiveViewer {
+    receiver: Receiver<DefaultImageBuffer>,
+    context: Arc<Mutex<Option<egui::Context>>>,
+}
+
+impl LiveViewer {
+    /// Opens the window and blocks until it's closed, see [`live`].
+    pub fn run(self) -> eframe::Result {
+        self.run_with_options(ViewerOptions::default())
+    }
+
+    /// As same as [`Self::run`], using the given [`ViewerOptions`].
+    pub fn run_with_options(self, viewer_options: ViewerOptions) -> eframe::Result {
+        let title = viewer_options
+            .title
+            .clone()
+            .unwrap_or_else(|| "Pixelart (live)".to_string());
+        let options = eframe::NativeOptions {
+            viewport: egui::ViewportBuilder::default()
+                .with_title(title.clone())
+                .with_resizable(viewer_options.resizable)
+                .with_maximize_button(viewer_options.resizable)
+                .with_icon(PIXELART_ICON.clone()),
+            ..Default::default()
+        };
+
+        eframe::run_native(
+            &title,
+            options,
+            Box::new(|cc| {
+                viewer_options.apply_theme(&cc.egui_ctx);
+                *self.context.lock().unwrap() = Some(cc.egui_ctx.clone());
+                Ok(Box::new(LiveApp {
+                    receiver: self.receiver,
+                    texture: None,
+                    size: egui::Vec2::ZERO,
+                    frames: 0,
+                    options: viewer_options,
+                }))
+            }),
+        )
+    }
+}
+
+/// Creates a live viewer, which shows frames as soon as they're sent, e.g. by a long running
+/// generator.
+///
+/// The window has to run on the main thread, so frames are usually sent from another thread.
+///
+/// ## Example
+/// ```rust,no_run
+/// # use pixelart::{prelude::*, viewer::live};
+/// let (sender, viewer) = live();
+/// std::thread::spawn(move || {
+///     let mut canvas = PixelCanvas::<10>::default();
+///     for color in [RED, GREEN, BLUE].into_iter().cycle() {
+///         canvas.fill(color);
+///         if sender.push_canvas(&canvas).is_err() {
+///             break; // Window is closed.
+///         }
+///         std::thread::sleep(std::time::Duration::from_millis(200));
+///     }
+/// });
+/// viewer.run().unwrap();
+/// ```
+pub fn live() -> (LiveSender, LiveViewer) {
+    let (sender, receiver) = mpsc::channel();
+    let context = Arc::new(Mutex::new(None));
+    (
+        LiveSender {
+            sender,
+            context: context.clone(),
+        },
+        LiveViewer { receiver, context },
+    )
+}
+
+struct LiveApp {
+    receiver: Receiver<DefaultImageBuffer>,
+    texture: Option<TextureHandle>,
+    size: egui::Vec2,
+    frames: usize,
+    options: ViewerOptions,
+}
+
+impl eframe::App for LiveApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let received: Vec<_> = self.receiver.try_iter().collect();
+        self.frames += received.len();
+        // Only the latest frame is worth showing.
+        if let Some(image) = received.into_iter().last() {
+            let size =
+                egui::vec2(image.width() as f32, image.height() as f32) * self.options.start_scale;
+            if size != self.size {
+                self.size = size;
+                ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(
+                    size + egui::Vec2::splat(WINDOW_PADDING),
+                ));
+            }
+
+            let image = ColorImage::from_rgba_unmultiplied(
+                [image.width() as usize, image.height() as usize],
+                &image.into_raw(),
+            );
+            match &mut self.texture {
+                Some(texture) => texture.set(image, TextureOptions::NEAREST),
+                None => {
+                    self.texture = Some(ctx.load_texture("live", image, TextureOptions::NEAREST))
+                }
+            }
+        }
+
+        self.options
+            .central_panel()
+            .show(ctx, |ui| match &self.texture {
+                Some(texture) => {
+                    ui.add(egui::Image::new(texture).fit_to_exact_size(self.size))
+                        .on_hover_text(format!("Frame {}", self.frames));
+                }
+                None => {
+                    ui.centered_and_justified(|ui| ui.weak("Waiting for frames..."));
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_live_sender() {
+        let (sender, viewer) = live();
+        sender.push_canvas(&PixelCanvas::<2>::default()).unwrap();
+        sender.clone().push(DefaultImageBuffer::new(1, 1)).unwrap();
+        assert_eq!(viewer.receiver.try_iter().count(), 2);
+
+        drop(viewer);
+        assert_eq!(
+            sender.push(DefaultImageBuffer::new(1, 1)),
+            Err(ViewerClosedError)
+        );
+    }
+}
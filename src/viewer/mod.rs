@@ -9,9 +9,10 @@ use std::time::{Duration, Instant};
 
 use atomic_time::AtomicInstant;
 use eframe::egui::IconData;
-use eframe::{
-    egui::{self, ColorImage, TextureHandle, TextureOptions},
-    CreationContext,
+use eframe::egui::{self, ColorImage, TextureHandle, TextureOptions};
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    Delay, Frame,
 };
 
 use uuid::Uuid;
@@ -19,7 +20,7 @@ use uuid::Uuid;
 use crate::image::DefaultImageBuffer;
 use crate::pixels::canvas::templates::alien_monster::AlienMonster;
 use crate::pixels::canvas::{SharedMutPixelCanvasExt, SharedPixelCanvasExt};
-use crate::prelude::{MaybePixel, PixelCanvas};
+use crate::prelude::{MaybePixel, PixelCanvas, PixelColor};
 
 pub type ViewResult = eframe::Result;
 
@@ -37,48 +38,601 @@ fn get_icon() -> IconData {
     }
 }
 
-pub fn view<T: IntoIterator<Item = DefaultImageBuffer>>(
-    images: impl IntoIterator<Item = T>,
+mod compare;
+mod edit;
+mod live;
+mod render;
+
+pub use compare::{compare, compare_with_options};
+pub use edit::{edit, edit_with_options};
+pub use live::{live, LiveSender, LiveViewer, ViewerClosedError};
+pub use render::render_viewer_frame_to_image;
+
+const DEFAULT_TITLE: &str = "Pixelart";
+
+/// Extra space around the image, added to the size of windows.
+const WINDOW_PADDING: f32 = 20.;
+
+/// Width of the playlist panel, shown when there are more than one entry.
+const PLAYLIST_WIDTH: f32 = 150.;
+
+/// Height of the status bar, shown when the inspector is enabled.
+const STATUS_BAR_HEIGHT: f32 = 24.;
+
+/// Height of the playback controls, shown for animations.
+const CONTROLS_HEIGHT: f32 = 32.;
+
+/// Height of the toolbar, shown when it's enabled.
+const TOOLBAR_HEIGHT: f32 = 28.;
+
+/// Height of the tab bar, shown with [`ViewerLayout::Tabs`].
+const TABS_HEIGHT: f32 = 28.;
+
+/// Height of thumbnails, shown with [`ViewerLayout::Gallery`].
+const THUMBNAIL_HEIGHT: f32 = 80.;
+
+/// How long each frame of animations is shown by default (at normal speed).
+const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(100);
+
+/// Playback speeds to pick from.
+const PLAYBACK_SPEEDS: [f32; 6] = [0.25, 0.5, 1., 1.5, 2., 4.];
+
+/// Size of squares of the background behind transparent images, see
+/// [`ViewerOptions::with_checkerboard`].
+const CHECKERBOARD_CELL: f32 = 8.;
+
+/// Colors of the squares of the checkerboard background.
+const CHECKERBOARD_COLORS: [[u8; 3]; 2] = [[255, 255, 255], [204, 204, 204]];
+
+/// Grid lines are hidden when cells are shown smaller than this (in points).
+const MIN_GRID_CELL: f32 = 4.;
+
+/// Minimum distance between labels of the rulers (in points).
+const MIN_RULER_SPACING: f32 = 24.;
+
+/// An entry to show in the viewer: frames of an image or an animation, with an optional title.
+#[derive(Debug, Clone)]
+pub struct ViewEntry {
+    title: Option<String>,
+    frames: Vec<DefaultImageBuffer>,
+    frame_delays: Vec<Option<Duration>>,
+    grid_cell: u32,
+}
+
+impl ViewEntry {
+    pub fn new(frames: impl IntoIterator<Item = DefaultImageBuffer>) -> Self {
+        Self {
+            title: None,
+            frames: frames.into_iter().collect(),
+            frame_delays: Vec::new(),
+            grid_cell: 1,
+        }
+    }
+
+    pub fn named(
+        title: impl Into<String>,
+        frames: impl IntoIterator<Item = DefaultImageBuffer>,
+    ) -> Self {
+        Self::new(frames).with_title(title)
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// How long each frame is shown, frames with `None` (or missing) use
+    /// [`ViewerOptions::with_frame_delay`].
+    pub fn with_frame_delays(mut self, delays: impl IntoIterator<Item = Option<Duration>>) -> Self {
+        self.frame_delays = delays.into_iter().collect();
+        self
+    }
+
+    /// Shows every frame for `delay`.
+    pub fn with_frame_delay(self, delay: Duration) -> Self {
+        let frames = self.frames.len();
+        self.with_frame_delays(vec![Some(delay); frames])
+    }
+
+    /// Size of each cell of the grid and rulers in image pixels, e.g. the size of a canvas pixel
+    /// with its border. Defaults to `1`, a cell per image pixel.
+    pub fn with_grid_cell(mut self, size: u32) -> Self {
+        self.grid_cell = size.max(1);
+        self
+    }
+
+    fn frame_delay(&self, index: usize, default: Duration) -> Duration {
+        self.frame_delays
+            .get(index)
+            .copied()
+            .flatten()
+            .unwrap_or(default)
+    }
+
+    fn title_or_default(&self, index: usize) -> String {
+        match (&self.title, index) {
+            (Some(title), _) => title.clone(),
+            (None, 0) => DEFAULT_TITLE.to_string(),
+            (None, index) => format!("{DEFAULT_TITLE} #{}", index + 1),
+        }
+    }
+}
+
+impl From<Vec<DefaultImageBuffer>> for ViewEntry {
+    fn from(value: Vec<DefaultImageBuffer>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<const N: usize> From<[DefaultImageBuffer; N]> for ViewEntry {
+    fn from(value: [DefaultImageBuffer; N]) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<S: Into<String>> From<(S, Vec<DefaultImageBuffer>)> for ViewEntry {
+    fn from((title, frames): (S, Vec<DefaultImageBuffer>)) -> Self {
+        Self::named(title, frames)
+    }
+}
+
+impl<S: Into<String>, const N: usize> From<(S, [DefaultImageBuffer; N])> for ViewEntry {
+    fn from((title, frames): (S, [DefaultImageBuffer; N])) -> Self {
+        Self::named(title, frames)
+    }
+}
+
+/// Theme used by the viewer windows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ViewerTheme {
+    #[default]
+    Light,
+    Dark,
+
+    /// Follow the theme of the operating system.
+    System,
+}
+
+/// How the viewer shows more than one entry, see [`ViewerOptions::with_layout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ViewerLayout {
+    /// A window for each entry, and a playlist in the main window to switch between them.
+    ///
+    /// Backends without support for multiple windows only show the main one.
+    #[default]
+    Windows,
+
+    /// A single window with a tab for each entry.
+    Tabs,
+
+    /// A single window with thumbnails of the entries on its side.
+    Gallery,
+}
+
+impl ViewerLayout {
+    /// Space taken in the main window to switch between `entries`, as (width, height).
+    fn size(&self, entries: usize) -> egui::Vec2 {
+        match self {
+            _ if entries <= 1 => egui::Vec2::ZERO,
+            ViewerLayout::Windows | ViewerLayout::Gallery => egui::vec2(PLAYLIST_WIDTH, 0.),
+            ViewerLayout::Tabs => egui::vec2(0., TABS_HEIGHT),
+        }
+    }
+}
+
+/// Options of the viewer, see [`view_with_options`].
+#[derive(Debug, Clone)]
+pub struct ViewerOptions {
+    title: Option<String>,
+    theme: ViewerTheme,
+    background_color: Option<PixelColor>,
+    start_scale: f32,
+    resizable: bool,
+    inspector: bool,
+    highlight_hovered: bool,
+    frame_delay: Duration,
+    toolbar: bool,
+    grid: bool,
+    rulers: bool,
+    layout: ViewerLayout,
+    checkerboard: bool,
+}
+
+impl Default for ViewerOptions {
+    fn default() -> Self {
+        Self {
+            title: None,
+            theme: ViewerTheme::Light,
+            background_color: None,
+            start_scale: 1.,
+            resizable: false,
+            inspector: true,
+            highlight_hovered: false,
+            frame_delay: DEFAULT_FRAME_DELAY,
+            toolbar: true,
+            grid: false,
+            rulers: false,
+            layout: ViewerLayout::Windows,
+            checkerboard: false,
+        }
+    }
+}
+
+impl ViewerOptions {
+    /// Title of the main window, instead of the first entry's title.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn with_theme(mut self, theme: ViewerTheme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Background color of windows, instead of the theme's one.
+    pub fn with_background_color(mut self, color: impl Into<PixelColor>) -> Self {
+        self.background_color = Some(color.into());
+        self
+    }
+
+    /// Shows a checkerboard behind images instead of the background, so transparent pixels
+    /// are easy to tell apart.
+    pub fn with_checkerboard(mut self, checkerboard: bool) -> Self {
+        self.checkerboard = checkerboard;
+        self
+    }
+
+    /// Images are shown this many times larger than their actual size.
+    pub fn with_start_scale(mut self, scale: f32) -> Self {
+        self.start_scale = scale.max(0.1);
+        self
+    }
+
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Shows position and color of the hovered pixel in a status bar, enabled by default.
+    pub fn with_inspector(mut self, inspector: bool) -> Self {
+        self.inspector = inspector;
+        self
+    }
+
+    /// Draws an outline around the hovered pixel.
+    pub fn with_hover_highlight(mut self, highlight: bool) -> Self {
+        self.highlight_hovered = highlight;
+        self
+    }
+
+    /// How long each frame of animations is shown, unless the entry has its own delays.
+    /// Defaults to 100ms.
+    pub fn with_frame_delay(mut self, delay: Duration) -> Self {
+        self.frame_delay = delay;
+        self
+    }
+
+    /// As same as [`Self::with_frame_delay`], using frames per second.
+    pub fn with_fps(self, fps: f32) -> Self {
+        self.with_frame_delay(Duration::from_secs_f64(1. / fps.max(0.1) as f64))
+    }
+
+    /// Shows a toolbar to save the shown image or animation, enabled by default.
+    pub fn with_toolbar(mut self, toolbar: bool) -> Self {
+        self.toolbar = toolbar;
+        self
+    }
+
+    /// Shows grid lines over images at first, they can be toggled with `G`.
+    ///
+    /// See [`ViewEntry::with_grid_cell`] for the size of cells.
+    pub fn with_grid(mut self, grid: bool) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    /// Shows row and column rulers over images at first, they can be toggled with `R`.
+    pub fn with_rulers(mut self, rulers: bool) -> Self {
+        self.rulers = rulers;
+        self
+    }
+
+    /// How more than one entry is shown, defaults to [`ViewerLayout::Windows`].
+    pub fn with_layout(mut self, layout: ViewerLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Height of the panels around the image, which are added to the size of windows.
+    fn panels_height(&self) -> f32 {
+        let toolbar = if self.toolbar { TOOLBAR_HEIGHT } else { 0. };
+        self.status_bar_height() + toolbar
+    }
+
+    fn status_bar_height(&self) -> f32 {
+        if self.inspector {
+            STATUS_BAR_HEIGHT
+        } else {
+            0.
+        }
+    }
+
+    fn apply_theme(&self, ctx: &egui::Context) {
+        ctx.set_theme(match self.theme {
+            ViewerTheme::Light => egui::ThemePreference::Light,
+            ViewerTheme::Dark => egui::ThemePreference::Dark,
+            ViewerTheme::System => egui::ThemePreference::System,
+        });
+    }
+
+    fn central_panel(&self) -> egui::CentralPanel {
+        match self.background_color {
+            Some(color) => egui::CentralPanel::default().frame(
+                egui::Frame::central_panel(&egui::Style::default())
+                    .fill(egui::Color32::from_rgb(color.r, color.g, color.b)),
+            ),
+            None => egui::CentralPanel::default(),
+        }
+    }
+}
+
+/// Shows the entries, each one in its own window.
+///
+/// Entries can be just frames or `(title, frames)`. When there are more than one entry,
+/// the main window has a playlist to switch between them. Use [`ViewerOptions::with_layout`]
+/// to show them all in a single window instead.
+pub fn view(entries: impl IntoIterator<Item = impl Into<ViewEntry>>) -> eframe::Result {
+    view_with_options(entries, ViewerOptions::default())
+}
+
+/// As same as [`view`], using the given [`ViewerOptions`].
+pub fn view_with_options(
+    entries: impl IntoIterator<Item = impl Into<ViewEntry>>,
+    viewer_options: ViewerOptions,
 ) -> eframe::Result {
-    let images: Vec<Vec<DefaultImageBuffer>> = images
-        .into_iter()
-        .map(|f| f.into_iter().collect())
-        .collect();
-    let first_msg = images
-        .first()
-        .expect("At least one image is excepted.")
-        .first()
-        .expect("At least one frame is excepted.");
+    let entries: Vec<ViewEntry> = entries.into_iter().map(Into::into).collect();
+    assert!(
+        entries.iter().all(|entry| !entry.frames.is_empty()),
+        "At least one frame is excepted."
+    );
+    let title = match &viewer_options.title {
+        Some(title) => title.clone(),
+        None => entries
+            .first()
+            .expect("At least one image is excepted.")
+            .title_or_default(0),
+    };
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
-            .with_resizable(false)
-            .with_maximize_button(false)
+            .with_title(title.clone())
+            .with_resizable(viewer_options.resizable)
+            .with_maximize_button(viewer_options.resizable)
             .with_icon(PIXELART_ICON.clone())
-            .with_inner_size([
-                (first_msg.width() as f32 + 20.),
-                (first_msg.height() as f32 + 20.),
-            ]),
+            .with_inner_size(window_size(&entries, &viewer_options)),
         ..Default::default()
     };
     eframe::run_native(
-        "Pixelart",
+        &title,
         options,
         Box::new(|cc| {
-            cc.egui_ctx.set_theme(egui::Theme::Light);
-            Ok(Box::<MyApp>::new(MyApp::new(cc, images)))
+            viewer_options.apply_theme(&cc.egui_ctx);
+            Ok(Box::<MyApp>::new(MyApp::new(
+                &cc.egui_ctx,
+                entries,
+                viewer_options,
+            )))
         }),
     )
 }
 
+/// Inner size of the main window showing `entries`, fitting the largest of them.
+fn window_size(entries: &[ViewEntry], options: &ViewerOptions) -> egui::Vec2 {
+    // The main window may show any of the entries using the playlist.
+    let (max_width, max_height) = entries
+        .iter()
+        .flat_map(|entry| entry.frames.first())
+        .fold((0, 0), |(width, height), image| {
+            (width.max(image.width()), height.max(image.height()))
+        });
+    let layout_size = options.layout.size(entries.len());
+    let controls_height = if entries.iter().any(|entry| entry.frames.len() > 1) {
+        CONTROLS_HEIGHT
+    } else {
+        0.
+    };
+    egui::vec2(
+        max_width as f32 * options.start_scale + WINDOW_PADDING + layout_size.x,
+        max_height as f32 * options.start_scale
+            + WINDOW_PADDING
+            + options.panels_height()
+            + controls_height
+            + layout_size.y,
+    )
+}
+
 #[derive(Clone)]
 struct ImageTextureInfo {
     image_id: Uuid,
+    title: String,
     texture: Arc<Mutex<TextureHandle>>,
     image_height: f32,
     image_width: f32,
 
     /// In case of a gif
     images_series: Option<Vec<DefaultImageBuffer>>,
+
+    /// How long each of the images is shown.
+    frame_delays: Vec<Duration>,
+
+    /// Size of cells of the grid and rulers, in image pixels.
+    grid_cell: u32,
+}
+
+impl ImageTextureInfo {
+    fn frame_count(&self) -> usize {
+        self.images_series.as_ref().map_or(1, Vec::len)
+    }
+
+    fn controls_height(&self) -> f32 {
+        if self.frame_count() > 1 {
+            CONTROLS_HEIGHT
+        } else {
+            0.
+        }
+    }
+
+    /// Shows the frame at `index` and restarts its timer.
+    fn show_frame(&self, view_data: &ViewPortData, index: usize) {
+        let Some(image) = self
+            .images_series
+            .as_ref()
+            .and_then(|images| images.get(index))
+        else {
+            return;
+        };
+        self.texture.lock().unwrap().set(
+            ColorImage::from_rgba_unmultiplied(
+                [image.width() as usize, image.height() as usize],
+                &image.clone().into_raw(),
+            ),
+            TextureOptions::NEAREST,
+        );
+        view_data
+            .last_shown_image_index
+            .store(index, Ordering::Relaxed);
+        view_data.instant.store(Instant::now(), Ordering::Relaxed);
+    }
+}
+
+/// Index of the frame `steps` away from `index`, wrapping around `len` frames.
+fn step_frame(index: usize, len: usize, steps: isize) -> usize {
+    (index as isize + steps).rem_euclid(len.max(1) as isize) as usize
+}
+
+/// Squares of [`CHECKERBOARD_COLORS`] covering `rect`.
+fn checkerboard(rect: egui::Rect, cell: f32) -> egui::Shape {
+    let [light, dark] = CHECKERBOARD_COLORS.map(|[r, g, b]| egui::Color32::from_rgb(r, g, b));
+    let mut shapes = vec![egui::Shape::rect_filled(rect, 0., light)];
+    let (columns, rows) = (
+        (rect.width() / cell).ceil() as usize,
+        (rect.height() / cell).ceil() as usize,
+    );
+    for row in 0..rows {
+        for column in (row % 2..columns).step_by(2) {
+            let min = rect.min + egui::vec2(column as f32, row as f32) * cell;
+            let square = egui::Rect::from_min_size(min, egui::Vec2::splat(cell)).intersect(rect);
+            shapes.push(egui::Shape::rect_filled(square, 0., dark));
+        }
+    }
+    egui::Shape::Vec(shapes)
+}
+
+/// Offsets (in image pixels) of the lines between cells of `cell` pixels, along `length` pixels.
+fn grid_lines(length: u32, cell: u32) -> impl Iterator<Item = u32> {
+    (0..=length).step_by(cell.max(1) as usize)
+}
+
+/// Every how many cells the rulers are labeled, so that labels don't overlap.
+fn ruler_step(shown_cell: f32) -> u32 {
+    [1, 2, 5]
+        .into_iter()
+        .cycle()
+        .scan(1, |magnitude, step| {
+            let value = step * *magnitude;
+            if step == 5 {
+                *magnitude *= 10;
+            }
+            Some(value)
+        })
+        .find(|&step| step as f32 * shown_cell >= MIN_RULER_SPACING || step >= 1_000_000)
+        .unwrap_or(1)
+}
+
+/// Draws the grid and rulers (whichever is enabled) over the image shown in `rect`.
+fn draw_overlays(
+    ui: &egui::Ui,
+    rect: egui::Rect,
+    image: &DefaultImageBuffer,
+    cell: u32,
+    view_data: &ViewPortData,
+) {
+    let scale = rect.size() / egui::vec2(image.width() as f32, image.height() as f32);
+    let painter = ui.painter().with_clip_rect(rect);
+
+    if view_data.grid.load(Ordering::Relaxed) && cell as f32 * scale.min_elem() >= MIN_GRID_CELL {
+        let stroke = (
+            1.,
+            egui::Color32::from_rgba_unmultiplied(128, 128, 128, 160),
+        );
+        for x in grid_lines(image.width(), cell) {
+            painter.vline(rect.min.x + x as f32 * scale.x, rect.y_range(), stroke);
+        }
+        for y in grid_lines(image.height(), cell) {
+            painter.hline(rect.x_range(), rect.min.y + y as f32 * scale.y, stroke);
+        }
+    }
+
+    if view_data.rulers.load(Ordering::Relaxed) {
+        let font = egui::FontId::monospace(9.);
+        let background = egui::Color32::from_black_alpha(150);
+        let label = |pos: egui::Pos2, anchor: egui::Align2, index: u32| {
+            let text =
+                painter.layout_no_wrap(index.to_string(), font.clone(), egui::Color32::WHITE);
+            let text_rect = anchor.anchor_size(pos, text.size()).expand(1.);
+            painter.rect_filled(text_rect, 2., background);
+            painter.galley(
+                text_rect.min + egui::vec2(1., 1.),
+                text,
+                egui::Color32::WHITE,
+            );
+        };
+
+        let step = ruler_step(cell as f32 * scale.x);
+        for column in (0..image.width().div_ceil(cell)).step_by(step as usize) {
+            let x = rect.min.x + (column * cell) as f32 * scale.x;
+            label(egui::pos2(x, rect.min.y), egui::Align2::LEFT_TOP, column);
+        }
+        let step = ruler_step(cell as f32 * scale.y);
+        for row in (step..image.height().div_ceil(cell)).step_by(step as usize) {
+            let y = rect.min.y + (row * cell) as f32 * scale.y;
+            label(egui::pos2(rect.min.x, y), egui::Align2::LEFT_TOP, row);
+        }
+    }
+}
+
+/// The pixel under the pointer, see [`ViewerOptions::with_inspector`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct HoveredPixel {
+    row: u32,
+    column: u32,
+    rgba: [u8; 4],
+}
+
+impl HoveredPixel {
+    /// Finds the pixel of `image` at `pointer`, relative to the top left of where the image
+    /// is shown with the `shown_size`.
+    fn find(
+        image: &DefaultImageBuffer,
+        shown_size: egui::Vec2,
+        pointer: egui::Vec2,
+    ) -> Option<Self> {
+        if pointer.x < 0. || pointer.y < 0. || shown_size.x <= 0. || shown_size.y <= 0. {
+            return None;
+        }
+        let column = (pointer.x / shown_size.x * image.width() as f32) as u32;
+        let row = (pointer.y / shown_size.y * image.height() as f32) as u32;
+        let rgba = image.get_pixel_checked(column, row)?.0;
+        Some(Self { row, column, rgba })
+    }
+
+    fn describe(&self) -> String {
+        let [r, g, b, a] = self.rgba;
+        format!(
+            "({}, {})  #{r:02x}{g:02x}{b:02x}  rgba({r}, {g}, {b}, {a})",
+            self.row, self.column
+        )
+    }
 }
 
 #[derive(Clone)]
@@ -86,38 +640,59 @@ struct ViewPortData {
     show_viewport: Arc<AtomicBool>,
     last_shown_image_index: Arc<AtomicUsize>,
     instant: Arc<AtomicInstant>,
+    hovered: Arc<Mutex<Option<HoveredPixel>>>,
+    playing: Arc<AtomicBool>,
+    /// Multiplier of the playback speed.
+    speed: Arc<Mutex<f32>>,
+    /// Result of the last save from the toolbar.
+    save_message: Arc<Mutex<Option<String>>>,
+    grid: Arc<AtomicBool>,
+    rulers: Arc<AtomicBool>,
 }
 
 struct MyApp {
     textures: Vec<ImageTextureInfo>,
 
+    /// Index of the entry shown in the main window.
+    selected: usize,
+
+    options: ViewerOptions,
+
     /// Data for viewports
     viewports_data: HashMap<Uuid, ViewPortData>,
 }
 
 impl MyApp {
-    fn new(cc: &CreationContext, images: Vec<Vec<DefaultImageBuffer>>) -> Self {
-        let textures: Vec<_> = images
+    fn new(ctx: &egui::Context, entries: Vec<ViewEntry>, options: ViewerOptions) -> Self {
+        let textures: Vec<_> = entries
             .into_iter()
-            .map(|image| {
+            .enumerate()
+            .map(|(index, entry)| {
+                let title = entry.title_or_default(index);
+                let frame_delays = (0..entry.frames.len())
+                    .map(|frame| entry.frame_delay(frame, options.frame_delay))
+                    .collect();
+                let image = entry.frames;
                 let first_image = image.first().expect("At least one frame is expected");
                 ImageTextureInfo {
                     image_id: Uuid::new_v4(),
+                    title,
                     texture: Arc::new(
-                        cc.egui_ctx
-                            .load_texture(
-                                "screen",
-                                ColorImage::from_rgba_unmultiplied(
-                                    [first_image.width() as usize, first_image.height() as usize],
-                                    &first_image.clone().into_raw(),
-                                ),
-                                TextureOptions::default(),
-                            )
-                            .into(),
+                        ctx.load_texture(
+                            "screen",
+                            ColorImage::from_rgba_unmultiplied(
+                                [first_image.width() as usize, first_image.height() as usize],
+                                &first_image.clone().into_raw(),
+                            ),
+                            TextureOptions::NEAREST,
+                        )
+                        .into(),
                     ),
-                    image_height: first_image.height() as f32,
-                    image_width: first_image.width() as f32,
+                    image_height: first_image.height() as f32 * options.start_scale,
+                    image_width: first_image.width() as f32 * options.start_scale,
                     images_series: Some(image),
+                    frame_delays,
+                    grid_cell: entry.grid_cell,
                 }
             })
             .collect();
@@ -132,146 +707,533 @@ impl MyApp {
                             show_viewport: Arc::new(true.into()),
                             last_shown_image_index: Arc::new(0.into()),
                             instant: Arc::new(AtomicInstant::now()),
+                            hovered: Arc::new(Mutex::new(None)),
+                            playing: Arc::new(true.into()),
+                            speed: Arc::new(Mutex::new(1.)),
+                            save_message: Arc::new(Mutex::new(None)),
+                            grid: Arc::new(options.grid.into()),
+                            rulers: Arc::new(options.rulers.into()),
                         },
                     )
                 })
                 .collect(),
             textures,
+            selected: 0,
+            options,
         }
     }
 }
 
-impl eframe::App for MyApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            let mut textures_info_iter = self.textures.clone().into_iter();
-            let first_texture_info = textures_info_iter.next().unwrap();
-
-            if let Some(images_series) = first_texture_info.images_series {
-                ui.add(
-                    egui::Image::new(&*first_texture_info.texture.lock().unwrap())
-                        .max_height(first_texture_info.image_height)
-                        .max_width(first_texture_info.image_width),
+/// Shows the current frame of the texture and moves to the next frame when it's the time.
+fn show_texture(
+    ui: &mut egui::Ui,
+    texture_info: &ImageTextureInfo,
+    view_data: &ViewPortData,
+    options: &ViewerOptions,
+) {
+    // Reserved to go under the image, once where it's shown is known.
+    let background = ui.painter().add(egui::Shape::Noop);
+    let response = ui.add(
+        egui::Image::new(&*texture_info.texture.lock().unwrap())
+            .fit_to_exact_size(egui::vec2(
+                texture_info.image_width,
+                texture_info.image_height,
+            ))
+            .sense(egui::Sense::hover()),
+    );
+    if options.checkerboard {
+        ui.painter()
+            .set(background, checkerboard(response.rect, CHECKERBOARD_CELL));
+    }
+
+    let (toggle_grid, toggle_rulers) = ui.input(|input| {
+        (
+            input.key_pressed(egui::Key::G),
+            input.key_pressed(egui::Key::R),
+        )
+    });
+    if toggle_grid {
+        view_data.grid.fetch_xor(true, Ordering::Relaxed);
+    }
+    if toggle_rulers {
+        view_data.rulers.fetch_xor(true, Ordering::Relaxed);
+    }
+
+    if let Some(images_series) = &texture_info.images_series {
+        let last_shown_image_index = view_data.last_shown_image_index.load(Ordering::Relaxed);
+        draw_overlays(
+            ui,
+            response.rect,
+            &images_series[last_shown_image_index],
+            texture_info.grid_cell,
+            view_data,
+        );
+
+        if options.inspector {
+            let hovered = response.hover_pos().and_then(|pointer| {
+                HoveredPixel::find(
+                    &images_series[last_shown_image_index],
+                    response.rect.size(),
+                    pointer - response.rect.min,
+                )
+            });
+            if let (Some(hovered), true) = (hovered, options.highlight_hovered) {
+                let image = &images_series[last_shown_image_index];
+                let cell =
+                    response.rect.size() / egui::vec2(image.width() as f32, image.height() as f32);
+                let min = response.rect.min
+                    + egui::vec2(hovered.column as f32, hovered.row as f32) * cell;
+                ui.painter().rect_stroke(
+                    egui::Rect::from_min_size(min, cell),
+                    0.,
+                    (1., ui.visuals().strong_text_color()),
                 );
+            }
+            *view_data.hovered.lock().unwrap() = hovered;
+        }
 
-                let view_data = &self.viewports_data[&first_texture_info.image_id];
+        let delay = texture_info
+            .frame_delays
+            .get(last_shown_image_index)
+            .copied()
+            .unwrap_or(DEFAULT_FRAME_DELAY)
+            .div_f32(*view_data.speed.lock().unwrap());
+        if view_data.playing.load(Ordering::Relaxed)
+            && view_data.instant.load(Ordering::Relaxed).elapsed() >= delay
+        {
+            texture_info.show_frame(
+                view_data,
+                step_frame(last_shown_image_index, images_series.len(), 1),
+            );
+        }
+        ui.ctx().request_repaint();
+    }
+}
 
-                let last_shown_image_index =
-                    view_data.last_shown_image_index.load(Ordering::Relaxed);
+/// Saves the frames as an animated GIF, each shown for its delay.
+fn save_gif(
+    frames: &[DefaultImageBuffer],
+    delays: &[Duration],
+    path: impl AsRef<std::path::Path>,
+) -> image::ImageResult<()> {
+    let mut encoder = GifEncoder::new(std::io::BufWriter::new(std::fs::File::create(path)?));
+    encoder.set_repeat(Repeat::Infinite)?;
+    encoder.encode_frames(frames.iter().zip(delays).map(|(image, delay)| {
+        Frame::from_parts(image.clone(), 0, 0, Delay::from_saturating_duration(*delay))
+    }))
+}
+
+/// Asks for a path and saves the current frame as PNG, or all frames as GIF.
+fn save_with_dialog(texture_info: &ImageTextureInfo, view_data: &ViewPortData, all_frames: bool) {
+    let Some(images) = &texture_info.images_series else {
+        return;
+    };
+    let (filter, extension) = if all_frames {
+        ("GIF", "gif")
+    } else {
+        ("PNG", "png")
+    };
+    let Some(path) = rfd::FileDialog::new()
+        .add_filter(filter, &[extension])
+        .set_file_name(format!("{}.{extension}", texture_info.title))
+        .save_file()
+    else {
+        return;
+    };
 
-                if view_data.instant.load(Ordering::Relaxed).elapsed() >= Duration::from_millis(100)
+    let result = if all_frames {
+        save_gif(images, &texture_info.frame_delays, &path)
+    } else {
+        let index = view_data.last_shown_image_index.load(Ordering::Relaxed);
+        images[index].save(&path)
+    };
+    *view_data.save_message.lock().unwrap() = Some(match result {
+        Ok(_) => format!("Saved to {}", path.display()),
+        Err(error) => format!("Failed to save: {error}"),
+    });
+}
+
+/// Buttons to save what's shown, in a top panel.
+fn show_toolbar(
+    ctx: &egui::Context,
+    texture_info: &ImageTextureInfo,
+    view_data: &ViewPortData,
+    options: &ViewerOptions,
+) {
+    if !options.toolbar {
+        return;
+    }
+
+    egui::TopBottomPanel::top("toolbar")
+        .exact_height(TOOLBAR_HEIGHT)
+        .show(ctx, |ui| {
+            ui.horizontal_centered(|ui| {
+                if ui
+                    .button("Save PNG")
+                    .on_hover_text("Save the current frame")
+                    .clicked()
                 {
-                    first_texture_info.texture.lock().unwrap().set(
-                        ColorImage::from_rgba_unmultiplied(
-                            [
-                                images_series[last_shown_image_index].width() as usize,
-                                images_series[last_shown_image_index].height() as usize,
-                            ],
-                            &images_series[last_shown_image_index].clone().into_raw(),
-                        ),
-                        TextureOptions::default(),
-                    );
-
-                    view_data.last_shown_image_index.store(
-                        if last_shown_image_index + 1 >= images_series.len() {
-                            0
-                        } else {
-                            last_shown_image_index + 1
-                        },
-                        Ordering::Relaxed,
-                    );
-                    view_data.instant.store(Instant::now(), Ordering::Relaxed);
+                    save_with_dialog(texture_info, view_data, false);
                 }
-                ctx.request_repaint();
-            } else {
-                ui.add(
-                    egui::Image::new(&*first_texture_info.texture.lock().unwrap())
-                        .max_height(first_texture_info.image_height)
-                        .max_width(first_texture_info.image_width),
-                );
-            }
+                if texture_info.frame_count() > 1
+                    && ui
+                        .button("Save GIF")
+                        .on_hover_text("Save all frames")
+                        .clicked()
+                {
+                    save_with_dialog(texture_info, view_data, true);
+                }
+                for (flag, text, hint) in [
+                    (&view_data.grid, "Grid", "Toggle grid lines (G)"),
+                    (
+                        &view_data.rulers,
+                        "Rulers",
+                        "Toggle row and column rulers (R)",
+                    ),
+                ] {
+                    let enabled = flag.load(Ordering::Relaxed);
+                    if ui
+                        .selectable_label(enabled, text)
+                        .on_hover_text(hint)
+                        .clicked()
+                    {
+                        flag.store(!enabled, Ordering::Relaxed);
+                    }
+                }
+                if let Some(message) = &*view_data.save_message.lock().unwrap() {
+                    ui.weak(message);
+                }
+            });
+        });
+}
 
-            for texture_info in textures_info_iter {
-                if self.viewports_data[&texture_info.image_id]
-                    .show_viewport
-                    .load(Ordering::Relaxed)
+/// Play/pause, stepping, a frame slider and playback speed of animations, in a bottom panel.
+fn show_controls(ctx: &egui::Context, texture_info: &ImageTextureInfo, view_data: &ViewPortData) {
+    let frame_count = texture_info.frame_count();
+    if frame_count <= 1 {
+        return;
+    }
+
+    egui::TopBottomPanel::bottom("playback_controls")
+        .exact_height(CONTROLS_HEIGHT)
+        .show(ctx, |ui| {
+            ui.horizontal_centered(|ui| {
+                let index = view_data.last_shown_image_index.load(Ordering::Relaxed);
+                let playing = view_data.playing.load(Ordering::Relaxed);
+
+                if ui.button("⏮").on_hover_text("Previous frame").clicked() {
+                    view_data.playing.store(false, Ordering::Relaxed);
+                    texture_info.show_frame(view_data, step_frame(index, frame_count, -1));
+                }
+                if ui
+                    .button(if playing { "⏸" } else { "▶" })
+                    .on_hover_text(if playing { "Pause" } else { "Play" })
+                    .clicked()
                 {
-                    let view_data = self.viewports_data[&texture_info.image_id].clone();
-                    ctx.show_viewport_deferred(
-                        egui::ViewportId::from_hash_of(texture_info.image_id),
-                        egui::ViewportBuilder::default()
-                            .with_title("Pixelart")
-                            .with_resizable(false)
-                            .with_maximize_button(false)
-                            .with_icon(PIXELART_ICON.clone())
-                            .with_inner_size([
-                                (texture_info.image_width + 20.),
-                                (texture_info.image_height + 20.),
-                            ]),
-                        move |ctx, class| {
-                            assert!(
-                                class == egui::ViewportClass::Deferred,
-                                "This egui backend doesn't support multiple viewports"
-                            );
-
-                            egui::CentralPanel::default().show(ctx, |ui| {
-                                let view_data = view_data.clone();
-                                if let Some(images_series) = &texture_info.images_series {
-                                    ui.add(
-                                        egui::Image::new(&*texture_info.texture.lock().unwrap())
-                                            .max_height(texture_info.image_height)
-                                            .max_width(texture_info.image_width),
-                                    );
+                    view_data.playing.store(!playing, Ordering::Relaxed);
+                }
+                if ui.button("⏭").on_hover_text("Next frame").clicked() {
+                    view_data.playing.store(false, Ordering::Relaxed);
+                    texture_info.show_frame(view_data, step_frame(index, frame_count, 1));
+                }
 
-                                    let last_shown_image_index =
-                                        view_data.last_shown_image_index.load(Ordering::Relaxed);
-
-                                    if view_data.instant.load(Ordering::Relaxed).elapsed()
-                                        >= Duration::from_millis(100)
-                                    {
-                                        texture_info.texture.lock().unwrap().set(
-                                            ColorImage::from_rgba_unmultiplied(
-                                                [
-                                                    images_series[last_shown_image_index].width()
-                                                        as usize,
-                                                    images_series[last_shown_image_index].height()
-                                                        as usize,
-                                                ],
-                                                &images_series[last_shown_image_index]
-                                                    .clone()
-                                                    .into_raw(),
-                                            ),
-                                            TextureOptions::default(),
-                                        );
-
-                                        view_data.last_shown_image_index.store(
-                                            if last_shown_image_index + 1 >= images_series.len() {
-                                                0
-                                            } else {
-                                                last_shown_image_index + 1
-                                            },
-                                            Ordering::Relaxed,
-                                        );
-                                        view_data.instant.store(Instant::now(), Ordering::Relaxed);
-                                    }
-                                    ctx.request_repaint();
-                                } else {
-                                    ui.add(
-                                        egui::Image::new(&*texture_info.texture.lock().unwrap())
-                                            .max_height(texture_info.image_height)
-                                            .max_width(texture_info.image_width),
+                let mut selected = index;
+                if ui
+                    .add(egui::Slider::new(&mut selected, 0..=frame_count - 1).text("frame"))
+                    .changed()
+                {
+                    view_data.playing.store(false, Ordering::Relaxed);
+                    texture_info.show_frame(view_data, selected);
+                }
+
+                let mut speed = view_data.speed.lock().unwrap();
+                egui::ComboBox::from_id_salt("playback_speed")
+                    .width(60.)
+                    .selected_text(format!("{}x", *speed))
+                    .show_ui(ui, |ui| {
+                        for option in PLAYBACK_SPEEDS {
+                            ui.selectable_value(&mut *speed, option, format!("{option}x"));
+                        }
+                    });
+            });
+        });
+}
+
+/// Shows what [`show_texture`] found under the pointer in a bottom panel.
+fn show_status_bar(ctx: &egui::Context, view_data: &ViewPortData, options: &ViewerOptions) {
+    if !options.inspector {
+        return;
+    }
+    egui::TopBottomPanel::bottom("status_bar")
+        .exact_height(STATUS_BAR_HEIGHT)
+        .show(ctx, |ui| {
+            ui.centered_and_justified(|ui| {
+                match *view_data.hovered.lock().unwrap() {
+                    Some(hovered) => ui.monospace(hovered.describe()),
+                    None => ui.weak("Hover a pixel to inspect it"),
+                };
+            });
+        });
+}
+
+impl MyApp {
+    /// Lets the user pick the entry shown in the main window, as set by [`ViewerLayout`].
+    fn show_entries(&mut self, ctx: &egui::Context) {
+        if self.textures.len() <= 1 {
+            return;
+        }
+
+        match self.options.layout {
+            ViewerLayout::Windows => {
+                egui::SidePanel::left("playlist")
+                    .exact_width(PLAYLIST_WIDTH)
+                    .resizable(false)
+                    .show(ctx, |ui| {
+                        ui.heading("Playlist");
+                        ui.separator();
+                        for (index, texture_info) in self.textures.iter().enumerate() {
+                            if ui
+                                .selectable_label(self.selected == index, &texture_info.title)
+                                .clicked()
+                            {
+                                self.selected = index;
+                            }
+                        }
+                    });
+            }
+            ViewerLayout::Tabs => {
+                egui::TopBottomPanel::top("tabs")
+                    .exact_height(TABS_HEIGHT)
+                    .show(ctx, |ui| {
+                        egui::ScrollArea::horizontal().show(ui, |ui| {
+                            ui.horizontal_centered(|ui| {
+                                for (index, texture_info) in self.textures.iter().enumerate() {
+                                    ui.selectable_value(
+                                        &mut self.selected,
+                                        index,
+                                        &texture_info.title,
                                     );
                                 }
                             });
-
-                            if ctx.input(|i| i.viewport().close_requested()) {
-                                // Tell parent to close us.
-                                view_data.show_viewport.store(false, Ordering::Relaxed);
+                        });
+                    });
+            }
+            ViewerLayout::Gallery => {
+                egui::SidePanel::left("gallery")
+                    .exact_width(PLAYLIST_WIDTH)
+                    .resizable(false)
+                    .show(ctx, |ui| {
+                        egui::ScrollArea::vertical().show(ui, |ui| {
+                            for (index, texture_info) in self.textures.iter().enumerate() {
+                                let size = thumbnail_size(
+                                    egui::vec2(texture_info.image_width, texture_info.image_height),
+                                    egui::vec2(ui.available_width(), THUMBNAIL_HEIGHT),
+                                );
+                                let thumbnail = ui
+                                    .add(
+                                        egui::Image::new(&*texture_info.texture.lock().unwrap())
+                                            .fit_to_exact_size(size)
+                                            .sense(egui::Sense::click()),
+                                    )
+                                    .on_hover_text(&texture_info.title);
+                                let label = ui
+                                    .selectable_label(self.selected == index, &texture_info.title);
+                                if thumbnail.clicked() || label.clicked() {
+                                    self.selected = index;
+                                }
+                                ui.add_space(4.);
                             }
-                        },
-                    );
-                }
+                        });
+                    });
             }
+        }
+    }
+}
+
+/// Size of a thumbnail of an image of `size`, fitting in `max` and keeping its aspect ratio.
+fn thumbnail_size(size: egui::Vec2, max: egui::Vec2) -> egui::Vec2 {
+    if size.x <= 0. || size.y <= 0. {
+        return egui::Vec2::ZERO;
+    }
+    size * (max / size).min_elem()
+}
+
+impl eframe::App for MyApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.show(ctx);
+    }
+}
+
+impl MyApp {
+    /// Shows the main window, and windows of the other entries if the layout has them.
+    fn show(&mut self, ctx: &egui::Context) {
+        self.show_entries(ctx);
+
+        let selected = &self.textures[self.selected];
+        let selected_data = &self.viewports_data[&selected.image_id];
+        show_toolbar(ctx, selected, selected_data, &self.options);
+        show_status_bar(ctx, selected_data, &self.options);
+        show_controls(ctx, selected, selected_data);
+        self.options.central_panel().show(ctx, |ui| {
+            show_texture(ui, selected, selected_data, &self.options);
         });
+
+        if self.options.layout != ViewerLayout::Windows {
+            return;
+        }
+        for texture_info in self.textures.iter().skip(1).cloned() {
+            if self.viewports_data[&texture_info.image_id]
+                .show_viewport
+                .load(Ordering::Relaxed)
+            {
+                let view_data = self.viewports_data[&texture_info.image_id].clone();
+                let options = self.options.clone();
+                ctx.show_viewport_deferred(
+                    egui::ViewportId::from_hash_of(texture_info.image_id),
+                    egui::ViewportBuilder::default()
+                        .with_title(texture_info.title.clone())
+                        .with_resizable(self.options.resizable)
+                        .with_maximize_button(self.options.resizable)
+                        .with_icon(PIXELART_ICON.clone())
+                        .with_inner_size([
+                            (texture_info.image_width + WINDOW_PADDING),
+                            (texture_info.image_height
+                                + WINDOW_PADDING
+                                + self.options.panels_height()
+                                + texture_info.controls_height()),
+                        ]),
+                    move |ctx, class| {
+                        if class != egui::ViewportClass::Deferred {
+                            // This backend can't show more windows, the playlist of the main
+                            // window still shows the entry.
+                            view_data.show_viewport.store(false, Ordering::Relaxed);
+                            return;
+                        }
+
+                        show_toolbar(ctx, &texture_info, &view_data, &options);
+                        show_status_bar(ctx, &view_data, &options);
+                        show_controls(ctx, &texture_info, &view_data);
+                        options.central_panel().show(ctx, |ui| {
+                            show_texture(ui, &texture_info, &view_data, &options);
+                        });
+
+                        if ctx.input(|i| i.viewport().close_requested()) {
+                            // Tell parent to close us.
+                            view_data.show_viewport.store(false, Ordering::Relaxed);
+                        }
+                    },
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pixels::color::RgbaInterface, prelude::*};
+
+    use super::*;
+
+    #[test]
+    fn test_hovered_pixel() {
+        let mut image = DefaultImageBuffer::from_pixel(4, 2, WHITE.rgba());
+        image.put_pixel(3, 1, RED.rgba());
+        let shown = egui::vec2(40., 20.);
+
+        let hovered = HoveredPixel::find(&image, shown, egui::vec2(35., 15.)).unwrap();
+        assert_eq!((hovered.row, hovered.column), (1, 3));
+        assert_eq!(hovered.describe(), "(1, 3)  #ff0000  rgba(255, 0, 0, 255)");
+
+        assert!(HoveredPixel::find(&image, shown, egui::vec2(40., 5.)).is_none());
+        assert!(HoveredPixel::find(&image, shown, egui::vec2(-1., 5.)).is_none());
+    }
+
+    #[test]
+    fn test_frame_delays() {
+        let image = DefaultImageBuffer::new(1, 1);
+        let default = Duration::from_millis(100);
+        let entry = ViewEntry::new([image.clone(), image.clone(), image])
+            .with_frame_delays([None, Some(Duration::from_millis(20))]);
+        assert_eq!(entry.frame_delay(0, default), default);
+        assert_eq!(entry.frame_delay(1, default), Duration::from_millis(20));
+        assert_eq!(entry.frame_delay(2, default), default);
+
+        let entry = entry.with_frame_delay(Duration::from_secs(1));
+        assert_eq!(entry.frame_delay(2, default), Duration::from_secs(1));
+        assert_eq!(
+            ViewerOptions::default().with_fps(20.).frame_delay,
+            Duration::from_millis(50)
+        );
+    }
+
+    #[test]
+    fn test_save_gif() {
+        let frames = [RED, BLUE].map(|color| DefaultImageBuffer::from_pixel(2, 2, color.rgba()));
+        let delays = [Duration::from_millis(30), Duration::from_millis(70)];
+
+        std::fs::create_dir_all("arts/test").unwrap();
+        let path = "arts/test/viewer_save.gif";
+        save_gif(&frames, &delays, path).unwrap();
+
+        let mut decoder = gif::DecodeOptions::new()
+            .read_info(std::fs::File::open(path).unwrap())
+            .unwrap();
+        let mut saved = vec![];
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            saved.push(frame.delay);
+        }
+        assert_eq!(saved, vec![3, 7]);
+    }
+
+    #[test]
+    fn test_step_frame() {
+        assert_eq!(step_frame(0, 4, 1), 1);
+        assert_eq!(step_frame(3, 4, 1), 0);
+        assert_eq!(step_frame(0, 4, -1), 3);
+        assert_eq!(step_frame(0, 1, -1), 0);
+    }
+
+    #[test]
+    fn test_checkerboard() {
+        let rect = egui::Rect::from_min_size(egui::pos2(5., 5.), egui::vec2(20., 16.));
+        let egui::Shape::Vec(shapes) = checkerboard(rect, 8.) else {
+            panic!("Expected a list of shapes");
+        };
+        // The background and 3 of the 3x2 squares.
+        assert_eq!(shapes.len(), 4);
+        assert_eq!(shapes[0].visual_bounding_rect(), rect);
+        assert!(shapes
+            .iter()
+            .all(|shape| rect.contains_rect(shape.visual_bounding_rect())));
+    }
+
+    #[test]
+    fn test_layout() {
+        assert_eq!(
+            thumbnail_size(egui::vec2(40., 20.), egui::vec2(100., 80.)),
+            egui::vec2(100., 50.)
+        );
+        assert_eq!(
+            thumbnail_size(egui::vec2(10., 40.), egui::vec2(100., 80.)),
+            egui::vec2(20., 80.)
+        );
+
+        assert_eq!(ViewerLayout::Tabs.size(1), egui::Vec2::ZERO);
+        assert_eq!(ViewerLayout::Tabs.size(3), egui::vec2(0., TABS_HEIGHT));
+        assert_eq!(
+            ViewerLayout::Gallery.size(3),
+            egui::vec2(PLAYLIST_WIDTH, 0.)
+        );
+    }
+
+    #[test]
+    fn test_grid_and_rulers() {
+        assert_eq!(grid_lines(34, 11).collect::<Vec<_>>(), vec![0, 11, 22, 33]);
+        assert_eq!(grid_lines(3, 1).count(), 4);
+
+        assert_eq!(ruler_step(30.), 1);
+        assert_eq!(ruler_step(11.), 5);
+        assert_eq!(ruler_step(1.), 50);
+        assert_eq!(ruler_step(0.), 1_000_000);
     }
 }
@@ -0,0 +1,329 @@
+//! Offscreen rendering of what the viewer shows, so it can be checked without opening a window.
+//!
+//! The viewer's own ui runs on an egui context without a window, and what it paints is
+//! rasterized in software.
+
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+
+use eframe::egui::{
+    self,
+    epaint::{ClippedPrimitive, ImageData, Primitive, Vertex},
+    Color32, ColorImage,
+};
+use image::Rgba;
+
+use crate::image::DefaultImageBuffer;
+
+use super::{window_size, MyApp, ViewEntry, ViewerOptions};
+
+/// Renders a frame of the entry the same way a viewer window shows it, using the given options.
+///
+/// The result has the size of the viewer's window, including the toolbar, status bar,
+/// playback controls, grid and rulers, whichever the options enable.
+/// `frame` wraps around the number of frames, and animations are paused on it.
+/// [`ViewerTheme::System`](super::ViewerTheme::System) is rendered as light, since there is
+/// no system to ask offscreen.
+pub fn render_viewer_frame_to_image(
+    entry: &ViewEntry,
+    frame: usize,
+    options: &ViewerOptions,
+) -> DefaultImageBuffer {
+    let ctx = egui::Context::default();
+    options.apply_theme(&ctx);
+    let entries = vec![entry.clone()];
+    let size = window_size(&entries, options);
+    let mut app = MyApp::new(&ctx, entries, options.clone());
+
+    let texture_info = &app.textures[0];
+    let view_data = &app.viewports_data[&texture_info.image_id];
+    view_data.playing.store(false, Ordering::Relaxed);
+    texture_info.show_frame(view_data, frame % texture_info.frame_count());
+
+    let input = egui::RawInput {
+        screen_rect: Some(egui::Rect::from_min_size(egui::Pos2::ZERO, size)),
+        system_theme: Some(egui::Theme::Light),
+        ..Default::default()
+    };
+    let mut textures = HashMap::new();
+    let mut output = None;
+    // Some widgets (e.g. combo boxes) are only measured on the first pass.
+    for _ in 0..2 {
+        let full_output = ctx.run(input.clone(), |ctx| app.show(ctx));
+        for (id, delta) in full_output.textures_delta.set {
+            let image = match delta.image {
+                ImageData::Color(image) => (*image).clone(),
+                ImageData::Font(image) => ColorImage {
+                    size: image.size,
+                    pixels: image.srgba_pixels(None).collect(),
+                },
+            };
+            match delta.pos {
+                Some([x, y]) => {
+                    let texture: &mut ColorImage = textures
+                        .get_mut(&id)
+                        .expect("Patched textures are allocated first");
+                    for row in 0..image.height() {
+                        let start = (y + row) * texture.width() + x;
+                        texture.pixels[start..start + image.width()].copy_from_slice(
+                            &image.pixels[row * image.width()..(row + 1) * image.width()],
+                        );
+                    }
+                }
+                None => {
+                    textures.insert(id, image);
+                }
+            }
+        }
+        output = Some((full_output.shapes, full_output.pixels_per_point));
+    }
+
+    let (shapes, pixels_per_point) = output.expect("The ui runs at least once");
+    let mut target = Target::new(
+        (size.x * pixels_per_point).ceil() as usize,
+        (size.y * pixels_per_point).ceil() as usize,
+    );
+    for ClippedPrimitive {
+        clip_rect,
+        primitive,
+    } in ctx.tessellate(shapes, pixels_per_point)
+    {
+        let Primitive::Mesh(mesh) = primitive else {
+            continue;
+        };
+        let Some(texture) = textures.get(&mesh.texture_id) else {
+            continue;
+        };
+        let clip_rect = egui::Rect::from_min_max(
+            (clip_rect.min.to_vec2() * pixels_per_point).to_pos2(),
+            (clip_rect.max.to_vec2() * pixels_per_point).to_pos2(),
+        );
+        for triangle in mesh.indices.chunks_exact(3) {
+            let vertices = [0, 1, 2].map(|corner| {
+                let mut vertex = mesh.vertices[triangle[corner] as usize];
+                vertex.pos = (vertex.pos.to_vec2() * pixels_per_point).to_pos2();
+                vertex
+            });
+            target.fill_triangle(vertices, texture, clip_rect);
+        }
+    }
+    target.into_image()
+}
+
+/// Barycentric weight of `opposite` at `point` in the triangle of it and `edge`, or none if
+/// `point` isn't on the same side of `edge` as `opposite`.
+///
+/// Edges are measured the same way in both directions, so of two triangles sharing an edge
+/// exactly one covers the points right on it.
+fn edge_weight(
+    (from, to): (egui::Pos2, egui::Pos2),
+    opposite: egui::Pos2,
+    point: egui::Pos2,
+) -> Option<f32> {
+    let (from, to) = if (from.x, from.y) <= (to.x, to.y) {
+        (from, to)
+    } else {
+        (to, from)
+    };
+    let side = |p: egui::Pos2| (to - from).x * (p - from).y - (to - from).y * (p - from).x;
+    let (point_side, opposite_side) = (side(point), side(opposite));
+    let weight = point_side / opposite_side;
+    (weight > 0. || (point_side == 0. && opposite_side > 0.)).then_some(weight)
+}
+
+/// Pixels being painted on, as premultiplied colors.
+struct Target {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color32>,
+}
+
+impl Target {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![Color32::TRANSPARENT; width * height],
+        }
+    }
+
+    /// Blends a textured triangle over the pixels whose center it covers inside `clip_rect`,
+    /// the way egui's painters do.
+    fn fill_triangle(
+        &mut self,
+        [a, b, c]: [Vertex; 3],
+        texture: &ColorImage,
+        clip_rect: egui::Rect,
+    ) {
+        let bounds = egui::Rect::from_points(&[a.pos, b.pos, c.pos]).intersect(clip_rect);
+        let (left, top) = (bounds.min.x.max(0.) as usize, bounds.min.y.max(0.) as usize);
+        let right = (bounds.max.x.ceil().max(0.) as usize).min(self.width);
+        let bottom = (bounds.max.y.ceil().max(0.) as usize).min(self.height);
+
+        for y in top..bottom {
+            for x in left..right {
+                let point = egui::pos2(x as f32 + 0.5, y as f32 + 0.5);
+                if !clip_rect.contains(point) {
+                    continue;
+                }
+                let (Some(weight_a), Some(weight_b), Some(weight_c)) = (
+                    edge_weight((b.pos, c.pos), a.pos, point),
+                    edge_weight((c.pos, a.pos), b.pos, point),
+                    edge_weight((a.pos, b.pos), c.pos, point),
+                ) else {
+                    continue;
+                };
+                let weights = [weight_a, weight_b, weight_c];
+
+                let blend = |channel: fn(&Vertex) -> f32| {
+                    weights[0] * channel(&a) + weights[1] * channel(&b) + weights[2] * channel(&c)
+                };
+                let texel = {
+                    let column = (blend(|v| v.uv.x) * texture.width() as f32) as usize;
+                    let row = (blend(|v| v.uv.y) * texture.height() as f32) as usize;
+                    texture.pixels[row.min(texture.height() - 1) * texture.width()
+                        + column.min(texture.width() - 1)]
+                };
+                let tint = [
+                    blend(|v| v.color.r() as f32),
+                    blend(|v| v.color.g() as f32),
+                    blend(|v| v.color.b() as f32),
+                    blend(|v| v.color.a() as f32),
+                ];
+                let source = texel.to_array().map(|value| value as f32 / 255.);
+                let source: [f32; 4] = std::array::from_fn(|index| source[index] * tint[index]);
+
+                let pixel = &mut self.pixels[y * self.width + x];
+                let destination = pixel.to_array();
+                let [r, g, b, a]: [u8; 4] = std::array::from_fn(|index| {
+                    (source[index] + destination[index] as f32 * (1. - source[3] / 255.))
+                        .round()
+                        .clamp(0., 255.) as u8
+                });
+                *pixel = Color32::from_rgba_premultiplied(r, g, b, a);
+            }
+        }
+    }
+
+    fn into_image(self) -> DefaultImageBuffer {
+        DefaultImageBuffer::from_fn(self.width as u32, self.height as u32, |x, y| {
+            Rgba(self.pixels[y as usize * self.width + x as usize].to_srgba_unmultiplied())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pixels::color::RgbaInterface, prelude::*};
+
+    use super::*;
+
+    /// Top left of the first square of `side` pixels of `color` in `image`, row by row.
+    fn find(image: &DefaultImageBuffer, color: PixelColor, side: u32) -> Option<(u32, u32)> {
+        image
+            .enumerate_pixels()
+            .map(|(x, y, _)| (x, y))
+            .find(|&(x, y)| {
+                (0..side).all(|dy| {
+                    (0..side)
+                        .all(|dx| image.get_pixel_checked(x + dx, y + dy) == Some(&color.rgba()))
+                })
+            })
+    }
+
+    #[test]
+    fn test_render_viewer_frame() {
+        let image = DefaultImageBuffer::from_pixel(3, 3, RED.rgba());
+        let side = image.width() * 2;
+        let entry = ViewEntry::new([image]);
+
+        let options = ViewerOptions::default()
+            .with_start_scale(2.)
+            .with_background_color(BLUE);
+        let rendered = render_viewer_frame_to_image(&entry, 3, &options);
+        let size = window_size(std::slice::from_ref(&entry), &options);
+        assert_eq!(
+            rendered.dimensions(),
+            (size.x.ceil() as u32, size.y.ceil() as u32)
+        );
+
+        // The scaled frame is shown on the background, under the toolbar.
+        let (x, y) = find(&rendered, RED, side).unwrap();
+        assert!(y > 0);
+        assert_eq!(rendered.get_pixel(x - 1, y), &BLUE.rgba());
+        assert_eq!(rendered.get_pixel(x + side, y + side), &BLUE.rgba());
+
+        // The toolbar and the status bar have the panel fill of the theme.
+        let visuals = egui::Visuals::dark();
+        let dark = render_viewer_frame_to_image(
+            &entry,
+            0,
+            &ViewerOptions::default().with_theme(ViewerTheme::Dark),
+        );
+        assert_eq!(
+            dark.get_pixel(0, 0),
+            &Rgba(visuals.panel_fill.to_srgba_unmultiplied())
+        );
+        assert_eq!(
+            dark.get_pixel(0, dark.height() - 1),
+            &Rgba(visuals.panel_fill.to_srgba_unmultiplied())
+        );
+
+        // Without the toolbar, the frame starts higher.
+        let rendered = render_viewer_frame_to_image(&entry, 0, &options.with_toolbar(false));
+        assert!(find(&rendered, RED, side).unwrap().1 < y);
+    }
+
+    #[test]
+    fn test_render_checkerboard() {
+        let entry = ViewEntry::new([DefaultImageBuffer::new(10, 10)]);
+        let options = ViewerOptions::default()
+            .with_start_scale(2.)
+            .with_checkerboard(true);
+        let rendered = render_viewer_frame_to_image(&entry, 0, &options);
+
+        let dark = PixelColor::new(204, 204, 204);
+        // The image starts with a dark square, on the background.
+        let (x, y) = find(&rendered, dark, 8).unwrap();
+        assert_eq!(rendered.get_pixel(x - 1, y), &Rgba([248, 248, 248, 255]));
+        assert_eq!(rendered.get_pixel(x, y - 1), &Rgba([248, 248, 248, 255]));
+        let at = |dx: u32, dy: u32| rendered.get_pixel(x + dx, y + dy);
+        assert_eq!(at(8, 0), &Rgba([255, 255, 255, 255]));
+        assert_eq!(at(0, 8), &Rgba([255, 255, 255, 255]));
+        assert_eq!(at(8, 8), &Rgba([204, 204, 204, 255]));
+    }
+
+    #[test]
+    fn test_render_grid_and_rulers() {
+        let entry =
+            ViewEntry::new([DefaultImageBuffer::from_pixel(10, 10, RED.rgba())]).with_grid_cell(5);
+        let options = ViewerOptions::default()
+            .with_start_scale(4.)
+            .with_toolbar(false)
+            .with_inspector(false);
+        let plain = render_viewer_frame_to_image(&entry, 0, &options);
+        let (x, y) = find(&plain, RED, 40).unwrap();
+        let is_red = |image: &DefaultImageBuffer, dx: u32, dy: u32| {
+            image.get_pixel(x + dx, y + dy) == &RED.rgba()
+        };
+
+        // Lines go between cells of 5 pixels, shown 20 pixels apart.
+        let grid = render_viewer_frame_to_image(&entry, 0, &options.clone().with_grid(true));
+        assert!(!(19..=20).all(|dx| is_red(&grid, dx, 10)));
+        assert!(!(19..=20).all(|dy| is_red(&grid, 10, dy)));
+        assert!(is_red(&grid, 10, 10) && is_red(&grid, 30, 30));
+
+        // Row and column numbers are labeled on dark boxes.
+        let rulers = render_viewer_frame_to_image(&entry, 0, &options.clone().with_rulers(true));
+        assert!(!is_red(&rulers, 1, 1));
+        assert!(is_red(&rulers, 30, 30));
+
+        // Cells too small to tell apart aren't drawn.
+        let entry = entry.with_grid_cell(1);
+        let options = options.with_start_scale(1.);
+        assert_eq!(
+            render_viewer_frame_to_image(&entry, 0, &options.clone().with_grid(true)),
+            render_viewer_frame_to_image(&entry, 0, &options)
+        );
+    }
+}